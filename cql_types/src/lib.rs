@@ -0,0 +1,78 @@
+/// Type option id of user defined types
+pub const UDT_OPTION: i16 = 0x0030;
+
+/// The reads of a bytes cursor needed to walk the type options of a result metadata
+pub trait TypeOptionReader {
+    type Error;
+
+    fn read_i16(&mut self) -> Result<i16, Self::Error>;
+    fn read_short(&mut self) -> Result<i16, Self::Error>;
+    fn read_string(&mut self) -> Result<String, Self::Error>;
+}
+
+/// Skips a column type option, including the field list of user defined types
+pub fn skip_type_option<R: TypeOptionReader>(cursor: &mut R) -> Result<(), R::Error> {
+    if cursor.read_i16()? == UDT_OPTION {
+        let _keyspace = cursor.read_string()?;
+        let _type_name = cursor.read_string()?;
+        for _ in 0..cursor.read_short()? {
+            let _field = cursor.read_string()?;
+            skip_type_option(cursor)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct SliceReader<'a>(&'a [u8]);
+
+    impl TypeOptionReader for SliceReader<'_> {
+        type Error = ();
+
+        fn read_i16(&mut self) -> Result<i16, ()> {
+            let (value, rest) = self.0.split_first_chunk::<2>().ok_or(())?;
+            self.0 = rest;
+            Ok(i16::from_be_bytes(*value))
+        }
+
+        fn read_short(&mut self) -> Result<i16, ()> {
+            self.read_i16()
+        }
+
+        fn read_string(&mut self) -> Result<String, ()> {
+            let len = self.read_short()? as usize;
+            let (string, rest) = self.0.split_at_checked(len).ok_or(())?;
+            self.0 = rest;
+            String::from_utf8(string.to_vec()).map_err(|_| ())
+        }
+    }
+
+    fn string(value: &str) -> Vec<u8> {
+        let mut bytes = (value.len() as i16).to_be_bytes().to_vec();
+        bytes.extend_from_slice(value.as_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_skips_nested_user_types() {
+        let mut bytes = UDT_OPTION.to_be_bytes().to_vec();
+        bytes.extend(string("ks"));
+        bytes.extend(string("address"));
+        bytes.extend(2i16.to_be_bytes());
+        bytes.extend(string("street"));
+        bytes.extend(0x000Ai16.to_be_bytes());
+        bytes.extend(string("city"));
+        bytes.extend(UDT_OPTION.to_be_bytes());
+        bytes.extend(string("ks"));
+        bytes.extend(string("city"));
+        bytes.extend(0i16.to_be_bytes());
+        bytes.extend(0x0009i16.to_be_bytes());
+        let mut reader = SliceReader(&bytes);
+        skip_type_option(&mut reader).unwrap();
+        assert_eq!(reader.read_i16(), Ok(0x0009));
+        assert!(reader.0.is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use cql_types::TypeOptionReader;
 use std::collections::HashMap;
 use std::io::{Cursor, Read};
 
@@ -102,3 +103,19 @@ impl BytesCursor {
         }
     }
 }
+
+impl TypeOptionReader for BytesCursor {
+    type Error = String;
+
+    fn read_i16(&mut self) -> Result<i16, String> {
+        self.read_i16()
+    }
+
+    fn read_short(&mut self) -> Result<i16, String> {
+        self.read_short()
+    }
+
+    fn read_string(&mut self) -> Result<String, String> {
+        self.read_string()
+    }
+}
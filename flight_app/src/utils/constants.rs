@@ -20,4 +20,7 @@ pub const OP_AUTH_CHALLENGE: u8 = 14;
 pub const OP_AUTH_RESPONSE: u8 = 15;
 pub const OP_AUTH_SUCCESS: u8 = 16;
 
-pub const ROW_RESPONSE: i32 = 2;
\ No newline at end of file
+pub const ROW_RESPONSE: i32 = 2;
+
+// Flags of the frame
+pub const COMPRESSION_FLAG: u8 = 0x01;
\ No newline at end of file
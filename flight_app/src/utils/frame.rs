@@ -1,6 +1,8 @@
-use super::{bytes_cursor::BytesCursor, types_to_bytes::TypesToBytes};
+use super::{
+    bytes_cursor::BytesCursor, constants::COMPRESSION_FLAG, types_to_bytes::TypesToBytes,
+};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Frame {
     pub version: u8,
     pub flags: u8,
@@ -61,6 +63,33 @@ impl Frame {
         Ok(frame)
     }
 
+    /// Compresses the body with LZ4, preceded by its original length
+    pub fn compress(&mut self) {
+        if self.body.is_empty() {
+            return;
+        }
+        let mut body = (self.body.len() as i32).to_be_bytes().to_vec();
+        body.extend(lz4_flex::block::compress(&self.body));
+        self.body = body;
+        self.length = self.body.len() as u32;
+        self.flags |= COMPRESSION_FLAG;
+    }
+
+    /// Decompresses the body if the node sent it compressed
+    pub fn decompress(&mut self) -> Result<(), String> {
+        if self.flags & COMPRESSION_FLAG == 0 {
+            return Ok(());
+        }
+        let mut cursor = BytesCursor::new(&self.body);
+        let length = cursor.read_int()?;
+        let block = cursor.read_remaining_bytes()?;
+        self.body = lz4_flex::block::decompress(&block, length.max(0) as usize)
+            .map_err(|e| format!("Error decompressing the frame: {}", e))?;
+        self.length = self.body.len() as u32;
+        self.flags &= !COMPRESSION_FLAG;
+        Ok(())
+    }
+
     pub fn validate_request_frame(&self) -> Result<(), String> {
         if self.version != 0x03 {
             return Err(format!("Version {} is incorrect", self.version));
@@ -74,3 +103,20 @@ impl Frame {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_body() {
+        let body = b"AR1234 EZE COR 2024-10-01 ".repeat(20);
+        let mut frame = Frame::new(3, 0, 1, 8, body.len() as u32, body.clone());
+        frame.compress();
+        assert_eq!(frame.flags, COMPRESSION_FLAG);
+        assert!(frame.body.len() < body.len());
+        frame.decompress().unwrap();
+        assert_eq!(frame.flags, 0);
+        assert_eq!(frame.body, body);
+    }
+}
@@ -0,0 +1,28 @@
+use super::authenticator::Authenticator;
+use super::sasl::{SaslNegotiator, SaslStep};
+use crate::utils::errors::Errors;
+
+/// AllowAllAuthenticator accepts any connection without credentials, meant for local development
+pub struct AllowAllAuthenticator;
+
+impl Authenticator for AllowAllAuthenticator {
+    fn class_name(&self) -> &'static str {
+        "org.apache.cassandra.auth.AllowAllAuthenticator"
+    }
+
+    fn requires_authentication(&self) -> bool {
+        false
+    }
+
+    fn new_sasl_negotiator(&self) -> Box<dyn SaslNegotiator> {
+        Box::new(AllowAllNegotiator)
+    }
+}
+
+struct AllowAllNegotiator;
+
+impl SaslNegotiator for AllowAllNegotiator {
+    fn evaluate_response(&mut self, _token: &[u8]) -> Result<SaslStep, Errors> {
+        Ok(SaslStep::Complete(None))
+    }
+}
@@ -1,7 +1,73 @@
-use crate::utils::errors::Errors;
+use crate::auth::allow_all_authenticator::AllowAllAuthenticator;
+use crate::auth::password_authenticator::PasswordAuthenticator;
+use crate::auth::sasl::SaslNegotiator;
+use serde::Deserialize;
+use std::sync::Mutex;
 
-pub trait Authenticator {
-    fn validate_credentials(&self, user: String, password: String) -> Result<bool, Errors>;
-    // fn create_user(&self, user: &str, pass: &str) -> Result<(), Errors>;
-    // fn change_password(&self, user: &str, password: &str, new_password: &str) -> Result<(), Errors>;
+/// Authenticator of the client connections.
+///
+/// Every connection that has to authenticate negotiates with its own `SaslNegotiator`, which
+/// receives the tokens of the AUTH_RESPONSE messages until the authentication completes.
+pub trait Authenticator: Send {
+    /// class name advertised in the AUTHENTICATE message
+    fn class_name(&self) -> &'static str;
+
+    /// if false the STARTUP is answered with READY and no SASL negotiation takes place
+    fn requires_authentication(&self) -> bool {
+        true
+    }
+
+    fn new_sasl_negotiator(&self) -> Box<dyn SaslNegotiator>;
+}
+
+/// Authenticator chosen in the node configuration with the `authenticator` key.
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub enum AuthenticatorKind {
+    #[default]
+    PasswordAuthenticator,
+    AllowAllAuthenticator,
+}
+
+static CONFIGURED: Mutex<AuthenticatorKind> = Mutex::new(AuthenticatorKind::PasswordAuthenticator);
+
+impl AuthenticatorKind {
+    /// sets the authenticator used by every new connection
+    pub fn configure(self) {
+        *CONFIGURED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = self;
+    }
+
+    pub fn configured() -> AuthenticatorKind {
+        *CONFIGURED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn build(&self) -> Box<dyn Authenticator> {
+        match self {
+            AuthenticatorKind::PasswordAuthenticator => Box::new(PasswordAuthenticator::new()),
+            AuthenticatorKind::AllowAllAuthenticator => Box::new(AllowAllAuthenticator),
+        }
+    }
+}
+
+/// returns the authenticator selected by the node configuration
+pub fn configured_authenticator() -> Box<dyn Authenticator> {
+    AuthenticatorKind::configured().build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authenticator_kind_from_config() {
+        let kind: AuthenticatorKind = serde_yaml::from_str("AllowAllAuthenticator").unwrap();
+        assert_eq!(kind, AuthenticatorKind::AllowAllAuthenticator);
+        assert!(!kind.build().requires_authentication());
+        assert!(AuthenticatorKind::default()
+            .build()
+            .requires_authentication());
+    }
 }
@@ -1,2 +1,4 @@
+pub mod allow_all_authenticator;
 pub mod authenticator;
 pub mod password_authenticator;
+pub mod sasl;
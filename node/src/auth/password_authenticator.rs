@@ -1,4 +1,6 @@
 use super::authenticator::Authenticator;
+use super::sasl::{PlainTextNegotiator, SaslNegotiator};
+use crate::utils::constants::CREDENTIALS_FILE;
 use crate::utils::errors::Errors;
 use crate::utils::functions::deserialize_from_str;
 use serde::{Deserialize, Serialize};
@@ -10,26 +12,25 @@ use argon2::{
     Argon2, PasswordHash, PasswordVerifier,
 };
 
-const CREDENTIALS: &str = "src/auth/credentials.json";
-
 #[derive(Serialize, Deserialize, Debug)]
 struct Credential {
     user: String,
     pass_hash: String,
 }
 
-/// PasswordAuthenticator uses user and password to validate credentials
+/// PasswordAuthenticator uses user and password to validate credentials, sent with SASL PLAIN
 pub struct PasswordAuthenticator;
 
 impl Authenticator for PasswordAuthenticator {
-    fn validate_credentials(&self, user: String, pass: String) -> Result<bool, Errors> {
-        let credentials = self.get_credentials()?;
-        for credential in credentials {
-            if credential.user == user {
-                return verify_password(&pass, &credential.pass_hash);
-            }
-        }
-        Ok(false)
+    fn class_name(&self) -> &'static str {
+        "org.apache.cassandra.auth.PasswordAuthenticator"
+    }
+
+    fn new_sasl_negotiator(&self) -> Box<dyn SaslNegotiator> {
+        Box::new(PlainTextNegotiator::new(Box::new(|user, password| {
+            PasswordAuthenticator::new()
+                .validate_credentials(user.to_string(), password.to_string())
+        })))
     }
 }
 
@@ -44,6 +45,16 @@ impl PasswordAuthenticator {
         PasswordAuthenticator {}
     }
 
+    pub fn validate_credentials(&self, user: String, pass: String) -> Result<bool, Errors> {
+        let credentials = self.get_credentials()?;
+        for credential in credentials {
+            if credential.user == user {
+                return verify_password(&pass, &credential.pass_hash);
+            }
+        }
+        Ok(false)
+    }
+
     fn default_credentials(&self) -> Result<Vec<Credential>, Errors> {
         let credentials = vec![Credential {
             user: "admin".to_string(),
@@ -55,18 +66,18 @@ impl PasswordAuthenticator {
     fn save_credentials(&self, credentials: Vec<Credential>) -> Result<(), Errors> {
         let data = serde_json::to_string(&credentials)
             .map_err(|_| Errors::ServerError(String::from("Failed to save credentials")))?;
-        std::fs::write(CREDENTIALS, data)
+        std::fs::write(CREDENTIALS_FILE.as_str(), data)
             .map_err(|_| Errors::ServerError(String::from("Failed to save credentials")))?;
         Ok(())
     }
 
     fn get_file(&self) -> Result<File, Errors> {
-        let file = match File::open(CREDENTIALS) {
+        let file = match File::open(CREDENTIALS_FILE.as_str()) {
             Ok(file) => file,
             Err(_) => {
                 let credentials = self.default_credentials()?;
                 self.save_credentials(credentials)?;
-                File::open(CREDENTIALS)
+                File::open(CREDENTIALS_FILE.as_str())
                     .map_err(|_| Errors::ServerError(String::from("Failed to open credentials")))?
             }
         };
@@ -0,0 +1,166 @@
+use crate::utils::errors::Errors;
+use std::sync::{Mutex, MutexGuard};
+
+const NUL: u8 = 0;
+
+/// Result of evaluating a token sent by the client in an AUTH_RESPONSE.
+#[derive(Debug, PartialEq)]
+pub enum SaslStep {
+    /// the negotiation goes on, the token is sent in an AUTH_CHALLENGE
+    Challenge(Vec<u8>),
+    /// the client is authenticated, the optional token is sent in the AUTH_SUCCESS
+    Complete(Option<Vec<u8>>),
+}
+
+pub trait SaslNegotiator: Send {
+    /// evaluates the token of an AUTH_RESPONSE, credentials that are not valid are an error
+    fn evaluate_response(&mut self, token: &[u8]) -> Result<SaslStep, Errors>;
+}
+
+type CredentialsValidator = Box<dyn Fn(&str, &str) -> Result<bool, Errors> + Send>;
+
+/// PLAIN mechanism (RFC 4616): the token is `[authzid] NUL authcid NUL passwd`.
+///
+/// If the client does not send the credentials in the first response they are asked for with an
+/// empty challenge, as SASL says, and the second response must bring them.
+pub struct PlainTextNegotiator {
+    validate: CredentialsValidator,
+    challenged: bool,
+}
+
+impl PlainTextNegotiator {
+    pub fn new(validate: CredentialsValidator) -> PlainTextNegotiator {
+        PlainTextNegotiator {
+            validate,
+            challenged: false,
+        }
+    }
+
+    /// returns the user and the password of a PLAIN token
+    pub fn decode_token(token: &[u8]) -> Result<(String, String), Errors> {
+        let parts: Vec<&[u8]> = token.split(|byte| *byte == NUL).collect();
+        let [authzid, user, password] = parts.as_slice() else {
+            return Err(Errors::ProtocolError(String::from("Invalid credentials")));
+        };
+        if user.is_empty() || password.is_empty() {
+            return Err(Errors::ProtocolError(String::from("Invalid credentials")));
+        }
+        if !authzid.is_empty() && authzid != user {
+            return Err(Errors::BadCredentials(String::from(
+                "Authorizing as a different user is not supported",
+            )));
+        }
+        let to_string = |bytes: &[u8]| {
+            String::from_utf8(bytes.to_vec())
+                .map_err(|_| Errors::ProtocolError(String::from("Invalid credentials")))
+        };
+        Ok((to_string(user)?, to_string(password)?))
+    }
+}
+
+impl SaslNegotiator for PlainTextNegotiator {
+    fn evaluate_response(&mut self, token: &[u8]) -> Result<SaslStep, Errors> {
+        if token.is_empty() && !self.challenged {
+            self.challenged = true;
+            return Ok(SaslStep::Challenge(Vec::new()));
+        }
+        let (user, password) = Self::decode_token(token)?;
+        if !(self.validate)(&user, &password)? {
+            return Err(Errors::BadCredentials(String::from(
+                "Invalid credentials provided",
+            )));
+        }
+        Ok(SaslStep::Complete(None))
+    }
+}
+
+/// Negotiations in progress, by connection, between an AUTH_CHALLENGE and the next AUTH_RESPONSE.
+static SESSIONS: Mutex<Vec<(String, Box<dyn SaslNegotiator>)>> = Mutex::new(Vec::new());
+
+fn sessions() -> MutexGuard<'static, Vec<(String, Box<dyn SaslNegotiator>)>> {
+    SESSIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// removes and returns the negotiation in progress of the connection
+pub fn take_session(connection_id: &str) -> Option<Box<dyn SaslNegotiator>> {
+    let mut sessions = sessions();
+    let position = sessions.iter().position(|(id, _)| id == connection_id)?;
+    Some(sessions.remove(position).1)
+}
+
+/// keeps the negotiation until the next AUTH_RESPONSE of the connection
+pub fn store_session(connection_id: &str, negotiator: Box<dyn SaslNegotiator>) {
+    let mut sessions = sessions();
+    sessions.retain(|(id, _)| id != connection_id);
+    sessions.push((connection_id.to_string(), negotiator));
+}
+
+pub fn end_session(connection_id: &str) {
+    sessions().retain(|(id, _)| id != connection_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn negotiator() -> PlainTextNegotiator {
+        PlainTextNegotiator::new(Box::new(|user, password| {
+            Ok(user == "admin" && password == "password")
+        }))
+    }
+
+    #[test]
+    fn test_plain_token() {
+        let mut negotiator = negotiator();
+        let step = negotiator.evaluate_response(b"\0admin\0password").unwrap();
+        assert_eq!(step, SaslStep::Complete(None));
+    }
+
+    #[test]
+    fn test_plain_token_with_authzid() {
+        let mut negotiator = negotiator();
+        assert!(negotiator
+            .evaluate_response(b"admin\0admin\0password")
+            .is_ok());
+        assert!(matches!(
+            negotiator.evaluate_response(b"root\0admin\0password"),
+            Err(Errors::BadCredentials(_))
+        ));
+    }
+
+    #[test]
+    fn test_empty_response_is_challenged_once() {
+        let mut negotiator = negotiator();
+        let step = negotiator.evaluate_response(b"").unwrap();
+        assert_eq!(step, SaslStep::Challenge(Vec::new()));
+        assert!(negotiator.evaluate_response(b"").is_err());
+        assert!(negotiator.evaluate_response(b"\0admin\0password").is_ok());
+    }
+
+    #[test]
+    fn test_invalid_credentials() {
+        let mut negotiator = negotiator();
+        assert!(matches!(
+            negotiator.evaluate_response(b"\0admin\0wrong"),
+            Err(Errors::BadCredentials(_))
+        ));
+        assert!(matches!(
+            negotiator.evaluate_response(b"admin:password"),
+            Err(Errors::ProtocolError(_))
+        ));
+        assert!(matches!(
+            negotiator.evaluate_response(b"\0\0password"),
+            Err(Errors::ProtocolError(_))
+        ));
+    }
+
+    #[test]
+    fn test_sessions_are_kept_per_connection() {
+        store_session("sasl_session", Box::new(negotiator()));
+        assert!(take_session("sasl_other").is_none());
+        assert!(take_session("sasl_session").is_some());
+        assert!(take_session("sasl_session").is_none());
+    }
+}
@@ -0,0 +1,67 @@
+use crate::queries::batch_query::BatchQuery;
+use crate::utils::config_constants::BATCHLOG_REPLAY_SECS;
+use crate::utils::types::node_ip::NodeIp;
+use crate::utils::types::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct BatchlogEntry {
+    pub id: String,
+    pub batch: BatchQuery,
+    pub timestamp: Timestamp,
+    /// nodes that keep the entry, the first of them that is alive replays it
+    #[serde(default)]
+    pub holders: Vec<NodeIp>,
+}
+
+impl BatchlogEntry {
+    /// Every statement is written with the timestamp of the batch, the one sent by the client
+    /// or the moment the entry is created, so a replay writes the same cells
+    pub fn new(mut batch: BatchQuery, holders: Vec<NodeIp>) -> Self {
+        let timestamp = Timestamp::new();
+        batch.share_timestamp(timestamp.timestamp);
+        Self {
+            id: format!("{}-{:08x}", timestamp.timestamp, rand::random::<u32>()),
+            batch,
+            timestamp,
+            holders,
+        }
+    }
+
+    /// returns true if the coordinator had enough time to apply the batch and remove the entry
+    pub fn should_replay(&self) -> bool {
+        self.timestamp.has_perished_seconds(BATCHLOG_REPLAY_SECS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queries::batch_query::BatchStatement;
+    use crate::queries::delete_query::DeleteQuery;
+    use crate::queries::insert_query::InsertQuery;
+    use crate::queries::query::Query;
+
+    #[test]
+    fn test_statements_get_the_batch_timestamp() {
+        let batch = BatchQuery {
+            statements: vec![
+                BatchStatement::Insert(InsertQuery::default()),
+                BatchStatement::Delete(DeleteQuery::default()),
+            ],
+            ..BatchQuery::default()
+        };
+        let entry = BatchlogEntry::new(batch.clone(), Vec::new());
+        assert_eq!(entry.batch.timestamp(), Some(entry.timestamp.timestamp));
+        assert!(entry
+            .batch
+            .statements
+            .iter()
+            .all(|statement| statement.timestamp() == Some(entry.timestamp.timestamp)));
+
+        let mut with_client_timestamp = batch;
+        with_client_timestamp.set_timestamp(42);
+        let entry = BatchlogEntry::new(with_client_timestamp, Vec::new());
+        assert_eq!(entry.batch.timestamp(), Some(42));
+    }
+}
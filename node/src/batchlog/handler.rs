@@ -0,0 +1,205 @@
+use crate::batchlog::batchlog_entry::BatchlogEntry;
+use crate::client_warnings;
+use crate::gossip::failure_detector;
+use crate::logger::Logger;
+use crate::meta_data::meta_data_handler::use_node_meta_data;
+use crate::meta_data::nodes::node::State;
+use crate::queries::batch_query::BatchQuery;
+use crate::queries::batchlog_query::BatchlogQuery;
+use crate::query_delegation::query_delegator::QueryDelegator;
+use crate::utils::config_constants::BATCH_SIZE_WARN_THRESHOLD;
+use crate::utils::consistency_level::ConsistencyLevel;
+use crate::utils::constants::{BATCHLOG_DATA, LOGGER_PATH, NODES_METADATA_PATH};
+use crate::utils::errors::Errors;
+use crate::utils::errors::Errors::ServerError;
+use crate::utils::functions::{deserialize_from_slice, serialize_to_string};
+use crate::utils::types::node_ip::NodeIp;
+use crate::utils::types::timestamp::Timestamp;
+use rand::seq::SliceRandom;
+use std::fs;
+use std::path::Path;
+
+/// Number of nodes, other than the coordinator, that store the batchlog.
+const BATCHLOG_NODES: usize = 2;
+
+pub struct BatchlogHandler;
+
+impl BatchlogHandler {
+    /// Stores the entry in this node's batchlog, one file per batch
+    pub fn store(entry: &BatchlogEntry) -> Result<(), Errors> {
+        fs::create_dir_all(BATCHLOG_DATA.as_str()).map_err(|e| ServerError(e.to_string()))?;
+        fs::write(Self::entry_path(&entry.id), serialize_to_string(entry)?)
+            .map_err(|_| ServerError(String::from("failed to write batchlog")))
+    }
+
+    /// Removes the entry from this node's batchlog, if it exists
+    pub fn remove(id: &str) -> Result<(), Errors> {
+        let path = Self::entry_path(id);
+        if Path::new(&path).exists() {
+            fs::remove_file(path)
+                .map_err(|_| ServerError(String::from("failed to remove batchlog")))?;
+        }
+        Ok(())
+    }
+
+    /// Applies a batch delegating each statement to its replicas.
+    ///
+    /// If the batch is logged, the batchlog is written to other nodes before applying it,
+    /// so the batch is replayed by them if this node fails halfway through. Logged or not,
+    /// every statement is written with the same timestamp.
+    /// The client is warned when the batch is too large
+    pub fn execute(mut batch: BatchQuery, consistency: ConsistencyLevel) -> Result<(), Errors> {
+        if batch.statements.len() > BATCH_SIZE_WARN_THRESHOLD {
+            client_warnings::warn(&format!(
+                "Batch of {} statements exceeds the warning threshold of {}",
+                batch.statements.len(),
+                BATCH_SIZE_WARN_THRESHOLD
+            ));
+        }
+        if !batch.is_logged() {
+            batch.share_timestamp(Timestamp::new().timestamp);
+            return Self::apply(batch, consistency);
+        }
+        let entry = BatchlogEntry::new(batch, Self::choose_holders()?);
+        Self::write_to_holders(&entry)?;
+        Self::apply(entry.batch.clone(), consistency)?;
+        Self::remove_from(&entry.holders, &entry.id);
+        Ok(())
+    }
+
+    /// Replays every batch whose coordinator did not remove it in time.
+    ///
+    /// Only the lowest live holder of an entry replays it, and once it succeeds it removes the
+    /// copy of the other holder. If that holder is down the other one takes over.
+    /// An entry that cannot be read is logged and skipped, it does not stop the rest
+    pub fn replay_expired() -> Result<(), Errors> {
+        fs::create_dir_all(BATCHLOG_DATA.as_str()).map_err(|e| ServerError(e.to_string()))?;
+        let logger = Logger::new(&LOGGER_PATH);
+        let own_ip = use_node_meta_data(|handler| handler.get_own_ip(&NODES_METADATA_PATH))?;
+        for dir_entry in fs::read_dir(BATCHLOG_DATA.as_str())
+            .map_err(|_| ServerError(String::from("cannot read directory")))?
+        {
+            let dir_entry =
+                dir_entry.map_err(|_| ServerError(String::from("cannot read directory")))?;
+            let entry = match Self::read_entry(&dir_entry.path()) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    logger.log_error(&format!(
+                        "Skipping batchlog entry {}: {}",
+                        dir_entry.path().display(),
+                        e
+                    ));
+                    continue;
+                }
+            };
+            if !entry.should_replay()
+                || !replays(&entry.holders, &own_ip, failure_detector::is_alive)
+            {
+                continue;
+            }
+            if Self::apply(entry.batch, ConsistencyLevel::One).is_ok() {
+                Self::remove(&entry.id)?;
+                let others: Vec<NodeIp> = entry
+                    .holders
+                    .into_iter()
+                    .filter(|holder| *holder != own_ip)
+                    .collect();
+                Self::remove_from(&others, &entry.id);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_entry(path: &Path) -> Result<BatchlogEntry, Errors> {
+        let content =
+            fs::read(path).map_err(|_| ServerError(String::from("cannot open file")))?;
+        deserialize_from_slice(content.as_slice())
+    }
+
+    fn apply(batch: BatchQuery, consistency: ConsistencyLevel) -> Result<(), Errors> {
+        for statement in batch.statements {
+            let query = statement.into_query();
+            let pk = query.get_partition()?;
+            QueryDelegator::new(pk, query, consistency).send()?;
+        }
+        Ok(())
+    }
+
+    /// Up to `BATCHLOG_NODES` live nodes other than the coordinator. Only a cluster of a single
+    /// node keeps the batchlog in the coordinator, as there is no other node to hand it to
+    fn choose_holders() -> Result<Vec<NodeIp>, Errors> {
+        let cluster = use_node_meta_data(|handler| handler.get_cluster(&NODES_METADATA_PATH))?;
+        let others = cluster.get_other_nodes();
+        if others.is_empty() {
+            return Ok(vec![NodeIp::new_from_ip(cluster.get_own_ip())]);
+        }
+        let mut holders: Vec<NodeIp> = others
+            .iter()
+            .filter(|node| node.state == State::Active)
+            .map(|node| NodeIp::new_from_ip(node.get_ip()))
+            .filter(failure_detector::is_alive)
+            .collect();
+        if holders.is_empty() {
+            return Err(Errors::UnavailableException(String::from(
+                "Cannot achieve consistency level ONE for the batchlog, no other node is alive",
+            )));
+        }
+        holders.shuffle(&mut rand::thread_rng());
+        holders.truncate(BATCHLOG_NODES);
+        Ok(holders)
+    }
+
+    fn write_to_holders(entry: &BatchlogEntry) -> Result<(), Errors> {
+        for ip in &entry.holders {
+            QueryDelegator::send_without_hint(ip, Box::new(BatchlogQuery::store(entry.clone())))
+                .map_err(|e| {
+                    Errors::WriteTimeout(format!("Could not write the batchlog: {}", e))
+                })?;
+        }
+        Ok(())
+    }
+
+    /// a holder that does not get the removal replays the batch again, with the same timestamp
+    fn remove_from(holders: &[NodeIp], id: &str) {
+        for ip in holders {
+            let _ = QueryDelegator::send_without_hint(ip, Box::new(BatchlogQuery::remove(id)));
+        }
+    }
+
+    fn entry_path(id: &str) -> String {
+        format!("{}/{}.json", BATCHLOG_DATA.as_str(), id)
+    }
+}
+
+/// the lowest live holder replays the entry, the own node is always alive. Entries without
+/// holders are replayed by the node that has them
+fn replays<F>(holders: &[NodeIp], own_ip: &NodeIp, is_alive: F) -> bool
+where
+    F: Fn(&NodeIp) -> bool,
+{
+    holders
+        .iter()
+        .filter(|holder| *holder == own_ip || is_alive(holder))
+        .min()
+        .is_none_or(|owner| owner == own_ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(address: &str) -> NodeIp {
+        NodeIp::new_from_string(address, 9042).unwrap()
+    }
+
+    #[test]
+    fn test_only_the_lowest_live_holder_replays() {
+        let holders = vec![ip("10.0.0.2"), ip("10.0.0.1")];
+        assert!(replays(&holders, &ip("10.0.0.1"), |_| true));
+        assert!(!replays(&holders, &ip("10.0.0.2"), |_| true));
+        assert!(replays(&holders, &ip("10.0.0.2"), |holder| {
+            *holder != ip("10.0.0.1")
+        }));
+        assert!(replays(&[], &ip("10.0.0.2"), |_| true));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod batchlog_entry;
+pub mod handler;
@@ -1,59 +1,223 @@
 use rustls::{ServerConnection, StreamOwned};
 
+use crate::auth::sasl;
+use crate::client_warnings;
+use crate::event_bus;
 use crate::logger::Logger;
+use crate::meta_data::clients::meta_data_client::ClientMetaDataAcces;
 use crate::meta_data::meta_data_handler::{use_client_meta_data, use_node_meta_data};
 use crate::meta_data::nodes::node::State;
 use crate::parsers::parser_factory::ParserFactory;
 use crate::response_builders::error_builder::ErrorBuilder;
+use crate::response_builders::frame_builder::FrameBuilder;
+use crate::stream_dispatcher::StreamDispatcher;
+use crate::tracing::trace_session::{self, TraceSession};
+use crate::tracing::trace_store;
+use crate::utils::compression::Compression;
+use crate::utils::config_constants::{CLIENT_POLL_MILLIS, MAX_IN_FLIGHT_REQUESTS};
 use crate::utils::constants::{CLIENT_METADATA_PATH, LOGGER_PATH, NODES_METADATA_PATH};
 use crate::utils::errors::Errors;
-use crate::utils::parser_constants::{AUTH_RESPONSE, AUTH_SUCCESS, STARTUP};
-use crate::utils::types::frame::Frame;
-use crate::utils::types::tls_stream::{read_exact_from_tls_stream, write_to_tls_stream};
+use crate::utils::parser_constants::{
+    AUTH_RESPONSE, AUTH_SUCCESS, BATCH, ERROR, EXECUTE, PREPARE, QUERY, READY, STARTUP,
+};
+use crate::utils::types::frame::{Frame, MAX_VERSION, TRACING_FLAG};
+use crate::utils::types::segment::{decode_segments, encode_segments};
+use crate::utils::types::tls_stream::{
+    next_message, read_available_from_tls_stream, write_to_tls_stream,
+};
 use std::net::TcpStream;
+use std::time::Duration;
 use crate::utils::frame_reader::FrameReader;
 
 pub struct ClientHandler {}
 
 impl ClientHandler {
-    pub fn handle_client(
-        mut stream: StreamOwned<ServerConnection, TcpStream>,
-    ) -> Result<(), Errors> {
-        let logger = Logger::new(LOGGER_PATH);
-        
+    pub fn handle_client(stream: StreamOwned<ServerConnection, TcpStream>) -> Result<(), Errors> {
+        let result = Self::serve(stream);
+        let connection_id = ClientMetaDataAcces::connection_id();
+        event_bus::unsubscribe(&connection_id);
+        sasl::end_session(&connection_id);
+        result
+    }
+
+    fn serve(mut stream: StreamOwned<ServerConnection, TcpStream>) -> Result<(), Errors> {
         add_new_client()?;
+        stream
+            .sock
+            .set_read_timeout(Some(Duration::from_millis(CLIENT_POLL_MILLIS)))
+            .map_err(|e| Errors::ServerError(e.to_string()))?;
+        let mut connection = Connection::new();
+        let mut buffer = Vec::new();
         loop {
-            match read_exact_from_tls_stream(&mut stream) {
-                Err(_) => {
-                    logger.log_message("Client disconnected.");
-                    delete_client()?;
+            if !read_available_from_tls_stream(&mut stream, &mut buffer).unwrap_or(false) {
+                connection.logger.log_message("Client disconnected.");
+                break;
+            }
+            while !connection.dispatcher.is_full() {
+                let Some(message) = next_message(&mut buffer) else {
                     break;
-                }
-                Ok(vec) => match execute_request(vec.clone()) {
-                    Ok(response) => {
-                        let string_response = FrameReader::read_frame(Frame::parse_frame(response.as_slice())?)?;
-                        logger.log_response(&string_response);    
-                        write_to_tls_stream(&mut stream, response.as_slice())?
+                };
+                match connection.handle_message(message) {
+                    Ok(responses) => {
+                        for response in responses {
+                            write_to_tls_stream(&mut stream, response.as_slice())?
+                        }
                     }
                     Err(e) => {
-                        logger.log_error(&format!("{}", &e));
-                        let frame = ErrorBuilder::build_error_frame(
-                            Frame::parse_frame(vec.as_slice())?,
-                            e,
-                        )?;
-                        write_to_tls_stream(&mut stream, frame.to_bytes().as_slice())?
+                        connection.logger.log_error(&format!("{}", &e));
+                        delete_client()?;
+                        return Ok(());
                     }
-                },
+                }
+            }
+            let poll = Duration::from_millis(CLIENT_POLL_MILLIS);
+            for response in connection.dispatcher.responses(poll) {
+                let response = connection.encode(response)?;
+                write_to_tls_stream(&mut stream, response.as_slice())?
+            }
+            for event in connection.events()? {
+                write_to_tls_stream(&mut stream, event.as_slice())?
             }
         }
-        Ok(())
+        delete_client()
     }
 }
 
-fn execute_request(bytes: Vec<u8>) -> Result<Vec<u8>, Errors> {
+/// State of a connection with a client.
+///
+/// Requests that change the state of the connection (STARTUP and AUTH_RESPONSE) are handled
+/// in the thread that reads it, the rest are spread among workers and their responses are
+/// written as they finish. If the connection sent a REGISTER, published events are written
+/// between the responses with the version of the last request.
+struct Connection {
+    logger: Logger,
+    segmented: bool,
+    version: u8,
+    dispatcher: StreamDispatcher,
+}
+
+impl Connection {
+    fn new() -> Self {
+        Self {
+            logger: Logger::new(&LOGGER_PATH),
+            segmented: false,
+            version: MAX_VERSION,
+            dispatcher: StreamDispatcher::new(MAX_IN_FLIGHT_REQUESTS),
+        }
+    }
+
+    /// handles a message read from the stream, returns the responses that are already available
+    fn handle_message(&mut self, message: Vec<u8>) -> Result<Vec<Vec<u8>>, Errors> {
+        if !self.segmented {
+            let response = self.handle_request(message, get_compression()?)?;
+            return Ok(response.into_iter().collect());
+        }
+        // in v5 compression is applied to the segments instead of the bodies
+        let payload = decode_segments(&message, get_compression()?.is_some())?;
+        let mut responses = Vec::new();
+        for frame in Frame::split_frames(&payload)? {
+            responses.extend(self.handle_request(frame, None)?);
+        }
+        Ok(responses)
+    }
+
+    fn handle_request(
+        &mut self,
+        bytes: Vec<u8>,
+        compression: Option<Compression>,
+    ) -> Result<Option<Vec<u8>>, Errors> {
+        let opcode = bytes.get(4).copied();
+        if let Some(version) = bytes.first() {
+            self.version = *version;
+        }
+        if opcode == Some(STARTUP) || opcode == Some(AUTH_RESPONSE) {
+            let response = handle_frame(&bytes, &self.logger, compression)?;
+            let encoded = self.encode(response.clone())?;
+            self.segmented = self.segmented || starts_segmentation(&bytes, &response)?;
+            return Ok(Some(encoded));
+        }
+        let stream = match bytes.get(2..4) {
+            Some(&[high, low]) => i16::from_be_bytes([high, low]),
+            _ => 0,
+        };
+        self.dispatcher.dispatch(self.version, stream, move || {
+            let logger = Logger::new(&LOGGER_PATH);
+            handle_frame(&bytes, &logger, compression)
+        });
+        Ok(None)
+    }
+
+    /// EVENT frames for the events published since the last poll
+    fn events(&self) -> Result<Vec<Vec<u8>>, Errors> {
+        let bodies = event_bus::take_events(&ClientMetaDataAcces::connection_id());
+        if bodies.is_empty() {
+            return Ok(Vec::new());
+        }
+        let compression = if self.segmented {
+            None
+        } else {
+            get_compression()?
+        };
+        let mut events = Vec::new();
+        for body in bodies {
+            let mut event = FrameBuilder::build_event_frame(self.version, body)?;
+            event.compress(compression)?;
+            events.push(self.encode(event.to_bytes())?);
+        }
+        Ok(events)
+    }
+
+    fn encode(&self, response: Vec<u8>) -> Result<Vec<u8>, Errors> {
+        if !self.segmented {
+            return Ok(response);
+        }
+        Ok(encode_segments(&response, get_compression()?.is_some()))
+    }
+}
+
+/// executes a single frame, errors are answered with an ERROR frame.
+///
+/// Once the compression is negotiated every response body is compressed, including errors.
+/// If the request was traced its id is sent in the response and its session is stored in
+/// the background.
+/// The warnings of the request and its custom payload go back with the response
+fn handle_frame(
+    bytes: &[u8],
+    logger: &Logger,
+    compression: Option<Compression>,
+) -> Result<Vec<u8>, Errors> {
+    let mut response = match execute_request(bytes.to_vec(), compression) {
+        Ok(response) => {
+            let string_response = FrameReader::read_frame(response.clone())?;
+            logger.log_response(&string_response);
+            response
+        }
+        Err(e) => {
+            logger.log_error(&format!("{}", &e));
+            ErrorBuilder::build_error_frame(Frame::parse_frame(bytes)?, e)?
+        }
+    };
+    // warnings of a failed request never reach the client
+    client_warnings::end();
+    if let Some(session) = trace_session::end() {
+        response.set_tracing_id(&session.session_id());
+        trace_store::persist(session);
+    }
+    response.compress(compression)?;
+    Ok(response.to_bytes())
+}
+
+/// In v5 the frames that follow a successful STARTUP travel inside segments.
+fn starts_segmentation(request: &[u8], response: &[u8]) -> Result<bool, Errors> {
+    let request = Frame::parse_frame(request)?;
+    let response = Frame::parse_frame(response)?;
+    Ok(request.version == MAX_VERSION && request.opcode == STARTUP && response.opcode != ERROR)
+}
+
+fn execute_request(bytes: Vec<u8>, compression: Option<Compression>) -> Result<Frame, Errors> {
     use_node_meta_data(|handler| {
         if handler
-            .get_cluster(NODES_METADATA_PATH)?
+            .get_cluster(&NODES_METADATA_PATH)?
             .get_own_node()
             .state
             == State::StandBy
@@ -62,23 +226,49 @@ fn execute_request(bytes: Vec<u8>) -> Result<Vec<u8>, Errors> {
         }
         Ok(())
     })?;
-    let frame = Frame::parse_frame(bytes.as_slice())?;
+    let mut frame = Frame::parse_frame(bytes.as_slice())?;
     frame.validate_request_frame()?;
+    frame.decompress(compression)?;
+    if frame.flags & TRACING_FLAG != 0 {
+        trace_session::begin(TraceSession::new(request_name(frame.opcode)));
+    }
+    client_warnings::begin();
+    let custom_payload = frame.custom_payload()?;
     let initial_opcode = frame.opcode;
     check_startup(frame.opcode)?;
     check_auth(frame.opcode)?;
-    let parser = ParserFactory::get_parser(frame.opcode)?;
-    let mut executable = parser.parse(frame.body.as_slice())?;
+    trace_session::trace("Parsing request");
+    let parser = ParserFactory::get_parser(frame.opcode, frame.version)?;
+    let mut executable = parser.parse(frame.request_body()?.as_slice())?;
+    trace_session::trace("Executing request");
     let frame = executable.execute(frame)?;
+    trace_session::trace("Request complete");
+    let frame =
+        FrameBuilder::add_warnings_and_payload(frame, &client_warnings::end(), &custom_payload)?;
     set_startup(initial_opcode)?;
     set_auth(initial_opcode, frame.opcode)?;
-    Ok(frame.to_bytes())
+    Ok(frame)
+}
+
+/// name of the request stored in `system_traces.sessions`
+fn request_name(opcode: u8) -> &'static str {
+    match opcode {
+        QUERY => "Execute CQL3 query",
+        PREPARE => "Preparing CQL3 query",
+        EXECUTE => "Execute CQL3 prepared query",
+        BATCH => "Execute batch of CQL3 queries",
+        _ => "Execute request",
+    }
 }
 
 fn add_new_client() -> Result<(), Errors> {
     use_client_meta_data(|handler| handler.add_new_client(CLIENT_METADATA_PATH.to_string()))
 }
 
+fn get_compression() -> Result<Option<Compression>, Errors> {
+    use_client_meta_data(|handler| handler.get_compression(CLIENT_METADATA_PATH.to_string()))
+}
+
 fn has_started() -> Result<bool, Errors> {
     use_client_meta_data(|handler| handler.has_started(CLIENT_METADATA_PATH.to_string()))
 }
@@ -94,9 +284,10 @@ fn check_startup(opcode: u8) -> Result<(), Errors> {
             "Client has already done startup",
         )));
     } else if opcode != STARTUP && !has_started {
-        return Err(Errors::Unprepared(String::from(
-            "Client must startup first",
-        )));
+        return Err(Errors::Unprepared(
+            String::from("Client must startup first"),
+            Vec::new(),
+        ));
     }
     Ok(())
 }
@@ -122,8 +313,12 @@ fn set_startup(initial_opcode: u8) -> Result<(), Errors> {
     Ok(())
 }
 
+/// the connection is authorized after a successful SASL negotiation, or right after STARTUP
+/// when the authenticator does not require it
 fn set_auth(initial_opcode: u8, response_opcode: u8) -> Result<(), Errors> {
-    if initial_opcode == AUTH_RESPONSE && response_opcode == AUTH_SUCCESS {
+    if (initial_opcode == AUTH_RESPONSE && response_opcode == AUTH_SUCCESS)
+        || (initial_opcode == STARTUP && response_opcode == READY)
+    {
         use_client_meta_data(|handler| handler.authorize_client(CLIENT_METADATA_PATH.to_string()))?
     }
     Ok(())
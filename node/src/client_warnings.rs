@@ -0,0 +1,97 @@
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+/// Warnings of the request being handled. The threads that send the query to the replicas
+/// share the same list, so the hints they store also reach the client.
+#[derive(Debug, Clone, Default)]
+pub struct Warnings {
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl Warnings {
+    pub fn warn(&self, message: &str) {
+        let mut messages = self
+            .messages
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !messages.iter().any(|warning| warning == message) {
+            messages.push(message.to_string());
+        }
+    }
+
+    fn messages(&self) -> Vec<String> {
+        self.messages
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Warnings>> = const { RefCell::new(None) };
+}
+
+/// starts collecting the warnings of the request handled by the current thread
+pub fn begin() {
+    attach(Warnings::default());
+}
+
+/// makes the current thread add its warnings to those of a request, used by the threads it spawns
+pub fn attach(warnings: Warnings) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(warnings));
+}
+
+pub fn current() -> Option<Warnings> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// adds a warning to the current request, outside of a request it is discarded
+pub fn warn(message: &str) {
+    CURRENT.with(|current| {
+        if let Some(warnings) = current.borrow().as_ref() {
+            warnings.warn(message)
+        }
+    });
+}
+
+/// stops collecting and returns the warnings of the request
+pub fn end() -> Vec<String> {
+    CURRENT
+        .with(|current| current.borrow_mut().take())
+        .map(|warnings| warnings.messages())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_warnings_outside_a_request_are_discarded() {
+        warn("Hint stored for 10.0.0.3");
+        assert!(end().is_empty());
+    }
+
+    #[test]
+    fn test_spawned_threads_share_the_warnings() {
+        begin();
+        warn("Read repair performed on 2 replicas");
+        let warnings = current().unwrap();
+        thread::spawn(move || {
+            attach(warnings);
+            warn("Hint stored for 10.0.0.3");
+            warn("Hint stored for 10.0.0.3");
+        })
+        .join()
+        .unwrap();
+        assert_eq!(
+            end(),
+            vec![
+                "Read repair performed on 2 replicas",
+                "Hint stored for 10.0.0.3"
+            ]
+        );
+        assert!(current().is_none());
+    }
+}
@@ -8,11 +8,13 @@ use crate::queries::if_clause::IfClause;
 use crate::queries::order_by_clause::OrderByClause;
 use crate::queries::set_logic::assigmente_value::AssignmentValue;
 use crate::queries::where_logic::where_clause::WhereClause;
+use crate::read_reparation::row_comparer::RowComparer;
 use crate::utils::constants::DATA_ACCESS_PATH;
 use crate::utils::errors::Errors;
 use crate::utils::errors::Errors::{Invalid, ServerError};
 use crate::utils::functions::{get_int_from_string, serialize_to_string, write_all_to_file};
 use crate::utils::parser_constants::ASC;
+use crate::utils::types::timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -24,7 +26,7 @@ pub struct DataAccess;
 
 impl DataAccess {
     fn create_file(&self, path: &String) -> Result<(), Errors> {
-        fs::create_dir_all(DATA_ACCESS_PATH).map_err(|e| ServerError(e.to_string()))?;
+        fs::create_dir_all(DATA_ACCESS_PATH.as_str()).map_err(|e| ServerError(e.to_string()))?;
         let mut file =
             File::create(path).map_err(|_| ServerError(String::from("Could not create file")))?;
         write_all_to_file(&mut file, b"[]")
@@ -50,13 +52,13 @@ impl DataAccess {
     /// let table_name = "keyspace.table";
     /// data_access.truncate_table(&table_name);
     pub fn truncate_table(&self, table_name: &String) -> Result<(), Errors> {
-        fs::create_dir_all(DATA_ACCESS_PATH).map_err(|e| ServerError(e.to_string()))?;
-        let _file = OpenOptions::new()
+        fs::create_dir_all(DATA_ACCESS_PATH.as_str()).map_err(|e| ServerError(e.to_string()))?;
+        let mut file = OpenOptions::new()
             .write(true)
             .truncate(true)
             .open(self.get_file_path(table_name))
             .map_err(|_| ServerError(String::from("Could not open file")))?;
-        Ok(())
+        write_all_to_file(&mut file, b"[]")
     }
 
     /// eliminates the table_name (keyspace.table) given
@@ -64,7 +66,7 @@ impl DataAccess {
     /// let table_name = "keyspace.table";
     /// data_access.drop_table(&table_name);
     pub fn drop_table(&self, table_name: String) -> Result<(), Errors> {
-        fs::create_dir_all(DATA_ACCESS_PATH).map_err(|e| ServerError(e.to_string()))?;
+        fs::create_dir_all(DATA_ACCESS_PATH.as_str()).map_err(|e| ServerError(e.to_string()))?;
         remove_file(self.get_file_path(&table_name))
             .map_err(|_| ServerError(String::from("Could not remove file")))?;
         Ok(())
@@ -82,8 +84,14 @@ impl DataAccess {
         Ok(true)
     }
 
-    /// Updates a row in the table. If the primary key does not exist, it does nothing.
-    pub fn simple_update_row(&self, table_name: &String, new_row: &Row) -> Result<bool, Errors> {
+    /// Updates a row in the table, removing the values of the null columns. If the primary key
+    /// does not exist, it does nothing.
+    pub fn simple_update_row(
+        &self,
+        table_name: &String,
+        new_row: &Row,
+        null_columns: &[String],
+    ) -> Result<bool, Errors> {
         let path = self.get_file_path(table_name);
         let temp_path = format!("{}.tmp", path);
         self.create_file(&temp_path)?;
@@ -91,8 +99,11 @@ impl DataAccess {
         let mut updated = false;
         for mut row in self.get_deserialized_stream(&path)? {
             if new_row.primary_key == row.primary_key {
-                let changes = new_row.get_row_hash_assigment();
-                row = self.build_updated_row(&row, &changes)?;
+                let mut changes = new_row.get_row_hash_assigment();
+                for column in null_columns {
+                    changes.insert(column.clone(), AssignmentValue::Null);
+                }
+                row = self.build_updated_row(&row, &changes, &new_row.timestamp())?;
                 updated = true;
             }
             self.append_row(&temp_path, &row)?;
@@ -103,8 +114,13 @@ impl DataAccess {
     }
 
     /// Inserts a new row into the table. If the primary key already exists, it updates the row.
-    pub fn insert_or_update(&self, table_name: &String, new_row: &Row) -> Result<(), Errors> {
-        let updated = self.simple_update_row(table_name, new_row)?;
+    pub fn insert_or_update(
+        &self,
+        table_name: &String,
+        new_row: &Row,
+        null_columns: &[String],
+    ) -> Result<(), Errors> {
+        let updated = self.simple_update_row(table_name, new_row, null_columns)?;
         if !updated {
             let path = self.get_file_path(table_name);
             self.append_row(&path, new_row)?;
@@ -112,6 +128,37 @@ impl DataAccess {
         Ok(())
     }
 
+    /// Merges rows coming from another replica into the table, each one is compared with the
+    /// stored row of the same primary key keeping the newest values. Rows not stored are appended
+    pub fn merge_rows(&self, table_name: &String, rows: Vec<Row>) -> Result<(), Errors> {
+        let path = self.get_file_path(table_name);
+        let temp_path = format!("{}.tmp", path);
+        self.create_file(&temp_path)?;
+
+        let mut incoming: HashMap<Vec<String>, Row> = rows
+            .into_iter()
+            .map(|row| (row.primary_key.clone(), row))
+            .collect();
+        for row in self.get_deserialized_stream(&path)? {
+            match incoming.remove(&row.primary_key) {
+                Some(new_row) => {
+                    self.append_row(&temp_path, &RowComparer::compare_row(&row, &new_row))?
+                }
+                None => self.append_row(&temp_path, &row)?,
+            }
+        }
+        for row in incoming.values() {
+            self.append_row(&temp_path, row)?;
+        }
+
+        rename(temp_path, path).map_err(|_| ServerError(String::from("Error renaming file")))
+    }
+
+    /// every row stored in the table, deleted ones included
+    pub fn get_table_rows(&self, table_name: &String) -> Result<Vec<Row>, Errors> {
+        self.get_rows(&self.get_file_path(table_name))
+    }
+
     /// sets de rows that matches the where clause to deleted
     ///
     ///  If the if clause is provided, returns true if the row was deleted and false otherwise.
@@ -121,6 +168,7 @@ impl DataAccess {
         table_name: &String,
         where_clause: &WhereClause,
         if_clause: &Option<IfClause>,
+        timestamp: &Timestamp,
     ) -> Result<Option<bool>, Errors> {
         let path = self.get_file_path(table_name);
         let temp_path = format!("{}.tmp", path);
@@ -134,13 +182,13 @@ impl DataAccess {
                     if if_clause.evaluate(&row.get_row_hash())? {
                         applied = Some(true);
                         erase = row.is_deleted();
-                        row.set_deleted();
+                        row.set_deleted(timestamp);
                     } else {
                         applied = Some(false);
                     }
                 } else {
                     erase = row.is_deleted();
-                    row.set_deleted();
+                    row.set_deleted(timestamp);
                 }
             }
             if !erase {
@@ -160,6 +208,7 @@ impl DataAccess {
         changes: &HashMap<String, AssignmentValue>,
         where_clause: &WhereClause,
         if_clause: &Option<IfClause>,
+        timestamp: &Timestamp,
     ) -> Result<Option<bool>, Errors> {
         let path = self.get_file_path(table_name);
         let temp_path = format!("{}.tmp", path);
@@ -171,12 +220,12 @@ impl DataAccess {
                 if let Some(if_clause) = if_clause {
                     if if_clause.evaluate(&row.get_row_hash())? {
                         applied = Some(true);
-                        row = self.build_updated_row(&row, changes)?;
+                        row = self.build_updated_row(&row, changes, timestamp)?;
                     } else {
                         applied = Some(false);
                     }
                 } else {
-                    row = self.build_updated_row(&row, changes)?;
+                    row = self.build_updated_row(&row, changes, timestamp)?;
                 }
             }
             self.append_row(&temp_path, &row)?;
@@ -189,18 +238,23 @@ impl DataAccess {
         &self,
         row: &Row,
         changes: &HashMap<String, AssignmentValue>,
+        timestamp: &Timestamp,
     ) -> Result<Row, Errors> {
         let mut new_columns = Vec::new();
         for column in &row.columns {
-            if !changes.contains_key(&column.column_name) {
-                new_columns.push(Column::new_from_column(column))
-            } else {
-                new_columns.push(Column::new_from_column(
-                    &self.get_updated_column(row, changes, column)?,
-                ))
+            match changes.get(&column.column_name) {
+                None => new_columns.push(Column::new_from_column(column)),
+                Some(AssignmentValue::Null) => {}
+                Some(_) => {
+                    let mut updated = self.get_updated_column(row, changes, column)?;
+                    updated.timestamp = Timestamp::new_from_timestamp(timestamp);
+                    new_columns.push(updated)
+                }
             }
         }
-        Ok(Row::new(new_columns, Vec::from(row.primary_key.as_slice())))
+        let mut updated_row = Row::new(new_columns, Vec::from(row.primary_key.as_slice()));
+        updated_row.set_timestamp(Timestamp::new_from_timestamp(timestamp));
+        Ok(updated_row)
     }
 
     fn get_updated_column(
@@ -342,11 +396,11 @@ impl DataAccess {
     }
 
     fn get_file_path(&self, table_name: &String) -> String {
-        format!("{}{}.json", DATA_ACCESS_PATH, table_name)
+        format!("{}{}.json", DATA_ACCESS_PATH.as_str(), table_name)
     }
 
     fn open_file(&self, path: &String) -> Result<File, Errors> {
-        fs::create_dir_all(DATA_ACCESS_PATH).map_err(|e| ServerError(e.to_string()))?;
+        fs::create_dir_all(DATA_ACCESS_PATH.as_str()).map_err(|e| ServerError(e.to_string()))?;
         let file = OpenOptions::new()
             .read(true)
             .write(true)
@@ -545,7 +599,13 @@ mod tests {
             literal,
         ));
 
-        let result = data_access.update_row(&table_name, &get_assignment(), &where_clause, &None);
+        let result = data_access.update_row(
+            &table_name,
+            &get_assignment(),
+            &where_clause,
+            &None,
+            &Timestamp::new(),
+        );
         assert!(result.is_ok());
         let table_path = data_access.get_file_path(&table_name);
         let file_content = read_to_string(&table_path).unwrap();
@@ -583,4 +643,30 @@ mod tests {
         assert_eq!(selected_rows[0], row1);
         remove_file(data_access.get_file_path(&table_name)).unwrap();
     }
+
+    #[test]
+    fn test_merge_rows_keeps_the_newest_values() {
+        let _lock = TABLE_MUTEX.lock();
+        let data_access = DataAccess {};
+        let table_name = get_unique_table_name();
+        data_access.create_table(&table_name).unwrap();
+        data_access.insert(&table_name, &get_row1()).unwrap();
+
+        let mut newer = get_row1();
+        newer.columns[0].value.value = "Jane".to_string();
+        newer.columns[0].timestamp = Timestamp::new_from_i64(2000);
+        let mut older = get_row1();
+        older.columns[0].value.value = "Jim".to_string();
+        older.columns[0].timestamp = Timestamp::new_from_i64(1000);
+        data_access
+            .merge_rows(&table_name, vec![newer, get_row3()])
+            .unwrap();
+        data_access.merge_rows(&table_name, vec![older]).unwrap();
+
+        let rows = data_access.get_table_rows(&table_name).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].columns[0].value.value, "Jane");
+        assert_eq!(rows[1].primary_key, get_row3().primary_key);
+        remove_file(data_access.get_file_path(&table_name)).unwrap();
+    }
 }
@@ -32,8 +32,8 @@ impl Row {
         }
     }
 
-    pub fn set_deleted(&mut self) {
-        self.timestamp = Timestamp::new();
+    pub fn set_deleted(&mut self, timestamp: &Timestamp) {
+        self.timestamp = Timestamp::new_from_timestamp(timestamp);
         self.deleted = true;
     }
 
@@ -0,0 +1,259 @@
+use crate::meta_data::nodes::node::State;
+use crate::utils::errors::Errors;
+use crate::utils::response::Response;
+use crate::utils::types::node_ip::NodeIp;
+use crate::utils::types_to_bytes::TypesToBytes;
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::{Mutex, MutexGuard};
+
+pub const TOPOLOGY_CHANGE: &str = "TOPOLOGY_CHANGE";
+pub const STATUS_CHANGE: &str = "STATUS_CHANGE";
+pub const SCHEMA_CHANGE: &str = "SCHEMA_CHANGE";
+
+/// Event types a connection can register to with a REGISTER.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum EventType {
+    TopologyChange,
+    StatusChange,
+    SchemaChange,
+}
+
+impl EventType {
+    pub fn from_name(name: &str) -> Result<EventType, Errors> {
+        match name {
+            TOPOLOGY_CHANGE => Ok(EventType::TopologyChange),
+            STATUS_CHANGE => Ok(EventType::StatusChange),
+            SCHEMA_CHANGE => Ok(EventType::SchemaChange),
+            _ => Err(Errors::ProtocolError(format!(
+                "Invalid event type {}, expected one of {}, {} or {}",
+                name, TOPOLOGY_CHANGE, STATUS_CHANGE, SCHEMA_CHANGE
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventType::TopologyChange => TOPOLOGY_CHANGE,
+            EventType::StatusChange => STATUS_CHANGE,
+            EventType::SchemaChange => SCHEMA_CHANGE,
+        }
+    }
+}
+
+/// Registered connection: the published events are queued until the thread of the connection
+/// takes them and writes them as EVENT frames.
+struct Subscriber {
+    event_types: HashSet<EventType>,
+    pending: Vec<Vec<u8>>,
+}
+
+static SUBSCRIBERS: Mutex<Vec<(String, Subscriber)>> = Mutex::new(Vec::new());
+
+fn subscribers() -> MutexGuard<'static, Vec<(String, Subscriber)>> {
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// registers the connection for the given event types, a second REGISTER adds to the previous ones
+pub fn subscribe(connection_id: &str, event_types: &[EventType]) {
+    let mut subscribers = subscribers();
+    if let Some((_, subscriber)) = subscribers.iter_mut().find(|(id, _)| id == connection_id) {
+        subscriber.event_types.extend(event_types.iter().copied());
+        return;
+    }
+    subscribers.push((
+        connection_id.to_string(),
+        Subscriber {
+            event_types: event_types.iter().copied().collect(),
+            pending: Vec::new(),
+        },
+    ));
+}
+
+pub fn unsubscribe(connection_id: &str) {
+    subscribers().retain(|(id, _)| id != connection_id);
+}
+
+/// returns the event bodies published since the last call for the connection
+pub fn take_events(connection_id: &str) -> Vec<Vec<u8>> {
+    subscribers()
+        .iter_mut()
+        .find(|(id, _)| id == connection_id)
+        .map(|(_, subscriber)| std::mem::take(&mut subscriber.pending))
+        .unwrap_or_default()
+}
+
+/// queues the event body in every connection registered to its type
+pub fn publish(event_type: EventType, body: Vec<u8>) {
+    for (_, subscriber) in subscribers().iter_mut() {
+        if subscriber.event_types.contains(&event_type) {
+            subscriber.pending.push(body.clone());
+        }
+    }
+}
+
+/// Publishes the events that match the change of state of a node.
+///
+/// A node is UP only if it is active or still serving while it leaves. If it becomes
+/// ShuttingDown it also leaves the ring, so a REMOVED_NODE is published too.
+pub fn publish_node_transition(previous: &State, current: &State, ip: &NodeIp) {
+    if previous == current {
+        return;
+    }
+    let is_serving = |state: &State| matches!(state, State::Active | State::Leaving);
+    let (was_up, is_up) = (is_serving(previous), is_serving(current));
+    if was_up != is_up {
+        let change = if is_up { "UP" } else { "DOWN" };
+        publish_node_event(EventType::StatusChange, change, ip);
+    }
+    if current == &State::ShuttingDown {
+        publish_node_event(EventType::TopologyChange, "REMOVED_NODE", ip);
+    }
+}
+
+pub fn publish_new_node(ip: &NodeIp) {
+    publish_node_event(EventType::TopologyChange, "NEW_NODE", ip);
+}
+
+/// publishes the body of a SCHEMA_CHANGE result: `<change_type><target><options>`
+pub fn publish_schema_change(schema_change: &[u8]) {
+    if let Ok(mut body) = event_header(EventType::SchemaChange) {
+        body.write_bytes(schema_change);
+        publish(EventType::SchemaChange, body.into_bytes());
+    }
+}
+
+/// Publishes the change if the result of a query is a SCHEMA_CHANGE. Every node publishes the
+/// changes it applies to its own schema, so clients registered on any node get them
+pub fn publish_if_schema_change(result: &[u8]) {
+    if let Some(schema_change) = Response::schema_change_body(result) {
+        publish_schema_change(schema_change);
+    }
+}
+
+fn publish_node_event(event_type: EventType, change: &str, ip: &NodeIp) {
+    if let Ok(body) = node_event_body(event_type, change, ip) {
+        publish(event_type, body);
+    }
+}
+
+/// `<event_type><change><inet>` where inet is `<n: byte><address: n bytes><port: int>`
+fn node_event_body(event_type: EventType, change: &str, ip: &NodeIp) -> Result<Vec<u8>, Errors> {
+    let mut body = event_header(event_type)?;
+    body.write_string(change)?;
+    let address = match ip.get_std_socket().ip() {
+        IpAddr::V4(address) => address.octets().to_vec(),
+        IpAddr::V6(address) => address.octets().to_vec(),
+    };
+    body.write_u8(address.len() as u8)?;
+    body.write_bytes(&address);
+    body.write_int(ip.get_port() as i32)?;
+    Ok(body.into_bytes())
+}
+
+fn event_header(event_type: EventType) -> Result<TypesToBytes, Errors> {
+    let mut body = TypesToBytes::default();
+    body.write_string(event_type.name())?;
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::types::bytes_cursor::BytesCursor;
+
+    fn node_ip() -> NodeIp {
+        NodeIp::new_from_string("10.9.8.7", 9042).unwrap()
+    }
+
+    /// the nodes of other tests also publish events, the ones of this node are filtered
+    fn take_own_events(connection_id: &str) -> Vec<Vec<u8>> {
+        let inet = [4, 10, 9, 8, 7, 0, 0, 0x23, 0x52];
+        take_events(connection_id)
+            .into_iter()
+            .filter(|event| event.ends_with(&inet))
+            .collect()
+    }
+
+    #[test]
+    fn test_event_type_from_name() {
+        assert_eq!(
+            EventType::from_name("STATUS_CHANGE").unwrap(),
+            EventType::StatusChange
+        );
+        assert!(EventType::from_name("KEYSPACE_CHANGE").is_err());
+    }
+
+    #[test]
+    fn test_only_registered_types_are_delivered() {
+        subscribe("event_bus_types", &[EventType::StatusChange]);
+        publish_new_node(&node_ip());
+        publish_node_transition(&State::Inactive, &State::Active, &node_ip());
+        let events = take_own_events("event_bus_types");
+        unsubscribe("event_bus_types");
+
+        assert_eq!(events.len(), 1);
+        let mut cursor = BytesCursor::new(&events[0]);
+        assert_eq!(cursor.read_string().unwrap(), STATUS_CHANGE);
+        assert_eq!(cursor.read_string().unwrap(), "UP");
+        assert_eq!(cursor.read_u8().unwrap(), 4);
+        assert_eq!(cursor.read_exact(4).unwrap(), vec![10, 9, 8, 7]);
+        assert_eq!(cursor.read_int().unwrap(), 9042);
+    }
+
+    #[test]
+    fn test_transitions_without_liveness_change_are_ignored() {
+        subscribe("event_bus_liveness", &[EventType::StatusChange]);
+        publish_node_transition(&State::Inactive, &State::Booting, &node_ip());
+        publish_node_transition(&State::Active, &State::Active, &node_ip());
+        assert!(take_own_events("event_bus_liveness").is_empty());
+        unsubscribe("event_bus_liveness");
+    }
+
+    #[test]
+    fn test_shutting_down_removes_the_node() {
+        subscribe(
+            "event_bus_removed",
+            &[EventType::StatusChange, EventType::TopologyChange],
+        );
+        publish_node_transition(&State::Active, &State::ShuttingDown, &node_ip());
+        let events = take_own_events("event_bus_removed");
+        unsubscribe("event_bus_removed");
+
+        assert_eq!(events.len(), 2);
+        let mut cursor = BytesCursor::new(&events[1]);
+        assert_eq!(cursor.read_string().unwrap(), TOPOLOGY_CHANGE);
+        assert_eq!(cursor.read_string().unwrap(), "REMOVED_NODE");
+    }
+
+    #[test]
+    fn test_schema_change_body() {
+        subscribe("event_bus_schema", &[EventType::SchemaChange]);
+        let mut change = TypesToBytes::default();
+        change.write_string("CREATED").unwrap();
+        change.write_string("KEYSPACE").unwrap();
+        change.write_string("ks_event_bus").unwrap();
+        publish_schema_change(&change.into_bytes());
+        let events: Vec<Vec<u8>> = take_events("event_bus_schema")
+            .into_iter()
+            .filter(|event| event.ends_with(b"ks_event_bus"))
+            .collect();
+        unsubscribe("event_bus_schema");
+
+        let mut cursor = BytesCursor::new(&events[0]);
+        assert_eq!(cursor.read_string().unwrap(), SCHEMA_CHANGE);
+        assert_eq!(cursor.read_string().unwrap(), "CREATED");
+        assert_eq!(cursor.read_string().unwrap(), "KEYSPACE");
+        assert_eq!(cursor.read_string().unwrap(), "ks_event_bus");
+    }
+
+    #[test]
+    fn test_unsubscribed_connection_gets_nothing() {
+        subscribe("event_bus_unsubscribed", &[EventType::TopologyChange]);
+        unsubscribe("event_bus_unsubscribed");
+        publish_new_node(&node_ip());
+        assert!(take_events("event_bus_unsubscribed").is_empty());
+    }
+}
@@ -1,40 +1,56 @@
 use crate::auth::authenticator::Authenticator;
+use crate::auth::sasl::{self, SaslStep};
 use crate::executables::executable::Executable;
+use crate::meta_data::clients::meta_data_client::ClientMetaDataAcces;
 use crate::response_builders::frame_builder::FrameBuilder;
 use crate::utils::errors::Errors;
-use crate::utils::errors::Errors::BadCredentials;
-use crate::utils::parser_constants::AUTH_SUCCESS;
+use crate::utils::parser_constants::{AUTH_CHALLENGE, AUTH_SUCCESS};
 use crate::utils::types::frame::Frame;
+use crate::utils::types_to_bytes::TypesToBytes;
 
 pub struct AuthResponseExecutable {
-    user: String,
-    password: String,
+    token: Vec<u8>,
     authenticator: Box<dyn Authenticator>,
 }
 
 impl AuthResponseExecutable {
-    pub fn new(
-        user: String,
-        password: String,
-        authenticator: Box<dyn Authenticator>,
-    ) -> AuthResponseExecutable {
+    pub fn new(token: Vec<u8>, authenticator: Box<dyn Authenticator>) -> AuthResponseExecutable {
         AuthResponseExecutable {
-            user,
-            password,
+            token,
             authenticator,
         }
     }
+
+    /// `[bytes]` body of AUTH_CHALLENGE and AUTH_SUCCESS, a missing token is sent as null
+    fn token_body(token: Option<Vec<u8>>) -> Result<Vec<u8>, Errors> {
+        let mut body = TypesToBytes::default();
+        match token {
+            Some(token) => {
+                body.write_int(token.len() as i32)?;
+                body.write_bytes(&token);
+            }
+            None => body.write_int(-1)?,
+        }
+        Ok(body.into_bytes())
+    }
 }
 
 impl Executable for AuthResponseExecutable {
+    /// continues the SASL negotiation of the connection, or starts it on the first AUTH_RESPONSE
     fn execute(&mut self, request: Frame) -> Result<Frame, Errors> {
-        let user = self.user.to_string();
-        let password = self.password.to_string();
-        let ok = self.authenticator.validate_credentials(user, password)?;
-        if !ok {
-            return Err(BadCredentials(String::from("Invalid credentials provided")));
+        let connection_id = ClientMetaDataAcces::connection_id();
+        let mut negotiator = sasl::take_session(&connection_id)
+            .unwrap_or_else(|| self.authenticator.new_sasl_negotiator());
+        match negotiator.evaluate_response(&self.token)? {
+            SaslStep::Challenge(challenge) => {
+                sasl::store_session(&connection_id, negotiator);
+                let body = Self::token_body(Some(challenge))?;
+                FrameBuilder::build_response_frame(request, AUTH_CHALLENGE, body)
+            }
+            SaslStep::Complete(token) => {
+                FrameBuilder::build_response_frame(request, AUTH_SUCCESS, Self::token_body(token)?)
+            }
         }
-        FrameBuilder::build_response_frame(request, AUTH_SUCCESS, Vec::new())
     }
 }
 
@@ -44,6 +60,7 @@ mod tests {
     const VALID_PASS: &str = "my_pass";
     const INVALID_PASS: &str = "invalid_pass";
     const INVALID_USER: &str = "invalid_user";
+    const NULL_TOKEN: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
 
     struct AuthenticatorMock;
     impl AuthenticatorMock {
@@ -53,46 +70,40 @@ mod tests {
     }
 
     impl Authenticator for AuthenticatorMock {
-        fn validate_credentials(&self, user: String, password: String) -> Result<bool, Errors> {
-            if user == "my_usr" && password == "my_pass" {
-                return Ok(true);
-            }
-            Ok(false)
+        fn class_name(&self) -> &'static str {
+            "AuthenticatorMock"
+        }
+
+        fn new_sasl_negotiator(&self) -> Box<dyn SaslNegotiator> {
+            Box::new(PlainTextNegotiator::new(Box::new(|user, password| {
+                Ok(user == "my_usr" && password == "my_pass")
+            })))
         }
     }
 
-    fn get_body_as_vec(usr: &str, pass: &str) -> Vec<u8> {
-        let usr_pass = format!("{}:{}", usr, pass);
-        let usr_pass_as_bytes = usr_pass.as_bytes();
-        let size = (usr_pass.len() as i32).to_be_bytes();
-        let mut body = Vec::new();
-        body.extend_from_slice(&size);
-        body.extend_from_slice(usr_pass_as_bytes);
-        body
+    fn plain_token(usr: &str, pass: &str) -> Vec<u8> {
+        format!("\0{}\0{}", usr, pass).into_bytes()
     }
 
-    fn build_request_with(user: &str, pass: &str) -> Frame {
-        let body = get_body_as_vec(user, pass);
-        let size = body.len() as u32;
+    fn build_request() -> Frame {
         Frame {
             version: 0x03,
             flags: 0x00,
             stream: 0x01,
-            opcode: AUTH_CHALLENGE,
-            length: size,
-            body,
+            opcode: AUTH_RESPONSE,
+            length: 0,
+            body: Vec::new(),
         }
     }
 
-    fn assert_ok(user: &str, pass: &str, expected_opcode: u8, expected_body: Vec<u8>) {
-        let request = build_request_with(user, pass);
+    fn execute(token: Vec<u8>) -> Result<Frame, Errors> {
         let authenticator = AuthenticatorMock::new();
-        let mut executable = AuthResponseExecutable::new(
-            user.to_string(),
-            pass.to_string(),
-            Box::new(authenticator),
-        );
-        let response = executable.execute(request);
+        let mut executable = AuthResponseExecutable::new(token, Box::new(authenticator));
+        executable.execute(build_request())
+    }
+
+    fn assert_ok(user: &str, pass: &str, expected_opcode: u8, expected_body: Vec<u8>) {
+        let response = execute(plain_token(user, pass));
         assert!(response.is_ok());
         let frame = response.unwrap();
         assert_eq!(frame.opcode, expected_opcode);
@@ -100,23 +111,16 @@ mod tests {
     }
 
     fn assert_err(user: &str, pass: &str) {
-        let request = build_request_with(user, pass);
-        let authenticator = AuthenticatorMock::new();
-        let mut executable = AuthResponseExecutable::new(
-            user.to_string(),
-            pass.to_string(),
-            Box::new(authenticator),
-        );
-        let response = executable.execute(request);
+        let response = execute(plain_token(user, pass));
         assert!(response.is_err());
     }
 
     use super::*;
-    use crate::utils::parser_constants::AUTH_CHALLENGE;
+    use crate::auth::sasl::{PlainTextNegotiator, SaslNegotiator};
+    use crate::utils::parser_constants::AUTH_RESPONSE;
     #[test]
     fn test_01_successfull_response() {
-        // En este test debería cambiar el body con los tokens si no me equivoco
-        assert_ok(VALID_USER, VALID_PASS, AUTH_SUCCESS, Vec::new());
+        assert_ok(VALID_USER, VALID_PASS, AUTH_SUCCESS, NULL_TOKEN.to_vec());
     }
 
     #[test]
@@ -133,4 +137,13 @@ mod tests {
     fn test_04_unsuccessfull_response_with_invalid_user_and_password() {
         assert_err(INVALID_USER, INVALID_PASS);
     }
+
+    #[test]
+    fn test_05_empty_response_gets_a_challenge() {
+        let challenge = execute(Vec::new()).unwrap();
+        assert_eq!(challenge.opcode, AUTH_CHALLENGE);
+        assert_eq!(challenge.body, vec![0x00, 0x00, 0x00, 0x00]);
+        // the negotiation continues on the next AUTH_RESPONSE of the same connection
+        assert!(execute(Vec::new()).is_err());
+    }
 }
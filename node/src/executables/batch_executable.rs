@@ -1,12 +1,37 @@
+use crate::batchlog::handler::BatchlogHandler;
 use crate::executables::executable::Executable;
+use crate::queries::batch_query::BatchQuery;
+use crate::queries::query::Query;
+use crate::response_builders::frame_builder::FrameBuilder;
+use crate::utils::consistency_level::ConsistencyLevel;
 use crate::utils::errors::Errors;
-use crate::utils::errors::Errors::ServerError;
+use crate::utils::parser_constants::RESULT;
+use crate::utils::response::Response;
 use crate::utils::types::frame::Frame;
 
-pub struct BatchExecutable;
+pub struct BatchExecutable {
+    batch: BatchQuery,
+    consistency_integer: i16,
+}
+
+impl BatchExecutable {
+    pub fn new(batch: BatchQuery, consistency_integer: i16) -> Self {
+        Self {
+            batch,
+            consistency_integer,
+        }
+    }
+}
 
 impl Executable for BatchExecutable {
-    fn execute(&mut self, _request: Frame) -> Result<Frame, Errors> {
-        Err(ServerError(String::from("Batch is not implemented.")))
+    fn execute(&mut self, request: Frame) -> Result<Frame, Errors> {
+        self.batch.set_table()?;
+        BatchlogHandler::execute(
+            self.batch.clone(),
+            ConsistencyLevel::from_i16(self.consistency_integer)?,
+        )?;
+        let response_frame =
+            FrameBuilder::build_response_frame(request, RESULT, Response::void()?)?;
+        Ok(response_frame)
     }
 }
@@ -1,12 +1,14 @@
 use crate::executables::executable::Executable;
 use crate::utils::errors::Errors;
-use crate::utils::errors::Errors::ServerError;
 use crate::utils::types::frame::Frame;
 
+/// EVENT is only sent by the server, with stream -1, to the registered connections.
 pub struct EventExecutable;
 
 impl Executable for EventExecutable {
     fn execute(&mut self, _request: Frame) -> Result<Frame, Errors> {
-        Err(ServerError(String::from("Event is not implemented.")))
+        Err(Errors::ProtocolError(String::from(
+            "EVENT is a server message, clients must send REGISTER instead",
+        )))
     }
 }
@@ -5,6 +5,7 @@ pub mod executable;
 pub mod execute_executable;
 pub mod options_executable;
 pub mod prepare_executable;
+pub mod prepared_statement;
 pub mod query_executable;
 pub mod register_executable;
 pub mod startup_executable;
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use crate::executables::executable::Executable;
 use crate::response_builders::frame_builder::FrameBuilder;
 use crate::utils::config_constants::{COMPRESSION, CQL_VERSION};
@@ -7,7 +5,6 @@ use crate::utils::errors::Errors;
 use crate::utils::parser_constants::SUPPORTED;
 use crate::utils::types::frame::Frame;
 
-const ARGUMENTS_QUANTITY: u8 = 0x02;
 pub struct OptionsExecutable {}
 
 impl Default for OptionsExecutable {
@@ -21,12 +18,14 @@ impl OptionsExecutable {
         OptionsExecutable {}
     }
 
-    fn get_options(&self) -> HashMap<String, String> {
-        let mut options = HashMap::<String, String>::new();
-        options.insert("CQL_VERSION".to_string(), CQL_VERSION.to_string());
-        options.insert("COMPRESSION".to_string(), COMPRESSION.to_string());
-
-        options
+    fn get_options(&self) -> Vec<(String, Vec<String>)> {
+        vec![
+            ("CQL_VERSION".to_string(), vec![CQL_VERSION.to_string()]),
+            (
+                "COMPRESSION".to_string(),
+                COMPRESSION.iter().map(|name| name.to_string()).collect(),
+            ),
+        ]
     }
 
     fn extend_multimap_with_string(&self, string_multimap: &mut Vec<u8>, element: &String) {
@@ -34,14 +33,18 @@ impl OptionsExecutable {
         string_multimap.extend_from_slice(element.as_bytes());
     }
 
+    /// `<n: short>` followed by n `<string><string list>` pairs
     fn get_string_multimap(&self) -> Vec<u8> {
         let mut string_multimap = Vec::new();
         let options = self.get_options();
-        string_multimap.extend_from_slice((ARGUMENTS_QUANTITY as u16).to_be_bytes().as_ref());
+        string_multimap.extend_from_slice((options.len() as u16).to_be_bytes().as_ref());
 
-        for (key, value) in options.iter() {
+        for (key, values) in options.iter() {
             self.extend_multimap_with_string(&mut string_multimap, key);
-            self.extend_multimap_with_string(&mut string_multimap, value);
+            string_multimap.extend_from_slice((values.len() as u16).to_be_bytes().as_ref());
+            for value in values {
+                self.extend_multimap_with_string(&mut string_multimap, value);
+            }
         }
 
         string_multimap
@@ -62,7 +65,7 @@ mod tests {
     const EXPECTED_FLAGS: u8 = 0x00;
     const EXPECTED_STREAM: i16 = 0x01;
     const EXPECTED_OPCODE: u8 = 0x06;
-    const EXPECTED_LENGTH: u32 = 40;
+    const EXPECTED_LENGTH: u32 = 52;
     use super::*;
 
     fn setup() -> (OptionsExecutable, Frame) {
@@ -0,0 +1,552 @@
+use crate::meta_data::keyspaces::user_type::UserType;
+use crate::meta_data::meta_data_handler::{use_client_meta_data, use_keyspace_meta_data};
+use crate::parsers::query_parser::{query_lexer, query_parser};
+use crate::parsers::tokens::data_type::DataType;
+use crate::parsers::tokens::literal::Literal;
+use crate::parsers::tokens::terms::Term;
+use crate::parsers::tokens::token::Token;
+use crate::queries::query::{Query, QueryEnum};
+use crate::queries::set_logic::assigmente_value::AssignmentValue;
+use crate::utils::bound_values::decode_value;
+use crate::utils::constants::{ASTERIK, CLIENT_METADATA_PATH, KEYSPACE_METADATA_PATH};
+use crate::utils::errors::Errors;
+use crate::utils::functions::{
+    bytes_to_hex, check_table_name, get_columns_from_table, get_table_pk, get_table_primary_key,
+    split_keyspace_table,
+};
+use crate::utils::parser_constants::{INTO, JSON, VALUES};
+use crate::utils::response::{get_header_data_type, Response};
+use crate::utils::types::query_parameters::{BoundValue, BoundValues};
+use crate::utils::types::token_conversor::is_keyword;
+use md5::{Digest, Md5};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::vec::IntoIter;
+
+/// Name of the column of an `INSERT ... JSON ?` and of the result of a `SELECT JSON`.
+const JSON_COLUMN: &str = "[json]";
+
+/// In-memory cache of the statements prepared on this node, by id.
+static STATEMENTS: Mutex<BTreeMap<Vec<u8>, PreparedStatement>> = Mutex::new(BTreeMap::new());
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BindVariable {
+    pub name: String,
+    pub column: Option<String>,
+    pub data_type: DataType,
+}
+
+/// Prepared statement: the original query with its bind markers and the metadata returned to
+/// the client.
+///
+/// The id is the MD5 of the keyspace in use and the query, so preparing the same query on
+/// another node returns the same id and drivers can prepare it again after an `Unprepared`.
+#[derive(Clone, Debug)]
+pub struct PreparedStatement {
+    pub id: Vec<u8>,
+    query: String,
+    table_name: Option<String>,
+    bind_variables: Vec<BindVariable>,
+    result_columns: Option<Vec<(String, DataType)>>,
+}
+
+impl PreparedStatement {
+    /// prepares the query, reusing the cached statement if it was already prepared
+    pub fn prepare(query: String) -> Result<Self, Errors> {
+        let id = statement_id(&query)?;
+        if let Some(statement) = lock_statements()?.get(&id) {
+            return Ok(statement.clone());
+        }
+        let statement = Self::new(id, query)?;
+        lock_statements()?.insert(statement.id.clone(), statement.clone());
+        Ok(statement)
+    }
+
+    /// parses a query sent with its values in a QUERY request. It is not cached, since it is
+    /// only bound once
+    pub fn parse(query: String) -> Result<Self, Errors> {
+        Self::new(statement_id(&query)?, query)
+    }
+
+    /// returns the statement prepared with the given id
+    pub fn get(id: &[u8]) -> Result<Self, Errors> {
+        match lock_statements()?.get(id) {
+            Some(statement) => Ok(statement.clone()),
+            None => Err(Errors::Unprepared(
+                format!("Prepared statement not found: {}", bytes_to_hex(id)),
+                id.to_vec(),
+            )),
+        }
+    }
+
+    fn new(id: Vec<u8>, query: String) -> Result<Self, Errors> {
+        let tokens = query_lexer(query.clone())?;
+        let mut markers = Vec::new();
+        collect_markers(&tokens, None, &mut markers);
+        let placeholders = markers
+            .iter()
+            .map(|_| Literal::new(String::new(), DataType::Text))
+            .collect::<Vec<Literal>>();
+        let parsed = query_parser(replace_markers(tokens, &mut placeholders.into_iter())?)?;
+        let table_name = match get_table_name(&parsed) {
+            Some(table_name) => Some(check_table_name(&table_name)?),
+            None if markers.is_empty() => None,
+            None => {
+                return Err(Errors::Invalid(String::from(
+                    "Bind markers are only supported in INSERT, UPDATE, DELETE and SELECT",
+                )))
+            }
+        };
+        let mut statement = Self {
+            id,
+            query,
+            table_name,
+            bind_variables: Vec::new(),
+            result_columns: None,
+        };
+        if let Some(table_name) = &statement.table_name {
+            let columns = get_columns_from_table(table_name)?;
+            statement.bind_variables = bind_variables(table_name, &markers, &columns)?;
+            statement.result_columns = result_columns(table_name, &parsed, &columns)?;
+        }
+        Ok(statement)
+    }
+
+    /// returns the query with every bind marker replaced by its bound value.
+    /// The columns whose value is not set are left out of the insert or update, the ones bound
+    /// to null have their value removed
+    pub fn bind(&self, values: &[BoundValue]) -> Result<Box<dyn Query>, Errors> {
+        if values.len() != self.bind_variables.len() {
+            return Err(Errors::Invalid(format!(
+                "There were {} markers(?) in CQL but {} bound variables",
+                self.bind_variables.len(),
+                values.len()
+            )));
+        }
+        let user_types = self.user_types()?;
+        let mut literals = Vec::new();
+        let mut unset_columns = Vec::new();
+        let mut null_columns = Vec::new();
+        for (variable, value) in self.bind_variables.iter().zip(values) {
+            match value {
+                BoundValue::Value(value) => {
+                    literals.push(decode_value(value, &variable.data_type, &user_types)?)
+                }
+                BoundValue::Null => {
+                    null_columns.push(self.null_column(variable)?);
+                    literals.push(Literal::new(String::new(), DataType::Text));
+                }
+                BoundValue::Unset => {
+                    unset_columns.extend(variable.column.clone());
+                    literals.push(Literal::new(String::new(), DataType::Text));
+                }
+            }
+        }
+        let tokens = query_lexer(self.query.clone())?;
+        let mut query = query_parser(replace_markers(tokens, &mut literals.into_iter())?)?;
+        if !unset_columns.is_empty() {
+            query = remove_unset_columns(&query, &unset_columns)?;
+        }
+        if !null_columns.is_empty() {
+            query = set_null_columns(&query, &null_columns)?;
+        }
+        Ok(query)
+    }
+
+    /// binds positional values, or named values matching them with the name of each marker
+    pub fn bind_values(&self, values: &BoundValues) -> Result<Box<dyn Query>, Errors> {
+        let named_values = match values {
+            BoundValues::Positional(values) => return self.bind(values),
+            BoundValues::Named(named_values) => named_values,
+        };
+        let mut positional = Vec::new();
+        for variable in &self.bind_variables {
+            let Some((_, value)) = named_values
+                .iter()
+                .find(|(name, _)| *name == variable.name)
+            else {
+                return Err(Errors::Invalid(format!(
+                    "Missing value for bind variable {}",
+                    variable.name
+                )));
+            };
+            positional.push(value.clone());
+        }
+        self.bind(&positional)
+    }
+
+    /// returns the body of the PREPARED result
+    pub fn metadata(&self) -> Result<Vec<u8>, Errors> {
+        let (keyspace, table) = match &self.table_name {
+            Some(table_name) => split_keyspace_table(table_name)?,
+            None => ("", ""),
+        };
+        let variables: Vec<(String, DataType)> = self
+            .bind_variables
+            .iter()
+            .map(|variable| (variable.name.clone(), variable.data_type.clone()))
+            .collect();
+        Response::prepared(
+            &self.id,
+            keyspace,
+            table,
+            &variables,
+            &self.pk_indexes()?,
+            self.result_columns.as_deref(),
+        )
+    }
+
+    /// column of a variable bound to null, primary key columns can not be null
+    fn null_column(&self, variable: &BindVariable) -> Result<String, Errors> {
+        let invalid = || Errors::Invalid(format!("Invalid null value for {}", variable.name));
+        let (Some(table_name), Some(column)) = (&self.table_name, &variable.column) else {
+            return Err(invalid());
+        };
+        if get_table_pk(table_name)?.contains(column) {
+            return Err(invalid());
+        }
+        Ok(column.clone())
+    }
+
+    /// indexes of the bind variables that form the partition key, empty if it is not fully bound
+    fn pk_indexes(&self) -> Result<Vec<u16>, Errors> {
+        let Some(table_name) = &self.table_name else {
+            return Ok(Vec::new());
+        };
+        let mut indexes = Vec::new();
+        for partition_key in get_table_primary_key(table_name)?.partition_keys {
+            match self
+                .bind_variables
+                .iter()
+                .position(|variable| variable.column.as_ref() == Some(&partition_key))
+            {
+                Some(index) => indexes.push(index as u16),
+                None => return Ok(Vec::new()),
+            }
+        }
+        Ok(indexes)
+    }
+
+    fn user_types(&self) -> Result<HashMap<String, UserType>, Errors> {
+        let has_user_types = self
+            .bind_variables
+            .iter()
+            .any(|variable| matches!(variable.data_type, DataType::Udt(_)));
+        let Some(table_name) = self.table_name.as_ref().filter(|_| has_user_types) else {
+            return Ok(HashMap::new());
+        };
+        let (keyspace, _) = split_keyspace_table(table_name)?;
+        use_keyspace_meta_data(|handler| {
+            handler.get_types_from_keyspace(KEYSPACE_METADATA_PATH.to_string(), keyspace)
+        })
+    }
+}
+
+fn lock_statements(
+) -> Result<std::sync::MutexGuard<'static, BTreeMap<Vec<u8>, PreparedStatement>>, Errors> {
+    STATEMENTS
+        .lock()
+        .map_err(|_| Errors::ServerError(String::from("Prepared statements cache is poisoned")))
+}
+
+fn statement_id(query: &str) -> Result<Vec<u8>, Errors> {
+    let keyspace = use_client_meta_data(|client_meta_data| {
+        client_meta_data.get_keyspace(CLIENT_METADATA_PATH.to_string())
+    })?
+    .unwrap_or_default();
+    let mut hasher = Md5::new();
+    hasher.update(keyspace.as_bytes());
+    hasher.update(query.as_bytes());
+    Ok(hasher.finalize().to_vec())
+}
+
+/// leaves out the assignments of the columns whose value was not set
+#[allow(clippy::borrowed_box)]
+fn remove_unset_columns(
+    query: &Box<dyn Query>,
+    unset_columns: &[String],
+) -> Result<Box<dyn Query>, Errors> {
+    match QueryEnum::from_query(query) {
+        Some(QueryEnum::Insert(mut insert)) if insert.json.is_none() => {
+            for column in unset_columns {
+                if let Some(position) = insert.headers.iter().position(|header| header == column) {
+                    insert.headers.remove(position);
+                    insert.values.remove(position);
+                }
+            }
+            Ok(Box::new(insert))
+        }
+        Some(QueryEnum::Update(mut update))
+            if unset_columns
+                .iter()
+                .all(|column| update.changes.contains_key(column)) =>
+        {
+            for column in unset_columns {
+                update.changes.remove(column);
+            }
+            Ok(Box::new(update))
+        }
+        _ => Err(Errors::Invalid(String::from(
+            "Unset values are only allowed in the values of an INSERT or the SET of an UPDATE",
+        ))),
+    }
+}
+
+/// removes the values of the columns bound to null, only allowed in the values of an insert or
+/// the assignments of an update
+#[allow(clippy::borrowed_box)]
+fn set_null_columns(
+    query: &Box<dyn Query>,
+    null_columns: &[String],
+) -> Result<Box<dyn Query>, Errors> {
+    match QueryEnum::from_query(query) {
+        Some(QueryEnum::Insert(mut insert))
+            if insert.json.is_none()
+                && null_columns
+                    .iter()
+                    .all(|column| insert.headers.contains(column)) =>
+        {
+            for column in null_columns {
+                if let Some(position) = insert.headers.iter().position(|header| header == column) {
+                    insert.headers.remove(position);
+                    insert.values.remove(position);
+                    insert.null_columns.push(column.clone());
+                }
+            }
+            Ok(Box::new(insert))
+        }
+        Some(QueryEnum::Update(mut update))
+            if null_columns
+                .iter()
+                .all(|column| update.changes.contains_key(column)) =>
+        {
+            for column in null_columns {
+                update.changes.insert(column.clone(), AssignmentValue::Null);
+            }
+            Ok(Box::new(update))
+        }
+        _ => Err(Errors::Invalid(String::from(
+            "Null values are only allowed in the values of an INSERT or the SET of an UPDATE",
+        ))),
+    }
+}
+
+#[allow(clippy::borrowed_box)]
+fn get_table_name(query: &Box<dyn Query>) -> Option<String> {
+    match QueryEnum::from_query(query)? {
+        QueryEnum::Insert(insert) => Some(insert.table_name),
+        QueryEnum::Update(update) => Some(update.table_name),
+        QueryEnum::Delete(delete) => Some(delete.table_name),
+        QueryEnum::Select(select) => Some(select.table_name),
+        _ => None,
+    }
+}
+
+/// Walks the tokens in order and matches each bind marker with the column it is assigned to
+/// or compared with, which is the last identifier read. In the `VALUES` of an insert the
+/// column comes from the position of the value.
+fn collect_markers(
+    tokens: &[Token],
+    mut column: Option<String>,
+    markers: &mut Vec<(Option<String>, Option<String>)>,
+) {
+    let mut insert_columns: Option<Vec<String>> = None;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            token if is_keyword(token, JSON) && follows_table_name(tokens, i) => {
+                column = Some(JSON_COLUMN.to_string())
+            }
+            Token::Identifier(identifier) => column = Some(identifier.clone()),
+            Token::Reserved(reserved) if reserved == VALUES => {
+                if let Some(Token::ParenList(list)) = i.checked_sub(1).and_then(|j| tokens.get(j)) {
+                    insert_columns = Some(list_identifiers(list));
+                }
+            }
+            Token::Term(Term::BindMarker(name)) => markers.push((name.clone(), column.clone())),
+            Token::ParenList(list) if insert_columns.is_some() => {
+                let columns = insert_columns.take().unwrap_or_default();
+                let values = list
+                    .iter()
+                    .filter(|token| !matches!(token, Token::Symbol(_)));
+                for (position, value) in values.enumerate() {
+                    let value_column = columns.get(position).cloned();
+                    match value {
+                        Token::Term(Term::BindMarker(name)) => {
+                            markers.push((name.clone(), value_column))
+                        }
+                        _ => collect_markers(std::slice::from_ref(value), value_column, markers),
+                    }
+                }
+            }
+            Token::ParenList(list) | Token::IterateToken(list) | Token::BraceList(list) => {
+                collect_markers(list, column.clone(), markers)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// whether the token at `i` follows the table of an `INSERT INTO`
+fn follows_table_name(tokens: &[Token], i: usize) -> bool {
+    i.checked_sub(2)
+        .and_then(|j| tokens.get(j))
+        .is_some_and(|token| is_keyword(token, INTO))
+}
+
+fn list_identifiers(list: &[Token]) -> Vec<String> {
+    list.iter()
+        .filter_map(|token| match token {
+            Token::Identifier(identifier) => Some(identifier.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// replaces the bind markers, in the same order `collect_markers` finds them
+fn replace_markers(
+    tokens: Vec<Token>,
+    literals: &mut IntoIter<Literal>,
+) -> Result<Vec<Token>, Errors> {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            Token::Term(Term::BindMarker(_)) => match literals.next() {
+                Some(literal) => Ok(Token::Term(Term::Literal(literal))),
+                None => Err(Errors::Invalid(String::from("Missing bound value"))),
+            },
+            Token::ParenList(list) => Ok(Token::ParenList(replace_markers(list, literals)?)),
+            Token::IterateToken(list) => Ok(Token::IterateToken(replace_markers(list, literals)?)),
+            Token::BraceList(list) => Ok(Token::BraceList(replace_markers(list, literals)?)),
+            token => Ok(token),
+        })
+        .collect()
+}
+
+fn bind_variables(
+    table_name: &str,
+    markers: &[(Option<String>, Option<String>)],
+    columns: &HashMap<String, DataType>,
+) -> Result<Vec<BindVariable>, Errors> {
+    let (keyspace, _) = split_keyspace_table(table_name)?;
+    let mut variables = Vec::new();
+    for (name, column) in markers {
+        let data_type = match column.as_deref() {
+            Some(JSON_COLUMN) => Some(DataType::Text),
+            Some(column) => get_header_data_type(keyspace, column, columns)?,
+            None => None,
+        };
+        let (Some(column), Some(data_type)) = (column, data_type) else {
+            return Err(Errors::Invalid(String::from(
+                "Could not infer the type of a bind marker",
+            )));
+        };
+        variables.push(BindVariable {
+            name: name.clone().unwrap_or_else(|| column.clone()),
+            column: Some(column.clone()),
+            data_type,
+        });
+    }
+    Ok(variables)
+}
+
+#[allow(clippy::borrowed_box)]
+fn result_columns(
+    table_name: &str,
+    query: &Box<dyn Query>,
+    columns: &HashMap<String, DataType>,
+) -> Result<Option<Vec<(String, DataType)>>, Errors> {
+    let Some(QueryEnum::Select(select)) = QueryEnum::from_query(query) else {
+        return Ok(None);
+    };
+    if select.json {
+        return Ok(Some(vec![(JSON_COLUMN.to_string(), DataType::Text)]));
+    }
+    if select.columns.first().map(String::as_str) == Some(ASTERIK) {
+        let mut all_columns: Vec<(String, DataType)> = columns
+            .iter()
+            .map(|(name, data_type)| (name.clone(), data_type.clone()))
+            .collect();
+        all_columns.sort_by(|a, b| a.0.cmp(&b.0));
+        return Ok(Some(all_columns));
+    }
+    let (keyspace, _) = split_keyspace_table(table_name)?;
+    let mut result = Vec::new();
+    for column in &select.columns {
+        let Some(data_type) = get_header_data_type(keyspace, column, columns)? else {
+            return Err(Errors::Invalid(format!("Undefined column name {}", column)));
+        };
+        result.push((column.clone(), data_type));
+    }
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markers(query: &str) -> Vec<(Option<String>, Option<String>)> {
+        let tokens = query_lexer(query.to_string()).unwrap();
+        let mut markers = Vec::new();
+        collect_markers(&tokens, None, &mut markers);
+        markers
+    }
+
+    fn column(name: &str) -> (Option<String>, Option<String>) {
+        (None, Some(name.to_string()))
+    }
+
+    #[test]
+    fn test_insert_markers_follow_columns() {
+        assert_eq!(
+            markers("INSERT INTO kp.users (id, name, age) VALUES (?, 'Ana', ?)"),
+            vec![column("id"), column("age")]
+        );
+    }
+
+    #[test]
+    fn test_update_and_where_markers() {
+        assert_eq!(
+            markers(
+                "UPDATE kp.users SET age = age + ?, name = :new_name WHERE id = ? AND name = ?"
+            ),
+            vec![
+                column("age"),
+                (Some("new_name".to_string()), Some("name".to_string())),
+                column("id"),
+                column("name"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_json_marker() {
+        assert_eq!(
+            markers("INSERT INTO kp.users JSON ?"),
+            vec![column(JSON_COLUMN)]
+        );
+    }
+
+    #[test]
+    fn test_replace_markers_in_order() {
+        let tokens = query_lexer(String::from(
+            "SELECT name FROM kp.users WHERE id = ? AND name = ?",
+        ))
+        .unwrap();
+        let literals = vec![
+            Literal::new("1".to_string(), DataType::Int),
+            Literal::new("Ana".to_string(), DataType::Text),
+        ];
+        let tokens = replace_markers(tokens, &mut literals.into_iter()).unwrap();
+        let expected = query_lexer(String::from(
+            "SELECT name FROM kp.users WHERE id = 1 AND name = 'Ana'",
+        ))
+        .unwrap();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn test_replace_markers_missing_value() {
+        let tokens = query_lexer(String::from("DELETE FROM kp.users WHERE id = ?")).unwrap();
+        assert!(replace_markers(tokens, &mut Vec::new().into_iter()).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::batchlog::handler::BatchlogHandler;
 use crate::executables::executable::Executable;
 use crate::queries::query::{Query, QueryEnum};
 use crate::query_delegation::query_delegator::QueryDelegator;
@@ -5,37 +6,80 @@ use crate::response_builders::frame_builder::FrameBuilder;
 use crate::utils::consistency_level::ConsistencyLevel;
 use crate::utils::errors::Errors;
 use crate::utils::parser_constants::RESULT;
+use crate::utils::response::Response;
 use crate::utils::types::frame::Frame;
+use crate::utils::types::query_parameters::QueryParameters;
 
 pub struct QueryExecutable {
     query: Box<dyn Query>,
-    consistency_integer: i16,
+    parameters: QueryParameters,
 }
 
 impl QueryExecutable {
-    pub fn new(query: Box<dyn Query>, consistency_integer: i16) -> QueryExecutable {
-        QueryExecutable {
-            query,
-            consistency_integer,
+    pub fn new(query: Box<dyn Query>, parameters: QueryParameters) -> QueryExecutable {
+        QueryExecutable { query, parameters }
+    }
+
+    fn consistency(&self) -> Result<ConsistencyLevel, Errors> {
+        ConsistencyLevel::from_i16(self.parameters.consistency)
+    }
+
+    fn serial_consistency(&self) -> Result<ConsistencyLevel, Errors> {
+        match self.parameters.serial_consistency {
+            Some(serial_consistency) => ConsistencyLevel::from_i16(serial_consistency),
+            None => Ok(ConsistencyLevel::Serial),
         }
     }
+
+    /// applies the paging and `skip_metadata` parameters to the rows of a select
+    fn page(&self, rows: Vec<u8>) -> Result<Vec<u8>, Errors> {
+        Response::page_rows(
+            &rows,
+            self.parameters.skip_metadata,
+            self.parameters.page_size,
+            self.parameters.paging_state.as_deref(),
+        )
+    }
 }
 
 impl Executable for QueryExecutable {
     fn execute(&mut self, request: Frame) -> Result<Frame, Errors> {
         self.query.set_table()?;
+        if let Some(timestamp) = self.parameters.timestamp {
+            self.query.set_timestamp(timestamp);
+        }
         let pk = self.query.get_partition()?;
         let Some(query_enum) = QueryEnum::from_query(&self.query) else {
             return Err(Errors::ServerError(String::from("")));
         };
         let response_msg = match query_enum {
             QueryEnum::Use(use_query) => use_query.run()?,
-            _ => {
+            QueryEnum::Describe(describe_query) => describe_query.run()?,
+            QueryEnum::Truncate(truncate_query) => {
                 let delegator = QueryDelegator::new(
                     pk,
-                    query_enum.into_query(),
-                    ConsistencyLevel::from_i16(self.consistency_integer)?,
+                    Box::new(truncate_query),
+                    ConsistencyLevel::All,
                 );
+                delegator.send_to_all_nodes()?
+            }
+            QueryEnum::Batch(batch_query) => {
+                BatchlogHandler::execute(batch_query, self.consistency()?)?;
+                Response::void()?
+            }
+            QueryEnum::Select(select_query) => {
+                let json = select_query.json;
+                let delegator = QueryDelegator::new(pk, Box::new(select_query), self.consistency()?);
+                let rows = delegator.send()?;
+                if json {
+                    self.page(Response::json_rows(&rows)?)?
+                } else {
+                    self.page(rows)?
+                }
+            }
+            _ => {
+                let delegator = QueryDelegator::new(pk, query_enum.into_query(), self.consistency()?)
+                    .with_serial_consistency(self.serial_consistency()?);
                 delegator.send()?
             }
         };
@@ -1,12 +1,48 @@
+use crate::event_bus::{self, EventType};
 use crate::executables::executable::Executable;
+use crate::meta_data::clients::meta_data_client::ClientMetaDataAcces;
+use crate::response_builders::frame_builder::FrameBuilder;
 use crate::utils::errors::Errors;
-use crate::utils::errors::Errors::ServerError;
+use crate::utils::parser_constants::READY;
 use crate::utils::types::frame::Frame;
 
-pub struct RegisterExecutable;
+pub struct RegisterExecutable {
+    event_types: Vec<EventType>,
+}
+
+impl RegisterExecutable {
+    pub fn new(event_types: Vec<EventType>) -> RegisterExecutable {
+        RegisterExecutable { event_types }
+    }
+}
 
 impl Executable for RegisterExecutable {
-    fn execute(&mut self, _request: Frame) -> Result<Frame, Errors> {
-        Err(ServerError(String::from("Register is not implemented.")))
+    /// registers the connection, the events are sent later from the thread that serves it
+    fn execute(&mut self, request: Frame) -> Result<Frame, Errors> {
+        event_bus::subscribe(&ClientMetaDataAcces::connection_id(), &self.event_types);
+        FrameBuilder::build_response_frame(request, READY, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parser_constants::REGISTER;
+
+    #[test]
+    fn test_register_answers_ready() {
+        let request = Frame {
+            version: 0x04,
+            flags: 0x00,
+            stream: 0x07,
+            opcode: REGISTER,
+            length: 0,
+            body: Vec::new(),
+        };
+        let mut executable = RegisterExecutable::new(vec![EventType::SchemaChange]);
+        let response = executable.execute(request).unwrap();
+        assert_eq!(response.opcode, READY);
+        assert_eq!(response.stream, 0x07);
+        event_bus::unsubscribe(&ClientMetaDataAcces::connection_id());
     }
 }
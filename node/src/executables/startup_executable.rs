@@ -1,30 +1,52 @@
 use std::collections::HashMap;
 
+use crate::auth::authenticator::configured_authenticator;
 use crate::executables::executable::Executable;
+use crate::meta_data::meta_data_handler::use_client_meta_data;
 use crate::response_builders::frame_builder::FrameBuilder;
+use crate::utils::compression::Compression;
+use crate::utils::constants::CLIENT_METADATA_PATH;
 use crate::utils::errors::Errors;
-use crate::utils::parser_constants::AUTHENTICATE;
+use crate::utils::parser_constants::{AUTHENTICATE, READY};
 use crate::utils::types::frame::Frame;
+use crate::utils::types_to_bytes::TypesToBytes;
 
 #[derive(Debug)]
 pub struct StartupExecutable {
     _config: HashMap<String, String>,
+    compression: Option<Compression>,
 }
 
 impl StartupExecutable {
-    pub fn new(_config: HashMap<String, String>) -> StartupExecutable {
-        StartupExecutable { _config }
+    pub fn new(
+        _config: HashMap<String, String>,
+        compression: Option<Compression>,
+    ) -> StartupExecutable {
+        StartupExecutable {
+            _config,
+            compression,
+        }
     }
 }
 
 impl Executable for StartupExecutable {
     fn execute(&mut self, request: Frame) -> Result<Frame, Errors> {
-        // Aplicar configs
-        // let auth_class = vec![]; // HAY QUE ELEGIR AUTH CLASS Y PASARLA EN BYTES
-        let body: Vec<u8> = vec![
-            0x00, 0x15, b'P', b'a', b's', b's', b'w', b'o', b'r', b'd', b'A', b'u', b't', b'h',
-            b'e', b'n', b't', b'i', b'c', b'a', b't', b'o', b'r',
-        ];
-        FrameBuilder::build_response_frame(request, AUTHENTICATE, body)
+        // in v5 compression is applied to the segments and only LZ4 is supported
+        if request.version >= 0x05 && self.compression == Some(Compression::Snappy) {
+            return Err(Errors::ProtocolError(String::from(
+                "Snappy compression is not supported in protocol v5",
+            )));
+        }
+        let compression = self.compression;
+        use_client_meta_data(|handler| {
+            handler.set_compression(CLIENT_METADATA_PATH.to_string(), compression)
+        })?;
+        let authenticator = configured_authenticator();
+        if !authenticator.requires_authentication() {
+            return FrameBuilder::build_response_frame(request, READY, Vec::new());
+        }
+        let mut body = TypesToBytes::default();
+        body.write_string(authenticator.class_name())?;
+        FrameBuilder::build_response_frame(request, AUTHENTICATE, body.into_bytes())
     }
 }
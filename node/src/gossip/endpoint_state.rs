@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Heartbeat the node gossips about itself.
+///
+/// `generation` is set when the process starts, so a restarted node is told apart from an
+/// old version of itself even if its versions start again from zero
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatState {
+    pub generation: u64,
+    pub version: u64,
+}
+
+/// Keys of the state each node publishes about itself
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApplicationState {
+    Status,
+    Tokens,
+    SchemaVersion,
+    Load,
+    Datacenter,
+    Rack,
+    Position,
+    Seed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VersionedValue {
+    pub value: String,
+    pub version: u64,
+}
+
+/// Everything known about a node: its heartbeat and the last value of each key with the version
+/// it was published with. The versions of the heartbeat and of the keys come from the same counter
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EndpointState {
+    pub heartbeat: HeartbeatState,
+    pub application_states: BTreeMap<ApplicationState, VersionedValue>,
+}
+
+impl EndpointState {
+    pub fn new(generation: u64) -> Self {
+        Self {
+            heartbeat: HeartbeatState {
+                generation,
+                version: 0,
+            },
+            application_states: BTreeMap::new(),
+        }
+    }
+
+    pub fn get_generation(&self) -> u64 {
+        self.heartbeat.generation
+    }
+
+    pub fn get(&self, key: ApplicationState) -> Option<&str> {
+        self.application_states
+            .get(&key)
+            .map(|versioned| versioned.value.as_str())
+    }
+
+    /// the highest version among the heartbeat and the application states
+    pub fn max_version(&self) -> u64 {
+        self.application_states
+            .values()
+            .map(|versioned| versioned.version)
+            .fold(self.heartbeat.version, u64::max)
+    }
+
+    /// the heartbeat and only the application states published after `version`
+    pub fn newer_than(&self, version: u64) -> Self {
+        Self {
+            heartbeat: self.heartbeat,
+            application_states: self
+                .application_states
+                .iter()
+                .filter(|(_, versioned)| versioned.version > version)
+                .map(|(key, versioned)| (*key, versioned.clone()))
+                .collect(),
+        }
+    }
+
+    /// Applies a state of the same generation, keeping the newest value of each key.
+    ///
+    /// Returns whether something changed
+    pub fn merge(&mut self, remote: &EndpointState) -> bool {
+        let mut changed = false;
+        if remote.heartbeat.version > self.heartbeat.version {
+            self.heartbeat.version = remote.heartbeat.version;
+            changed = true;
+        }
+        for (key, versioned) in &remote.application_states {
+            let is_newer = self
+                .application_states
+                .get(key)
+                .is_none_or(|local| versioned.version > local.version);
+            if is_newer {
+                self.application_states.insert(*key, versioned.clone());
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(generation: u64, values: &[(ApplicationState, &str, u64)]) -> EndpointState {
+        let mut state = EndpointState::new(generation);
+        for (key, value, version) in values {
+            state.application_states.insert(
+                *key,
+                VersionedValue {
+                    value: value.to_string(),
+                    version: *version,
+                },
+            );
+        }
+        state
+    }
+
+    #[test]
+    fn test_only_newer_values_are_merged() {
+        let mut local = state(
+            1,
+            &[
+                (ApplicationState::Status, "Active", 3),
+                (ApplicationState::Load, "10", 5),
+            ],
+        );
+        let remote = state(
+            1,
+            &[
+                (ApplicationState::Status, "ShuttingDown", 7),
+                (ApplicationState::Load, "8", 4),
+            ],
+        );
+        assert!(local.merge(&remote));
+        assert_eq!(local.get(ApplicationState::Status), Some("ShuttingDown"));
+        assert_eq!(local.get(ApplicationState::Load), Some("10"));
+        assert_eq!(local.max_version(), 7);
+        assert!(!local.merge(&remote));
+        let delta = local.newer_than(5);
+        assert_eq!(delta.application_states.len(), 1);
+        assert_eq!(delta.get(ApplicationState::Status), Some("ShuttingDown"));
+    }
+}
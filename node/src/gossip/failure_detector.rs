@@ -0,0 +1,179 @@
+use crate::meta_data::nodes::node::Node;
+use crate::utils::config_constants::{GOSSIP_INTERVAL_MILLIS, PHI_CONVICT_THRESHOLD};
+use crate::utils::types::node_ip::NodeIp;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+/// amount of intervals remembered for each node
+const WINDOW_SIZE: usize = 1000;
+/// interval assumed before there are samples, as Cassandra does
+const INITIAL_INTERVAL_MILLIS: f64 = 2.0 * GOSSIP_INTERVAL_MILLIS as f64;
+/// intervals longer than this one are pauses and are not part of the distribution
+const MAX_INTERVAL_MILLIS: f64 = 2.0 * INITIAL_INTERVAL_MILLIS;
+/// 1 / ln(10), turns the exponential probability into phi
+const PHI_FACTOR: f64 = std::f64::consts::LOG10_E;
+
+static DETECTOR: Mutex<FailureDetector> = Mutex::new(FailureDetector::new(PHI_CONVICT_THRESHOLD));
+
+/// intervals between the heartbeats of a node
+struct ArrivalWindow {
+    last: Instant,
+    intervals: VecDeque<f64>,
+}
+
+impl ArrivalWindow {
+    fn new(now: Instant) -> Self {
+        Self {
+            last: now,
+            intervals: VecDeque::from([INITIAL_INTERVAL_MILLIS]),
+        }
+    }
+
+    fn add(&mut self, now: Instant) {
+        let interval = now.duration_since(self.last).as_secs_f64() * 1000.0;
+        if interval <= MAX_INTERVAL_MILLIS {
+            if self.intervals.len() == WINDOW_SIZE {
+                self.intervals.pop_front();
+            }
+            self.intervals.push_back(interval);
+        }
+        self.last = now;
+    }
+
+    fn mean(&self) -> f64 {
+        self.intervals.iter().sum::<f64>() / self.intervals.len() as f64
+    }
+
+    /// suspicion that the node is down, assuming the heartbeats arrive with an
+    /// exponential distribution
+    fn phi(&self, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.last).as_secs_f64() * 1000.0;
+        PHI_FACTOR * elapsed / self.mean().max(1.0)
+    }
+}
+
+/// Phi accrual failure detector.
+///
+/// Each new heartbeat that arrives through gossip is recorded in the window of the node, and a node
+/// is considered down when its phi goes over `phi_convict_threshold`
+pub struct FailureDetector {
+    windows: BTreeMap<String, ArrivalWindow>,
+    phi_convict_threshold: f64,
+}
+
+impl FailureDetector {
+    pub const fn new(phi_convict_threshold: f64) -> Self {
+        Self {
+            windows: BTreeMap::new(),
+            phi_convict_threshold,
+        }
+    }
+
+    /// a new heartbeat of the node arrived
+    pub fn report(&mut self, ip: &NodeIp, now: Instant) {
+        match self.windows.get_mut(&ip.get_string_ip()) {
+            Some(window) => window.add(now),
+            None => {
+                self.windows
+                    .insert(ip.get_string_ip(), ArrivalWindow::new(now));
+            }
+        }
+    }
+
+    /// the nodes that were never heard of start to be watched, as if they had just sent a heartbeat
+    pub fn phi(&mut self, ip: &NodeIp, now: Instant) -> f64 {
+        self.windows
+            .entry(ip.get_string_ip())
+            .or_insert_with(|| ArrivalWindow::new(now))
+            .phi(now)
+    }
+
+    pub fn is_alive(&mut self, ip: &NodeIp, now: Instant) -> bool {
+        self.phi(ip, now) <= self.phi_convict_threshold
+    }
+
+    /// reports the nodes whose heartbeat changed after merging the gossiped nodes
+    pub fn observe(&mut self, registered: &[Node], merged: &[Node], now: Instant) {
+        for node in merged {
+            let changed = registered
+                .iter()
+                .find(|known| known.get_ip() == node.get_ip())
+                .is_none_or(|known| known.heartbeat != node.heartbeat);
+            if changed {
+                self.report(node.get_ip(), now);
+            }
+        }
+    }
+}
+
+fn detector() -> MutexGuard<'static, FailureDetector> {
+    DETECTOR
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// sets the `phi_convict_threshold` of the configuration
+pub fn configure(phi_convict_threshold: f64) {
+    detector().phi_convict_threshold = phi_convict_threshold;
+}
+
+pub fn observe(registered: &[Node], merged: &[Node]) {
+    detector().observe(registered, merged, Instant::now())
+}
+
+pub fn is_alive(ip: &NodeIp) -> bool {
+    detector().is_alive(ip, Instant::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn ip() -> NodeIp {
+        NodeIp::new_from_string("10.0.0.1", 9042).unwrap()
+    }
+
+    #[test]
+    fn test_node_is_convicted_after_missing_heartbeats() {
+        let mut detector = FailureDetector::new(8.0);
+        let start = Instant::now();
+        for second in 0..10 {
+            detector.report(&ip(), start + Duration::from_secs(second));
+        }
+        let last = start + Duration::from_secs(9);
+        assert!(detector.is_alive(&ip(), last + Duration::from_secs(2)));
+        // with a heartbeat every second phi reaches 8 after about 20 seconds
+        assert!(detector.phi(&ip(), last + Duration::from_secs(10)) < 8.0);
+        assert!(!detector.is_alive(&ip(), last + Duration::from_secs(30)));
+        detector.report(&ip(), last + Duration::from_secs(31));
+        assert!(detector.is_alive(&ip(), last + Duration::from_secs(32)));
+    }
+
+    #[test]
+    fn test_threshold_and_unknown_nodes() {
+        let start = Instant::now();
+        let mut strict = FailureDetector::new(1.0);
+        assert!(strict.is_alive(&ip(), start));
+        assert!(!strict.is_alive(&ip(), start + Duration::from_secs(5)));
+        let mut lenient = FailureDetector::new(16.0);
+        assert!(lenient.is_alive(&ip(), start));
+        assert!(lenient.is_alive(&ip(), start + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_only_new_heartbeats_are_reported() {
+        let mut detector = FailureDetector::new(8.0);
+        let start = Instant::now();
+        let registered = vec![Node::new(&ip(), 1, false, Vec::new()).unwrap()];
+        detector.report(&ip(), start);
+        let later = start + Duration::from_secs(60);
+        detector.observe(&registered, &registered, later);
+        assert!(!detector.is_alive(&ip(), later));
+        let mut beaten = Node::new_from_node(&registered[0]);
+        beaten.heartbeat += 1;
+        detector.observe(&registered, &[beaten], later);
+        assert!(detector.is_alive(&ip(), later));
+    }
+}
@@ -0,0 +1,41 @@
+use crate::gossip::endpoint_state::EndpointState;
+use crate::gossip::schema_agreement::Schema;
+use crate::utils::types::node_ip::NodeIp;
+use serde::{Deserialize, Serialize};
+
+/// Summary of what is known about a node: enough to know who has the newest state
+/// without sending the whole state
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GossipDigest {
+    pub endpoint: NodeIp,
+    pub generation: u64,
+    pub max_version: u64,
+}
+
+impl GossipDigest {
+    pub fn new(endpoint: &NodeIp, generation: u64, max_version: u64) -> Self {
+        Self {
+            endpoint: NodeIp::new_from_ip(endpoint),
+            generation,
+            max_version,
+        }
+    }
+}
+
+/// Messages of a gossip round.
+///
+/// The initiator sends `Syn` with its digests, the other one answers `Ack` with the states it
+/// has newer and the digests of what it is missing, and the first one closes with `Ack2` sending
+/// what was requested. Outside the rounds, a `SchemaRequest` is answered with `Schema` holding the
+/// schema of the node
+#[derive(Serialize, Deserialize, Debug)]
+pub enum GossipMessage {
+    Syn(Vec<GossipDigest>),
+    Ack {
+        requests: Vec<GossipDigest>,
+        states: Vec<(NodeIp, EndpointState)>,
+    },
+    Ack2(Vec<(NodeIp, EndpointState)>),
+    SchemaRequest,
+    Schema(Schema),
+}
@@ -1,43 +1,68 @@
-use crate::meta_data::meta_data_handler::use_node_meta_data;
-use crate::meta_data::nodes::cluster::Cluster;
+use crate::gossip::failure_detector;
+use crate::gossip::gossip_digest::GossipMessage;
+use crate::gossip::gossiper::{self, use_gossiper};
+use crate::meta_data::meta_data_handler::{use_keyspace_meta_data, use_node_meta_data};
+use crate::meta_data::nodes::node::State;
 use crate::meta_data::nodes::node::State::{Booting, Recovering};
-use crate::meta_data::nodes::node::{Node, State};
-use crate::utils::constants::NODES_METADATA_PATH;
+use crate::utils::constants::{DATA_ACCESS_PATH, KEYSPACE_METADATA_PATH, NODES_METADATA_PATH};
 use crate::utils::errors::Errors;
 use crate::utils::functions::{
     deserialize_from_slice, read_exact_from_stream, serialize_to_string, write_to_stream,
 };
 use crate::utils::types::node_ip::NodeIp;
 use rand::seq::SliceRandom;
+use std::fs;
 use std::net::TcpStream;
 
 pub struct GossipEmitter;
 
 impl GossipEmitter {
     /// Starts Gossip process
-    /// It connects to a rando ip from the cluster and exchanges information from node metadata
+    /// It connects to a random ip from the cluster and exchanges with it the versioned state of
+    /// every node, only what one of them has newer is sent.
+    ///
+    /// A node that does not answer is not marked as inactive here, the failure detector
+    /// decides it once its heartbeats stop arriving
     pub fn start_gossip() -> Result<bool, Errors> {
-        let Some(ip) = Self::get_random_ip()? else {
-            return Ok(false);
-        };
-        if let Ok(mut stream) = TcpStream::connect(ip.get_gossip_socket()) {
-            Self::send_nodes_list(&mut stream)?;
-            let node_added_or_removed = Self::get_nodes_list(&mut stream)?;
-            Ok(node_added_or_removed)
-        } else {
-            Self::set_inactive(ip)?;
-            Ok(false)
+        Self::publish_own_state()?;
+        let mut node_added_or_removed = false;
+        if let Some(ip) = Self::get_random_ip()? {
+            if let Ok(mut stream) = TcpStream::connect(ip.get_gossip_socket()) {
+                Self::exchange(&mut stream)?;
+                node_added_or_removed = gossiper::update_cluster()?;
+            }
+        }
+        Self::update_liveness()?;
+        Ok(node_added_or_removed)
+    }
+
+    /// marks as inactive the active nodes that the failure detector convicts, and as active
+    /// again the inactive ones whose heartbeats came back
+    fn update_liveness() -> Result<(), Errors> {
+        let cluster = use_node_meta_data(|handler| handler.get_cluster(&NODES_METADATA_PATH))?;
+        for node in cluster.get_other_nodes() {
+            let alive = failure_detector::is_alive(node.get_ip());
+            match node.state {
+                State::Active if !alive => use_node_meta_data(|handler| {
+                    handler.set_inactive(&NODES_METADATA_PATH, node.get_ip())
+                })?,
+                State::Inactive if alive => use_node_meta_data(|handler| {
+                    handler.set_active(&NODES_METADATA_PATH, node.get_ip())
+                })?,
+                _ => {}
+            }
         }
+        Ok(())
     }
 
     /// Retrieves a random node IP from the cluster, excluding `Booting` or `Recovering` nodes.
     fn get_random_ip() -> Result<Option<NodeIp>, Errors> {
         use_node_meta_data(|node_meta_data| {
-            if node_meta_data.get_nodes_quantity(NODES_METADATA_PATH)? == 1 {
+            if node_meta_data.get_nodes_quantity(&NODES_METADATA_PATH)? == 1 {
                 return Ok(None);
             }
             let mut rng = rand::thread_rng();
-            let cluster = node_meta_data.get_cluster(NODES_METADATA_PATH)?;
+            let cluster = node_meta_data.get_cluster(&NODES_METADATA_PATH)?;
             let nodes = cluster.get_other_nodes();
             if let Some(random_node) = nodes.choose(&mut rng) {
                 if random_node.state != Booting
@@ -51,68 +76,50 @@ impl GossipEmitter {
         })
     }
 
-    fn set_inactive(ip: NodeIp) -> Result<(), Errors> {
-        use_node_meta_data(|handler| handler.set_inactive(NODES_METADATA_PATH, &ip))
+    /// publishes the schema version and the load of the own node along with its state
+    pub fn publish_own_state() -> Result<(), Errors> {
+        let schema_version = use_keyspace_meta_data(|handler| {
+            handler.get_schema_version(KEYSPACE_METADATA_PATH.to_string())
+        })?;
+        let own_node = use_node_meta_data(|handler| {
+            handler.refresh_own_node(&NODES_METADATA_PATH, schema_version, Self::get_load())
+        })?;
+        use_gossiper(|gossiper| {
+            gossiper.publish_local(&own_node);
+            gossiper.beat();
+        })
     }
 
-    fn send_nodes_list(stream: &mut TcpStream) -> Result<(), Errors> {
-        let nodes_list =
-            use_node_meta_data(|handler| handler.get_full_nodes_list(NODES_METADATA_PATH))?;
-        let serialized = serialize_to_string(&nodes_list)?;
-        write_to_stream(stream, serialized.as_bytes())
+    /// bytes of the tables stored by the node
+    fn get_load() -> u64 {
+        fs::read_dir(DATA_ACCESS_PATH.as_str())
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| entry.metadata().ok())
+                    .map(|metadata| metadata.len())
+                    .sum()
+            })
+            .unwrap_or(0)
     }
 
-    fn get_nodes_list(stream: &mut TcpStream) -> Result<bool, Errors> {
+    /// `Syn` with the digests, the `Ack` is applied and it ends with the `Ack2` with what the other asked for
+    fn exchange(stream: &mut TcpStream) -> Result<(), Errors> {
+        let digests = use_gossiper(|gossiper| gossiper.digests())?;
+        Self::send_message(stream, &GossipMessage::Syn(digests))?;
         let buf = read_exact_from_stream(stream)?;
-        let received_nodes: Vec<Node> = deserialize_from_slice(buf.as_slice())?;
-        let cluster = use_node_meta_data(|handler| handler.get_cluster(NODES_METADATA_PATH))?;
-        let mut node_added = false;
-        for node in &received_nodes {
-            if !Self::is_node_in_list(cluster.get_other_nodes(), node) {
-                node_added = true;
-            }
-        }
-        let (new_cluster, node_removed) = Self::get_new_cluster(&cluster, received_nodes)?;
-        use_node_meta_data(|handler| handler.set_new_cluster(NODES_METADATA_PATH, &new_cluster))?;
-        Ok(node_added || node_removed)
-    }
-
-    fn get_new_cluster(
-        cluster: &Cluster,
-        received_nodes: Vec<Node>,
-    ) -> Result<(Cluster, bool), Errors> {
-        let mut new_list = Vec::new();
-        for node in received_nodes {
-            if node.get_pos() != cluster.get_own_node().get_pos() {
-                new_list.push(node);
-            }
-        }
-        for node in cluster.get_other_nodes() {
-            if !Self::is_node_in_list(&new_list, node) {
-                new_list.push(Node::new_from_node(node));
-            }
-        }
-        let node_removed = Self::shutting_down_count(&new_list)
-            > Self::shutting_down_count(cluster.get_other_nodes());
-        Ok((
-            Cluster::new(Node::new_from_node(cluster.get_own_node()), new_list),
-            node_removed,
-        ))
-    }
-
-    fn is_node_in_list(node_list: &[Node], node: &Node) -> bool {
-        for n in node_list.iter() {
-            if n.get_ip().get_string_ip() == node.get_ip().get_string_ip() {
-                return true;
-            }
-        }
-        false
+        let GossipMessage::Ack { requests, states } = deserialize_from_slice(buf.as_slice())?
+        else {
+            return Err(Errors::ServerError(String::from(
+                "Expected a gossip ACK message",
+            )));
+        };
+        let requested = use_gossiper(|gossiper| gossiper.handle_ack(&requests, states))?;
+        Self::send_message(stream, &GossipMessage::Ack2(requested))
     }
 
-    fn shutting_down_count(new_nodes: &[Node]) -> usize {
-        new_nodes
-            .iter()
-            .filter(|n| n.state == State::ShuttingDown)
-            .count()
+    fn send_message(stream: &mut TcpStream, message: &GossipMessage) -> Result<(), Errors> {
+        let serialized = serialize_to_string(message)?;
+        write_to_stream(stream, serialized.as_bytes())
     }
 }
@@ -0,0 +1,549 @@
+use crate::event_bus;
+use crate::gossip::endpoint_state::{ApplicationState, EndpointState, VersionedValue};
+use crate::gossip::failure_detector;
+use crate::gossip::gossip_digest::GossipDigest;
+use crate::meta_data::meta_data_handler::use_node_meta_data;
+use crate::meta_data::nodes::cluster::Cluster;
+use crate::meta_data::nodes::node::{Node, State};
+use crate::partitioner::Token;
+use crate::utils::config_constants::SHUTTING_DOWN_TIMEOUT_SECS;
+use crate::utils::constants::NODES_METADATA_PATH;
+use crate::utils::errors::Errors;
+use crate::utils::types::node_ip::NodeIp;
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+static GOSSIPER: Mutex<Option<Gossiper>> = Mutex::new(None);
+
+/// Versioned state of every known node, including the own one.
+///
+/// Only the owner of a state modifies it; the rest forward it as is and keep the one with the
+/// highest generation or, in the same generation, the highest version of each key
+pub struct Gossiper {
+    local: NodeIp,
+    endpoints: BTreeMap<NodeIp, EndpointState>,
+    /// nodes that left the cluster with the generation they had, they do not come back unless they restart
+    removed: BTreeMap<NodeIp, u64>,
+}
+
+impl Gossiper {
+    pub fn new(local: &NodeIp, generation: u64) -> Self {
+        Self {
+            local: NodeIp::new_from_ip(local),
+            endpoints: BTreeMap::from([(
+                NodeIp::new_from_ip(local),
+                EndpointState::new(generation),
+            )]),
+            removed: BTreeMap::new(),
+        }
+    }
+
+    fn local_state(&mut self) -> &mut EndpointState {
+        self.endpoints
+            .entry(NodeIp::new_from_ip(&self.local))
+            .or_insert_with(|| EndpointState::new(0))
+    }
+
+    fn next_version(&mut self) -> u64 {
+        self.local_state().max_version() + 1
+    }
+
+    /// the node is still alive, its heartbeat is gossiped with a newer version
+    pub fn beat(&mut self) {
+        let version = self.next_version();
+        self.local_state().heartbeat.version = version;
+    }
+
+    /// the value only gets a new version when it changes
+    pub fn set_application_state(&mut self, key: ApplicationState, value: String) {
+        if self.local_state().get(key) == Some(value.as_str()) {
+            return;
+        }
+        let version = self.next_version();
+        self.local_state()
+            .application_states
+            .insert(key, VersionedValue { value, version });
+    }
+
+    /// publishes the state of the own node
+    pub fn publish_local(&mut self, node: &Node) {
+        let tokens: Vec<String> = node.get_tokens().iter().map(Token::to_string).collect();
+        self.set_application_state(ApplicationState::Status, node.state.to_string());
+        self.set_application_state(ApplicationState::Tokens, tokens.join(","));
+        self.set_application_state(
+            ApplicationState::SchemaVersion,
+            node.schema_version.to_string(),
+        );
+        self.set_application_state(ApplicationState::Load, node.load.to_string());
+        self.set_application_state(
+            ApplicationState::Datacenter,
+            node.get_datacenter().to_string(),
+        );
+        self.set_application_state(ApplicationState::Rack, node.get_rack().to_string());
+        self.set_application_state(ApplicationState::Position, node.get_pos().to_string());
+        self.set_application_state(ApplicationState::Seed, node.is_seed().to_string());
+    }
+
+    pub fn get_state(&self, ip: &NodeIp) -> Option<&EndpointState> {
+        self.endpoints.get(ip)
+    }
+
+    pub fn digests(&self) -> Vec<GossipDigest> {
+        self.endpoints
+            .iter()
+            .map(|(ip, state)| GossipDigest::new(ip, state.get_generation(), state.max_version()))
+            .collect()
+    }
+
+    /// Answer to the `Syn`: sends what the other one does not know or knows in an old version, and
+    /// requests the nodes it has something newer of
+    pub fn handle_syn(
+        &self,
+        digests: &[GossipDigest],
+    ) -> (Vec<GossipDigest>, Vec<(NodeIp, EndpointState)>) {
+        let mut requests = Vec::new();
+        let mut states = Vec::new();
+        for digest in digests {
+            match self.endpoints.get(&digest.endpoint) {
+                Some(local) if local.get_generation() > digest.generation => {
+                    states.extend(self.delta_for(digest))
+                }
+                Some(local) if local.get_generation() < digest.generation => {
+                    requests.push(GossipDigest::new(&digest.endpoint, digest.generation, 0))
+                }
+                Some(local) if local.max_version() > digest.max_version => {
+                    states.extend(self.delta_for(digest))
+                }
+                Some(local) if local.max_version() < digest.max_version => requests.push(
+                    GossipDigest::new(&digest.endpoint, digest.generation, local.max_version()),
+                ),
+                Some(_) => {}
+                None if self.is_removed(&digest.endpoint, digest.generation) => {}
+                None => requests.push(GossipDigest::new(&digest.endpoint, digest.generation, 0)),
+            }
+        }
+        for (ip, state) in &self.endpoints {
+            if !digests.iter().any(|digest| &digest.endpoint == ip) {
+                states.push((NodeIp::new_from_ip(ip), state.clone()));
+            }
+        }
+        (requests, states)
+    }
+
+    /// applies the states of the `Ack` and returns the ones requested, to be sent in the `Ack2`
+    pub fn handle_ack(
+        &mut self,
+        requests: &[GossipDigest],
+        states: Vec<(NodeIp, EndpointState)>,
+    ) -> Vec<(NodeIp, EndpointState)> {
+        self.apply(states);
+        requests
+            .iter()
+            .filter_map(|digest| self.delta_for(digest))
+            .collect()
+    }
+
+    /// what is known of the node after the version of the digest, or everything if it is
+    /// of an older generation
+    fn delta_for(&self, digest: &GossipDigest) -> Option<(NodeIp, EndpointState)> {
+        let local = self.endpoints.get(&digest.endpoint)?;
+        let delta = if local.get_generation() > digest.generation {
+            local.clone()
+        } else if local.get_generation() == digest.generation {
+            local.newer_than(digest.max_version)
+        } else {
+            return None;
+        };
+        Some((NodeIp::new_from_ip(&digest.endpoint), delta))
+    }
+
+    /// Applies the received states. A higher generation means the node restarted and it
+    /// replaces everything that was known about it; the ones of an old generation are discarded.
+    ///
+    /// Returns whether something changed
+    pub fn apply(&mut self, states: Vec<(NodeIp, EndpointState)>) -> bool {
+        let mut changed = false;
+        for (ip, remote) in states {
+            if ip == self.local || self.is_removed(&ip, remote.get_generation()) {
+                continue;
+            }
+            match self.endpoints.get_mut(&ip) {
+                Some(local) if remote.get_generation() == local.get_generation() => {
+                    changed |= local.merge(&remote)
+                }
+                Some(local) if remote.get_generation() < local.get_generation() => {}
+                _ => {
+                    self.removed.remove(&ip);
+                    self.endpoints.insert(ip, remote);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// forgets a node that left the cluster
+    pub fn remove(&mut self, ip: &NodeIp) {
+        if let Some(state) = self.endpoints.remove(ip) {
+            self.removed
+                .insert(NodeIp::new_from_ip(ip), state.get_generation());
+        }
+    }
+
+    /// Announces that a down node left the cluster. As it is not going to publish it, the state goes out
+    /// with a version higher than anything it published so it wins in the rest of the nodes
+    pub fn announce_removed(&mut self, ip: &NodeIp) -> Result<(), Errors> {
+        if ip == &self.local {
+            return Err(Errors::Invalid(String::from(
+                "The own node cannot be announced as removed",
+            )));
+        }
+        let state = self.endpoints.get_mut(ip).ok_or_else(|| {
+            Errors::Invalid(format!("{} is not a known node", ip.get_string_ip()))
+        })?;
+        let version = state.max_version() + 1;
+        state.application_states.insert(
+            ApplicationState::Status,
+            VersionedValue {
+                value: State::ShuttingDown.to_string(),
+                version,
+            },
+        );
+        Ok(())
+    }
+
+    fn is_removed(&self, ip: &NodeIp, generation: u64) -> bool {
+        self.removed
+            .get(ip)
+            .is_some_and(|removed_generation| generation <= *removed_generation)
+    }
+
+    /// View of the cluster with the latest known about each node.
+    ///
+    /// Also returns whether a node joined, one finished starting or one is leaving, to
+    /// redistribute the data
+    pub fn merge_into(&self, cluster: &Cluster) -> (Cluster, bool) {
+        let mut changed = false;
+        let mut nodes = Vec::new();
+        for registered in cluster.get_other_nodes() {
+            let merged = self
+                .endpoints
+                .get(registered.get_ip())
+                .and_then(|state| to_node(registered.get_ip(), state, Some(registered)));
+            match merged {
+                Some(node) => {
+                    event_bus::publish_node_transition(
+                        &registered.state,
+                        &node.state,
+                        node.get_ip(),
+                    );
+                    changed |= (node.state == State::ShuttingDown
+                        && registered.state != State::ShuttingDown)
+                        || (registered.state == State::Booting && node.state == State::Active);
+                    nodes.push(node)
+                }
+                None => nodes.push(Node::new_from_node(registered)),
+            }
+        }
+        for (ip, state) in &self.endpoints {
+            if ip == cluster.get_own_ip() || nodes.iter().any(|node| node.get_ip() == ip) {
+                continue;
+            }
+            if let Some(node) = to_node(ip, state, None) {
+                event_bus::publish_new_node(ip);
+                changed = true;
+                nodes.push(node);
+            }
+        }
+        (
+            Cluster::new(Node::new_from_node(cluster.get_own_node()), nodes),
+            changed,
+        )
+    }
+}
+
+/// Node built from its gossiped state, None if what is needed to place it in the ring
+/// has not arrived yet
+fn to_node(ip: &NodeIp, state: &EndpointState, registered: Option<&Node>) -> Option<Node> {
+    let status: State = state.get(ApplicationState::Status)?.parse().ok()?;
+    let position = state.get(ApplicationState::Position)?.parse().ok()?;
+    let tokens = parse_tokens(state.get(ApplicationState::Tokens)?)?;
+    let is_seed = state.get(ApplicationState::Seed) == Some("true");
+    let mut node = Node::new(ip, position, is_seed, tokens).ok()?;
+    // each node's failure detector decides whether a node is down, it is not gossiped
+    node.state = match registered {
+        Some(registered) if registered.state == State::Inactive && status == State::Active => {
+            State::Inactive
+        }
+        _ => status,
+    };
+    if let Some(registered) = registered.filter(|registered| registered.state == node.state) {
+        node.timestamp = registered.get_timestamp();
+    }
+    node.heartbeat = state.heartbeat.version;
+    node.schema_version = state
+        .get(ApplicationState::SchemaVersion)
+        .unwrap_or_default()
+        .to_string();
+    node.load = state
+        .get(ApplicationState::Load)
+        .and_then(|load| load.parse().ok())
+        .unwrap_or(0);
+    if let (Some(datacenter), Some(rack)) = (
+        state.get(ApplicationState::Datacenter),
+        state.get(ApplicationState::Rack),
+    ) {
+        node.set_location(datacenter.to_string(), rack.to_string());
+    }
+    Some(node)
+}
+
+fn parse_tokens(tokens: &str) -> Option<Vec<Token>> {
+    if tokens.is_empty() {
+        return Some(Vec::new());
+    }
+    tokens.split(',').map(|token| token.parse().ok()).collect()
+}
+
+/// Uses the gossiper of the node. The generation is the moment it is first used, so
+/// every time the node starts it has a higher one
+pub fn use_gossiper<F, T>(action: F) -> Result<T, Errors>
+where
+    F: FnOnce(&mut Gossiper) -> T,
+{
+    let mut gossiper = GOSSIPER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if gossiper.is_none() {
+        let own_ip = use_node_meta_data(|handler| handler.get_own_ip(&NODES_METADATA_PATH))?;
+        *gossiper = Some(Gossiper::new(&own_ip, Utc::now().timestamp() as u64));
+    }
+    gossiper
+        .as_mut()
+        .map(action)
+        .ok_or_else(|| Errors::ServerError(String::from("Gossiper not initialized")))
+}
+
+/// Moves what is known through gossip into the metadata of the nodes.
+///
+/// The nodes that left more than `SHUTTING_DOWN_TIMEOUT_SECS` ago are forgotten. Returns whether
+/// some node joined or is leaving
+pub fn update_cluster() -> Result<bool, Errors> {
+    let cluster = use_node_meta_data(|handler| handler.get_cluster(&NODES_METADATA_PATH))?;
+    let (new_cluster, changed) = use_gossiper(|gossiper| {
+        for node in cluster.get_other_nodes() {
+            if node.state == State::ShuttingDown
+                && node
+                    .get_timestamp()
+                    .has_perished_seconds(SHUTTING_DOWN_TIMEOUT_SECS)
+            {
+                gossiper.remove(node.get_ip());
+            }
+        }
+        gossiper.merge_into(&cluster)
+    })?;
+    failure_detector::observe(cluster.get_other_nodes(), new_cluster.get_other_nodes());
+    use_node_meta_data(|handler| handler.set_new_cluster(&NODES_METADATA_PATH, &new_cluster))?;
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(last_byte: u8) -> NodeIp {
+        NodeIp::new_from_string(&format!("10.0.0.{}", last_byte), 9042).unwrap()
+    }
+
+    fn node(last_byte: u8) -> Node {
+        Node::new(
+            &ip(last_byte),
+            last_byte as usize,
+            false,
+            vec![last_byte as Token * 100],
+        )
+        .unwrap()
+    }
+
+    fn gossiper(last_byte: u8, generation: u64) -> Gossiper {
+        let mut gossiper = Gossiper::new(&ip(last_byte), generation);
+        gossiper.publish_local(&node(last_byte));
+        gossiper.beat();
+        gossiper
+    }
+
+    /// SYN / ACK / ACK2 between two nodes
+    fn round(initiator: &mut Gossiper, receiver: &mut Gossiper) {
+        let (requests, states) = receiver.handle_syn(&initiator.digests());
+        let requested = initiator.handle_ack(&requests, states);
+        receiver.apply(requested);
+    }
+
+    fn status(gossiper: &Gossiper, last_byte: u8) -> Option<&str> {
+        gossiper
+            .get_state(&ip(last_byte))?
+            .get(ApplicationState::Status)
+    }
+
+    #[test]
+    fn test_state_spreads_through_rounds() {
+        let (mut a, mut b, mut c) = (gossiper(1, 1), gossiper(2, 1), gossiper(3, 1));
+        round(&mut a, &mut b);
+        round(&mut b, &mut c);
+        assert_eq!(status(&c, 1), Some("Active"));
+        assert_eq!(
+            c.get_state(&ip(1)).unwrap().get(ApplicationState::Tokens),
+            Some("100")
+        );
+        assert_eq!(status(&a, 2), Some("Active"));
+        assert!(status(&a, 3).is_none());
+        round(&mut c, &mut a);
+        assert_eq!(a.digests(), c.digests());
+    }
+
+    #[test]
+    fn test_only_newer_state_is_sent() {
+        let (mut a, mut b) = (gossiper(1, 1), gossiper(2, 1));
+        round(&mut a, &mut b);
+        let (requests, states) = a.handle_syn(&b.digests());
+        assert!(requests.is_empty() && states.is_empty());
+        a.beat();
+        let (requests, states) = a.handle_syn(&b.digests());
+        assert!(requests.is_empty());
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].0, ip(1));
+        assert!(states[0].1.application_states.is_empty());
+        a.set_application_state(ApplicationState::Load, String::from("512"));
+        let (requests, _) = b.handle_syn(&a.digests());
+        assert_eq!(
+            requests,
+            vec![GossipDigest::new(
+                &ip(1),
+                1,
+                b.get_state(&ip(1)).unwrap().max_version()
+            )]
+        );
+        let requested = a.handle_ack(&requests, Vec::new());
+        assert_eq!(requested[0].1.application_states.len(), 1);
+        b.apply(requested);
+        assert_eq!(b.get_state(&ip(1)), a.get_state(&ip(1)));
+    }
+
+    #[test]
+    fn test_partitioned_node_catches_up_without_going_back() {
+        let (mut a, mut b, mut c) = (gossiper(1, 1), gossiper(2, 1), gossiper(3, 1));
+        round(&mut a, &mut b);
+        round(&mut b, &mut c);
+        // c is isolated while a changes its state twice
+        a.set_application_state(ApplicationState::Status, State::StandBy.to_string());
+        a.set_application_state(ApplicationState::Status, State::ShuttingDown.to_string());
+        round(&mut a, &mut b);
+        assert_eq!(status(&b, 1), Some("ShuttingDown"));
+        // what c knows is older and does not overwrite what b knows
+        let stale = c.get_state(&ip(1)).unwrap().clone();
+        assert!(!b.apply(vec![(ip(1), stale)]));
+        round(&mut c, &mut b);
+        assert_eq!(status(&b, 1), Some("ShuttingDown"));
+        assert_eq!(status(&c, 1), Some("ShuttingDown"));
+    }
+
+    #[test]
+    fn test_restarted_node_replaces_its_old_state() {
+        let (mut a, mut b, mut c) = (gossiper(1, 1), gossiper(2, 1), gossiper(3, 1));
+        for _ in 0..10 {
+            a.beat();
+        }
+        round(&mut a, &mut b);
+        round(&mut b, &mut c);
+        let mut restarted = Gossiper::new(&ip(1), 2);
+        let mut recovering = node(1);
+        recovering.set_recovering();
+        restarted.publish_local(&recovering);
+        round(&mut restarted, &mut b);
+        let state = b.get_state(&ip(1)).unwrap();
+        assert_eq!(state.get_generation(), 2);
+        assert!(state.max_version() < 10);
+        assert_eq!(status(&b, 1), Some("Recovering"));
+        // c still has the previous generation with higher versions, but it does not come back
+        round(&mut c, &mut b);
+        assert_eq!(b.get_state(&ip(1)).unwrap().get_generation(), 2);
+        assert_eq!(status(&c, 1), Some("Recovering"));
+    }
+
+    #[test]
+    fn test_removed_node_comes_back_only_after_restarting() {
+        let (mut a, mut b, mut c) = (gossiper(1, 1), gossiper(2, 1), gossiper(3, 1));
+        round(&mut a, &mut b);
+        round(&mut b, &mut c);
+        b.remove(&ip(1));
+        round(&mut c, &mut b);
+        assert!(b.get_state(&ip(1)).is_none());
+        round(&mut b, &mut c);
+        assert!(b.get_state(&ip(1)).is_none());
+        let mut restarted = gossiper(1, 2);
+        round(&mut restarted, &mut b);
+        assert_eq!(b.get_state(&ip(1)).unwrap().get_generation(), 2);
+    }
+
+    #[test]
+    fn test_cluster_view_follows_the_gossiped_state() {
+        let (mut a, mut b, mut c) = (gossiper(1, 1), gossiper(2, 1), gossiper(3, 1));
+        round(&mut a, &mut b);
+        round(&mut c, &mut b);
+        let mut down = node(1);
+        down.set_inactive();
+        let cluster = Cluster::new(node(2), vec![down]);
+        let (merged, changed) = b.merge_into(&cluster);
+        assert!(changed);
+        let nodes = merged.get_other_nodes();
+        assert_eq!(nodes.len(), 2);
+        // the local failure detector decides the node is down
+        assert_eq!(nodes[0].state, State::Inactive);
+        assert_eq!(nodes[1].get_ip(), &ip(3));
+        assert_eq!(nodes[1].get_tokens(), &[300]);
+        let (_, changed) = b.merge_into(&merged);
+        assert!(!changed);
+        a.set_application_state(ApplicationState::Status, State::ShuttingDown.to_string());
+        round(&mut a, &mut b);
+        let (merged, changed) = b.merge_into(&merged);
+        assert!(changed);
+        assert_eq!(merged.get_other_nodes()[0].state, State::ShuttingDown);
+    }
+
+    #[test]
+    fn test_node_finishing_its_bootstrap_changes_the_ring() {
+        let (mut a, mut b) = (gossiper(1, 1), gossiper(2, 1));
+        let mut booting = node(1);
+        booting.set_booting();
+        a.publish_local(&booting);
+        round(&mut a, &mut b);
+        let (merged, changed) = b.merge_into(&Cluster::new(node(2), Vec::new()));
+        assert!(changed);
+        assert_eq!(merged.get_other_nodes()[0].state, State::Booting);
+        let (merged, changed) = b.merge_into(&merged);
+        assert!(!changed);
+        a.publish_local(&node(1));
+        round(&mut a, &mut b);
+        let (merged, changed) = b.merge_into(&merged);
+        assert!(changed);
+        assert_eq!(merged.get_other_nodes()[0].state, State::Active);
+    }
+
+    #[test]
+    fn test_removal_announced_by_others_wins_over_the_dead_node_state() {
+        let (mut a, mut b, mut c) = (gossiper(1, 1), gossiper(2, 1), gossiper(3, 1));
+        for _ in 0..5 {
+            a.beat();
+        }
+        round(&mut a, &mut b);
+        round(&mut a, &mut c);
+        assert!(b.announce_removed(&ip(2)).is_err());
+        assert!(b.announce_removed(&ip(4)).is_err());
+        b.announce_removed(&ip(1)).unwrap();
+        assert_eq!(status(&b, 1), Some("ShuttingDown"));
+        round(&mut b, &mut c);
+        assert_eq!(status(&c, 1), Some("ShuttingDown"));
+        assert_eq!(c.get_state(&ip(1)), b.get_state(&ip(1)));
+    }
+}
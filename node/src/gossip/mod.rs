@@ -1,3 +1,8 @@
+pub mod endpoint_state;
+pub mod failure_detector;
+pub mod gossip_digest;
 pub mod gossip_emitter;
 pub mod gossip_listener;
+pub mod gossiper;
+pub mod schema_agreement;
 pub mod seed_listener;
@@ -0,0 +1,227 @@
+use crate::event_bus;
+use crate::gossip::failure_detector;
+use crate::gossip::gossip_digest::GossipMessage;
+use crate::gossip::gossip_emitter::GossipEmitter;
+use crate::logger::Logger;
+use crate::meta_data::meta_data_handler::{use_keyspace_meta_data, use_node_meta_data};
+use crate::meta_data::nodes::cluster::Cluster;
+use crate::meta_data::nodes::node::{Node, State};
+use crate::parsers::query_parser::{query_lexer, query_parser};
+use crate::utils::config_constants::{SCHEMA_AGREEMENT_POLL_MILLIS, SCHEMA_AGREEMENT_WAIT_MILLIS};
+use crate::utils::constants::{KEYSPACE_METADATA_PATH, LOGGER_PATH, NODES_METADATA_PATH};
+use crate::utils::errors::Errors;
+use crate::utils::functions::{
+    connect_to_socket, deserialize_from_slice, read_exact_from_stream, serialize_to_string,
+    write_to_stream,
+};
+use crate::utils::types::node_ip::NodeIp;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// DDL of each keyspace as `describe_keyspace` returns it: (type, name, statement)
+pub type Schema = BTreeMap<String, Vec<(String, String, String)>>;
+
+/// version of the other node and the own one with which the schema of each node was already pulled
+static PULLED: Mutex<BTreeMap<NodeIp, (String, String)>> = Mutex::new(BTreeMap::new());
+
+pub fn get_schema_version() -> Result<String, Errors> {
+    use_keyspace_meta_data(|handler| handler.get_schema_version(KEYSPACE_METADATA_PATH.to_string()))
+}
+
+/// Waits, at most `SCHEMA_AGREEMENT_WAIT_MILLIS`, for every node that is up to have the
+/// same schema version as this one. The own version is published first, so the rest
+/// learn it in the next gossip round.
+///
+/// Returns whether there was agreement
+pub fn wait_for_agreement() -> Result<bool, Errors> {
+    GossipEmitter::publish_own_state()?;
+    let version = get_schema_version()?;
+    let deadline = Instant::now() + Duration::from_millis(SCHEMA_AGREEMENT_WAIT_MILLIS);
+    loop {
+        let cluster = use_node_meta_data(|handler| handler.get_cluster(&NODES_METADATA_PATH))?;
+        if live_nodes_with_other_version(&cluster, &version).is_empty() {
+            return Ok(true);
+        }
+        if Instant::now() >= deadline {
+            return Ok(false);
+        }
+        sleep(Duration::from_millis(SCHEMA_AGREEMENT_POLL_MILLIS));
+    }
+}
+
+/// the nodes that are up and do not report the given schema version
+fn live_nodes_with_other_version<'a>(cluster: &'a Cluster, version: &str) -> Vec<&'a Node> {
+    cluster
+        .get_other_nodes()
+        .iter()
+        .filter(|node| matches!(node.state, State::Active | State::StandBy))
+        .filter(|node| failure_detector::is_alive(node.get_ip()))
+        .filter(|node| node.schema_version != version)
+        .collect()
+}
+
+/// If a node that is up gossips another schema version its schema is requested and what is
+/// missing here is created, for example a table created while this node was down.
+///
+/// What was dropped in the meantime arrives with the hints. Each node is tried once per
+/// pair of versions
+pub fn pull_missing_schema() -> Result<(), Errors> {
+    let version = get_schema_version()?;
+    let cluster = use_node_meta_data(|handler| handler.get_cluster(&NODES_METADATA_PATH))?;
+    for node in live_nodes_with_other_version(&cluster, &version) {
+        if node.schema_version.is_empty() {
+            continue;
+        }
+        let versions = (node.schema_version.to_string(), version.to_string());
+        let mut pulled = PULLED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if pulled.get(node.get_ip()) == Some(&versions) {
+            continue;
+        }
+        pulled.insert(NodeIp::new_from_ip(node.get_ip()), versions);
+        drop(pulled);
+        return pull_schema(node.get_ip());
+    }
+    Ok(())
+}
+
+/// creates what the node has in its schema and is missing here
+pub fn pull_schema(ip: &NodeIp) -> Result<(), Errors> {
+    let remote = request_schema(ip)?;
+    for statement in missing_statements(&describe_schema()?, remote) {
+        match query_parser(query_lexer(statement.to_string())?)?.run() {
+            Ok(result) => event_bus::publish_if_schema_change(&result),
+            Err(e) => {
+                Logger::new(&LOGGER_PATH).log_error(&format!(
+                    "Could not apply the schema of {}, {}: {}",
+                    ip.get_string_ip(),
+                    statement,
+                    e
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn request_schema(ip: &NodeIp) -> Result<Schema, Errors> {
+    let mut stream = connect_to_socket(ip.get_gossip_socket())?;
+    let serialized = serialize_to_string(&GossipMessage::SchemaRequest)?;
+    write_to_stream(&mut stream, serialized.as_bytes())?;
+    let buf = read_exact_from_stream(&mut stream)?;
+    match deserialize_from_slice(buf.as_slice())? {
+        GossipMessage::Schema(schema) => Ok(schema),
+        _ => Err(Errors::ServerError(String::from(
+            "Expected the schema of the node",
+        ))),
+    }
+}
+
+/// the DDL of every keyspace of the node
+pub fn describe_schema() -> Result<Schema, Errors> {
+    use_keyspace_meta_data(|handler| {
+        let mut schema = Schema::new();
+        for keyspace in handler.get_keyspaces_names(KEYSPACE_METADATA_PATH.to_string())? {
+            let statements =
+                handler.describe_keyspace(KEYSPACE_METADATA_PATH.to_string(), &keyspace)?;
+            schema.insert(keyspace, statements);
+        }
+        Ok(schema)
+    })
+}
+
+/// statements of the keyspaces, types and tables that do not exist locally, in the order in
+/// which they have to be created
+fn missing_statements(local: &Schema, remote: Schema) -> Vec<String> {
+    let mut statements = Vec::new();
+    for (keyspace, remote_statements) in remote {
+        let known = local.get(&keyspace);
+        for (kind, name, statement) in remote_statements {
+            let exists = known.is_some_and(|known| {
+                known
+                    .iter()
+                    .any(|(known_kind, known_name, _)| known_kind == &kind && known_name == &name)
+            });
+            if !exists {
+                statements.push(statement);
+            }
+        }
+    }
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(last_byte: u8) -> NodeIp {
+        NodeIp::new_from_string(&format!("10.0.4.{}", last_byte), 9042).unwrap()
+    }
+
+    fn node(last_byte: u8, schema_version: &str, state: State) -> Node {
+        let mut node = Node::new(&ip(last_byte), last_byte as usize, false, Vec::new()).unwrap();
+        node.schema_version = schema_version.to_string();
+        node.state = state;
+        node
+    }
+
+    fn statement(kind: &str, name: &str) -> (String, String, String) {
+        (
+            kind.to_string(),
+            name.to_string(),
+            format!("CREATE {} {}", kind, name),
+        )
+    }
+
+    #[test]
+    fn test_only_live_nodes_must_agree() {
+        let cluster = Cluster::new(
+            node(1, "a", State::Active),
+            vec![
+                node(2, "a", State::Active),
+                node(3, "b", State::Inactive),
+                node(4, "b", State::ShuttingDown),
+                node(5, "b", State::StandBy),
+            ],
+        );
+        let disagreeing = live_nodes_with_other_version(&cluster, "a");
+        assert_eq!(disagreeing.len(), 1);
+        assert_eq!(disagreeing[0].get_ip(), &ip(5));
+        assert_eq!(live_nodes_with_other_version(&cluster, "b").len(), 1);
+    }
+
+    #[test]
+    fn test_only_missing_objects_are_created_in_order() {
+        let local = Schema::from([(
+            "ks".to_string(),
+            vec![statement("keyspace", "ks"), statement("table", "users")],
+        )]);
+        let remote = Schema::from([
+            (
+                "ks".to_string(),
+                vec![
+                    statement("keyspace", "ks"),
+                    statement("type", "address"),
+                    statement("table", "users"),
+                    statement("table", "flights"),
+                ],
+            ),
+            (
+                "other".to_string(),
+                vec![statement("keyspace", "other"), statement("table", "users")],
+            ),
+        ]);
+        assert_eq!(
+            missing_statements(&local, remote),
+            vec![
+                "CREATE type address",
+                "CREATE table flights",
+                "CREATE keyspace other",
+                "CREATE table users"
+            ]
+        );
+    }
+}
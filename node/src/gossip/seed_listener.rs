@@ -1,3 +1,4 @@
+use crate::event_bus;
 use crate::meta_data::meta_data_handler::use_node_meta_data;
 use crate::meta_data::nodes::node::Node;
 use crate::utils::constants::NODES_METADATA_PATH;
@@ -26,7 +27,7 @@ impl SeedListener {
 
     fn send_nodes_list(stream: &mut TcpStream) -> Result<(), Errors> {
         let cluster =
-            use_node_meta_data(|handler| handler.get_full_nodes_list(NODES_METADATA_PATH))?;
+            use_node_meta_data(|handler| handler.get_full_nodes_list(&NODES_METADATA_PATH))?;
         let serialized = serialize_to_string(&cluster)?;
         write_to_stream(stream, serialized.as_bytes())
     }
@@ -38,20 +39,21 @@ impl SeedListener {
 
     fn set_new_node(new_node: &Node) -> Result<(), Errors> {
         use_node_meta_data(|node_metadata| {
-            let cluster = node_metadata.get_cluster(NODES_METADATA_PATH)?;
+            let cluster = node_metadata.get_cluster(&NODES_METADATA_PATH)?;
             for node in cluster.get_other_nodes().iter() {
                 if node.get_ip() == new_node.get_ip() {
-                    node_metadata.set_recovering(NODES_METADATA_PATH, new_node.get_ip())?;
+                    node_metadata.set_recovering(&NODES_METADATA_PATH, new_node.get_ip())?;
                     return Ok(());
                 }
             }
-            node_metadata.append_new_node(NODES_METADATA_PATH, Node::new_from_node(new_node))?;
-            node_metadata.update_ranges(NODES_METADATA_PATH)
+            node_metadata.append_new_node(&NODES_METADATA_PATH, Node::new_from_node(new_node))?;
+            event_bus::publish_new_node(new_node.get_ip());
+            node_metadata.update_ranges(&NODES_METADATA_PATH)
         })
     }
 
+    /// the new node fetches its data itself, here only the schema is sent
     fn redistribute(new_node: Node) -> Result<(), Errors> {
-        MessageSender::send_meta_data(new_node.get_ip().clone())?;
-        MessageSender::redistribute()
+        MessageSender::send_meta_data(new_node.get_ip().clone())
     }
 }
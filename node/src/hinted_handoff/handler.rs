@@ -1,73 +1,124 @@
+use crate::hinted_handoff::hint_metrics;
+use crate::hinted_handoff::hint_store::{HintPosition, HintStore};
+use crate::hinted_handoff::hints_config::HintsConfig;
 use crate::hinted_handoff::stored_query::StoredQuery;
-use crate::utils::constants::HINTED_HANDOFF_DATA;
+use crate::logger::Logger;
+use crate::utils::config_constants::HINTS_SEGMENT_KB;
+use crate::utils::constants::{HINTED_HANDOFF_DATA, LOGGER_PATH};
 use crate::utils::errors::Errors;
-use crate::utils::errors::Errors::ServerError;
-use crate::utils::functions::{deserialize_from_slice, serialize_to_string, write_all_to_file};
+use crate::utils::functions::{deserialize_from_slice, serialize_to_string};
 use crate::utils::types::node_ip::NodeIp;
-use std::fs;
-use std::fs::{rename, File, OpenOptions};
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+/// clients store hints while gossip replays or expires them
+static HINTS: Mutex<()> = Mutex::new(());
 
 pub struct Handler;
 
 impl Handler {
-    /// Stores a hint for de node with the ip given
+    /// Stores a hint for de node with the ip given. If it already has
+    /// `max_hints_size_per_node_kb` of hints the hint is dropped and `Overloaded` is returned,
+    /// so the write is not acknowledged without a copy
     pub fn store_query(query: StoredQuery, ip: NodeIp) -> Result<(), Errors> {
-        fs::create_dir_all(HINTED_HANDOFF_DATA).map_err(|e| ServerError(e.to_string()))?;
-        let path = format!("{}/{}.txt", HINTED_HANDOFF_DATA, ip.get_string_ip());
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&path)
-            .map_err(|_| ServerError(String::from("failed to open file")))?;
-
-        let line = format!("{}\n", serialize_to_string(&query)?);
-        write_all_to_file(&mut file, line.as_bytes())
+        let max_bytes = HintsConfig::configured().max_hints_size_per_node_kb * 1024;
+        let _lock = lock();
+        let store = Self::store_for(&ip);
+        if store.size()? >= max_bytes {
+            hint_metrics::record(|metrics| metrics.dropped += 1);
+            let message = format!(
+                "Hint for {} dropped, it already has {} KB of hints",
+                ip.get_string_ip(),
+                max_bytes / 1024
+            );
+            Logger::new(&LOGGER_PATH).log_error(&message);
+            return Err(Errors::Overloaded(message));
+        }
+        store.append(&serialize_to_string(&query)?)?;
+        hint_metrics::record(|metrics| metrics.stored += 1);
+        Ok(())
     }
 
+    /// Drops the hints older than `max_hint_window_secs`. Since they are stored in order, the
+    /// expired hints of each node are always the first ones left to replay
     pub fn check_for_perished() -> Result<(), Errors> {
-        fs::create_dir_all(HINTED_HANDOFF_DATA).map_err(|e| ServerError(e.to_string()))?;
-        for entry in fs::read_dir(HINTED_HANDOFF_DATA)
-            .map_err(|_| ServerError(String::from("cannot read directory")))?
-        {
-            let entry = entry.map_err(|_| ServerError(String::from("cannot read directory")))?;
-            let path = entry.path();
-            let file =
-                File::open(&path).map_err(|_| ServerError(String::from("cannot open file")))?;
-            let mut reader = BufReader::new(file);
-            let mut first_line = String::new();
-            if reader
-                .read_line(&mut first_line)
-                .map_err(|_| ServerError(String::from("cannot open file")))?
-                > 0
-            {
-                let stored_query: StoredQuery =
-                    deserialize_from_slice(first_line.trim().as_bytes())?;
-                if stored_query.has_perished() {
-                    Self::eliminate_perished(path)?
-                }
-            }
+        let window = HintsConfig::configured().max_hint_window_secs;
+        let _lock = lock();
+        for store in HintStore::all(&HINTED_HANDOFF_DATA, HINTS_SEGMENT_KB * 1024)? {
+            let expired = store.skip_while(|hint| {
+                deserialize_from_slice::<StoredQuery>(hint.as_bytes())
+                    .map_or(true, |stored_query| stored_query.has_perished(window))
+            })?;
+            hint_metrics::record(|metrics| metrics.expired += expired as u64);
         }
         Ok(())
     }
 
-    fn eliminate_perished(path: PathBuf) -> Result<(), Errors> {
-        let file = File::open(&path).map_err(|_| ServerError(String::from("cannot open file")))?;
-        let reader = BufReader::new(file);
+    /// Removes every stored hint that modifies the given table
+    pub fn remove_table_hints(table_name: &str) -> Result<(), Errors> {
+        let _lock = lock();
+        for store in HintStore::all(&HINTED_HANDOFF_DATA, HINTS_SEGMENT_KB * 1024)? {
+            store.retain(|hint| {
+                deserialize_from_slice::<StoredQuery>(hint.as_bytes())
+                    .is_ok_and(|stored_query| !stored_query.targets_table(table_name))
+            })?;
+        }
+        Ok(())
+    }
 
-        let mut temp_path = path.clone();
-        temp_path.set_extension("tmp");
-        let mut temp_file =
-            File::create(&temp_path).map_err(|_| ServerError(String::from("cannot open file")))?;
+    /// where the replay of the hints of the node has to continue
+    pub fn replay_position(ip: &NodeIp) -> Result<HintPosition, Errors> {
+        let _lock = lock();
+        Self::store_for(ip).checkpoint()
+    }
 
-        for line in reader.lines().map_while(Result::ok) {
-            let stored_query: StoredQuery = deserialize_from_slice(line.trim().as_bytes())?;
-            if !stored_query.has_perished() {
-                write_all_to_file(&mut temp_file, line.as_bytes())?;
-            }
-        }
-        rename(temp_path, path).map_err(|_| ServerError(String::from("cannot rename file")))?;
+    pub fn next_hint(
+        ip: &NodeIp,
+        position: HintPosition,
+    ) -> Result<Option<(String, HintPosition)>, Errors> {
+        let _lock = lock();
+        Self::store_for(ip).read(position)
+    }
+
+    /// the node acknowledged the hints before `position`, they are not sent again
+    pub fn mark_replayed(ip: &NodeIp, position: HintPosition) -> Result<(), Errors> {
+        let _lock = lock();
+        Self::store_for(ip).commit(position)?;
+        hint_metrics::record(|metrics| metrics.replayed += 1);
         Ok(())
     }
+
+    fn store_for(ip: &NodeIp) -> HintStore {
+        HintStore::new(&HINTED_HANDOFF_DATA, ip, HINTS_SEGMENT_KB * 1024)
+    }
+}
+
+fn lock() -> MutexGuard<'static, ()> {
+    HINTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queries::query::QueryEnum;
+    use crate::queries::use_query::UseQuery;
+    use crate::utils::types::timestamp::Timestamp;
+
+    #[test]
+    fn test_hint_over_the_limit_is_an_error() {
+        HintsConfig {
+            max_hints_size_per_node_kb: 0,
+            ..HintsConfig::default()
+        }
+        .configure();
+        let query = StoredQuery {
+            query: QueryEnum::Use(UseQuery::new()),
+            timestamp: Timestamp::new(),
+        };
+        let ip = NodeIp::new_from_single_string("127.0.0.250:9042").unwrap();
+        let result = Handler::store_query(query, ip);
+        HintsConfig::default().configure();
+        assert!(matches!(result, Err(Errors::Overloaded(_))));
+    }
 }
@@ -0,0 +1,46 @@
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+static METRICS: Mutex<HintMetrics> = Mutex::new(HintMetrics {
+    stored: 0,
+    replayed: 0,
+    expired: 0,
+    dropped: 0,
+});
+
+/// Counters of the hints since the node started
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct HintMetrics {
+    pub stored: u64,
+    pub replayed: u64,
+    pub expired: u64,
+    /// the ones that were not stored because the node already had the maximum of hints
+    pub dropped: u64,
+}
+
+impl fmt::Display for HintMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} stored, {} replayed, {} expired, {} dropped",
+            self.stored, self.replayed, self.expired, self.dropped
+        )
+    }
+}
+
+fn metrics() -> MutexGuard<'static, HintMetrics> {
+    METRICS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn record<F>(update: F)
+where
+    F: FnOnce(&mut HintMetrics),
+{
+    update(&mut metrics())
+}
+
+pub fn snapshot() -> HintMetrics {
+    *metrics()
+}
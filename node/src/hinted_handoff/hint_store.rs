@@ -0,0 +1,323 @@
+use crate::utils::errors::Errors;
+use crate::utils::errors::Errors::ServerError;
+use crate::utils::functions::{deserialize_from_slice, serialize_to_string, write_all_to_file};
+use crate::utils::types::node_ip::NodeIp;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::{rename, File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const CHECKPOINT_FILE: &str = "checkpoint.json";
+
+/// Place of a hint: the segment and the byte where it starts
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HintPosition {
+    pub segment: u64,
+    pub offset: u64,
+}
+
+/// Hints stored for a node.
+///
+/// They are appended one per line to the last segment of its directory and, when it goes over
+/// `segment_bytes`, a new one is started. The checkpoint keeps the position of the first hint
+/// still to be replayed: everything before it was already consumed and the segments left fully
+/// behind are deleted
+pub struct HintStore {
+    dir: PathBuf,
+    segment_bytes: u64,
+}
+
+impl HintStore {
+    pub fn new(root: &str, ip: &NodeIp, segment_bytes: u64) -> Self {
+        Self {
+            dir: Path::new(root).join(ip.get_string_ip()),
+            segment_bytes,
+        }
+    }
+
+    /// the stores of every node with hints under `root`
+    pub fn all(root: &str, segment_bytes: u64) -> Result<Vec<HintStore>, Errors> {
+        if !Path::new(root).exists() {
+            return Ok(Vec::new());
+        }
+        let mut stores = Vec::new();
+        for entry in
+            fs::read_dir(root).map_err(|_| ServerError(String::from("cannot read directory")))?
+        {
+            let entry = entry.map_err(|_| ServerError(String::from("cannot read directory")))?;
+            if entry.path().is_dir() {
+                stores.push(Self {
+                    dir: entry.path(),
+                    segment_bytes,
+                });
+            }
+        }
+        Ok(stores)
+    }
+
+    pub fn append(&self, hint: &str) -> Result<(), Errors> {
+        fs::create_dir_all(&self.dir).map_err(|e| ServerError(e.to_string()))?;
+        let segment = match self.segments()?.last() {
+            Some(last) if self.segment_len(*last) < self.segment_bytes => *last,
+            Some(last) => last + 1,
+            None => self.checkpoint()?.segment,
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.segment_path(segment))
+            .map_err(|_| ServerError(String::from("failed to open file")))?;
+        write_all_to_file(&mut file, format!("{}\n", hint).as_bytes())
+    }
+
+    /// bytes of the segments, including the hints already replayed of the first one
+    pub fn size(&self) -> Result<u64, Errors> {
+        Ok(self
+            .segments()?
+            .into_iter()
+            .map(|segment| self.segment_len(segment))
+            .sum())
+    }
+
+    /// position of the first hint that was not consumed
+    pub fn checkpoint(&self) -> Result<HintPosition, Errors> {
+        let first = HintPosition {
+            segment: self.segments()?.first().copied().unwrap_or(0),
+            offset: 0,
+        };
+        match fs::read(self.dir.join(CHECKPOINT_FILE)) {
+            Ok(content) => Ok(deserialize_from_slice::<HintPosition>(&content)?.max(first)),
+            Err(_) => Ok(first),
+        }
+    }
+
+    /// The hint at the position along with the position of the next one, None if there are none left.
+    /// An unfinished line is still being written, it does not count yet
+    pub fn read(&self, position: HintPosition) -> Result<Option<(String, HintPosition)>, Errors> {
+        for segment in self.segments()? {
+            if segment < position.segment {
+                continue;
+            }
+            let offset = if segment == position.segment {
+                position.offset
+            } else {
+                0
+            };
+            let mut reader = self.open_at(segment, offset)?;
+            let mut line = String::new();
+            let read = reader
+                .read_line(&mut line)
+                .map_err(|_| ServerError(String::from("cannot read hints")))?;
+            if read == 0 {
+                continue;
+            }
+            if !line.ends_with('\n') {
+                return Ok(None);
+            }
+            let next = HintPosition {
+                segment,
+                offset: offset + read as u64,
+            };
+            return Ok(Some((line.trim_end().to_string(), next)));
+        }
+        Ok(None)
+    }
+
+    /// Records that everything before the position was consumed. If nothing is left the whole
+    /// directory is deleted
+    pub fn commit(&self, position: HintPosition) -> Result<(), Errors> {
+        let segments = self.segments()?;
+        let Some(last) = segments.last().copied() else {
+            return Ok(());
+        };
+        if position.segment > last
+            || (position.segment == last && position.offset >= self.segment_len(last))
+        {
+            return fs::remove_dir_all(&self.dir).map_err(|e| ServerError(e.to_string()));
+        }
+        for segment in segments
+            .iter()
+            .filter(|segment| **segment < position.segment)
+        {
+            fs::remove_file(self.segment_path(*segment))
+                .map_err(|_| ServerError(String::from("Failed to remove file")))?;
+        }
+        fs::write(
+            self.dir.join(CHECKPOINT_FILE),
+            serialize_to_string(&position)?,
+        )
+        .map_err(|_| ServerError(String::from("Failed to write to file")))
+    }
+
+    /// consumes the hints at the start while they match `skip`, returns how many
+    pub fn skip_while<F>(&self, skip: F) -> Result<usize, Errors>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut position = self.checkpoint()?;
+        let mut skipped = 0;
+        while let Some((hint, next)) = self.read(position)? {
+            if !skip(&hint) {
+                break;
+            }
+            position = next;
+            skipped += 1;
+        }
+        if skipped > 0 {
+            self.commit(position)?;
+        }
+        Ok(skipped)
+    }
+
+    /// Rewrites the segments keeping only the unconsumed hints that match `keep`, returns
+    /// how many were removed
+    pub fn retain<F>(&self, keep: F) -> Result<usize, Errors>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let checkpoint = self.checkpoint()?;
+        let mut removed = 0;
+        for segment in self.segments()? {
+            let offset = if segment == checkpoint.segment {
+                checkpoint.offset
+            } else {
+                0
+            };
+            let reader = self.open_at(segment, offset)?;
+            let mut temp_path = self.segment_path(segment);
+            temp_path.set_extension("tmp");
+            let mut temp_file = File::create(&temp_path)
+                .map_err(|_| ServerError(String::from("cannot open file")))?;
+            for line in reader.lines().map_while(Result::ok) {
+                if keep(&line) {
+                    write_all_to_file(&mut temp_file, format!("{}\n", line).as_bytes())?;
+                } else {
+                    removed += 1;
+                }
+            }
+            rename(temp_path, self.segment_path(segment))
+                .map_err(|_| ServerError(String::from("cannot rename file")))?;
+        }
+        self.commit(HintPosition {
+            segment: checkpoint.segment,
+            offset: 0,
+        })?;
+        Ok(removed)
+    }
+
+    /// ids of the segments, oldest first
+    fn segments(&self) -> Result<Vec<u64>, Errors> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut segments = Vec::new();
+        for entry in fs::read_dir(&self.dir)
+            .map_err(|_| ServerError(String::from("cannot read directory")))?
+        {
+            let path = entry
+                .map_err(|_| ServerError(String::from("cannot read directory")))?
+                .path();
+            if path.extension().is_some_and(|extension| extension == "txt") {
+                if let Some(segment) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse().ok())
+                {
+                    segments.push(segment);
+                }
+            }
+        }
+        segments.sort();
+        Ok(segments)
+    }
+
+    fn segment_path(&self, segment: u64) -> PathBuf {
+        self.dir.join(format!("{:08}.txt", segment))
+    }
+
+    fn segment_len(&self, segment: u64) -> u64 {
+        fs::metadata(self.segment_path(segment))
+            .map(|metadata| metadata.len())
+            .unwrap_or(0)
+    }
+
+    fn open_at(&self, segment: u64, offset: u64) -> Result<BufReader<File>, Errors> {
+        let mut file = File::open(self.segment_path(segment))
+            .map_err(|_| ServerError(String::from("cannot open file")))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|_| ServerError(String::from("cannot read hints")))?;
+        Ok(BufReader::new(file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(name: &str, segment_bytes: u64) -> HintStore {
+        let root = std::env::temp_dir().join(format!("hint_store_{}", name));
+        let _ = fs::remove_dir_all(&root);
+        let ip = NodeIp::new_from_single_string("127.0.0.1:9042").unwrap();
+        HintStore::new(root.to_str().unwrap(), &ip, segment_bytes)
+    }
+
+    fn replay(store: &HintStore, count: usize) -> Vec<String> {
+        let mut position = store.checkpoint().unwrap();
+        let mut replayed = Vec::new();
+        while replayed.len() < count {
+            let Some((hint, next)) = store.read(position).unwrap() else {
+                break;
+            };
+            replayed.push(hint);
+            position = next;
+            store.commit(position).unwrap();
+        }
+        replayed
+    }
+
+    #[test]
+    fn test_segments_roll_over_and_replay_resumes_from_the_checkpoint() {
+        let store = store("resume", 10);
+        for hint in ["hint-1", "hint-2", "hint-3", "hint-4"] {
+            store.append(hint).unwrap();
+        }
+        // each segment goes over 10 bytes with the second hint
+        assert_eq!(store.segments().unwrap(), vec![0, 1]);
+        assert_eq!(store.size().unwrap(), 28);
+        assert_eq!(replay(&store, 3), vec!["hint-1", "hint-2", "hint-3"]);
+        // the first segment was consumed entirely
+        assert_eq!(store.segments().unwrap(), vec![1]);
+        store.append("hint-5").unwrap();
+        assert_eq!(replay(&store, 10), vec!["hint-4", "hint-5"]);
+        assert!(!store.dir.exists());
+        store.append("hint-6").unwrap();
+        assert_eq!(replay(&store, 10), vec!["hint-6"]);
+    }
+
+    #[test]
+    fn test_expired_hints_are_skipped_only_from_the_start() {
+        let store = store("expire", 1024);
+        for hint in ["old-1", "old-2", "new-1", "old-3"] {
+            store.append(hint).unwrap();
+        }
+        assert_eq!(store.skip_while(|hint| hint.starts_with("old")).unwrap(), 2);
+        assert_eq!(store.skip_while(|hint| hint.starts_with("old")).unwrap(), 0);
+        assert_eq!(replay(&store, 10), vec!["new-1", "old-3"]);
+        let _ = fs::remove_dir_all(store.dir);
+    }
+
+    #[test]
+    fn test_retained_hints_drop_the_consumed_ones() {
+        let store = store("retain", 1024);
+        for hint in ["a-1", "b-1", "a-2", "b-2"] {
+            store.append(hint).unwrap();
+        }
+        assert_eq!(replay(&store, 1), vec!["a-1"]);
+        assert_eq!(store.retain(|hint| hint.starts_with('a')).unwrap(), 2);
+        assert_eq!(store.checkpoint().unwrap(), HintPosition::default());
+        assert_eq!(store.size().unwrap(), 4);
+        assert_eq!(replay(&store, 10), vec!["a-2"]);
+        assert!(!store.dir.exists());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::utils::config_constants::{
+    HINTED_HANDOFF_THROTTLE_KB, MAX_HINTS_SIZE_PER_NODE_KB, MAX_HINT_WINDOW_SECS,
+};
+use std::sync::Mutex;
+
+static CONFIGURED: Mutex<HintsConfig> = Mutex::new(HintsConfig::DEFAULT);
+
+/// Limits of the hints stored for the nodes that are down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HintsConfig {
+    /// hints older than this are no longer replayed, the node catches up with `repair`
+    pub max_hint_window_secs: i64,
+    /// past this size the new hints of a node are discarded
+    pub max_hints_size_per_node_kb: u64,
+    /// maximum replay speed, 0 does not limit it
+    pub hinted_handoff_throttle_kb: u64,
+}
+
+impl HintsConfig {
+    const DEFAULT: HintsConfig = HintsConfig {
+        max_hint_window_secs: MAX_HINT_WINDOW_SECS,
+        max_hints_size_per_node_kb: MAX_HINTS_SIZE_PER_NODE_KB,
+        hinted_handoff_throttle_kb: HINTED_HANDOFF_THROTTLE_KB,
+    };
+
+    /// sets the limits used from now on by the hinted handoff
+    pub fn configure(self) {
+        *CONFIGURED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = self;
+    }
+
+    pub fn configured() -> HintsConfig {
+        *CONFIGURED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for HintsConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -1,3 +1,4 @@
+use crate::hinted_handoff::hints_sender::ACK;
 use crate::hinted_handoff::stored_query::StoredQuery;
 use crate::logger::Logger;
 use crate::meta_data::meta_data_handler::use_node_meta_data;
@@ -20,10 +21,9 @@ impl HintsReceiver {
         listener
             .set_nonblocking(true)
             .map_err(|_| ServerError(String::from("Could not set nonblocking")))?;
-        let mut hints = Vec::new();
         let timeout = Duration::from_secs(HINTED_HANDOFF_TIMEOUT_SECS);
         let mut last_connection_time = Instant::now();
-        let logger = Logger::new(LOGGER_PATH);
+        let logger = Logger::new(&LOGGER_PATH);
         logger.log_message("Recovering hints...");
         loop {
             if last_connection_time.elapsed() >= timeout {
@@ -31,39 +31,38 @@ impl HintsReceiver {
             }
             match listener.accept() {
                 Ok((mut stream, _)) => {
-                    Self::handle_connection(&mut stream, &mut hints)?;
+                    Self::handle_connection(&mut stream, &logger)?;
                     last_connection_time = Instant::now();
                 }
                 _ => continue,
             }
         }
-        Self::execute_queries(&mut hints)?;
         Self::finish_recovering()?;
         Ok(())
     }
 
-    fn handle_connection(
-        stream: &mut TcpStream,
-        hints: &mut Vec<StoredQuery>,
-    ) -> Result<(), Errors> {
-        while let Ok(hint) = deserialize_from_slice(&read_exact_from_stream(stream)?) {
-            hints.push(hint);
-            write_to_stream(stream, b"ACK")?;
-        }
-        Ok(())
-    }
-
-    fn execute_queries(hints: &mut [StoredQuery]) -> Result<(), Errors> {
-        hints.sort_by_key(|stored_query| stored_query.timestamp.timestamp);
-        for stored in hints.iter() {
-            if stored.get_query().run().is_ok() {};
+    /// Every hint is executed before it is acknowledged, the sender only moves its checkpoint
+    /// past the hints that were already applied here. If the connection is cut it keeps
+    /// listening, the sender resumes from the first hint that was not acknowledged
+    fn handle_connection(stream: &mut TcpStream, logger: &Logger) -> Result<(), Errors> {
+        while let Ok(buf) = read_exact_from_stream(stream) {
+            let Ok(hint) = deserialize_from_slice::<StoredQuery>(&buf) else {
+                break;
+            };
+            // a hint that cannot be applied is not sent again, it would block the ones after it
+            if let Err(e) = hint.get_query().run() {
+                logger.log_error(&format!("Hint could not be applied: {}", e));
+            }
+            if write_to_stream(stream, ACK).is_err() {
+                break;
+            }
         }
         Ok(())
     }
 
     fn finish_recovering() -> Result<(), Errors> {
-        use_node_meta_data(|handler| handler.set_own_node_active(NODES_METADATA_PATH))?;
-        let logger = Logger::new(LOGGER_PATH);
+        use_node_meta_data(|handler| handler.set_own_node_active(&NODES_METADATA_PATH))?;
+        let logger = Logger::new(&LOGGER_PATH);
         logger.log_message("Finished recovering");
         Ok(())
     }
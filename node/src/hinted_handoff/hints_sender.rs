@@ -1,37 +1,83 @@
-use crate::utils::constants::HINTED_HANDOFF_DATA;
+use crate::hinted_handoff::handler::Handler;
+use crate::hinted_handoff::hints_config::HintsConfig;
 use crate::utils::errors::Errors;
-use crate::utils::errors::Errors::ServerError;
 use crate::utils::functions::{connect_to_socket, read_from_stream_no_zero, write_to_stream};
 use crate::utils::types::node_ip::NodeIp;
-use std::fs;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::net::TcpStream;
-use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// reply of the receiver once a hint was applied
+pub const ACK: &[u8] = b"ACK";
 
 pub struct HintsSender;
 
 impl HintsSender {
+    /// Replays the hints of the node at no more than `hinted_handoff_throttle_kb` per second.
+    ///
+    /// Each acknowledged hint is marked, so if the connection drops the next attempt
+    /// goes on from the first one that did not arrive
     pub fn send_hints(ip: NodeIp) -> Result<(), Errors> {
-        let hints_path = format!("{}/{}.txt", HINTED_HANDOFF_DATA, ip.get_string_ip());
-        if Path::new(&hints_path).exists() {
-            let mut stream = connect_to_socket(ip.get_hints_receiver_socket())?;
-            let file = File::open(Path::new(&hints_path))
-                .map_err(|_| ServerError(String::from("Could not open file")))?;
-            let mut reader = BufReader::new(file).lines();
-            while let Some(Ok(line)) = reader.next() {
-                write_to_stream(&mut stream, line.trim().as_bytes())?;
-                Self::expect_acknowledge(&mut stream)?;
-            }
-            write_to_stream(&mut stream, b"FINISHED")?;
-            fs::remove_file(Path::new(&hints_path))
-                .map_err(|_| ServerError(String::from("Failed to remove file")))?;
+        let position = Handler::replay_position(&ip)?;
+        let Some(mut next) = Handler::next_hint(&ip, position)? else {
+            return Ok(());
         };
-        Ok(())
+        let mut stream = connect_to_socket(ip.get_hints_receiver_socket())?;
+        let throttle_kb = HintsConfig::configured().hinted_handoff_throttle_kb;
+        let started = Instant::now();
+        let mut sent_bytes = 0;
+        loop {
+            let (hint, position) = next;
+            write_to_stream(&mut stream, hint.as_bytes())?;
+            Self::expect_acknowledge(&mut stream)?;
+            Handler::mark_replayed(&ip, position)?;
+            sent_bytes += hint.len() as u64;
+            sleep(throttle_delay(sent_bytes, started.elapsed(), throttle_kb));
+            match Handler::next_hint(&ip, position)? {
+                Some(hint) => next = hint,
+                None => break,
+            }
+        }
+        write_to_stream(&mut stream, b"FINISHED")
     }
 
     fn expect_acknowledge(stream: &mut TcpStream) -> Result<(), Errors> {
-        read_from_stream_no_zero(stream)?;
+        if read_from_stream_no_zero(stream)? != ACK {
+            return Err(Errors::ServerError(String::from(
+                "The hint was not acknowledged",
+            )));
+        }
         Ok(())
     }
 }
+
+/// how long to wait so that what was sent does not exceed `kb_per_sec`, 0 does not limit
+fn throttle_delay(sent_bytes: u64, elapsed: Duration, kb_per_sec: u64) -> Duration {
+    if kb_per_sec == 0 {
+        return Duration::ZERO;
+    }
+    let expected = Duration::from_secs_f64(sent_bytes as f64 / (kb_per_sec * 1024) as f64);
+    expected.saturating_sub(elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_waits_only_when_ahead_of_the_throttle() {
+        assert_eq!(
+            throttle_delay(2048, Duration::ZERO, 1),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            throttle_delay(2048, Duration::from_millis(500), 1),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(
+            throttle_delay(2048, Duration::from_secs(3), 1),
+            Duration::ZERO
+        );
+        assert_eq!(throttle_delay(2048, Duration::ZERO, 0), Duration::ZERO);
+    }
+}
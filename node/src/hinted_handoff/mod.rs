@@ -1,4 +1,7 @@
 pub mod handler;
+pub mod hint_metrics;
+pub mod hint_store;
+pub mod hints_config;
 pub mod hints_receiver;
 pub mod hints_sender;
 pub mod stored_query;
@@ -1,5 +1,4 @@
 use crate::queries::query::{Query, QueryEnum};
-use crate::utils::config_constants::HINTED_HANDOFF_HOURS;
 use crate::utils::errors::Errors;
 use crate::utils::types::timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
@@ -26,7 +25,18 @@ impl StoredQuery {
         QueryEnum::into_query(QueryEnum::new_from(&self.query))
     }
 
-    pub fn has_perished(&self) -> bool {
-        self.timestamp.has_perished_hours(HINTED_HANDOFF_HOURS)
+    /// returns true if the stored query modifies the given table
+    pub fn targets_table(&self, table_name: &str) -> bool {
+        match &self.query {
+            QueryEnum::Insert(query) => query.table_name == table_name,
+            QueryEnum::Update(query) => query.table_name == table_name,
+            QueryEnum::Delete(query) => query.table_name == table_name,
+            _ => false,
+        }
+    }
+
+    /// the hint is older than the hint window, in seconds
+    pub fn has_perished(&self, window_secs: i64) -> bool {
+        self.timestamp.has_perished_seconds(window_secs)
     }
 }
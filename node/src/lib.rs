@@ -1,23 +1,31 @@
 extern crate core;
 
 pub mod auth;
+pub mod batchlog;
 pub mod data_access;
+pub mod event_bus;
 pub mod executables;
 pub mod gossip;
 pub mod hinted_handoff;
 pub mod meta_data;
 pub mod node_initializer;
 pub mod parsers;
+pub mod partitioner;
 pub mod queries;
 pub mod query_delegation;
 pub mod read_reparation;
 pub mod redistribution;
+pub mod repair;
 pub mod response_builders;
+pub mod streaming;
 pub mod terminal_input;
+pub mod tracing;
 pub mod utils;
 pub mod logger;
 
 pub mod client_handler;
+pub mod client_warnings;
+pub mod stream_dispatcher;
 #[cfg(test)]
 mod tests {
     pub mod delete_tests;
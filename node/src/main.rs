@@ -1,14 +1,19 @@
+use node::batchlog::handler::BatchlogHandler;
 use node::client_handler::ClientHandler;
 use node::gossip::gossip_emitter::GossipEmitter;
+use node::gossip::schema_agreement;
 use node::hinted_handoff::handler::Handler;
 use node::hinted_handoff::hints_receiver::HintsReceiver;
 use node::hinted_handoff::hints_sender::HintsSender;
 use node::logger::Logger;
 use node::meta_data::meta_data_handler::use_node_meta_data;
 use node::node_initializer::NodeInitializer;
+use node::streaming::bootstrap::Bootstrap;
 use node::terminal_input::TerminalInput;
 use node::utils::constants::{LOGGER_PATH, NODES_METADATA_PATH};
-use node::utils::config_constants::{BOOTING_TIMEOUT_SECS, MAX_CLIENTS};
+use node::utils::config_constants::{
+    BATCHLOG_REPLAY_INTERVAL_MILLIS, GOSSIP_INTERVAL_MILLIS, MAX_CLIENTS,
+};
 use node::utils::errors::Errors;
 use node::utils::types::node_ip::NodeIp;
 use node::utils::types::tls_stream::{create_server_config, get_stream_owned};
@@ -32,14 +37,15 @@ fn main() -> Result<(), Errors> {
 
     if needs_recovering {
         HintsReceiver::start_listening(node_data.get_ip())?;
-    } else if needs_booting {
-        sleep(Duration::from_secs(BOOTING_TIMEOUT_SECS));
-        use_node_meta_data(|handler| handler.set_own_node_active(NODES_METADATA_PATH))?;
-        let logger = Logger::new("server.log");
-        logger.log_message("Booting Finished");
     }
-    use_node_meta_data(|handler| handler.update_ranges(NODES_METADATA_PATH))?;
+    use_node_meta_data(|handler| handler.update_ranges(&NODES_METADATA_PATH))?;
     start_gossip()?;
+    start_batchlog_replay();
+    // gossip keeps running while the data is fetched, so the rest know the node is joining
+    if needs_booting && !needs_recovering {
+        let replacing = node_data.get_replace_address();
+        Bootstrap::join(&node_data.get_seed_ip(), replacing.as_ref())?;
+    }
 
     set_node_listener(node_data.get_ip())
 }
@@ -60,7 +66,7 @@ fn get_args() -> (bool, String) {
 
 fn start_gossip() -> Result<(), Errors> {
     thread::spawn(move || -> Result<(), Errors> {
-        let logger = Logger::new(LOGGER_PATH);
+        let logger = Logger::new(&LOGGER_PATH);
         loop {
             let result = gossip();
             if let Err(e) = result {
@@ -71,17 +77,31 @@ fn start_gossip() -> Result<(), Errors> {
     Ok(())
 }
 
+/// the batchlog is replayed apart from gossip, a slow replay does not stop the heartbeats
+fn start_batchlog_replay() {
+    thread::spawn(move || {
+        let logger = Logger::new(&LOGGER_PATH);
+        loop {
+            sleep(Duration::from_millis(BATCHLOG_REPLAY_INTERVAL_MILLIS));
+            if let Err(e) = BatchlogHandler::replay_expired() {
+                logger.log_error(format!("Failed to replay the batchlog: {}", e).as_str());
+            }
+        }
+    });
+}
+
 fn gossip() -> Result<(), Errors> {
-    sleep(Duration::from_secs(1));
+    sleep(Duration::from_millis(GOSSIP_INTERVAL_MILLIS));
     let node_added_or_removed = GossipEmitter::start_gossip()?;
     if node_added_or_removed {
         redistribute_data()?
     }
+    schema_agreement::pull_missing_schema()?;
     use_node_meta_data(|handler| handler.check_for_perished_shutting_down_nodes())?;
     Handler::check_for_perished()?;
     {
         use_node_meta_data(|handler| {
-            for ip in handler.get_recovering_nodes(NODES_METADATA_PATH)? {
+            for ip in handler.get_recovering_nodes(&NODES_METADATA_PATH)? {
                 HintsSender::send_hints(ip)?;
             }
             Ok(())
@@ -92,7 +112,7 @@ fn gossip() -> Result<(), Errors> {
 
 fn set_node_listener(ip: NodeIp) -> Result<(), Errors> {
     let listener = TcpListener::bind(ip.get_std_socket()).expect("Error binding socket");
-    let logger = Logger::new(LOGGER_PATH);
+    let logger = Logger::new(&LOGGER_PATH);
     logger.log_message(format!("Server listening on {}", ip.get_string_ip()).as_str());
 
     let (tx, rx) = mpsc::channel();
@@ -105,7 +125,7 @@ fn set_node_listener(ip: NodeIp) -> Result<(), Errors> {
 }
 
 fn accept_connections(listener: TcpListener, tx: mpsc::Sender<TcpStream>) {
-    let logger = Logger::new(LOGGER_PATH);
+    let logger = Logger::new(&LOGGER_PATH);
     for incoming in listener.incoming() {
         match incoming {
             Ok(stream) => {
@@ -127,7 +147,7 @@ fn start_thread_pool(rx: Arc<Mutex<mpsc::Receiver<TcpStream>>>) -> Result<(), Er
         let rx = Arc::clone(&rx);
         let server_config = Arc::new(server_config.clone());
         thread::spawn(move || loop {
-            let logger = Logger::new(LOGGER_PATH);
+            let logger = Logger::new(&LOGGER_PATH);
             let stream = {
                 let lock = rx.lock().unwrap();
                 lock.recv()
@@ -1,24 +1,27 @@
-use std::thread::{self};
-
 use serde::{Deserialize, Serialize};
 
+use super::meta_data_client::ClientMetaDataAcces;
+use crate::utils::compression::Compression;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Client {
     id: String,
     startup: bool,
     authorized: bool,
     keyspace: Option<String>,
+    #[serde(default)]
+    compression: Option<Compression>,
 }
 
 impl Client {
     pub fn new() -> Self {
-        let thread_id = thread::current().id();
-        let id = format!("{:?}", thread_id);
+        let id = ClientMetaDataAcces::connection_id();
         Client {
             id,
             startup: false,
             authorized: false,
             keyspace: None,
+            compression: None,
         }
     }
 
@@ -34,6 +37,10 @@ impl Client {
         self.keyspace.to_owned()
     }
 
+    pub fn get_compression(&self) -> Option<Compression> {
+        self.compression
+    }
+
     pub fn authorize(&mut self) {
         self.authorized = true;
     }
@@ -46,6 +53,10 @@ impl Client {
         self.keyspace = Some(keyspace);
     }
 
+    pub fn set_compression(&mut self, compression: Option<Compression>) {
+        self.compression = compression;
+    }
+
     pub fn is_id(&self, searched_id: &str) -> bool {
         self.id == searched_id
     }
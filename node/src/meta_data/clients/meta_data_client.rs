@@ -1,8 +1,10 @@
 use super::client::Client;
+use crate::utils::compression::Compression;
 use crate::utils::constants::CLIENT_METADATA_PATH;
 use crate::utils::errors::Errors;
 use crate::utils::errors::Errors::ServerError;
 use crate::utils::functions::deserialize_from_slice;
+use std::cell::RefCell;
 use std::fs::remove_file;
 use std::io::Write;
 use std::{
@@ -10,11 +12,17 @@ use std::{
     thread,
 };
 
+thread_local! {
+    /// Connection served by the thread. Workers that run requests of a connection inherit it
+    /// to share the client state with the thread that reads it.
+    static CONNECTION_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
 pub struct ClientMetaDataAcces {}
 
 impl ClientMetaDataAcces {
     fn open_file(path: &str) -> Result<File, Errors> {
-        fs::create_dir_all(CLIENT_METADATA_PATH).map_err(|e| ServerError(e.to_string()))?;
+        fs::create_dir_all(CLIENT_METADATA_PATH.as_str()).map_err(|e| ServerError(e.to_string()))?;
         let file =
             File::create(path).map_err(|_| ServerError("Unable to create file".to_string()))?;
         Ok(file)
@@ -37,12 +45,20 @@ impl ClientMetaDataAcces {
     }
 
     fn get_file_path(path: &str) -> String {
-        format!("{}{}.json", path, Self::thread_id_string())
+        format!("{}{}.json", path, Self::connection_id())
+    }
+
+    /// returns the id of the connection served by the current thread, by default the thread id
+    pub fn connection_id() -> String {
+        CONNECTION_ID.with(|id| match id.borrow().as_ref() {
+            Some(id) => id.to_string(),
+            None => format!("{:?}", thread::current().id()),
+        })
     }
 
-    fn thread_id_string() -> String {
-        let thread_id = thread::current().id();
-        format!("{:?}", thread_id)
+    /// makes the current thread use the client state of the given connection
+    pub fn set_connection_id(connection_id: &str) {
+        CONNECTION_ID.with(|id| *id.borrow_mut() = Some(connection_id.to_string()));
     }
 
     pub fn add_new_client(&self, path: String) -> Result<(), Errors> {
@@ -86,6 +102,17 @@ impl ClientMetaDataAcces {
         })
     }
 
+    pub fn set_compression(
+        &self,
+        path: String,
+        compression: Option<Compression>,
+    ) -> Result<(), Errors> {
+        Self::process_clients_alter(path, |client| {
+            client.set_compression(compression);
+            true
+        })
+    }
+
     pub fn delete_client(&self, path: String) -> Result<(), Errors> {
         remove_file(Self::get_file_path(path.as_str()))
             .map_err(|_| ServerError("Unable to delete file".to_string()))
@@ -111,6 +138,10 @@ impl ClientMetaDataAcces {
     pub fn has_started(&self, path: String) -> Result<bool, Errors> {
         Self::process_client_view(path, |client| Ok(client.has_started()))
     }
+
+    pub fn get_compression(&self, path: String) -> Result<Option<Compression>, Errors> {
+        Self::process_client_view(path, |client| Ok(client.get_compression()))
+    }
 }
 
 #[cfg(test)]
@@ -215,4 +246,47 @@ mod tests {
         assert_eq!(key, Some("keyspace_new".to_owned()));
         cleanup_temp_file(name);
     }
+
+    #[test]
+    fn test_set_compression() {
+        let name = "test_set_compression";
+        create_test_file(name).expect("Failed to create test file");
+        let meta_data = ClientMetaDataAcces {};
+        let compression = meta_data
+            .get_compression(name.to_owned())
+            .expect("Failed to get compression");
+        assert_eq!(compression, None);
+        meta_data
+            .set_compression(name.to_string(), Some(Compression::Snappy))
+            .expect("Failed to set compression");
+        let compression = meta_data
+            .get_compression(name.to_owned())
+            .expect("Failed to get compression");
+        assert_eq!(compression, Some(Compression::Snappy));
+        cleanup_temp_file(name);
+    }
+
+    #[test]
+    fn test_worker_shares_connection_state() {
+        let name = "test_worker_shares_connection_state";
+        let meta_data = ClientMetaDataAcces {};
+        meta_data
+            .add_new_client(name.to_string())
+            .expect("Failed to add client");
+        let connection_id = ClientMetaDataAcces::connection_id();
+        let path = name.to_string();
+        thread::spawn(move || {
+            ClientMetaDataAcces::set_connection_id(&connection_id);
+            ClientMetaDataAcces {}
+                .startup_client(path)
+                .expect("Failed to startup client");
+        })
+        .join()
+        .expect("Failed to join thread");
+        let key = meta_data
+            .has_started(name.to_owned())
+            .expect("Failed to get startup");
+        assert!(key);
+        cleanup_temp_file(name);
+    }
 }
@@ -1,14 +1,31 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 
-use super::table::Table;
+use super::{table::Table, user_type::UserType};
+
+pub const SIMPLE_STRATEGY: &str = "SimpleStrategy";
+pub const NETWORK_TOPOLOGY_STRATEGY: &str = "NetworkTopologyStrategy";
+
+/// How the replicas of a token are chosen according to the keyspace strategy.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ReplicaPlacement {
+    /// the next nodes of the ring
+    Simple(usize),
+    /// replicas per datacenter, spread across its racks
+    NetworkTopology(BTreeMap<String, usize>),
+}
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Keyspace {
     pub tables: HashMap<String, Table>,
     pub replication_strategy: String,
     pub replication_factor: usize,
+    /// replicas per datacenter, only used by `NetworkTopologyStrategy`
+    #[serde(default)]
+    pub datacenters: BTreeMap<String, usize>,
+    #[serde(default)]
+    pub user_types: HashMap<String, UserType>,
 }
 
 impl Keyspace {
@@ -22,6 +39,8 @@ impl Keyspace {
             tables: HashMap::new(), // Inicializamos el HashMap de tablas vacío
             replication_strategy: strategy,
             replication_factor: factor,
+            datacenters: BTreeMap::new(),
+            user_types: HashMap::new(),
         }
     }
 
@@ -32,4 +51,69 @@ impl Keyspace {
     pub fn set_replication_factor(&mut self, factor: usize) {
         self.replication_factor = factor;
     }
+
+    /// with `NetworkTopologyStrategy` the replication factor is the total of the datacenters
+    pub fn set_datacenters(&mut self, datacenters: BTreeMap<String, usize>) {
+        self.replication_factor = datacenters.values().sum();
+        self.datacenters = datacenters;
+    }
+
+    pub fn placement(&self) -> ReplicaPlacement {
+        if self.replication_strategy == NETWORK_TOPOLOGY_STRATEGY {
+            ReplicaPlacement::NetworkTopology(self.datacenters.clone())
+        } else {
+            ReplicaPlacement::Simple(self.replication_factor)
+        }
+    }
+
+    /// reconstructs the `CREATE KEYSPACE` statement of the keyspace
+    pub fn to_cql(&self, name: &str) -> String {
+        match self.placement() {
+            ReplicaPlacement::NetworkTopology(datacenters) => {
+                let datacenters: Vec<String> = datacenters
+                    .iter()
+                    .map(|(datacenter, factor)| format!(", '{}': {}", datacenter, factor))
+                    .collect();
+                format!(
+                    "CREATE KEYSPACE {} WITH replication = {{'class': '{}'{}}};",
+                    name,
+                    NETWORK_TOPOLOGY_STRATEGY,
+                    datacenters.concat()
+                )
+            }
+            ReplicaPlacement::Simple(factor) => format!(
+                "CREATE KEYSPACE {} WITH replication = {{'class': '{}', 'replication_factor': {}}};",
+                name, SIMPLE_STRATEGY, factor
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_topology_replication() {
+        let mut keyspace = Keyspace::new(Some(NETWORK_TOPOLOGY_STRATEGY.to_string()), None);
+        keyspace.set_datacenters(BTreeMap::from([
+            ("dc1".to_string(), 3),
+            ("dc2".to_string(), 2),
+        ]));
+        assert_eq!(keyspace.replication_factor, 5);
+        assert_eq!(
+            keyspace.to_cql("ks"),
+            "CREATE KEYSPACE ks WITH replication = {'class': 'NetworkTopologyStrategy', 'dc1': 3, 'dc2': 2};"
+        );
+        assert!(matches!(
+            keyspace.placement(),
+            ReplicaPlacement::NetworkTopology(datacenters) if datacenters["dc2"] == 2
+        ));
+        let simple = Keyspace::new(None, Some(2));
+        assert_eq!(simple.placement(), ReplicaPlacement::Simple(2));
+        assert_eq!(
+            simple.to_cql("ks"),
+            "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 2};"
+        );
+    }
 }
@@ -3,11 +3,21 @@ use serde_json;
 use std::fs::{File, OpenOptions};
 use std::io::{Seek, SeekFrom, Write};
 //use std::sync::{Arc, Mutex, MutexGuard};
-use super::{keyspace::Keyspace, table::Table};
+use super::keyspace::ReplicaPlacement;
+use super::{
+    keyspace::Keyspace,
+    table::Table,
+    user_type::{creation_order, UserType},
+};
+use crate::queries::order_by_clause::OrderByClause;
 use crate::utils::constants::KEYSPACE_METADATA_PATH;
-use crate::utils::functions::{deserialize_from_str, write_all_to_file};
+use crate::utils::functions::{bytes_to_hex, deserialize_from_str, write_all_to_file};
 use crate::utils::types::primary_key::PrimaryKey;
-use std::{collections::HashMap, io::Read};
+use md5::{Digest, Md5};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Read,
+};
 
 #[derive(Debug)]
 pub struct KeyspaceMetaDataAccess;
@@ -30,6 +40,24 @@ impl KeyspaceMetaDataAccess {
         name: &str,
         replication_strategy: Option<String>,
         replication_factor: Option<usize>,
+    ) -> Result<(), Errors> {
+        self.add_keyspace_with_datacenters(
+            path,
+            name,
+            replication_strategy,
+            replication_factor,
+            BTreeMap::new(),
+        )
+    }
+
+    /// adds a keyspace whose replicas are defined per datacenter when it uses `NetworkTopologyStrategy`
+    pub fn add_keyspace_with_datacenters(
+        &self,
+        path: String,
+        name: &str,
+        replication_strategy: Option<String>,
+        replication_factor: Option<usize>,
+        datacenters: BTreeMap<String, usize>,
     ) -> Result<(), Errors> {
         //let (mut file, mut keyspaces) = self.lock_and_extract_keyspaces()?;
         let mut file = Self::open_file(path)?;
@@ -39,7 +67,10 @@ impl KeyspaceMetaDataAccess {
                 "El keyspace ya está creado".to_string(),
             ));
         }
-        let keyspace = Keyspace::new(replication_strategy, replication_factor);
+        let mut keyspace = Keyspace::new(replication_strategy, replication_factor);
+        if !datacenters.is_empty() {
+            keyspace.set_datacenters(datacenters);
+        }
         keyspaces.insert(name.to_owned(), keyspace);
         Self::save_hash_to_json(&mut file, &keyspaces)?;
         Ok(())
@@ -52,6 +83,18 @@ impl KeyspaceMetaDataAccess {
         Ok(keyspace.replication_factor)
     }
 
+    /// how the replicas of the keyspace are chosen in the ring
+    pub fn get_placement(
+        &self,
+        path: String,
+        keyspace_name: &str,
+    ) -> Result<ReplicaPlacement, Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let keyspace = get_keyspace_mutable(&mut keyspaces, keyspace_name)?;
+        Ok(keyspace.placement())
+    }
+
     pub fn get_strategy(&self, path: String, keyspace_name: &str) -> Result<String, Errors> {
         let mut file = Self::open_file(path)?;
         let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
@@ -108,6 +151,78 @@ impl KeyspaceMetaDataAccess {
         Ok(keyspaces.keys().cloned().collect::<Vec<String>>())
     }
 
+    /// Schema version: a UUID built from the md5 of every keyspace.
+    ///
+    /// It goes through `serde_json::Value` so the keys end up sorted, so two nodes with the same
+    /// schema compute the same version
+    pub fn get_schema_version(&self, path: String) -> Result<String, Errors> {
+        let mut file = Self::open_file(path)?;
+        let keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let canonical = serde_json::to_value(&keyspaces)
+            .and_then(|value| serde_json::to_vec(&value))
+            .map_err(|_| Errors::ServerError("Failed to serialize keyspaces".to_string()))?;
+        let digest = Md5::digest(canonical);
+        let hex = bytes_to_hex(&digest);
+        Ok(format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        ))
+    }
+
+    /// returns the DDL of the keyspace, its types and its tables as (type, name, statement)
+    pub fn describe_keyspace(
+        &self,
+        path: String,
+        keyspace_name: &str,
+    ) -> Result<Vec<(String, String, String)>, Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let keyspace = get_keyspace_mutable(&mut keyspaces, keyspace_name)?;
+        Self::reset_pointer(&mut file)?;
+        let mut statements = vec![(
+            "keyspace".to_string(),
+            keyspace_name.to_string(),
+            keyspace.to_cql(keyspace_name),
+        )];
+        for type_name in creation_order(&keyspace.user_types) {
+            let user_type = &keyspace.user_types[type_name];
+            statements.push((
+                "type".to_string(),
+                type_name.to_string(),
+                user_type.to_cql(keyspace_name, type_name),
+            ));
+        }
+        let mut table_names: Vec<&String> = keyspace.tables.keys().collect();
+        table_names.sort();
+        for table_name in table_names {
+            let table = &keyspace.tables[table_name];
+            statements.push((
+                "table".to_string(),
+                table_name.to_string(),
+                table.to_cql(keyspace_name, table_name),
+            ));
+        }
+        Ok(statements)
+    }
+
+    /// returns the `CREATE TABLE` statement of the table
+    pub fn describe_table(
+        &self,
+        path: String,
+        keyspace_name: &str,
+        table_name: &str,
+    ) -> Result<String, Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let table = get_table_mutable(&mut keyspaces, keyspace_name, table_name)?;
+        Self::reset_pointer(&mut file)?;
+        Ok(table.to_cql(keyspace_name, table_name))
+    }
+
     pub fn get_columns_type(
         &self,
         path: String,
@@ -134,13 +249,26 @@ impl KeyspaceMetaDataAccess {
         Ok(table.primary_key.to_owned())
     }
 
+    /// the order of the rows of a partition declared with the CLUSTERING ORDER BY of the table
+    pub fn get_clustering_order(
+        &self,
+        path: String,
+        keyspace_name: &str,
+        table_name: &str,
+    ) -> Result<Option<Vec<OrderByClause>>, Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let table = get_table_mutable(&mut keyspaces, keyspace_name, table_name)?;
+        Self::reset_pointer(&mut file)?;
+        Ok(table.clustering_order_clauses())
+    }
+
     pub fn add_table(
         &self,
         path: String,
         keyspace_name: &str,
         table_name: &str,
-        primary_key: PrimaryKey,
-        columns: HashMap<String, DataType>,
+        table: Table,
     ) -> Result<(), Errors> {
         let mut file = Self::open_file(path)?;
         let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
@@ -148,7 +276,6 @@ impl KeyspaceMetaDataAccess {
         if keyspace.tables.contains_key(table_name) {
             return Err(Errors::SyntaxError("La tabla ya está creada".to_string()));
         }
-        let table = Table::new(primary_key, columns);
         keyspace.tables.insert(table_name.to_string(), table);
         Self::save_hash_to_json(&mut file, &keyspaces)?;
         Ok(())
@@ -225,6 +352,121 @@ impl KeyspaceMetaDataAccess {
         Ok(())
     }
 
+    pub fn add_type(
+        &self,
+        path: String,
+        keyspace_name: &str,
+        type_name: &str,
+        user_type: UserType,
+    ) -> Result<(), Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let keyspace = get_keyspace_mutable(&mut keyspaces, keyspace_name)?;
+        if keyspace.user_types.contains_key(type_name) {
+            return Err(Errors::AlreadyExists(format!(
+                "El tipo '{}' ya existe en el keyspace '{}'",
+                type_name, keyspace_name
+            )));
+        }
+        keyspace.user_types.insert(type_name.to_string(), user_type);
+        Self::save_hash_to_json(&mut file, &keyspaces)?;
+        Ok(())
+    }
+
+    pub fn get_type(
+        &self,
+        path: String,
+        keyspace_name: &str,
+        type_name: &str,
+    ) -> Result<UserType, Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let user_type = get_type_mutable(&mut keyspaces, keyspace_name, type_name)?;
+        Self::reset_pointer(&mut file)?;
+        Ok(user_type.to_owned())
+    }
+
+    pub fn get_types_from_keyspace(
+        &self,
+        path: String,
+        keyspace_name: &str,
+    ) -> Result<HashMap<String, UserType>, Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let keyspace = get_keyspace_mutable(&mut keyspaces, keyspace_name)?;
+        Self::reset_pointer(&mut file)?;
+        Ok(keyspace.user_types.clone())
+    }
+
+    pub fn add_type_field(
+        &self,
+        path: String,
+        keyspace_name: &str,
+        type_name: &str,
+        field: &str,
+        data_type: DataType,
+    ) -> Result<(), Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let user_type = get_type_mutable(&mut keyspaces, keyspace_name, type_name)?;
+        user_type.add_field(field, data_type)?;
+        Self::save_hash_to_json(&mut file, &keyspaces)?;
+        Ok(())
+    }
+
+    pub fn rename_type_field(
+        &self,
+        path: String,
+        keyspace_name: &str,
+        type_name: &str,
+        old_field: &str,
+        new_field: &str,
+    ) -> Result<(), Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let user_type = get_type_mutable(&mut keyspaces, keyspace_name, type_name)?;
+        user_type.rename_field(old_field, new_field)?;
+        Self::save_hash_to_json(&mut file, &keyspaces)?;
+        Ok(())
+    }
+
+    pub fn drop_type(
+        &self,
+        path: String,
+        keyspace_name: &str,
+        type_name: &str,
+    ) -> Result<(), Errors> {
+        let mut file = Self::open_file(path)?;
+        let mut keyspaces = Self::extract_hash_from_json(&mut file)?;
+        let keyspace = get_keyspace_mutable(&mut keyspaces, keyspace_name)?;
+        if !keyspace.user_types.contains_key(type_name) {
+            return Err(Errors::Invalid(format!(
+                "El tipo '{}' no existe en el keyspace '{}'",
+                type_name, keyspace_name
+            )));
+        }
+        let in_use = keyspace.tables.values().any(|table| {
+            table
+                .columns
+                .values()
+                .any(|data_type| data_type == &DataType::Udt(type_name.to_string()))
+        }) || keyspace.user_types.values().any(|user_type| {
+            user_type
+                .fields
+                .iter()
+                .any(|(_, data_type)| data_type == &DataType::Udt(type_name.to_string()))
+        });
+        if in_use {
+            return Err(Errors::Invalid(format!(
+                "El tipo '{}' está en uso y no puede eliminarse",
+                type_name
+            )));
+        }
+        keyspace.user_types.remove(type_name);
+        Self::save_hash_to_json(&mut file, &keyspaces)?;
+        Ok(())
+    }
+
     fn extract_hash_from_json(file: &mut File) -> Result<HashMap<String, Keyspace>, Errors> {
         let mut contents = String::new();
         file.read_to_string(&mut contents)
@@ -289,6 +531,20 @@ fn get_table_mutable<'a>(
     })
 }
 
+fn get_type_mutable<'a>(
+    keyspaces: &'a mut HashMap<String, Keyspace>,
+    keyspace_name: &str,
+    type_name: &str,
+) -> Result<&'a mut UserType, Errors> {
+    let keyspace = get_keyspace_mutable(keyspaces, keyspace_name)?;
+    keyspace.user_types.get_mut(type_name).ok_or_else(|| {
+        Errors::Invalid(format!(
+            "El tipo '{}' no existe en el keyspace '{}'",
+            type_name, keyspace_name
+        ))
+    })
+}
+
 fn rename_key<K, V>(map: &mut HashMap<K, V>, old_key: K, new_key: K)
 where
     K: std::hash::Hash + Eq,
@@ -358,8 +614,7 @@ mod tests {
             file_name.to_string(),
             "test_keyspace",
             "test_table",
-            PrimaryKey::new(vec!["column2".to_string()], None),
-            columns,
+            Table::new(PrimaryKey::new(vec!["column2".to_string()], None), columns),
         )?;
 
         Ok(())
@@ -470,8 +725,10 @@ mod tests {
                 file_name.to_string(),
                 "test_keyspace",
                 "test_table_additional",
-                PrimaryKey::new(vec!["columnB".to_string()], None),
-                additional_columns,
+                Table::new(
+                    PrimaryKey::new(vec!["columnB".to_string()], None),
+                    additional_columns,
+                ),
             )
             .expect("Failed to add additional table");
 
@@ -555,4 +812,64 @@ mod tests {
         assert_eq!(pk, "column2", "Expected primary key to be 'column2'.");
         cleanup_test_file(file_name);
     }
+
+    #[test]
+    fn test_add_alter_and_drop_type() {
+        let file_name = "test_user_types.json";
+        create_test_file(file_name).expect("Failed to create test file");
+        assert!(add_keyspace_test(file_name).is_ok());
+        let meta_data = KeyspaceMetaDataAccess {};
+        let user_type = UserType::new(vec![
+            ("lat".to_string(), DataType::Decimal),
+            ("lon".to_string(), DataType::Decimal),
+        ]);
+        meta_data
+            .add_type(file_name.to_string(), "test_keyspace", "coordinates", user_type.clone())
+            .expect("Failed to add type");
+        assert!(meta_data
+            .add_type(file_name.to_string(), "test_keyspace", "coordinates", user_type)
+            .is_err());
+        meta_data
+            .add_type_field(
+                file_name.to_string(),
+                "test_keyspace",
+                "coordinates",
+                "alt",
+                DataType::Int,
+            )
+            .expect("Failed to add field");
+        let stored = meta_data
+            .get_type(file_name.to_string(), "test_keyspace", "coordinates")
+            .expect("Failed to get type");
+        assert_eq!(stored.get_field_type("alt"), Some(&DataType::Int));
+        meta_data
+            .drop_type(file_name.to_string(), "test_keyspace", "coordinates")
+            .expect("Failed to drop type");
+        assert!(meta_data
+            .get_type(file_name.to_string(), "test_keyspace", "coordinates")
+            .is_err());
+        cleanup_test_file(file_name);
+    }
+
+    #[test]
+    fn test_schema_version_follows_the_schema() {
+        let (first, second) = ("test_schema_version_1.json", "test_schema_version_2.json");
+        let meta_data = KeyspaceMetaDataAccess {};
+        for file_name in [first, second] {
+            create_test_file(file_name).expect("Failed to create test file");
+            add_keyspace_test(file_name).expect("Failed to add keyspace");
+        }
+        let version = meta_data.get_schema_version(first.to_string()).unwrap();
+        assert_eq!(version.len(), 36);
+        assert_eq!(version, meta_data.get_schema_version(second.to_string()).unwrap());
+        add_test_table_with_columns(first).expect("Failed to add table");
+        assert_ne!(version, meta_data.get_schema_version(first.to_string()).unwrap());
+        add_test_table_with_columns(second).expect("Failed to add table");
+        assert_eq!(
+            meta_data.get_schema_version(first.to_string()).unwrap(),
+            meta_data.get_schema_version(second.to_string()).unwrap()
+        );
+        cleanup_test_file(first);
+        cleanup_test_file(second);
+    }
 }
@@ -1,3 +1,4 @@
 pub mod keyspace;
 pub mod keyspace_meta_data_acces;
 pub mod table;
+pub mod user_type;
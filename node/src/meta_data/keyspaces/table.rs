@@ -1,13 +1,21 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::parsers::tokens::data_type::DataType;
+use crate::parsers::tokens::data_type::{data_type_to_string, DataType};
+use crate::queries::order_by_clause::OrderByClause;
+use crate::utils::parser_constants::ASC;
 use crate::utils::types::primary_key::PrimaryKey;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Table {
     pub primary_key: PrimaryKey,
     pub columns: HashMap<String, DataType>,
+    /// ASC or DESC of the clustering columns, the ones left out are ASC
+    #[serde(default)]
+    pub clustering_order: HashMap<String, String>,
+    /// options of the `WITH` clause, each value as it is written in CQL
+    #[serde(default)]
+    pub options: BTreeMap<String, String>,
 }
 
 impl Table {
@@ -15,6 +23,180 @@ impl Table {
         Table {
             primary_key,
             columns,
+            clustering_order: HashMap::new(),
+            options: BTreeMap::new(),
         }
     }
+
+    pub fn with_properties(
+        mut self,
+        clustering_order: HashMap<String, String>,
+        options: BTreeMap<String, String>,
+    ) -> Self {
+        self.clustering_order = clustering_order;
+        self.options = options;
+        self
+    }
+
+    /// If the table was created with a CLUSTERING ORDER BY, its rows are ordered by the
+    /// clustering columns, each one ASC or DESC as it was declared. Otherwise they keep the
+    /// order in which they were written
+    pub fn clustering_order_clauses(&self) -> Option<Vec<OrderByClause>> {
+        if self.clustering_order.is_empty() {
+            return None;
+        }
+        let clauses = self
+            .primary_key
+            .get_clustering_columns()
+            .iter()
+            .map(|column| {
+                let order = self.clustering_order.get(column).map_or(ASC, |order| order);
+                OrderByClause::new_with_order(column.to_string(), order.to_string())
+            })
+            .collect();
+        Some(clauses)
+    }
+
+    /// reconstructs the `CREATE TABLE` statement of the table. Primary key columns go first,
+    /// the rest are sorted by name, and the options follow the clustering order
+    pub fn to_cql(&self, keyspace: &str, name: &str) -> String {
+        let primary_key = self.primary_key.get_full_primary_key();
+        let mut other_columns: Vec<&String> = self
+            .columns
+            .keys()
+            .filter(|column| !primary_key.contains(column))
+            .collect();
+        other_columns.sort();
+        let mut definitions = Vec::new();
+        for column in primary_key.iter().chain(other_columns) {
+            if let Some(data_type) = self.columns.get(column) {
+                definitions.push(format!("    {} {}", column, data_type_to_string(data_type)));
+            }
+        }
+        let partition_keys = self.primary_key.get_partition_key();
+        let mut key = match partition_keys.len() {
+            1 => partition_keys[0].to_string(),
+            _ => format!("({})", partition_keys.join(", ")),
+        };
+        let clustering_columns = self.primary_key.get_clustering_columns();
+        if !clustering_columns.is_empty() {
+            key = format!("{}, {}", key, clustering_columns.join(", "));
+        }
+        definitions.push(format!("    PRIMARY KEY ({})", key));
+        let mut statement = format!(
+            "CREATE TABLE {}.{} (\n{}\n)",
+            keyspace,
+            name,
+            definitions.join(",\n")
+        );
+        let mut properties = Vec::new();
+        if !clustering_columns.is_empty() {
+            let order: Vec<String> = clustering_columns
+                .iter()
+                .map(|column| {
+                    let order = self.clustering_order.get(column).map_or(ASC, |order| order);
+                    format!("{} {}", column, order)
+                })
+                .collect();
+            properties.push(format!("CLUSTERING ORDER BY ({})", order.join(", ")));
+        }
+        for (option, value) in &self.options {
+            properties.push(format!("{} = {}", option, value));
+        }
+        if !properties.is_empty() {
+            statement.push_str(&format!(" WITH {}", properties.join("\n    AND ")));
+        }
+        statement.push(';');
+        statement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_to_cql() {
+        let table = Table::new(
+            PrimaryKey::new(
+                vec!["id".to_string()],
+                Some(vec!["name".to_string()]),
+            ),
+            HashMap::from([
+                ("id".to_string(), DataType::Int),
+                ("name".to_string(), DataType::Text),
+                ("age".to_string(), DataType::Int),
+                ("position".to_string(), DataType::Udt("coordinates".to_string())),
+            ]),
+        );
+        assert_eq!(
+            table.to_cql("kp", "users"),
+            "CREATE TABLE kp.users (\n    id int,\n    name text,\n    age int,\n    position frozen<coordinates>,\n    PRIMARY KEY (id, name)\n) WITH CLUSTERING ORDER BY (name ASC);"
+        );
+    }
+
+    #[test]
+    fn test_table_to_cql_keeps_clustering_order_and_options() {
+        let table = Table::new(
+            PrimaryKey::new(
+                vec!["id".to_string()],
+                Some(vec!["day".to_string(), "hour".to_string()]),
+            ),
+            HashMap::from([
+                ("id".to_string(), DataType::Int),
+                ("day".to_string(), DataType::Date),
+                ("hour".to_string(), DataType::Time),
+            ]),
+        )
+        .with_properties(
+            HashMap::from([("day".to_string(), "DESC".to_string())]),
+            BTreeMap::from([
+                ("gc_grace_seconds".to_string(), "10".to_string()),
+                ("comment".to_string(), "'flights'".to_string()),
+            ]),
+        );
+        assert_eq!(
+            table.to_cql("kp", "t"),
+            "CREATE TABLE kp.t (\n    id int,\n    day date,\n    hour time,\n    PRIMARY KEY (id, day, hour)\n) WITH CLUSTERING ORDER BY (day DESC, hour ASC)\n    AND comment = 'flights'\n    AND gc_grace_seconds = 10;"
+        );
+    }
+
+    #[test]
+    fn test_clustering_order_clauses() {
+        let table = Table::new(
+            PrimaryKey::new(
+                vec!["id".to_string()],
+                Some(vec!["day".to_string(), "hour".to_string()]),
+            ),
+            HashMap::new(),
+        )
+        .with_properties(
+            HashMap::from([("day".to_string(), "DESC".to_string())]),
+            BTreeMap::new(),
+        );
+        assert_eq!(
+            table.clustering_order_clauses(),
+            Some(vec![
+                OrderByClause::new_with_order("day".to_string(), "DESC".to_string()),
+                OrderByClause::new_with_order("hour".to_string(), "ASC".to_string()),
+            ])
+        );
+        let table = table.with_properties(HashMap::new(), BTreeMap::new());
+        assert_eq!(table.clustering_order_clauses(), None);
+    }
+
+    #[test]
+    fn test_table_to_cql_composite_partition_key() {
+        let table = Table::new(
+            PrimaryKey::new(vec!["a".to_string(), "b".to_string()], None),
+            HashMap::from([
+                ("a".to_string(), DataType::Int),
+                ("b".to_string(), DataType::Date),
+            ]),
+        );
+        assert_eq!(
+            table.to_cql("kp", "t"),
+            "CREATE TABLE kp.t (\n    a int,\n    b date,\n    PRIMARY KEY ((a, b))\n);"
+        );
+    }
 }
@@ -0,0 +1,229 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::parsers::tokens::data_type::{data_type_to_string, DataType};
+use crate::parsers::tokens::literal::{split_udt_value, to_literal};
+use crate::parsers::tokens::terms::Term;
+use crate::parsers::tokens::token::Token;
+use crate::utils::errors::Errors;
+
+/// User defined type, scoped to a keyspace. Fields keep their declaration order.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct UserType {
+    pub fields: Vec<(String, DataType)>,
+}
+
+impl UserType {
+    pub fn new(fields: Vec<(String, DataType)>) -> Self {
+        UserType { fields }
+    }
+
+    /// returns the data type of the given field, if defined
+    pub fn get_field_type(&self, field: &str) -> Option<&DataType> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, data_type)| data_type)
+    }
+
+    /// adds a new field at the end of the type
+    pub fn add_field(&mut self, field: &str, data_type: DataType) -> Result<(), Errors> {
+        if self.get_field_type(field).is_some() {
+            return Err(Errors::Invalid(format!("Field {} already exists", field)));
+        }
+        self.fields.push((field.to_string(), data_type));
+        Ok(())
+    }
+
+    /// renames an existing field
+    pub fn rename_field(&mut self, old_field: &str, new_field: &str) -> Result<(), Errors> {
+        if self.get_field_type(new_field).is_some() {
+            return Err(Errors::Invalid(format!("Field {} already exists", new_field)));
+        }
+        let Some(field) = self.fields.iter_mut().find(|(name, _)| name == old_field) else {
+            return Err(Errors::Invalid(format!("Field {} not found", old_field)));
+        };
+        field.0 = new_field.to_string();
+        Ok(())
+    }
+
+    /// reconstructs the `CREATE TYPE` statement of the type
+    pub fn to_cql(&self, keyspace: &str, name: &str) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(field, data_type)| format!("    {} {}", field, data_type_to_string(data_type)))
+            .collect();
+        format!("CREATE TYPE {}.{} (\n{}\n);", keyspace, name, fields.join(",\n"))
+    }
+
+    /// names of the user types used by the fields
+    fn field_types(&self) -> Vec<&String> {
+        self.fields
+            .iter()
+            .filter_map(|(_, data_type)| match data_type {
+                DataType::Udt(name) => Some(name),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// validates that a UDT literal value (`{field: value, ...}`) matches this type. Fields of
+    /// another user type are validated against its definition in `user_types`
+    pub fn check_value(
+        &self,
+        value: &str,
+        user_types: &HashMap<String, UserType>,
+    ) -> Result<(), Errors> {
+        for (field, raw_value) in split_udt_value(value)? {
+            let Some(expected) = self.get_field_type(&field) else {
+                return Err(Errors::Invalid(format!(
+                    "Field {} is not defined in type",
+                    field
+                )));
+            };
+            if let DataType::Udt(type_name) = expected {
+                let Some(field_type) = user_types.get(type_name) else {
+                    return Err(Errors::Invalid(format!("Type {} does not exist", type_name)));
+                };
+                field_type.check_value(&raw_value, user_types)?;
+                continue;
+            }
+            let Some(Token::Term(Term::Literal(literal))) = to_literal(&raw_value) else {
+                return Err(Errors::Invalid(format!("Invalid value for field {}", field)));
+            };
+            if &literal.data_type != expected {
+                return Err(Errors::Invalid(format!(
+                    "Value datatype for field {} does not match the type definition",
+                    field
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Names of the types in an order in which they can be created: a type always comes after
+/// the types of its fields, the rest is sorted by name
+pub fn creation_order(user_types: &HashMap<String, UserType>) -> Vec<&String> {
+    let mut names: Vec<&String> = user_types.keys().collect();
+    names.sort();
+    let mut ordered = Vec::new();
+    let mut visited = HashSet::new();
+    for name in names {
+        visit(name, user_types, &mut visited, &mut ordered);
+    }
+    ordered
+}
+
+fn visit<'a>(
+    name: &'a String,
+    user_types: &'a HashMap<String, UserType>,
+    visited: &mut HashSet<&'a String>,
+    ordered: &mut Vec<&'a String>,
+) {
+    let Some((name, user_type)) = user_types.get_key_value(name) else {
+        return;
+    };
+    if !visited.insert(name) {
+        return;
+    }
+    let mut dependencies = user_type.field_types();
+    dependencies.sort();
+    for dependency in dependencies {
+        visit(dependency, user_types, visited, ordered);
+    }
+    ordered.push(name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinates() -> UserType {
+        UserType::new(vec![
+            ("lat".to_string(), DataType::Decimal),
+            ("lon".to_string(), DataType::Decimal),
+        ])
+    }
+
+    #[test]
+    fn test_check_valid_value() {
+        assert!(coordinates().check_value("{lat: 1.0, lon: -2.5}", &HashMap::new()).is_ok());
+    }
+
+    #[test]
+    fn test_check_value_unknown_field() {
+        assert!(coordinates().check_value("{lat: 1.0, alt: 2.0}", &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_check_value_wrong_type() {
+        assert!(coordinates().check_value("{lat: 'north'}", &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_check_value_nested_type() {
+        let place = UserType::new(vec![
+            ("name".to_string(), DataType::Text),
+            ("position".to_string(), DataType::Udt("coordinates".to_string())),
+        ]);
+        let user_types = HashMap::from([("coordinates".to_string(), coordinates())]);
+        assert!(place
+            .check_value("{name: 'home', position: {lat: 1.0, lon: 2.0}}", &user_types)
+            .is_ok());
+        assert!(place
+            .check_value("{name: 'home', position: {lat: 'north'}}", &user_types)
+            .is_err());
+        assert!(place
+            .check_value("{name: 'home', position: {alt: 1.0}}", &user_types)
+            .is_err());
+        assert!(place
+            .check_value("{name: 'home', position: {lat: 1.0}}", &HashMap::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_to_cql() {
+        assert_eq!(
+            coordinates().to_cql("kp", "coordinates"),
+            "CREATE TYPE kp.coordinates (\n    lat decimal,\n    lon decimal\n);"
+        );
+    }
+
+    #[test]
+    fn test_add_and_rename_field() {
+        let mut user_type = coordinates();
+        user_type.add_field("alt", DataType::Int).unwrap();
+        assert!(user_type.add_field("alt", DataType::Int).is_err());
+        user_type.rename_field("alt", "altitude").unwrap();
+        assert_eq!(user_type.get_field_type("altitude"), Some(&DataType::Int));
+        assert_eq!(user_type.get_field_type("alt"), None);
+    }
+
+    #[test]
+    fn test_types_are_created_after_their_field_types() {
+        let mut user_types = HashMap::new();
+        user_types.insert(
+            "address".to_string(),
+            UserType::new(vec![
+                ("city".to_string(), DataType::Udt("city".to_string())),
+                ("location".to_string(), DataType::Udt("coordinates".to_string())),
+            ]),
+        );
+        user_types.insert(
+            "city".to_string(),
+            UserType::new(vec![
+                ("name".to_string(), DataType::Text),
+                ("center".to_string(), DataType::Udt("coordinates".to_string())),
+            ]),
+        );
+        user_types.insert("coordinates".to_string(), coordinates());
+        user_types.insert("alias".to_string(), UserType::new(vec![]));
+        assert_eq!(
+            creation_order(&user_types),
+            vec!["coordinates", "city", "address", "alias"]
+        );
+    }
+}
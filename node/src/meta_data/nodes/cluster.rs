@@ -1,7 +1,9 @@
-use super::node::Node;
+use super::node::{Node, State};
+use crate::partitioner::{Token, TokenRange};
 use crate::utils::errors::Errors;
 use crate::utils::types::node_ip::NodeIp;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Cluster {
@@ -21,6 +23,10 @@ impl Cluster {
         &self.own_node
     }
 
+    pub fn get_own_node_mut(&mut self) -> &mut Node {
+        &mut self.own_node
+    }
+
     pub fn get_other_nodes(&self) -> &Vec<Node> {
         &self.other_nodes
     }
@@ -39,43 +45,183 @@ impl Cluster {
         }
     }
 
+    /// Returns the replicas of the ring position: the ring is walked from that position and the
+    /// first `replication` distinct nodes are taken.
     pub fn get_nodes(&self, position: usize, replication: usize) -> Result<Vec<NodeIp>, Errors> {
-        let end_position = position + replication;
+        let ring = self.ring();
         let mut ips: Vec<NodeIp> = Vec::new();
-        let total_nodes = self.len_nodes();
-        for node in self.other_nodes.iter() {
-            let node_pos = node.get_pos();
-            if Self::is_in_range(position, end_position, node_pos, total_nodes) {
+        for offset in 0..ring.len() {
+            if ips.len() == replication {
+                break;
+            }
+            let (_, node) = ring[(position + offset) % ring.len()];
+            if !ips.contains(node.get_ip()) {
                 ips.push(NodeIp::new_from_ip(node.get_ip()));
             }
         }
-        let own_pos = self.own_node.get_pos();
-        if Self::is_in_range(position, end_position, own_pos, total_nodes) {
-            ips.push(NodeIp::new_from_ip(self.own_node.get_ip()));
-        }
         Ok(ips)
     }
 
-    pub fn get_node_pos_by_range(&self, range: usize) -> Result<usize, Errors> {
-        if self.own_node.get_range().is_in_range(range) {
-            return Ok(self.own_node.get_pos());
-        } else {
-            for node in self.other_nodes.iter() {
-                if node.get_range().is_in_range(range) {
-                    return Ok(node.get_pos());
+    /// Replicas of `NetworkTopologyStrategy`: the ring is walked from the position taking the
+    /// requested number of replicas in each datacenter. Within a datacenter a rack without a
+    /// replica is preferred; nodes on repeated racks are kept aside and only used once every rack
+    /// of the datacenter has been seen.
+    pub fn get_nodes_by_topology(
+        &self,
+        position: usize,
+        datacenters: &BTreeMap<String, usize>,
+    ) -> Result<Vec<NodeIp>, Errors> {
+        let ring = self.ring();
+        let mut racks: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for (_, node) in ring.iter() {
+            racks
+                .entry(node.get_datacenter())
+                .or_default()
+                .insert(node.get_rack());
+        }
+        let mut chosen: HashMap<&str, Vec<&NodeIp>> = HashMap::new();
+        let mut seen_racks: HashMap<&str, HashSet<&str>> = HashMap::new();
+        let mut skipped: HashMap<&str, Vec<&NodeIp>> = HashMap::new();
+        let mut ips: Vec<NodeIp> = Vec::new();
+        for offset in 0..ring.len() {
+            let (_, node) = ring[(position + offset) % ring.len()];
+            let datacenter = node.get_datacenter();
+            let wanted = datacenters.get(datacenter).copied().unwrap_or(0);
+            let replicas = chosen.entry(datacenter).or_default();
+            if replicas.len() >= wanted
+                || replicas.contains(&node.get_ip())
+                || skipped
+                    .entry(datacenter)
+                    .or_default()
+                    .contains(&node.get_ip())
+            {
+                continue;
+            }
+            let seen = seen_racks.entry(datacenter).or_default();
+            let all_racks = racks.get(datacenter).map(|racks| racks.len()).unwrap_or(0);
+            if seen.insert(node.get_rack()) {
+                replicas.push(node.get_ip());
+                if seen.len() == all_racks {
+                    // every rack already has a replica, the skipped nodes come back in ring order
+                    let pending = skipped.entry(datacenter).or_default();
+                    while replicas.len() < wanted && !pending.is_empty() {
+                        replicas.push(pending.remove(0));
+                    }
                 }
+            } else if seen.len() == all_racks {
+                replicas.push(node.get_ip());
+            } else {
+                skipped.entry(datacenter).or_default().push(node.get_ip());
             }
         }
-        Ok(1)
+        for (datacenter, replicas) in chosen.iter_mut() {
+            let wanted = datacenters.get(*datacenter).copied().unwrap_or(0);
+            let pending = skipped.entry(datacenter).or_default();
+            while replicas.len() < wanted && !pending.is_empty() {
+                replicas.push(pending.remove(0));
+            }
+        }
+        for datacenter in datacenters.keys() {
+            if let Some(replicas) = chosen.get(datacenter.as_str()) {
+                ips.extend(replicas.iter().map(|ip| NodeIp::new_from_ip(ip)));
+            }
+        }
+        Ok(ips)
     }
 
-    fn is_in_range(start: usize, end: usize, position: usize, maximum: usize) -> bool {
-        if end <= maximum {
-            position >= start && position < end
-        } else {
-            let modified_end = end - maximum;
-            position >= start || position < modified_end
-        }
+    /// position in the ring of the first token that is equal or greater than the given one,
+    /// the ring wraps around after the last token
+    pub fn get_node_pos_by_range(&self, token: Token) -> Result<usize, Errors> {
+        let ring = self.ring();
+        let position = ring.partition_point(|(node_token, _)| *node_token < token);
+        Ok(if position == ring.len() { 0 } else { position })
+    }
+
+    /// tokens of every node in the ring, used to choose the tokens of a new node
+    pub fn get_tokens(&self) -> Vec<Token> {
+        self.ring().into_iter().map(|(token, _)| token).collect()
+    }
+
+    /// ranges of the ring, each one closed by the token in the same position of `get_tokens`
+    pub fn get_ranges(&self) -> Vec<TokenRange> {
+        ranges_between(&self.get_tokens())
+    }
+
+    /// The ring as it will be once the joining nodes finish joining and the leaving nodes finish
+    /// leaving. A node that joins with the tokens of another one replaces it.
+    ///
+    /// Used to know which ranges each node gains or loses while the data is still moving
+    pub fn settled(&self) -> Cluster {
+        let booting_tokens: HashSet<Token> = self
+            .nodes()
+            .filter(|node| node.state == State::Booting)
+            .flat_map(|node| node.get_tokens().iter().copied())
+            .collect();
+        let settle = |node: &Node| {
+            let mut settled = Node::new_from_node(node);
+            settled.state = match node.state {
+                State::Booting => State::Active,
+                State::Leaving => State::ShuttingDown,
+                _ if node
+                    .get_tokens()
+                    .iter()
+                    .any(|token| booting_tokens.contains(token)) =>
+                {
+                    State::ShuttingDown
+                }
+                _ => node.state.clone(),
+            };
+            settled
+        };
+        Cluster::new(
+            settle(&self.own_node),
+            self.other_nodes.iter().map(settle).collect(),
+        )
+    }
+
+    /// the same ring without the node, as if it had already left
+    pub fn without(&self, ip: &NodeIp) -> Cluster {
+        let remove = |node: &Node| {
+            let mut node = Node::new_from_node(node);
+            if node.get_ip() == ip {
+                node.state = State::ShuttingDown;
+            }
+            node
+        };
+        Cluster::new(
+            remove(&self.own_node),
+            self.other_nodes.iter().map(remove).collect(),
+        )
+    }
+
+    /// Ranges split by the tokens of both rings: within each one the replicas do not change in
+    /// either of them
+    pub fn get_common_ranges(&self, other: &Cluster) -> Vec<TokenRange> {
+        let mut tokens = self.get_tokens();
+        tokens.extend(other.get_tokens());
+        tokens.sort();
+        tokens.dedup();
+        ranges_between(&tokens)
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = &Node> {
+        std::iter::once(&self.own_node).chain(self.other_nodes.iter())
+    }
+
+    /// Sorted tokens of the nodes in the ring, leaving nodes take no ranges and joining nodes
+    /// only take them once they finish fetching their data.
+    ///
+    /// If a node replaced a dead one both have the same tokens until the dead one leaves the
+    /// ring, in that case the token belongs to the one that is not dead
+    fn ring(&self) -> Vec<(Token, &Node)> {
+        let mut ring: Vec<(Token, &Node)> = self
+            .nodes()
+            .filter(|node| node.state != State::ShuttingDown && node.state != State::Booting)
+            .flat_map(|node| node.get_tokens().iter().map(move |token| (*token, node)))
+            .collect();
+        ring.sort_by_key(|(token, node)| (*token, node.state == State::Inactive));
+        ring.dedup_by_key(|(token, _)| *token);
+        ring
     }
 
     pub fn get_all_ips(&self) -> Result<Vec<NodeIp>, Errors> {
@@ -87,3 +233,165 @@ impl Cluster {
         Ok(ips)
     }
 }
+
+/// ranges closed by each of the sorted tokens, the first one starts at the last token
+fn ranges_between(tokens: &[Token]) -> Vec<TokenRange> {
+    (0..tokens.len())
+        .map(|i| (tokens[(i + tokens.len() - 1) % tokens.len()], tokens[i]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta_data::nodes::token_allocation::TokenAllocation;
+
+    fn node(last_byte: u8, tokens: Vec<Token>) -> Node {
+        let ip = NodeIp::new_from_string(&format!("10.0.0.{}", last_byte), 9042).unwrap();
+        Node::new(&ip, last_byte as usize, false, tokens).unwrap()
+    }
+
+    fn replicas(cluster: &Cluster, token: Token, replication: usize) -> Vec<NodeIp> {
+        let position = cluster.get_node_pos_by_range(token).unwrap();
+        cluster.get_nodes(position, replication).unwrap()
+    }
+
+    fn ip(last_byte: u8) -> NodeIp {
+        NodeIp::new_from_string(&format!("10.0.0.{}", last_byte), 9042).unwrap()
+    }
+
+    #[test]
+    fn test_token_owned_by_the_next_token_of_the_ring() {
+        let cluster = Cluster::new(
+            node(1, vec![100, 400]),
+            vec![node(2, vec![200, 500]), node(3, vec![300])],
+        );
+        assert_eq!(replicas(&cluster, 150, 1), vec![ip(2)]);
+        assert_eq!(replicas(&cluster, 400, 1), vec![ip(1)]);
+        // after the last token it wraps around to the start of the ring
+        assert_eq!(replicas(&cluster, 501, 1), vec![ip(1)]);
+        assert_eq!(replicas(&cluster, 350, 2), vec![ip(1), ip(2)]);
+        assert_eq!(replicas(&cluster, 450, 3), vec![ip(2), ip(1), ip(3)]);
+    }
+
+    fn located(last_byte: u8, tokens: Vec<Token>, datacenter: &str, rack: &str) -> Node {
+        let mut node = node(last_byte, tokens);
+        node.set_location(datacenter.to_string(), rack.to_string());
+        node
+    }
+
+    #[test]
+    fn test_replicas_spread_across_racks_of_each_datacenter() {
+        let cluster = Cluster::new(
+            located(1, vec![100], "dc1", "r1"),
+            vec![
+                located(2, vec![200], "dc1", "r1"),
+                located(3, vec![300], "dc2", "r1"),
+                located(4, vec![400], "dc1", "r2"),
+                located(5, vec![500], "dc2", "r2"),
+                located(6, vec![600], "dc1", "r3"),
+                located(7, vec![700], "dc2", "r2"),
+            ],
+        );
+        let datacenters = BTreeMap::from([("dc1".to_string(), 2), ("dc2".to_string(), 2)]);
+        let position = cluster.get_node_pos_by_range(150).unwrap();
+        // 10.0.0.1 comes last in the walk and 10.0.0.2 shares its rack but comes first
+        assert_eq!(
+            cluster
+                .get_nodes_by_topology(position, &datacenters)
+                .unwrap(),
+            vec![ip(2), ip(4), ip(3), ip(5)]
+        );
+        let datacenters = BTreeMap::from([("dc1".to_string(), 4), ("dc2".to_string(), 1)]);
+        let position = cluster.get_node_pos_by_range(50).unwrap();
+        // with more replicas than racks the skipped nodes fill the rest
+        assert_eq!(
+            cluster
+                .get_nodes_by_topology(position, &datacenters)
+                .unwrap(),
+            vec![ip(1), ip(4), ip(6), ip(2), ip(3)]
+        );
+        let unknown = BTreeMap::from([("dc3".to_string(), 3)]);
+        assert!(cluster
+            .get_nodes_by_topology(position, &unknown)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_nodes_leaving_do_not_own_ranges() {
+        let mut leaving = node(2, vec![200]);
+        leaving.set_shutting_down();
+        let cluster = Cluster::new(node(1, vec![100]), vec![leaving, node(3, vec![300])]);
+        assert_eq!(replicas(&cluster, 150, 1), vec![ip(3)]);
+        assert_eq!(cluster.get_tokens(), vec![100, 300]);
+    }
+
+    #[test]
+    fn test_nodes_booting_own_ranges_only_after_bootstrap() {
+        let mut booting = node(2, vec![200]);
+        booting.set_booting();
+        let cluster = Cluster::new(node(1, vec![100]), vec![booting, node(3, vec![300])]);
+        assert_eq!(replicas(&cluster, 150, 1), vec![ip(3)]);
+        assert_eq!(replicas(&cluster.settled(), 150, 1), vec![ip(2)]);
+        assert_eq!(cluster.settled().get_tokens(), vec![100, 200, 300]);
+    }
+
+    #[test]
+    fn test_leaving_and_replaced_nodes_lose_their_ranges_once_settled() {
+        let mut leaving = node(2, vec![200]);
+        leaving.set_leaving();
+        let mut dead = node(3, vec![300]);
+        dead.set_inactive();
+        let mut replacement = node(4, vec![300]);
+        replacement.set_booting();
+        let cluster = Cluster::new(node(1, vec![100]), vec![leaving, dead, replacement]);
+        assert_eq!(replicas(&cluster, 150, 1), vec![ip(2)]);
+        assert_eq!(replicas(&cluster, 250, 1), vec![ip(3)]);
+        let settled = cluster.settled();
+        assert_eq!(replicas(&settled, 150, 1), vec![ip(4)]);
+        assert_eq!(settled.get_tokens(), vec![100, 300]);
+        assert_eq!(
+            cluster.get_common_ranges(&settled),
+            vec![(300, 100), (100, 200), (200, 300)]
+        );
+        // once replaced, the dead node stays in the ring until it leaves but is not a replica
+        let mut replacement = node(4, vec![300]);
+        replacement.set_active();
+        let mut dead = node(3, vec![300]);
+        dead.set_inactive();
+        let cluster = Cluster::new(node(1, vec![100]), vec![dead, replacement]);
+        assert_eq!(replicas(&cluster, 250, 2), vec![ip(4), ip(1)]);
+        assert_eq!(replicas(&cluster.without(&ip(4)), 250, 1), vec![ip(3)]);
+    }
+
+    #[test]
+    fn test_new_node_only_takes_the_ranges_it_gains() {
+        let mut nodes = Vec::new();
+        for last_byte in 2..=4 {
+            let tokens = TokenAllocation::Random.allocate(16, &[]);
+            nodes.push(node(last_byte, tokens));
+        }
+        let before = Cluster::new(node(1, TokenAllocation::Random.allocate(16, &[])), nodes);
+        let tokens = TokenAllocation::Even.allocate(16, &before.get_tokens());
+        let mut nodes: Vec<Node> = before
+            .get_other_nodes()
+            .iter()
+            .map(Node::new_from_node)
+            .collect();
+        nodes.push(node(5, tokens));
+        let after = Cluster::new(Node::new_from_node(before.get_own_node()), nodes);
+
+        let mut moved = 0;
+        for step in 0..4096u64 {
+            let key = i64::MIN.wrapping_add((step << 52) as i64);
+            let (old, new) = (replicas(&before, key, 2), replicas(&after, key, 2));
+            if old != new {
+                moved += 1;
+                assert!(new.contains(&ip(5)));
+            }
+        }
+        // with 5 nodes and replication 2 the new node owns about 2/5 of the keys
+        assert!(moved < 4096 * 3 / 5, "{} of 4096 keys moved", moved);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod cluster;
 pub mod node;
 pub mod node_meta_data_acces;
+pub mod token_allocation;
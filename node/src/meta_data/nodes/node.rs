@@ -1,12 +1,15 @@
+use crate::event_bus;
 use crate::meta_data::nodes::node::State::{
-    Active, Booting, Inactive, Recovering, ShuttingDown, StandBy,
+    Active, Booting, Inactive, Leaving, Recovering, ShuttingDown, StandBy,
 };
+use crate::partitioner::Token;
+use crate::utils::config_constants::{DEFAULT_DATACENTER, DEFAULT_RACK};
 use crate::utils::errors::Errors;
 use crate::utils::types::node_ip::NodeIp;
-use crate::utils::types::range::Range;
 use crate::utils::types::timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::str::FromStr;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum State {
@@ -14,6 +17,8 @@ pub enum State {
     Inactive,
     Booting,
     StandBy,
+    /// it is still a replica of its ranges while it sends them to the nodes that take them
+    Leaving,
     ShuttingDown,
     Recovering,
 }
@@ -25,31 +30,83 @@ impl Display for State {
             Inactive => write!(f, "Inactive"),
             Booting => write!(f, "Booting"),
             StandBy => write!(f, "StandBy"),
+            Leaving => write!(f, "Leaving"),
             ShuttingDown => write!(f, "ShuttingDown"),
             Recovering => write!(f, "Recovering"),
         }
     }
 }
 
+impl FromStr for State {
+    type Err = Errors;
+
+    fn from_str(state: &str) -> Result<Self, Self::Err> {
+        match state {
+            "Active" => Ok(Active),
+            "Inactive" => Ok(Inactive),
+            "Booting" => Ok(Booting),
+            "StandBy" => Ok(StandBy),
+            "Leaving" => Ok(Leaving),
+            "ShuttingDown" => Ok(ShuttingDown),
+            "Recovering" => Ok(Recovering),
+            _ => Err(Errors::ServerError(format!("Unknown node state {}", state))),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct Node {
     pub ip: NodeIp,
     pub position: usize,
-    pub range: Range,
+    /// tokens of the ring that belong to it, each one closes the range that starts at the previous token
+    #[serde(default)]
+    pub tokens: Vec<Token>,
     pub is_seed: bool,
     pub state: State,
     pub timestamp: Timestamp,
+    /// version of the heartbeat that arrived through gossip, the failure detector uses its arrival
+    #[serde(default)]
+    pub heartbeat: u64,
+    /// version of the schema of keyspaces and tables the node has
+    #[serde(default)]
+    pub schema_version: String,
+    /// bytes of data stored by the node
+    #[serde(default)]
+    pub load: u64,
+    /// datacenter and rack declared in the config, used by `NetworkTopologyStrategy`
+    #[serde(default = "default_datacenter")]
+    pub datacenter: String,
+    #[serde(default = "default_rack")]
+    pub rack: String,
+}
+
+fn default_datacenter() -> String {
+    DEFAULT_DATACENTER.to_string()
+}
+
+fn default_rack() -> String {
+    DEFAULT_RACK.to_string()
 }
 
 impl Node {
-    pub fn new(ip: &NodeIp, position: usize, is_seed: bool, range: Range) -> Result<Self, Errors> {
+    pub fn new(
+        ip: &NodeIp,
+        position: usize,
+        is_seed: bool,
+        tokens: Vec<Token>,
+    ) -> Result<Self, Errors> {
         Ok(Self {
             ip: NodeIp::new_from_ip(ip),
             position,
-            range,
+            tokens,
             is_seed,
             state: Active,
             timestamp: Timestamp::new(),
+            heartbeat: 0,
+            schema_version: String::new(),
+            load: 0,
+            datacenter: default_datacenter(),
+            rack: default_rack(),
         })
     }
 
@@ -57,10 +114,15 @@ impl Node {
         Self {
             ip: NodeIp::new_from_ip(&node.ip),
             position: node.position,
-            range: node.range.clone(),
+            tokens: node.tokens.clone(),
             is_seed: node.is_seed,
             state: node.state.clone(),
             timestamp: Timestamp::new_from_timestamp(&node.timestamp),
+            heartbeat: node.heartbeat,
+            schema_version: node.schema_version.clone(),
+            load: node.load,
+            datacenter: node.datacenter.clone(),
+            rack: node.rack.clone(),
         }
     }
 
@@ -105,6 +167,10 @@ impl Node {
         self.set_state(&StandBy)
     }
 
+    pub fn set_leaving(&mut self) {
+        self.set_state(&Leaving)
+    }
+
     pub fn set_shutting_down(&mut self) {
         self.set_state(&ShuttingDown)
     }
@@ -113,26 +179,36 @@ impl Node {
         self.set_state(&Recovering)
     }
 
-    pub fn get_range(&self) -> Range {
-        self.range.clone()
+    pub fn get_tokens(&self) -> &[Token] {
+        &self.tokens
     }
 
-    pub fn set_range(&mut self, range: Range) {
-        self.range = range;
+    pub fn set_tokens(&mut self, tokens: Vec<Token>) {
+        self.tokens = tokens;
         self.update_timestamp()
     }
 
-    pub fn set_range_by_pos(&mut self, nodes_quantity: usize) {
-        self.range = Range::from_fraction(self.position, nodes_quantity);
+    /// the node leaves the ring, its ranges go to the nodes that follow each of its tokens
+    pub fn clear_tokens(&mut self) {
+        self.tokens = Vec::new();
         self.update_timestamp()
     }
 
-    pub fn set_nonexistent_range(&mut self) {
-        self.range = Range::new_nonexistent();
-        self.update_timestamp()
+    pub fn get_datacenter(&self) -> &str {
+        &self.datacenter
+    }
+
+    pub fn get_rack(&self) -> &str {
+        &self.rack
+    }
+
+    pub fn set_location(&mut self, datacenter: String, rack: String) {
+        self.datacenter = datacenter;
+        self.rack = rack;
     }
 
     pub fn set_state(&mut self, state: &State) {
+        event_bus::publish_node_transition(&self.state, state, &self.ip);
         self.state = state.clone();
         self.update_timestamp()
     }
@@ -1,21 +1,29 @@
 use super::{cluster::Cluster, node::Node};
 use crate::meta_data::nodes::node::State;
+use crate::partitioner::{partition_key::PartitionKey, Token, TokenRange};
 use crate::utils::config_constants::SHUTTING_DOWN_TIMEOUT_SECS;
 use crate::utils::constants::NODES_METADATA_PATH;
 use crate::utils::errors::Errors::ServerError;
 use crate::utils::functions::{deserialize_from_slice, write_all_to_file};
 use crate::utils::types::node_ip::NodeIp;
 use crate::{
-    meta_data::keyspaces::keyspace_meta_data_acces::KeyspaceMetaDataAccess,
+    meta_data::keyspaces::{keyspace::ReplicaPlacement, keyspace_meta_data_acces::KeyspaceMetaDataAccess},
     utils::{constants::KEYSPACE_METADATA_PATH, errors::Errors},
 };
-use murmur3::murmur3_32;
 use std::collections::HashSet;
-use std::{fs::File, io::Cursor};
+use std::fs::File;
 
 #[derive(Debug)]
 pub struct NodesMetaDataAccess;
 
+/// Range whose replicas change: the ones that have it now and the ones that will also have it
+#[derive(Debug, PartialEq)]
+pub struct RangeMovement {
+    pub range: TokenRange,
+    pub from: Vec<NodeIp>,
+    pub gaining: Vec<NodeIp>,
+}
+
 impl NodesMetaDataAccess {
     fn read_cluster(path: &str) -> Result<Cluster, Errors> {
         let content = std::fs::read_to_string(path)
@@ -108,11 +116,27 @@ impl NodesMetaDataAccess {
         Ok(())
     }
 
+    /// updates the schema version and load of the own node, returns it to be gossiped
+    pub fn refresh_own_node(
+        &self,
+        path: &str,
+        schema_version: String,
+        load: u64,
+    ) -> Result<Node, Errors> {
+        let mut cluster = NodesMetaDataAccess::read_cluster(path)?;
+        let own_node = cluster.get_own_node_mut();
+        own_node.schema_version = schema_version;
+        own_node.load = load;
+        let refreshed = Node::new_from_node(own_node);
+        Self::write_cluster(path, &cluster)?;
+        Ok(refreshed)
+    }
+
     pub fn set_own_node_to_shutting_down(&self, path: &str) -> Result<(), Errors> {
         let cluster = NodesMetaDataAccess::read_cluster(path)?;
         let mut new_node = Node::new_from_node(cluster.get_own_node());
         new_node.set_shutting_down();
-        new_node.set_nonexistent_range();
+        new_node.clear_tokens();
         new_node.set_pos(0);
         let mut nodes_list = Vec::new();
         for node in cluster.get_other_nodes() {
@@ -139,44 +163,121 @@ impl NodesMetaDataAccess {
     pub fn get_partition_full_ips(
         &self,
         path: &str,
-        primary_key: &Option<Vec<String>>,
+        partition_key: &Option<PartitionKey>,
         keyspace: String,
     ) -> Result<Vec<NodeIp>, Errors> {
         let cluster = Self::read_cluster(path)?;
-        if let Some(primary_key) = primary_key {
-            let hashing_key = hash_string_murmur3(&primary_key.join(""));
-            Self::get_range_based_partitions(hashing_key, &cluster, keyspace)
+        if let Some(partition_key) = partition_key {
+            Self::get_range_based_partitions(partition_key.token()?, &cluster, keyspace)
         } else {
             cluster.get_all_ips()
         }
     }
 
-    // rangos mejores distribuidos pero mayor cambio al agregar o sacar nodos
-    #[allow(dead_code)]
-    fn get_mod_based_partitions(
-        hashing_key: usize,
-        cluster: &Cluster,
+    /// Ranges of the ring this node is a replica of in the keyspace, each one with all its
+    /// replicas
+    pub fn get_own_ranges(
+        &self,
+        path: &str,
+        keyspace: &str,
+    ) -> Result<Vec<(TokenRange, Vec<NodeIp>)>, Errors> {
+        let cluster = Self::read_cluster(path)?;
+        let mut ranges = Vec::new();
+        for range in cluster.get_ranges() {
+            let replicas =
+                Self::get_range_based_partitions(range.1, &cluster, keyspace.to_string())?;
+            if replicas.contains(cluster.get_own_ip()) {
+                ranges.push((range, replicas));
+            }
+        }
+        Ok(ranges)
+    }
+
+    /// Nodes that will be replicas of the partition once the data of the ones that are starting
+    /// or leaving finishes moving. The writes reach them too, so what is written in the meantime
+    /// is not lost
+    pub fn get_pending_ips(
+        &self,
+        path: &str,
+        partition_key: &Option<PartitionKey>,
         keyspace: String,
     ) -> Result<Vec<NodeIp>, Errors> {
-        let pos = hashing_key % cluster.len_nodes() + 1;
-        let keyspace_metadata = KeyspaceMetaDataAccess {};
-        let replication =
-            keyspace_metadata.get_replication(KEYSPACE_METADATA_PATH.to_owned(), &keyspace)?;
-        cluster.get_nodes(pos, replication)
+        let Some(partition_key) = partition_key else {
+            return Ok(Vec::new());
+        };
+        let cluster = Self::read_cluster(path)?;
+        let token = partition_key.token()?;
+        let replicas = Self::get_range_based_partitions(token, &cluster, keyspace.to_string())?;
+        let future = Self::get_range_based_partitions(token, &cluster.settled(), keyspace)?;
+        Ok(future
+            .into_iter()
+            .filter(|ip| !replicas.contains(ip))
+            .collect())
+    }
+
+    /// Ranges of the keyspace that some node gains once the ring settles. With `removed` it is
+    /// also computed as if that node were no longer there
+    pub fn get_range_movements(
+        &self,
+        path: &str,
+        keyspace: &str,
+        removed: Option<&NodeIp>,
+    ) -> Result<Vec<RangeMovement>, Errors> {
+        let cluster = Self::read_cluster(path)?;
+        let mut settled = cluster.settled();
+        if let Some(ip) = removed {
+            settled = settled.without(ip);
+        }
+        let mut movements = Vec::new();
+        for range in cluster.get_common_ranges(&settled) {
+            let from = Self::get_range_based_partitions(range.1, &cluster, keyspace.to_string())?;
+            let gaining: Vec<NodeIp> =
+                Self::get_range_based_partitions(range.1, &settled, keyspace.to_string())?
+                    .into_iter()
+                    .filter(|ip| !from.contains(ip))
+                    .collect();
+            if !gaining.is_empty() {
+                movements.push(RangeMovement {
+                    range,
+                    from,
+                    gaining,
+                });
+            }
+        }
+        Ok(movements)
+    }
+
+    /// Ranges this node will gain in the keyspace when it finishes starting, each one with its
+    /// current replicas, which are the ones that have the data
+    pub fn get_bootstrap_ranges(
+        &self,
+        path: &str,
+        keyspace: &str,
+    ) -> Result<Vec<(TokenRange, Vec<NodeIp>)>, Errors> {
+        let own_ip = self.get_own_ip(path)?;
+        Ok(self
+            .get_range_movements(path, keyspace, None)?
+            .into_iter()
+            .filter(|movement| movement.gaining.contains(&own_ip))
+            .map(|movement| (movement.range, movement.from))
+            .collect())
     }
 
-    // rangos distribuidos linealmente pero menor cambio al agregar o sacar nodos
-    #[allow(dead_code)]
+    /// replicas of the token: the owner of the range where it falls and the nodes that follow it,
+    /// with `NetworkTopologyStrategy` they are chosen per datacenter
     fn get_range_based_partitions(
-        hashing_key: usize,
+        token: Token,
         cluster: &Cluster,
         keyspace: String,
     ) -> Result<Vec<NodeIp>, Errors> {
-        let pos = cluster.get_node_pos_by_range(hashing_key)?;
+        let pos = cluster.get_node_pos_by_range(token)?;
         let keyspace_metadata = KeyspaceMetaDataAccess {};
-        let replication =
-            keyspace_metadata.get_replication(KEYSPACE_METADATA_PATH.to_owned(), &keyspace)?;
-        cluster.get_nodes(pos, replication)
+        match keyspace_metadata.get_placement(KEYSPACE_METADATA_PATH.to_owned(), &keyspace)? {
+            ReplicaPlacement::Simple(replication) => cluster.get_nodes(pos, replication),
+            ReplicaPlacement::NetworkTopology(datacenters) => {
+                cluster.get_nodes_by_topology(pos, &datacenters)
+            }
+        }
     }
 
     pub fn append_new_node(&self, path: &str, new_node: Node) -> Result<(), Errors> {
@@ -213,20 +314,22 @@ impl NodesMetaDataAccess {
         Ok(nodes)
     }
 
+    /// Compacts the positions and takes the nodes that are leaving out of the ring.
+    ///
+    /// The tokens of the rest of the nodes do not change, so only the ranges gained or lost
+    /// by the nodes that join or leave move
     pub fn update_ranges(&self, path: &str) -> Result<(), Errors> {
         let cluster = Self::read_cluster(path)?;
         let mut own_node = Node::new_from_node(cluster.get_own_node());
         let mut updated_other_nodes = self.get_new_node_vec(cluster.get_other_nodes());
 
-        let active_node_count = self.calculate_active_node_count(&own_node, &cluster)?;
-
         if let Some(missing_position) =
             self.get_missing_position_from_nodes(&own_node, &updated_other_nodes)?
         {
             self.adjust_node_positions(&mut own_node, &mut updated_other_nodes, missing_position);
         }
 
-        self.update_node_ranges(&mut own_node, &mut updated_other_nodes, active_node_count);
+        self.update_node_ranges(&mut own_node, &mut updated_other_nodes);
 
         Self::write_cluster(path, &Cluster::new(own_node, updated_other_nodes))?;
         Ok(())
@@ -240,42 +343,10 @@ impl NodesMetaDataAccess {
         new_nodes
     }
 
-    fn calculate_active_node_count(
-        &self,
-        own_node: &Node,
-        cluster: &Cluster,
-    ) -> Result<usize, Errors> {
-        let active_node_count = cluster
-            .get_other_nodes()
-            .iter()
-            .filter(|node| node.state != State::ShuttingDown)
-            .count();
-
-        Ok(active_node_count
-            + (if own_node.state != State::ShuttingDown {
-                1
-            } else {
-                0
-            }))
-    }
-
-    fn update_node_ranges(
-        &self,
-        own_node: &mut Node,
-        updated_other_nodes: &mut Vec<Node>,
-        active_node_count: usize,
-    ) {
-        if own_node.state == State::ShuttingDown {
-            own_node.set_nonexistent_range();
-        } else {
-            own_node.set_range_by_pos(active_node_count);
-        }
-
-        for node in updated_other_nodes {
-            if node.state == State::ShuttingDown {
-                node.set_nonexistent_range();
-            } else {
-                node.set_range_by_pos(active_node_count);
+    fn update_node_ranges(&self, own_node: &mut Node, updated_other_nodes: &mut [Node]) {
+        for node in std::iter::once(own_node).chain(updated_other_nodes.iter_mut()) {
+            if node.state == State::ShuttingDown && !node.get_tokens().is_empty() {
+                node.clear_tokens();
             }
         }
     }
@@ -320,7 +391,7 @@ impl NodesMetaDataAccess {
     }
 
     pub fn check_for_perished_shutting_down_nodes(&self) -> Result<(), Errors> {
-        for node in Self::read_cluster(NODES_METADATA_PATH)?.get_other_nodes() {
+        for node in Self::read_cluster(&NODES_METADATA_PATH)?.get_other_nodes() {
             if node.state == State::ShuttingDown
                 && node
                     .get_timestamp()
@@ -333,7 +404,7 @@ impl NodesMetaDataAccess {
     }
 
     fn eliminate_perished_shutting_down_nodes(&self) -> Result<(), Errors> {
-        let cluster = Self::read_cluster(NODES_METADATA_PATH)?;
+        let cluster = Self::read_cluster(&NODES_METADATA_PATH)?;
         let mut nodes_list = Vec::new();
         for node in cluster.get_other_nodes() {
             if node.state != State::ShuttingDown
@@ -345,12 +416,6 @@ impl NodesMetaDataAccess {
             }
         }
         let new_cluster = Cluster::new(Node::new_from_node(cluster.get_own_node()), nodes_list);
-        Self::write_cluster(NODES_METADATA_PATH, &new_cluster)
+        Self::write_cluster(&NODES_METADATA_PATH, &new_cluster)
     }
 }
-
-fn hash_string_murmur3(input: &str) -> usize {
-    let mut buffer = Cursor::new(input.as_bytes());
-    let hash = murmur3_32(&mut buffer, 0).expect("Unable to compute hash");
-    hash as usize
-}
@@ -0,0 +1,87 @@
+use crate::partitioner::Token;
+use serde::Deserialize;
+use std::collections::BTreeSet;
+
+/// amount of values in the token space of the ring, tokens go from `i64::MIN` to `i64::MAX`
+const TOKEN_SPACE: i128 = 1 << 64;
+
+/// How the tokens of a new node are chosen, `token_allocation` key of the configuration.
+///
+/// Tokens are chosen once when joining the ring and never change, so a new node only takes
+/// the ranges that end at its tokens.
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub enum TokenAllocation {
+    #[default]
+    Random,
+    /// each token splits the largest range of the ring in two halves
+    Even,
+}
+
+impl TokenAllocation {
+    /// chooses `num_tokens` tokens that are not already in the ring
+    pub fn allocate(&self, num_tokens: usize, ring: &[Token]) -> Vec<Token> {
+        let mut taken: BTreeSet<Token> = ring.iter().copied().collect();
+        let mut tokens = Vec::new();
+        while tokens.len() < num_tokens {
+            let token = match self {
+                TokenAllocation::Random => rand::random::<i64>(),
+                TokenAllocation::Even if taken.is_empty() => {
+                    to_token(tokens.len() as i128 * (TOKEN_SPACE / num_tokens as i128))
+                }
+                TokenAllocation::Even => split_largest_range(&taken),
+            };
+            if taken.insert(token) {
+                tokens.push(token);
+            }
+        }
+        tokens.sort();
+        tokens
+    }
+}
+
+/// token at the given distance from the start of the ring
+fn to_token(offset: i128) -> Token {
+    (i64::MIN as i128 + offset.rem_euclid(TOKEN_SPACE)) as Token
+}
+
+/// middle of the largest range between consecutive tokens, including the one that wraps around
+fn split_largest_range(taken: &BTreeSet<Token>) -> Token {
+    let (Some(first), Some(last)) = (taken.first(), taken.last()) else {
+        return 0;
+    };
+    let (first, last) = (*first as i128, *last as i128);
+    let mut largest = (last, first + TOKEN_SPACE - last);
+    for (start, end) in taken.iter().zip(taken.iter().skip(1)) {
+        let (start, end) = (*start as i128, *end as i128);
+        if end - start > largest.1 {
+            largest = (start, end - start);
+        }
+    }
+    to_token(largest.0 + largest.1 / 2 - i64::MIN as i128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_even_tokens_of_the_first_node() {
+        let tokens = TokenAllocation::Even.allocate(4, &[]);
+        assert_eq!(tokens, vec![i64::MIN, -(1 << 62), 0, 1 << 62]);
+    }
+
+    #[test]
+    fn test_even_tokens_split_the_largest_ranges() {
+        let ring = TokenAllocation::Even.allocate(4, &[]);
+        let tokens = TokenAllocation::Even.allocate(2, &ring);
+        assert_eq!(tokens, vec![-(3 << 61), 3 << 61]);
+    }
+
+    #[test]
+    fn test_random_tokens_are_new() {
+        let ring = TokenAllocation::Random.allocate(16, &[]);
+        let tokens = TokenAllocation::Random.allocate(16, &ring);
+        assert_eq!(tokens.len(), 16);
+        assert!(tokens.iter().all(|token| !ring.contains(token)));
+    }
+}
@@ -1,11 +1,22 @@
+use crate::auth::authenticator::AuthenticatorKind;
 use crate::data_access::data_access_handler::DataAccessHandler;
+use crate::gossip::failure_detector;
 use crate::gossip::gossip_listener::GossipListener;
 use crate::gossip::seed_listener::SeedListener;
+use crate::hinted_handoff::hints_config::HintsConfig;
 use crate::meta_data::meta_data_handler::MetaDataHandler;
 use crate::meta_data::nodes::cluster::Cluster;
-use crate::meta_data::nodes::node::Node;
+use crate::meta_data::nodes::node::{Node, State};
 use crate::meta_data::nodes::node_meta_data_acces::NodesMetaDataAccess;
+use crate::meta_data::nodes::token_allocation::TokenAllocation;
+use crate::partitioner::Token;
 use crate::query_delegation::query_receiver::QueryReceiver;
+use crate::repair::repair_listener::RepairListener;
+use crate::streaming::stream_listener::StreamListener;
+use crate::utils::config_constants::{
+    DEFAULT_DATACENTER, DEFAULT_RACK, HINTED_HANDOFF_THROTTLE_KB, MAX_HINTS_SIZE_PER_NODE_KB,
+    MAX_HINT_WINDOW_SECS, NUM_TOKENS, PHI_CONVICT_THRESHOLD,
+};
 use crate::utils::constants::{CONFIG_FILE, IP_FILE, NODES_METADATA_PATH};
 use crate::utils::errors::Errors;
 use crate::utils::errors::Errors::ServerError;
@@ -14,7 +25,6 @@ use crate::utils::functions::{
     write_to_stream,
 };
 use crate::utils::types::node_ip::NodeIp;
-use crate::utils::types::range::Range;
 use serde::Deserialize;
 use std::fs::File;
 use std::io::Write;
@@ -26,6 +36,10 @@ pub struct NodeInitializer {
     pub node: Node,
     pub is_first: bool,
     pub is_seed: bool,
+    pub num_tokens: usize,
+    pub token_allocation: TokenAllocation,
+    /// down node whose tokens this one takes when it starts
+    pub replace_address: Option<NodeIp>,
 }
 
 #[derive(Deserialize)]
@@ -34,6 +48,54 @@ struct Config {
     seed_ip: NodeIp,
     is_seed: bool,
     is_first: bool,
+    #[serde(default)]
+    authenticator: AuthenticatorKind,
+    #[serde(default = "default_num_tokens")]
+    num_tokens: usize,
+    #[serde(default)]
+    token_allocation: TokenAllocation,
+    #[serde(default = "default_datacenter")]
+    datacenter: String,
+    #[serde(default = "default_rack")]
+    rack: String,
+    #[serde(default = "default_phi_convict_threshold")]
+    phi_convict_threshold: f64,
+    #[serde(default)]
+    replace_address: Option<NodeIp>,
+    #[serde(default = "default_max_hint_window_secs")]
+    max_hint_window_secs: i64,
+    #[serde(default = "default_max_hints_size_per_node_kb")]
+    max_hints_size_per_node_kb: u64,
+    #[serde(default = "default_hinted_handoff_throttle_kb")]
+    hinted_handoff_throttle_kb: u64,
+}
+
+fn default_num_tokens() -> usize {
+    NUM_TOKENS
+}
+
+fn default_datacenter() -> String {
+    DEFAULT_DATACENTER.to_string()
+}
+
+fn default_rack() -> String {
+    DEFAULT_RACK.to_string()
+}
+
+fn default_phi_convict_threshold() -> f64 {
+    PHI_CONVICT_THRESHOLD
+}
+
+fn default_max_hint_window_secs() -> i64 {
+    MAX_HINT_WINDOW_SECS
+}
+
+fn default_max_hints_size_per_node_kb() -> u64 {
+    MAX_HINTS_SIZE_PER_NODE_KB
+}
+
+fn default_hinted_handoff_throttle_kb() -> u64 {
+    HINTED_HANDOFF_THROTTLE_KB
 }
 
 impl NodeInitializer {
@@ -75,9 +137,12 @@ impl NodeInitializer {
         Ok(Self {
             ip: NodeIp::new_from_string(ip.as_str(), port)?,
             seed_ip: NodeIp::new_from_string(seed_ip.as_str(), seed_port)?,
-            node: Node::new(&node_ip, 1, is_seed, Range::new_full()).expect("Error creating node"),
+            node: Node::new(&node_ip, 1, is_seed, Vec::new()).expect("Error creating node"),
             is_first,
             is_seed,
+            num_tokens: NUM_TOKENS,
+            token_allocation: TokenAllocation::default(),
+            replace_address: None,
         })
     }
 
@@ -91,13 +156,26 @@ impl NodeInitializer {
             config.seed_ip = NodeIp::new_from_ip(&config.ip);
         }
         store_ip(&NodeIp::new_from_ip(&config.ip))?;
+        config.authenticator.configure();
+        failure_detector::configure(config.phi_convict_threshold);
+        HintsConfig {
+            max_hint_window_secs: config.max_hint_window_secs,
+            max_hints_size_per_node_kb: config.max_hints_size_per_node_kb,
+            hinted_handoff_throttle_kb: config.hinted_handoff_throttle_kb,
+        }
+        .configure();
+        let mut node =
+            Node::new(&config.ip, 1, config.is_seed, Vec::new()).expect("Error creating node");
+        node.set_location(config.datacenter, config.rack);
         Ok(Self {
-            node: Node::new(&config.ip, 1, config.is_seed, Range::new_full())
-                .expect("Error creating node"),
+            node,
             ip: config.ip,
             seed_ip: config.seed_ip,
             is_first: config.is_first,
             is_seed: config.is_seed,
+            num_tokens: config.num_tokens.max(1),
+            token_allocation: config.token_allocation,
+            replace_address: config.replace_address,
         })
     }
 
@@ -121,11 +199,17 @@ impl NodeInitializer {
         self.is_seed
     }
 
+    pub fn get_replace_address(&self) -> Option<NodeIp> {
+        self.replace_address.clone()
+    }
+
     pub fn start_listeners(&self) {
         let metadata_ip = self.get_ip();
         let data_access_ip = self.get_ip();
         let query_receiver_ip = self.get_ip();
         let gossip_ip = self.get_ip();
+        let repair_ip = self.get_ip();
+        let streaming_ip = self.get_ip();
         thread::spawn(move || {
             MetaDataHandler::start_listening(metadata_ip)
                 .expect("Failed to start metadata listener");
@@ -141,6 +225,13 @@ impl NodeInitializer {
         thread::spawn(move || {
             GossipListener::start_listening(gossip_ip).expect("Failed to start gossip listener");
         });
+        thread::spawn(move || {
+            RepairListener::start_listening(repair_ip).expect("Failed to start repair listener");
+        });
+        thread::spawn(move || {
+            StreamListener::start_listening(streaming_ip)
+                .expect("Failed to start streaming listener");
+        });
         if self.is_seed {
             let seed_ip = NodeIp::new_from_ip(&self.get_ip());
             thread::spawn(move || SeedListener::start_listening(seed_ip));
@@ -159,20 +250,34 @@ impl NodeInitializer {
             needs_recovering = set_node_pos(&mut node, &nodes);
             if needs_recovering {
                 nodes = eliminate_node_by_ip(&nodes, node.get_ip())
+            } else if let Some(replaced) = &self.replace_address {
+                node.set_tokens(replaced_tokens(&nodes, replaced, node.get_ip())?);
+            } else {
+                node.set_tokens(self.allocate_tokens(&nodes));
             }
-            node.set_range(Range::from_fraction(node.get_pos(), nodes.len() + 1)); // set range in pos / total nodes
             write_to_stream(&mut stream, serialize_to_string(&node)?.as_bytes())?
+        } else {
+            node.set_tokens(self.allocate_tokens(&nodes));
         }
         let cluster = Cluster::new(Node::new_from_node(&node), nodes);
-        if let Err(e) = NodesMetaDataAccess::write_cluster(NODES_METADATA_PATH, &cluster) {
+        if let Err(e) = NodesMetaDataAccess::write_cluster(&NODES_METADATA_PATH, &cluster) {
             println!("{}", e);
         }
         Ok((needs_recovering, needs_booting))
     }
+
+    /// tokens of a node that joins the ring, the rest of the nodes keep theirs
+    fn allocate_tokens(&self, nodes: &[Node]) -> Vec<Token> {
+        let ring: Vec<Token> = nodes
+            .iter()
+            .flat_map(|node| node.get_tokens().iter().copied())
+            .collect();
+        self.token_allocation.allocate(self.num_tokens, &ring)
+    }
 }
 
 fn store_ip(ip: &NodeIp) -> Result<(), Errors> {
-    let mut file = File::create(IP_FILE).expect("Error creating file");
+    let mut file = File::create(IP_FILE.as_str()).expect("Error creating file");
     file.write_all(ip.get_string_ip().as_bytes())
         .expect("Error writing to file");
     Ok(())
@@ -183,6 +288,7 @@ fn set_node_pos(node: &mut Node, nodes: &Vec<Node>) -> bool {
     for received_node in nodes {
         if received_node.get_ip() == node.get_ip() {
             node.position = received_node.get_pos();
+            node.tokens = received_node.tokens.clone();
             node.set_recovering();
             return true;
         }
@@ -194,6 +300,28 @@ fn set_node_pos(node: &mut Node, nodes: &Vec<Node>) -> bool {
     false
 }
 
+/// Tokens of the replaced node, which has to be down: one that is up leaves with `decommission`
+fn replaced_tokens(
+    nodes: &[Node],
+    replaced: &NodeIp,
+    own_ip: &NodeIp,
+) -> Result<Vec<Token>, Errors> {
+    if replaced == own_ip {
+        return Err(ServerError(String::from("A node cannot replace itself")));
+    }
+    match nodes.iter().find(|node| node.get_ip() == replaced) {
+        Some(node) if node.state == State::Inactive => Ok(node.get_tokens().to_vec()),
+        Some(_) => Err(ServerError(format!(
+            "{} is not down, it cannot be replaced",
+            replaced.get_string_ip()
+        ))),
+        None => Err(ServerError(format!(
+            "{} is not part of the cluster",
+            replaced.get_string_ip()
+        ))),
+    }
+}
+
 fn eliminate_node_by_ip(nodes: &Vec<Node>, ip: &NodeIp) -> Vec<Node> {
     let mut new_nodes = Vec::<Node>::new();
     for node in nodes {
@@ -1,12 +1,169 @@
 use crate::executables::batch_executable::BatchExecutable;
 use crate::executables::executable::Executable;
+use crate::executables::prepared_statement::PreparedStatement;
 use crate::parsers::parser::Parser;
+use crate::parsers::query_parser::{query_lexer, query_parser};
+use crate::queries::batch_query::{BatchQuery, BatchStatement, BatchType};
+use crate::queries::query::Query;
 use crate::utils::errors::Errors;
+use crate::utils::types::bytes_cursor::BytesCursor;
+use crate::utils::types::query_parameters::QueryParameters;
 
-pub struct BatchParser;
+const QUERY_KIND: u8 = 0;
+const PREPARED_KIND: u8 = 1;
+
+pub struct BatchParser {
+    version: u8,
+}
+
+impl BatchParser {
+    pub fn new(version: u8) -> Self {
+        Self { version }
+    }
+}
+
+impl BatchParser {
+    /// The statements are followed by `<consistency><flags>` and the serial consistency and
+    /// default timestamp the flags indicate, the same fields of a QUERY. The timestamp sent by
+    /// the client is the one of every statement of the batch
+    fn read_batch(&self, body: &[u8]) -> Result<(BatchQuery, QueryParameters), Errors> {
+        let mut cursor = BytesCursor::new(body);
+        let mut batch = BatchQuery::new();
+        batch.batch_type = BatchType::from_u8(cursor.read_u8()?)?;
+        for _ in 0..cursor.read_short()? {
+            batch.statements.push(read_statement(&mut cursor)?);
+        }
+        let parameters = QueryParameters::parse(&mut cursor, self.version)?;
+        if let Some(timestamp) = parameters.timestamp {
+            batch.set_timestamp(timestamp);
+        }
+        Ok((batch, parameters))
+    }
+}
 
 impl Parser for BatchParser {
-    fn parse(&self, _body: &[u8]) -> Result<Box<dyn Executable>, Errors> {
-        Ok(Box::new(BatchExecutable))
+    fn parse(&self, body: &[u8]) -> Result<Box<dyn Executable>, Errors> {
+        let (batch, parameters) = self.read_batch(body)?;
+        let executable = BatchExecutable::new(batch, parameters.consistency);
+        Ok(Box::new(executable))
+    }
+}
+
+/// reads one `<kind><string_or_id><n>[<value_1>...<value_n>]` entry of the batch
+fn read_statement(cursor: &mut BytesCursor) -> Result<BatchStatement, Errors> {
+    let query = match cursor.read_u8()? {
+        QUERY_KIND => {
+            let query = cursor.read_long_string()?;
+            let values = cursor.read_values()?;
+            if values.is_empty() {
+                query_parser(query_lexer(query)?)?
+            } else {
+                PreparedStatement::prepare(query)?.bind(&values)?
+            }
+        }
+        PREPARED_KIND => {
+            let Some(id) = cursor.read_short_bytes()? else {
+                return Err(Errors::ProtocolError(String::from("Missing prepared id")));
+            };
+            PreparedStatement::get(&id)?.bind(&cursor.read_values()?)?
+        }
+        kind => {
+            return Err(Errors::ProtocolError(format!(
+                "Invalid batch statement kind {}",
+                kind
+            )))
+        }
+    };
+    BatchStatement::from_query(&query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_entry(query: &str) -> Vec<u8> {
+        let mut entry = vec![QUERY_KIND];
+        entry.extend_from_slice(&(query.len() as i32).to_be_bytes());
+        entry.extend_from_slice(query.as_bytes());
+        entry.extend_from_slice(&0i16.to_be_bytes());
+        entry
+    }
+
+    fn body(batch_type: u8, entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut body = vec![batch_type];
+        body.extend_from_slice(&(entries.len() as i16).to_be_bytes());
+        for entry in entries {
+            body.extend_from_slice(entry);
+        }
+        body.extend_from_slice(&0x0001_i16.to_be_bytes());
+        body.push(0);
+        body
+    }
+
+    #[test]
+    fn test_parse_batch_body() {
+        let body = body(
+            1,
+            &[
+                query_entry("INSERT INTO kp.users (id, name) VALUES (1, 'Ana')"),
+                query_entry("DELETE FROM kp.users WHERE id = 1"),
+            ],
+        );
+        assert!(BatchParser::new(0x04).parse(&body).is_ok());
+    }
+
+    #[test]
+    fn test_parse_batch_flags() {
+        let mut body = body(1, &[query_entry("DELETE FROM kp.users WHERE id = 1")]);
+        // serial consistency and default timestamp, in microseconds
+        *body.last_mut().unwrap() = 0x30;
+        body.extend_from_slice(&0x0008_i16.to_be_bytes());
+        body.extend_from_slice(&42_000i64.to_be_bytes());
+        let (batch, parameters) = BatchParser::new(0x04).read_batch(&body).unwrap();
+        assert_eq!(parameters.serial_consistency, Some(0x0008));
+        assert_eq!(batch.timestamp(), Some(42));
+        body.truncate(body.len() - 4);
+        assert!(BatchParser::new(0x04).read_batch(&body).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_without_timestamp() {
+        let body = body(1, &[query_entry("DELETE FROM kp.users WHERE id = 1")]);
+        let (batch, _) = BatchParser::new(0x04).read_batch(&body).unwrap();
+        assert_eq!(batch.timestamp(), None);
+    }
+
+    #[test]
+    fn test_parse_batch_rejects_select() {
+        let body = body(0, &[query_entry("SELECT id FROM kp.users")]);
+        assert!(BatchParser::new(0x04).parse(&body).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_invalid_type() {
+        let body = body(7, &[query_entry("DELETE FROM kp.users WHERE id = 1")]);
+        assert!(BatchParser::new(0x04).parse(&body).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_unknown_prepared_id() {
+        let mut entry = vec![PREPARED_KIND];
+        entry.extend_from_slice(&2i16.to_be_bytes());
+        entry.extend_from_slice(&[0xAB, 0xCD]);
+        entry.extend_from_slice(&0i16.to_be_bytes());
+        assert!(matches!(
+            BatchParser::new(0x04).parse(&body(0, &[entry])),
+            Err(Errors::Unprepared(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_parse_batch_with_values() {
+        let mut entry = query_entry("DELETE FROM kp.users WHERE id = 1");
+        entry.truncate(entry.len() - 2);
+        entry.extend_from_slice(&1i16.to_be_bytes());
+        entry.extend_from_slice(&1i32.to_be_bytes());
+        entry.push(1);
+        assert!(BatchParser::new(0x04).parse(&body(0, &[entry])).is_err());
     }
 }
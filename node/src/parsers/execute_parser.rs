@@ -3,15 +3,30 @@ use crate::executables::execute_executable::ExecuteExecutable;
 use crate::parsers::parser::Parser;
 use crate::utils::errors::Errors;
 use crate::utils::types::bytes_cursor::BytesCursor;
+use crate::utils::types::query_parameters::QueryParameters;
 
-pub struct ExecuteParser;
+pub struct ExecuteParser {
+    version: u8,
+}
+
+impl ExecuteParser {
+    pub fn new(version: u8) -> Self {
+        Self { version }
+    }
+}
 
 impl Parser for ExecuteParser {
     fn parse(&self, body: &[u8]) -> Result<Box<dyn Executable>, Errors> {
         let mut cursor = BytesCursor::new(body);
-        let id = cursor.read_short()?;
-        let consistency = cursor.read_short()?;
-        let executable = ExecuteExecutable::new(id, consistency);
+        let Some(id) = cursor.read_short_bytes()? else {
+            return Err(Errors::ProtocolError(String::from("Missing prepared id")));
+        };
+        if self.version >= 0x05 {
+            // the result metadata id, the node always sends the full metadata
+            let _result_metadata_id = cursor.read_short_bytes()?;
+        }
+        let parameters = QueryParameters::parse(&mut cursor, self.version)?;
+        let executable = ExecuteExecutable::new(id, parameters);
         Ok(Box::new(executable))
     }
 }
@@ -14,17 +14,17 @@ use crate::utils::parser_constants::*;
 pub struct ParserFactory {}
 
 impl ParserFactory {
-    /// given a request opcode, returns the matching parser
-    pub fn get_parser(opcode: u8) -> Result<Box<dyn Parser>, Errors> {
+    /// given a request opcode and the protocol version of the frame, returns the matching parser
+    pub fn get_parser(opcode: u8, version: u8) -> Result<Box<dyn Parser>, Errors> {
         match opcode {
             STARTUP => Ok(Box::new(StartupParser)),
             OPTIONS => Ok(Box::new(OptionsParser)),
-            QUERY => Ok(Box::new(QueryParser)),
+            QUERY => Ok(Box::new(QueryParser::new(version))),
             PREPARE => Ok(Box::new(PrepareParser)),
-            EXECUTE => Ok(Box::new(ExecuteParser)),
+            EXECUTE => Ok(Box::new(ExecuteParser::new(version))),
             REGISTER => Ok(Box::new(RegisterParser)),
             EVENT => Ok(Box::new(EventParser)),
-            BATCH => Ok(Box::new(BatchParser)),
+            BATCH => Ok(Box::new(BatchParser::new(version))),
             AUTH_RESPONSE => Ok(Box::new(AuthResponseParser)),
             _ => Err(Errors::ProtocolError(format!(
                 "Opcode {} is invalid",
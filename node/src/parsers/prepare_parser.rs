@@ -10,9 +10,7 @@ impl Parser for PrepareParser {
     fn parse(&self, body: &[u8]) -> Result<Box<dyn Executable>, Errors> {
         let mut cursor = BytesCursor::new(body);
         let string = cursor.read_long_string()?;
-        let tokens = crate::parsers::query_parser::query_lexer(string)?;
-        let query = crate::parsers::query_parser::query_parser(tokens)?;
-        let executable = PrepareExecutable::new(query);
+        let executable = PrepareExecutable::new(string);
         Ok(Box::new(executable))
     }
 }
@@ -1,16 +1,22 @@
 use crate::executables::executable::Executable;
+use crate::executables::prepared_statement::PreparedStatement;
 use crate::executables::query_executable::QueryExecutable;
 use crate::parsers::parser::Parser;
 use crate::queries::query::Query;
 use crate::utils::errors::Errors;
 use crate::utils::types::bytes_cursor::BytesCursor;
-use crate::utils::types::token_conversor::get_next_value;
+use crate::utils::types::query_parameters::QueryParameters;
+use crate::utils::parser_constants::{BEGIN, DESCRIBE};
+use crate::utils::types::token_conversor::{get_next_value, is_keyword};
 
-use super::query_parsers::alter_table_parser::AlterTableParser;
+use super::query_parsers::alter_query_parser::AlterQueryParser;
+use super::query_parsers::batch_query_parser::BatchQueryParser;
 use super::query_parsers::delete_query_parser::DeleteQueryParser;
+use super::query_parsers::describe_query_parser::DescribeQueryParser;
 use super::query_parsers::drop_query_parser::DropQueryParser;
 use super::query_parsers::insert_query_parser::InsertQueryParser;
 use super::query_parsers::select_query_parser::SelectQueryParser;
+use super::query_parsers::truncate_query_parser::TruncateQueryParser;
 use super::query_parsers::update_query_parser::UpdateQueryParser;
 use super::query_parsers::use_query_parser::UseQueryParser;
 use super::tokens::lexer::standardize;
@@ -18,16 +24,27 @@ use super::tokens::token::{tokenize, Token};
 use crate::parsers::query_parsers::create_query_parser::CreateQueryParser;
 use Token::*;
 
-pub struct QueryParser;
+pub struct QueryParser {
+    version: u8,
+}
+
+impl QueryParser {
+    pub fn new(version: u8) -> Self {
+        Self { version }
+    }
+}
 
 impl Parser for QueryParser {
     fn parse(&self, body: &[u8]) -> Result<Box<dyn Executable>, Errors> {
         let mut cursor = BytesCursor::new(body);
         let string = cursor.read_long_string()?;
-        let tokens = query_lexer(string)?;
-        let query = query_parser(tokens)?;
-        let consistency = cursor.read_short()?;
-        let executable = QueryExecutable::new(query, consistency);
+        let parameters = QueryParameters::parse(&mut cursor, self.version)?;
+        let query = if parameters.values.is_empty() {
+            query_parser(query_lexer(string)?)?
+        } else {
+            PreparedStatement::parse(string)?.bind_values(&parameters.values)?
+        };
+        let executable = QueryExecutable::new(query, parameters);
         Ok(Box::new(executable))
     }
 }
@@ -51,12 +68,20 @@ pub fn query_parser(tokens: Vec<Token>) -> Result<Box<dyn Query>, Errors> {
                 "UPDATE" => Ok(Box::new(UpdateQueryParser::parse(tokens)?)),
                 "DELETE" => Ok(Box::new(DeleteQueryParser::parse(tokens)?)),
                 "USE" => Ok(Box::new(UseQueryParser.parse(tokens)?)),
-                "ALTER" => Ok(Box::new(AlterTableParser.parse(tokens)?)),
+                "ALTER" => AlterQueryParser::parse(tokens),
                 "DROP" => DropQueryParser::parse(tokens),
                 "CREATE" => CreateQueryParser::parse(tokens),
+                "DESC" => Ok(Box::new(DescribeQueryParser::parse(tokens)?)),
+                "TRUNCATE" => Ok(Box::new(TruncateQueryParser::parse(tokens)?)),
                 _ => Err(Errors::SyntaxError(format!("Unknown query type: {}", res))),
             }
         }
+        Ok(token) if is_keyword(&token, DESCRIBE) => {
+            Ok(Box::new(DescribeQueryParser::parse(tokens_iter.collect())?))
+        }
+        Ok(token) if is_keyword(&token, BEGIN) => {
+            Ok(Box::new(BatchQueryParser::parse(tokens_iter.collect())?))
+        }
         _ => Err(Errors::SyntaxError("Invalid CQL syntax".to_string())),
     }
 }
@@ -77,7 +102,7 @@ mod tests {
         body.extend_from_slice(query);
         body.extend_from_slice(&consistency);
 
-        let parser = QueryParser;
+        let parser = QueryParser::new(0x03);
         let result = parser.parse(&body);
         if let Err(e) = &result {
             println!("Error al parsear: {:?}", e);
@@ -93,7 +118,7 @@ mod tests {
         body.extend_from_slice(&len);
         body.extend_from_slice(query);
 
-        let parser = QueryParser;
+        let parser = QueryParser::new(0x03);
         let result = parser.parse(&body);
 
         assert!(result.is_err());
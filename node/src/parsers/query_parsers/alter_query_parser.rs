@@ -0,0 +1,59 @@
+use super::{alter_table_parser::AlterTableParser, alter_type_parser::AlterTypeParser};
+use crate::utils::parser_constants::TYPE;
+use crate::{
+    parsers::tokens::token::Token,
+    queries::query::Query,
+    utils::{
+        errors::Errors,
+        types::token_conversor::{is_keyword, peek_next_value},
+    },
+};
+
+pub struct AlterQueryParser;
+
+impl AlterQueryParser {
+    pub fn parse(tokens: Vec<Token>) -> Result<Box<dyn Query>, Errors> {
+        let mut tokens_iter = tokens.into_iter().peekable();
+        match peek_next_value(&mut tokens_iter)? {
+            token if is_keyword(token, TYPE) => {
+                tokens_iter.next();
+                Ok(Box::new(AlterTypeParser::parse(&mut tokens_iter)?))
+            }
+            _ => Ok(Box::new(AlterTableParser.parse(tokens_iter.collect())?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::tokens::data_type::DataType;
+    use crate::queries::alter_table_query::AlterTableQuery;
+    use crate::queries::alter_type_query::AlterTypeQuery;
+
+    #[test]
+    fn test_alter_dispatches_table() {
+        let tokens = vec![
+            Token::Reserved(String::from("TABLE")),
+            Token::Identifier(String::from("kp.table")),
+            Token::Reserved(String::from("ADD")),
+            Token::Identifier(String::from("column")),
+            Token::DataType(DataType::Int),
+        ];
+        let query = AlterQueryParser::parse(tokens).unwrap();
+        assert!(query.as_any().downcast_ref::<AlterTableQuery>().is_some());
+    }
+
+    #[test]
+    fn test_alter_dispatches_type() {
+        let tokens = vec![
+            Token::Reserved(String::from(TYPE)),
+            Token::Identifier(String::from("kp.coordinates")),
+            Token::Reserved(String::from("ADD")),
+            Token::Identifier(String::from("alt")),
+            Token::DataType(DataType::Int),
+        ];
+        let query = AlterQueryParser::parse(tokens).unwrap();
+        assert!(query.as_any().downcast_ref::<AlterTypeQuery>().is_some());
+    }
+}
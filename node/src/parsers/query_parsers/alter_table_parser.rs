@@ -6,7 +6,7 @@ use crate::{
     utils::errors::Errors,
 };
 
-use crate::utils::types::token_conversor::get_next_value;
+use crate::utils::types::token_conversor::{get_next_value, is_keyword};
 
 const MISSING_KEYWORD: &str = "Missing keyword following table name";
 const UNEXPECTED_TOKEN: &str = "Unexpected token";
@@ -109,7 +109,7 @@ impl AlterTableParser {
         query: &mut AlterTableQuery,
     ) -> Result<(), Errors> {
         match get_next_value(tokens)? {
-            Token::Reserved(keyword) if keyword == TYPE => self.data_type(tokens, query),
+            token if is_keyword(&token, TYPE) => self.data_type(tokens, query),
             _ => Err(Errors::SyntaxError(UNEXPECTED_TOKEN.to_string())),
         }
     }
@@ -0,0 +1,121 @@
+use std::{iter::Peekable, vec::IntoIter};
+
+use super::create_table_query_parser::get_data_type;
+use crate::{
+    parsers::tokens::token::Token,
+    queries::{alter_table_query::Operations, alter_type_query::AlterTypeQuery},
+    utils::{errors::Errors, types::token_conversor::get_next_value},
+};
+
+const UNEXPECTED_TOKEN: &str = "Unexpected token";
+const MISSING_KEYWORD: &str = "Missing keyword following type name";
+const ADD: &str = "ADD";
+const RENAME: &str = "RENAME";
+const TO: &str = "TO";
+
+pub struct AlterTypeParser;
+
+impl AlterTypeParser {
+    pub fn parse(tokens: &mut Peekable<IntoIter<Token>>) -> Result<AlterTypeQuery, Errors> {
+        let mut alter_type_query = AlterTypeQuery::new();
+        type_name(tokens, &mut alter_type_query)?;
+        if tokens.next().is_some() {
+            return Err(Errors::SyntaxError(String::from(
+                "ALTER TYPE with left over parameters",
+            )));
+        }
+        Ok(alter_type_query)
+    }
+}
+
+fn type_name(
+    tokens: &mut Peekable<IntoIter<Token>>,
+    query: &mut AlterTypeQuery,
+) -> Result<(), Errors> {
+    match get_next_value(tokens)? {
+        Token::Identifier(name) => {
+            query.type_name = name;
+            keyword(tokens, query)
+        }
+        _ => Err(Errors::SyntaxError(UNEXPECTED_TOKEN.to_string())),
+    }
+}
+
+fn keyword(tokens: &mut Peekable<IntoIter<Token>>, query: &mut AlterTypeQuery) -> Result<(), Errors> {
+    match get_next_value(tokens)? {
+        Token::Reserved(keyword) if keyword == ADD => {
+            query.first_field = field_name(tokens)?;
+            query.operation = Some(Operations::ADD);
+            query.data = get_data_type(tokens)?;
+            Ok(())
+        }
+        Token::Reserved(keyword) if keyword == RENAME => {
+            query.first_field = field_name(tokens)?;
+            query.operation = Some(Operations::RENAME);
+            match get_next_value(tokens)? {
+                Token::Reserved(keyword) if keyword == TO => {
+                    query.second_field = field_name(tokens)?;
+                    Ok(())
+                }
+                _ => Err(Errors::SyntaxError(UNEXPECTED_TOKEN.to_string())),
+            }
+        }
+        Token::Reserved(_) => Err(Errors::SyntaxError(UNEXPECTED_TOKEN.to_string())),
+        _ => Err(Errors::SyntaxError(MISSING_KEYWORD.to_string())),
+    }
+}
+
+fn field_name(tokens: &mut Peekable<IntoIter<Token>>) -> Result<String, Errors> {
+    match get_next_value(tokens)? {
+        Token::Identifier(field) => Ok(field),
+        _ => Err(Errors::SyntaxError(UNEXPECTED_TOKEN.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::tokens::data_type::DataType;
+
+    #[test]
+    fn test_alter_type_add() {
+        let tokens = vec![
+            Token::Identifier(String::from("kp.coordinates")),
+            Token::Reserved(ADD.to_string()),
+            Token::Identifier(String::from("alt")),
+            Token::DataType(DataType::Int),
+        ];
+        let query = AlterTypeParser::parse(&mut tokens.into_iter().peekable()).unwrap();
+        assert_eq!(query.first_field, "alt");
+        assert_eq!(query.data, DataType::Int);
+        assert_eq!(query.operation, Some(Operations::ADD));
+    }
+
+    #[test]
+    fn test_alter_type_rename() {
+        let tokens = vec![
+            Token::Identifier(String::from("kp.coordinates")),
+            Token::Reserved(RENAME.to_string()),
+            Token::Identifier(String::from("lat")),
+            Token::Reserved(TO.to_string()),
+            Token::Identifier(String::from("latitude")),
+        ];
+        let query = AlterTypeParser::parse(&mut tokens.into_iter().peekable()).unwrap();
+        assert_eq!(query.first_field, "lat");
+        assert_eq!(query.second_field, "latitude");
+        assert_eq!(query.operation, Some(Operations::RENAME));
+    }
+
+    #[test]
+    fn test_alter_type_missing_keyword() {
+        let tokens = vec![
+            Token::Identifier(String::from("kp.coordinates")),
+            Token::Identifier(String::from("lat")),
+        ];
+        let result = AlterTypeParser::parse(&mut tokens.into_iter().peekable());
+        assert_eq!(
+            result,
+            Err(Errors::SyntaxError(MISSING_KEYWORD.to_string()))
+        );
+    }
+}
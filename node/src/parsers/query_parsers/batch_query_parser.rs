@@ -0,0 +1,168 @@
+use crate::parsers::query_parser::query_parser;
+use crate::parsers::tokens::token::Token;
+use crate::queries::batch_query::{BatchQuery, BatchStatement, BatchType};
+use crate::utils::errors::Errors;
+use crate::utils::parser_constants::{
+    APPLY, BATCH_KEYWORD, COUNTER, DELETE, INSERT, LOGGED, UNLOGGED, UPDATE,
+};
+use crate::utils::types::token_conversor::{get_next_value, is_keyword};
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+pub struct BatchQueryParser;
+
+impl BatchQueryParser {
+    /// parses the tokens of a `BEGIN [UNLOGGED | COUNTER] BATCH ... APPLY BATCH` statement
+    pub fn parse(tokens_list: Vec<Token>) -> Result<BatchQuery, Errors> {
+        let mut batch_query = BatchQuery::new();
+        let tokens = &mut tokens_list.into_iter().peekable();
+        batch_type(tokens, &mut batch_query)?;
+        batch_keyword(tokens)?;
+        statements(tokens, &mut batch_query)?;
+        finish(tokens)?;
+        Ok(batch_query)
+    }
+}
+
+fn batch_type(
+    tokens: &mut Peekable<IntoIter<Token>>,
+    query: &mut BatchQuery,
+) -> Result<(), Errors> {
+    let Some(token) = tokens.peek() else {
+        return Ok(());
+    };
+    query.batch_type = if is_keyword(token, LOGGED) {
+        BatchType::Logged
+    } else if is_keyword(token, UNLOGGED) {
+        BatchType::Unlogged
+    } else if is_keyword(token, COUNTER) {
+        BatchType::Counter
+    } else {
+        return Ok(());
+    };
+    tokens.next();
+    Ok(())
+}
+
+fn batch_keyword(tokens: &mut Peekable<IntoIter<Token>>) -> Result<(), Errors> {
+    match get_next_value(tokens)? {
+        Token::Reserved(res) if res == BATCH_KEYWORD => Ok(()),
+        _ => Err(Errors::SyntaxError(String::from(
+            "BEGIN must be followed by BATCH",
+        ))),
+    }
+}
+
+fn statements(
+    tokens: &mut Peekable<IntoIter<Token>>,
+    query: &mut BatchQuery,
+) -> Result<(), Errors> {
+    let mut statement: Vec<Token> = Vec::new();
+    loop {
+        match tokens.next() {
+            Some(token) if is_keyword(&token, APPLY) && is_batch_keyword(tokens.peek()) => break,
+            Some(Token::Reserved(res)) if res == INSERT || res == UPDATE || res == DELETE => {
+                push_statement(statement, query)?;
+                statement = vec![Token::Reserved(res)];
+            }
+            Some(token) if !statement.is_empty() => statement.push(token),
+            Some(_) => {
+                return Err(Errors::Invalid(String::from(
+                    "Only INSERT, UPDATE and DELETE statements are allowed in a batch",
+                )))
+            }
+            None => {
+                return Err(Errors::SyntaxError(String::from(
+                    "BATCH not finished with APPLY BATCH",
+                )))
+            }
+        }
+    }
+    push_statement(statement, query)
+}
+
+fn push_statement(statement: Vec<Token>, query: &mut BatchQuery) -> Result<(), Errors> {
+    if statement.is_empty() {
+        return Ok(());
+    }
+    let parsed = query_parser(statement)?;
+    query.statements.push(BatchStatement::from_query(&parsed)?);
+    Ok(())
+}
+
+fn is_batch_keyword(token: Option<&Token>) -> bool {
+    matches!(token, Some(Token::Reserved(res)) if res == BATCH_KEYWORD)
+}
+
+fn finish(tokens: &mut Peekable<IntoIter<Token>>) -> Result<(), Errors> {
+    match tokens.next() {
+        Some(Token::Reserved(res)) if res == BATCH_KEYWORD => {}
+        _ => {
+            return Err(Errors::SyntaxError(String::from(
+                "APPLY must be followed by BATCH",
+            )))
+        }
+    }
+    if tokens.next().is_none() {
+        return Ok(());
+    }
+    Err(Errors::SyntaxError(String::from(
+        "BATCH with left over paramameters",
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::query_parser::query_lexer;
+    use crate::utils::parser_constants::BEGIN;
+
+    fn parse(query: &str) -> Result<BatchQuery, Errors> {
+        let mut tokens = query_lexer(query.to_string()).unwrap();
+        assert!(is_keyword(&tokens.remove(0), BEGIN));
+        BatchQueryParser::parse(tokens)
+    }
+
+    #[test]
+    fn test_logged_batch() {
+        let batch = parse(
+            "BEGIN BATCH INSERT INTO kp.users (id, name) VALUES (1, 'Ana'); UPDATE kp.users SET name = 'Eva' WHERE id = 2; DELETE FROM kp.users WHERE id = 3; APPLY BATCH;",
+        )
+        .unwrap();
+        assert_eq!(batch.batch_type, BatchType::Logged);
+        assert_eq!(batch.statements.len(), 3);
+        assert!(matches!(batch.statements[0], BatchStatement::Insert(_)));
+        assert!(matches!(batch.statements[1], BatchStatement::Update(_)));
+        assert!(matches!(batch.statements[2], BatchStatement::Delete(_)));
+    }
+
+    #[test]
+    fn test_batch_types() {
+        let unlogged =
+            parse("BEGIN UNLOGGED BATCH DELETE FROM kp.users WHERE id = 3 APPLY BATCH").unwrap();
+        assert_eq!(unlogged.batch_type, BatchType::Unlogged);
+        let counter =
+            parse("BEGIN COUNTER BATCH UPDATE kp.users SET age = age + 1 WHERE id = 3 APPLY BATCH")
+                .unwrap();
+        assert_eq!(counter.batch_type, BatchType::Counter);
+        let logged =
+            parse("BEGIN LOGGED BATCH DELETE FROM kp.users WHERE id = 3 APPLY BATCH").unwrap();
+        assert_eq!(logged.batch_type, BatchType::Logged);
+    }
+
+    #[test]
+    fn test_batch_without_apply() {
+        assert!(parse("BEGIN BATCH DELETE FROM kp.users WHERE id = 3").is_err());
+        assert!(parse("BEGIN BATCH DELETE FROM kp.users WHERE id = 3 APPLY").is_err());
+    }
+
+    #[test]
+    fn test_batch_rejects_select() {
+        assert!(parse("BEGIN BATCH SELECT id FROM kp.users APPLY BATCH").is_err());
+    }
+
+    #[test]
+    fn test_batch_without_batch_keyword() {
+        assert!(parse("BEGIN DELETE FROM kp.users WHERE id = 3 APPLY BATCH").is_err());
+    }
+}
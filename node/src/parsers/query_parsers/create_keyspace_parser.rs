@@ -1,5 +1,4 @@
 use crate::parsers::tokens::terms::{BooleanOperations, ComparisonOperators, Term};
-use crate::utils::constants::{REPLICATION, STRATEGY};
 use crate::{
     parsers::tokens::token::Token, queries::create_keyspace_query::CreateKeyspaceQuery,
     utils::errors::Errors,
@@ -114,13 +113,9 @@ impl CreateKeyspaceParser {
         replication: &mut HashMap<String, String>,
     ) -> Result<(), Errors> {
         match self.get_next_value(tokens_list)? {
+            // besides 'class' and 'replication_factor' the NetworkTopologyStrategy datacenters are accepted
             Token::Term(Term::Literal(literal)) => {
-                let key = literal.value;
-                if key == STRATEGY || key == REPLICATION {
-                    self.check_colon(tokens_list, key, replication)
-                } else {
-                    Err(Errors::SyntaxError(String::from(MISSING_KEY)))
-                }
+                self.check_colon(tokens_list, literal.value, replication)
             }
             _ => Err(Errors::SyntaxError(String::from(MISSING_KEY))),
         }
@@ -349,4 +344,28 @@ mod tests {
             panic!("El error no es del tipo Errors::SyntaxError");
         }
     }
+
+    #[test]
+    fn test_07_create_keyspace_with_datacenters() {
+        let mut tokens = create_tokens();
+        let literal = |value: &str, data_type| {
+            Token::Term(Term::Literal(Literal::new(value.to_string(), data_type)))
+        };
+        tokens.push(Token::BraceList(vec![
+            literal(CLASS, DataType::Text),
+            Token::Symbol(COLON.to_string()),
+            literal("NetworkTopologyStrategy", DataType::Text),
+            Token::Symbol(COMMA.to_string()),
+            literal("dc1", DataType::Text),
+            Token::Symbol(COLON.to_string()),
+            literal("3", DataType::Int),
+            Token::Symbol(COMMA.to_string()),
+            literal("dc2", DataType::Text),
+            Token::Symbol(COLON.to_string()),
+            literal("2", DataType::Int),
+        ]));
+        let query = CreateKeyspaceParser.parse(tokens).unwrap();
+        assert_eq!(query.replication.get("dc1").unwrap(), "3");
+        assert_eq!(query.replication.get("dc2").unwrap(), "2");
+    }
 }
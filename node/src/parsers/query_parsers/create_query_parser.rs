@@ -1,11 +1,15 @@
 use super::{
     create_keyspace_parser::CreateKeyspaceParser, create_table_query_parser::CreateTableQueryParser,
+    create_type_parser::CreateTypeParser,
 };
-use crate::utils::parser_constants::{KEYSPACE, TABLE};
+use crate::utils::parser_constants::{KEYSPACE, TABLE, TYPE};
 use crate::{
     parsers::tokens::token::Token,
     queries::query::Query,
-    utils::{errors::Errors, types::token_conversor::get_next_value},
+    utils::{
+        errors::Errors,
+        types::token_conversor::{get_next_value, is_keyword},
+    },
 };
 use Token::*;
 pub struct CreateQueryParser;
@@ -15,6 +19,9 @@ impl CreateQueryParser {
         let mut tokens_iter = tokens.into_iter().peekable();
 
         match get_next_value(&mut tokens_iter) {
+            Ok(token) if is_keyword(&token, TYPE) => {
+                Ok(Box::new(CreateTypeParser::parse(tokens_iter.collect())?))
+            }
             Ok(Reserved(res)) => {
                 let tokens: Vec<Token> = tokens_iter.collect();
                 match res.as_str() {
@@ -1,7 +1,13 @@
 use crate::parsers::tokens::data_type::DataType;
+use crate::parsers::tokens::data_type::DataType::{Date, Text, Time};
+use crate::parsers::tokens::literal::Literal;
+use crate::parsers::tokens::terms::{
+    BooleanOperations, ComparisonOperators, LogicalOperators, Term,
+};
 use crate::parsers::tokens::token::Token;
 use crate::queries::create_table_query::CreateTableQuery;
 use crate::utils::errors::Errors;
+use crate::utils::parser_constants::{ASC, BY, DESC, ORDER};
 use std::vec::IntoIter;
 
 const PRIMARY: &str = "PRIMARY";
@@ -15,6 +21,29 @@ const MISSING_KEY_ERR: &str = "PRIMARY not followed by KEY";
 const UNEXPECTED_PK_ERR: &str = "Unexpected token in primary key list";
 const MISSING_DT_ERR: &str = "Missing data type";
 const SHORT_QUERY_ERR: &str = "Query lacks parameters";
+const FROZEN_ERR: &str = "Expected frozen<type_name>";
+const FROZEN: &str = "frozen";
+const WITH: &str = "WITH";
+const CLUSTERING: &str = "clustering";
+const UNEXPECTED_PROPERTY_ERR: &str = "Unexpected token in table properties";
+const CLUSTERING_ORDER_ERR: &str = "Expected CLUSTERING ORDER BY (column [ASC | DESC], ...)";
+const TABLE_OPTIONS: &[&str] = &[
+    "additional_write_policy",
+    "bloom_filter_fp_chance",
+    "caching",
+    "cdc",
+    "comment",
+    "compaction",
+    "compression",
+    "crc_check_chance",
+    "default_time_to_live",
+    "gc_grace_seconds",
+    "max_index_interval",
+    "memtable_flush_period_in_ms",
+    "min_index_interval",
+    "read_repair",
+    "speculative_retry",
+];
 
 pub struct CreateTableQueryParser;
 
@@ -41,15 +70,152 @@ fn column_list(tokens: &mut IntoIter<Token>, query: &mut CreateTableQuery) -> Re
     match get_next_value(tokens)? {
         Token::ParenList(list) => {
             column(&mut list.into_iter(), query)?;
-            let None = tokens.next() else {
-                return Err(Errors::SyntaxError(String::from(NOTHING_AFTER_CL_ERR)));
-            };
-            Ok(())
+            match tokens.next() {
+                None => Ok(()),
+                Some(Token::Reserved(with)) if with == WITH => table_property(tokens, query),
+                Some(_) => Err(Errors::SyntaxError(String::from(NOTHING_AFTER_CL_ERR))),
+            }
         }
         _ => Err(Errors::SyntaxError(String::from(UNEXPECTED_COLUMN_ERR))),
     }
 }
 
+/// `CLUSTERING ORDER BY (...)` or `option = value`, the properties are joined by AND
+fn table_property(
+    tokens: &mut IntoIter<Token>,
+    query: &mut CreateTableQuery,
+) -> Result<(), Errors> {
+    match get_next_value(tokens)? {
+        Token::Identifier(clustering) if clustering == CLUSTERING => {
+            clustering_order(tokens, query)?
+        }
+        Token::Identifier(option) => table_option(tokens, query, option)?,
+        _ => return Err(Errors::SyntaxError(String::from(UNEXPECTED_PROPERTY_ERR))),
+    }
+    match tokens.next() {
+        None => Ok(()),
+        Some(Token::Term(Term::BooleanOperations(BooleanOperations::Logical(
+            LogicalOperators::And,
+        )))) => table_property(tokens, query),
+        Some(_) => Err(Errors::SyntaxError(String::from(UNEXPECTED_PROPERTY_ERR))),
+    }
+}
+
+fn clustering_order(
+    tokens: &mut IntoIter<Token>,
+    query: &mut CreateTableQuery,
+) -> Result<(), Errors> {
+    let (Some(Token::Reserved(order)), Some(Token::Reserved(by))) = (tokens.next(), tokens.next())
+    else {
+        return Err(Errors::SyntaxError(String::from(CLUSTERING_ORDER_ERR)));
+    };
+    if order != ORDER || by != BY {
+        return Err(Errors::SyntaxError(String::from(CLUSTERING_ORDER_ERR)));
+    }
+    let list = match get_next_value(tokens)? {
+        Token::IterateToken(mut list) if list.len() == 1 => match list.remove(0) {
+            Token::ParenList(list) => list,
+            _ => return Err(Errors::SyntaxError(String::from(CLUSTERING_ORDER_ERR))),
+        },
+        Token::ParenList(list) => list,
+        _ => return Err(Errors::SyntaxError(String::from(CLUSTERING_ORDER_ERR))),
+    };
+    let mut orders: Vec<(String, String)> = Vec::new();
+    for token in list {
+        match token {
+            Token::Identifier(column) => orders.push((column, ASC.to_string())),
+            Token::Reserved(order) if order == ASC || order == DESC => match orders.last_mut() {
+                Some(last) => last.1 = order,
+                None => return Err(Errors::SyntaxError(String::from(CLUSTERING_ORDER_ERR))),
+            },
+            Token::Symbol(symbol) if symbol == COMMA => {}
+            _ => return Err(Errors::SyntaxError(String::from(CLUSTERING_ORDER_ERR))),
+        }
+    }
+    let clustering_columns = query.primary_key.get_clustering_columns();
+    for (position, (column, _)) in orders.iter().enumerate() {
+        if !clustering_columns.contains(column) {
+            return Err(Errors::Invalid(format!(
+                "Only clustering key columns can be defined in CLUSTERING ORDER directive, {} is not",
+                column
+            )));
+        }
+        if clustering_columns.get(position) != Some(column) {
+            return Err(Errors::Invalid(String::from(
+                "The order of columns in the CLUSTERING ORDER directive must match that of the clustering columns",
+            )));
+        }
+    }
+    query.clustering_order = orders.into_iter().collect();
+    Ok(())
+}
+
+fn table_option(
+    tokens: &mut IntoIter<Token>,
+    query: &mut CreateTableQuery,
+    option: String,
+) -> Result<(), Errors> {
+    if !TABLE_OPTIONS.contains(&option.as_str()) {
+        return Err(Errors::SyntaxError(format!(
+            "Unknown property '{}'",
+            option
+        )));
+    }
+    if query.options.contains_key(&option) {
+        return Err(Errors::SyntaxError(format!(
+            "Multiple definition for property '{}'",
+            option
+        )));
+    }
+    let Token::Term(Term::BooleanOperations(BooleanOperations::Comparison(
+        ComparisonOperators::Equal,
+    ))) = get_next_value(tokens)?
+    else {
+        return Err(Errors::SyntaxError(format!("Missing = after {}", option)));
+    };
+    let value = match get_next_value(tokens)? {
+        Token::Term(Term::Literal(literal)) => option_value(&literal),
+        Token::BraceList(list) => option_map(list)?,
+        _ => return Err(Errors::SyntaxError(format!("Invalid value for {}", option))),
+    };
+    query.options.insert(option, value);
+    Ok(())
+}
+
+/// the value as it is written in CQL, text between single quotes
+fn option_value(literal: &Literal) -> String {
+    match literal.data_type {
+        Text | Date | Time => format!("'{}'", literal.value.replace('\'', "''")),
+        _ => literal.value.to_string(),
+    }
+}
+
+/// `{'key': value, ...}` of options like `compaction` or `caching`
+fn option_map(list: Vec<Token>) -> Result<String, Errors> {
+    let mut entries = Vec::new();
+    let mut tokens = list.into_iter();
+    while let Some(token) = tokens.next() {
+        let (
+            Token::Term(Term::Literal(key)),
+            Some(Token::Symbol(colon)),
+            Some(Token::Term(Term::Literal(value))),
+        ) = (token, tokens.next(), tokens.next())
+        else {
+            return Err(Errors::SyntaxError(String::from(UNEXPECTED_PROPERTY_ERR)));
+        };
+        if colon != ":" {
+            return Err(Errors::SyntaxError(String::from(UNEXPECTED_PROPERTY_ERR)));
+        }
+        entries.push(format!("{}: {}", option_value(&key), option_value(&value)));
+        match tokens.next() {
+            None => break,
+            Some(Token::Symbol(symbol)) if symbol == COMMA => {}
+            Some(_) => return Err(Errors::SyntaxError(String::from(UNEXPECTED_PROPERTY_ERR))),
+        }
+    }
+    Ok(format!("{{{}}}", entries.join(", ")))
+}
+
 fn column(tokens: &mut IntoIter<Token>, query: &mut CreateTableQuery) -> Result<(), Errors> {
     let Some(token) = tokens.next() else {
         return Ok(());
@@ -199,13 +365,35 @@ fn try_comma(tokens: &mut IntoIter<Token>) -> Result<(), Errors> {
     }
 }
 
-fn get_data_type(tokens: &mut IntoIter<Token>) -> Result<DataType, Errors> {
+/// reads a column data type: a native type, `frozen<type_name>` or a bare user type name
+pub fn get_data_type<I: Iterator<Item = Token>>(tokens: &mut I) -> Result<DataType, Errors> {
     match tokens.next() {
         Some(Token::DataType(data_type)) => Ok(data_type),
+        Some(Token::Identifier(frozen)) if frozen == FROZEN => frozen_type(tokens),
+        Some(Token::Identifier(type_name)) => Ok(DataType::Udt(type_name)),
         _ => Err(Errors::SyntaxError(String::from(MISSING_DT_ERR))),
     }
 }
 
+fn frozen_type<I: Iterator<Item = Token>>(tokens: &mut I) -> Result<DataType, Errors> {
+    let (Some(open), Some(Token::Identifier(type_name)), Some(close)) =
+        (tokens.next(), tokens.next(), tokens.next())
+    else {
+        return Err(Errors::SyntaxError(String::from(FROZEN_ERR)));
+    };
+    match (open, close) {
+        (
+            Token::Term(Term::BooleanOperations(BooleanOperations::Comparison(
+                ComparisonOperators::Less,
+            ))),
+            Token::Term(Term::BooleanOperations(BooleanOperations::Comparison(
+                ComparisonOperators::Greater,
+            ))),
+        ) => Ok(DataType::Udt(type_name)),
+        _ => Err(Errors::SyntaxError(String::from(FROZEN_ERR))),
+    }
+}
+
 fn get_next_value(tokens: &mut IntoIter<Token>) -> Result<Token, Errors> {
     tokens
         .next()
@@ -232,9 +420,11 @@ fn check_primary_key(query: &mut CreateTableQuery) -> Result<(), Errors> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::meta_data::keyspaces::table::Table;
+    use crate::parsers::query_parser::query_lexer;
     use crate::parsers::tokens::token::Token;
     use crate::utils::types::primary_key::PrimaryKey;
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
 
     fn assert_error(result: Result<CreateTableQuery, Errors>, expected: &str) {
         assert!(result.is_err());
@@ -283,6 +473,8 @@ mod tests {
                 (String::from("name"), DataType::Text),
             ]),
             primary_key: PrimaryKey::new(vec![String::from("id")], None),
+            clustering_order: HashMap::new(),
+            options: BTreeMap::new(),
         }
     }
 
@@ -480,4 +672,88 @@ mod tests {
         );
         assert_eq!(result.primary_key, expected_pk);
     }
+
+    #[test]
+    fn test_create_table_with_frozen_user_type() {
+        let tokens = vec![
+            Token::Identifier(String::from("kp.flights")),
+            Token::ParenList(vec![
+                Token::Identifier(String::from("id")),
+                Token::DataType(DataType::Int),
+                Token::Reserved(String::from(PRIMARY)),
+                Token::Reserved(String::from(KEY)),
+                Token::Symbol(String::from(COMMA)),
+                Token::Identifier(String::from("position")),
+                Token::Identifier(String::from("frozen")),
+                Token::Term(Term::BooleanOperations(BooleanOperations::Comparison(
+                    ComparisonOperators::Less,
+                ))),
+                Token::Identifier(String::from("coordinates")),
+                Token::Term(Term::BooleanOperations(BooleanOperations::Comparison(
+                    ComparisonOperators::Greater,
+                ))),
+            ]),
+        ];
+        let query = CreateTableQueryParser::parse(tokens).unwrap();
+        assert_eq!(
+            query.columns.get("position"),
+            Some(&DataType::Udt(String::from("coordinates")))
+        );
+    }
+
+    #[test]
+    fn test_create_table_with_clustering_order_and_options() {
+        let tokens = query_lexer(String::from(
+            "CREATE TABLE kp.t (id int, day date, hour time, PRIMARY KEY (id, day, hour)) \
+             WITH CLUSTERING ORDER BY (day DESC) AND comment = 'flights' \
+             AND compaction = {'class': 'SizeTieredCompactionStrategy', 'min_threshold': 4}",
+        ))
+        .unwrap();
+        let query = CreateTableQueryParser::parse(tokens.into_iter().skip(2).collect()).unwrap();
+        assert_eq!(
+            query.clustering_order,
+            HashMap::from([(String::from("day"), String::from(DESC))])
+        );
+        let table = Table::new(query.primary_key.clone(), query.columns.clone())
+            .with_properties(query.clustering_order.clone(), query.options.clone());
+        let described = query_lexer(table.to_cql("kp", "t")).unwrap();
+        let reparsed = CreateTableQueryParser::parse(described.into_iter().skip(2).collect());
+        assert_eq!(reparsed.unwrap().options, query.options);
+        assert_eq!(
+            query.options,
+            BTreeMap::from([
+                (String::from("comment"), String::from("'flights'")),
+                (
+                    String::from("compaction"),
+                    String::from("{'class': 'SizeTieredCompactionStrategy', 'min_threshold': 4}")
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_create_table_clustering_order_must_follow_the_clustering_columns() {
+        for properties in [
+            "CLUSTERING ORDER BY (hour DESC)",
+            "CLUSTERING ORDER BY (id ASC)",
+        ] {
+            let tokens = query_lexer(format!(
+                "CREATE TABLE kp.t (id int, day date, hour time, PRIMARY KEY (id, day, hour)) WITH {}",
+                properties
+            ))
+            .unwrap();
+            assert!(matches!(
+                CreateTableQueryParser::parse(tokens.into_iter().skip(2).collect()),
+                Err(Errors::Invalid(_))
+            ));
+        }
+        let tokens = query_lexer(String::from(
+            "CREATE TABLE kp.t (id int, PRIMARY KEY (id)) WITH colour = 1",
+        ))
+        .unwrap();
+        assert_error(
+            CreateTableQueryParser::parse(tokens.into_iter().skip(2).collect()),
+            "Unknown property 'colour'",
+        );
+    }
 }
@@ -0,0 +1,124 @@
+use super::create_table_query_parser::get_data_type;
+use crate::parsers::tokens::token::Token;
+use crate::queries::create_type_query::CreateTypeQuery;
+use crate::utils::errors::Errors;
+use std::vec::IntoIter;
+
+const COMMA: &str = ",";
+const UNEXPECTED_TYPE_ERR: &str = "Unexpected token in type name";
+const UNEXPECTED_FIELD_ERR: &str = "Unexpected token in field definition";
+const NOTHING_AFTER_FL_ERR: &str = "Nothing should follow the field list";
+const SHORT_QUERY_ERR: &str = "Query lacks parameters";
+
+pub struct CreateTypeParser;
+
+impl CreateTypeParser {
+    pub fn parse(tokens: Vec<Token>) -> Result<CreateTypeQuery, Errors> {
+        let mut create_type_query = CreateTypeQuery::new();
+        type_name(&mut tokens.into_iter(), &mut create_type_query)?;
+        Ok(create_type_query)
+    }
+}
+
+fn type_name(tokens: &mut IntoIter<Token>, query: &mut CreateTypeQuery) -> Result<(), Errors> {
+    match get_next_value(tokens)? {
+        Token::Identifier(identifier) => {
+            query.type_name = identifier;
+            field_list(tokens, query)
+        }
+        _ => Err(Errors::SyntaxError(String::from(UNEXPECTED_TYPE_ERR))),
+    }
+}
+
+fn field_list(tokens: &mut IntoIter<Token>, query: &mut CreateTypeQuery) -> Result<(), Errors> {
+    match get_next_value(tokens)? {
+        Token::ParenList(list) => {
+            field(&mut list.into_iter(), query)?;
+            let None = tokens.next() else {
+                return Err(Errors::SyntaxError(String::from(NOTHING_AFTER_FL_ERR)));
+            };
+            Ok(())
+        }
+        _ => Err(Errors::SyntaxError(String::from(UNEXPECTED_FIELD_ERR))),
+    }
+}
+
+fn field(tokens: &mut IntoIter<Token>, query: &mut CreateTypeQuery) -> Result<(), Errors> {
+    let Some(token) = tokens.next() else {
+        return Ok(());
+    };
+    match token {
+        Token::Identifier(identifier) => {
+            query.fields.push((identifier, get_data_type(tokens)?));
+            match tokens.next() {
+                None => Ok(()),
+                Some(Token::Symbol(s)) if s == COMMA => field(tokens, query),
+                _ => Err(Errors::SyntaxError(String::from(UNEXPECTED_FIELD_ERR))),
+            }
+        }
+        _ => Err(Errors::SyntaxError(String::from(UNEXPECTED_FIELD_ERR))),
+    }
+}
+
+fn get_next_value(tokens: &mut IntoIter<Token>) -> Result<Token, Errors> {
+    tokens
+        .next()
+        .ok_or(Errors::SyntaxError(String::from(SHORT_QUERY_ERR)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::tokens::data_type::DataType;
+
+    #[test]
+    fn test_create_type() {
+        let tokens = vec![
+            Token::Identifier(String::from("kp.coordinates")),
+            Token::ParenList(vec![
+                Token::Identifier(String::from("lat")),
+                Token::DataType(DataType::Decimal),
+                Token::Symbol(String::from(COMMA)),
+                Token::Identifier(String::from("lon")),
+                Token::DataType(DataType::Decimal),
+            ]),
+        ];
+        let query = CreateTypeParser::parse(tokens).unwrap();
+        assert_eq!(query.type_name, "kp.coordinates");
+        assert_eq!(
+            query.fields,
+            vec![
+                (String::from("lat"), DataType::Decimal),
+                (String::from("lon"), DataType::Decimal)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_type_missing_field_list() {
+        let tokens = vec![Token::Identifier(String::from("kp.coordinates"))];
+        let result = CreateTypeParser::parse(tokens);
+        assert_eq!(
+            result,
+            Err(Errors::SyntaxError(String::from(SHORT_QUERY_ERR)))
+        );
+    }
+
+    #[test]
+    fn test_create_type_missing_comma() {
+        let tokens = vec![
+            Token::Identifier(String::from("kp.coordinates")),
+            Token::ParenList(vec![
+                Token::Identifier(String::from("lat")),
+                Token::DataType(DataType::Decimal),
+                Token::Identifier(String::from("lon")),
+                Token::DataType(DataType::Decimal),
+            ]),
+        ];
+        let result = CreateTypeParser::parse(tokens);
+        assert_eq!(
+            result,
+            Err(Errors::SyntaxError(String::from(UNEXPECTED_FIELD_ERR)))
+        );
+    }
+}
@@ -105,6 +105,7 @@ mod tests {
             table_name: "kp.table_name".to_string(),
             where_clause: None,
             if_clause: None,
+            timestamp: None,
         };
         assert_eq!(expected, DeleteQueryParser::parse(tokens).unwrap());
     }
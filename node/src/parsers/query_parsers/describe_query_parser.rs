@@ -0,0 +1,86 @@
+use crate::parsers::tokens::token::Token;
+use crate::queries::describe_query::{DescribeQuery, DescribeTarget};
+use crate::utils::errors::Errors;
+use crate::utils::parser_constants::{KEYSPACE, KEYSPACES, TABLE, TABLES};
+use crate::utils::types::token_conversor::{get_next_value, is_keyword};
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+const UNEXPECTED_TARGET_ERR: &str = "DESCRIBE must be followed by KEYSPACES, TABLES, KEYSPACE or TABLE";
+
+pub struct DescribeQueryParser;
+
+impl DescribeQueryParser {
+    pub fn parse(tokens_list: Vec<Token>) -> Result<DescribeQuery, Errors> {
+        let tokens = &mut tokens_list.into_iter().peekable();
+        let target = match get_next_value(tokens)? {
+            Token::Reserved(res) if res == KEYSPACES => DescribeTarget::Keyspaces,
+            token if is_keyword(&token, TABLES) => DescribeTarget::Tables,
+            Token::Reserved(res) if res == KEYSPACE => {
+                DescribeTarget::Keyspace(optional_name(tokens)?.unwrap_or_default())
+            }
+            Token::Reserved(res) if res == TABLE => match optional_name(tokens)? {
+                Some(table) => DescribeTarget::Table(table),
+                None => return Err(Errors::SyntaxError(String::from("Missing table name"))),
+            },
+            _ => return Err(Errors::SyntaxError(String::from(UNEXPECTED_TARGET_ERR))),
+        };
+        if tokens.next().is_some() {
+            return Err(Errors::SyntaxError(String::from(
+                "DESCRIBE with left over paramameters",
+            )));
+        }
+        Ok(DescribeQuery::new(target))
+    }
+}
+
+fn optional_name(tokens: &mut Peekable<IntoIter<Token>>) -> Result<Option<String>, Errors> {
+    match tokens.next() {
+        Some(Token::Identifier(name)) => Ok(Some(name)),
+        None => Ok(None),
+        _ => Err(Errors::SyntaxError(String::from(
+            "Unexpected token in DESCRIBE name",
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reserved(word: &str) -> Token {
+        Token::Reserved(word.to_string())
+    }
+
+    #[test]
+    fn test_describe_keyspaces_and_tables() {
+        let query = DescribeQueryParser::parse(vec![reserved(KEYSPACES)]).unwrap();
+        assert_eq!(query.target, DescribeTarget::Keyspaces);
+        let query = DescribeQueryParser::parse(vec![reserved(TABLES)]).unwrap();
+        assert_eq!(query.target, DescribeTarget::Tables);
+    }
+
+    #[test]
+    fn test_describe_keyspace() {
+        let tokens = vec![reserved(KEYSPACE), Token::Identifier("kp".to_string())];
+        let query = DescribeQueryParser::parse(tokens).unwrap();
+        assert_eq!(query.target, DescribeTarget::Keyspace("kp".to_string()));
+        let query = DescribeQueryParser::parse(vec![reserved(KEYSPACE)]).unwrap();
+        assert_eq!(query.target, DescribeTarget::Keyspace(String::new()));
+    }
+
+    #[test]
+    fn test_describe_table() {
+        let tokens = vec![reserved(TABLE), Token::Identifier("kp.users".to_string())];
+        let query = DescribeQueryParser::parse(tokens).unwrap();
+        assert_eq!(query.target, DescribeTarget::Table("kp.users".to_string()));
+        assert!(DescribeQueryParser::parse(vec![reserved(TABLE)]).is_err());
+    }
+
+    #[test]
+    fn test_describe_invalid() {
+        assert!(DescribeQueryParser::parse(vec![]).is_err());
+        let tokens = vec![reserved(KEYSPACES), Token::Identifier("kp".to_string())];
+        assert!(DescribeQueryParser::parse(tokens).is_err());
+    }
+}
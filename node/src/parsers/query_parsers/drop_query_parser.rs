@@ -1,14 +1,18 @@
 use crate::{
     parsers::tokens::token::Token,
     queries::query::Query,
-    utils::{errors::Errors, types::token_conversor::get_next_value},
+    utils::{
+        errors::Errors,
+        types::token_conversor::{get_next_value, is_keyword},
+    },
 };
 use std::{iter::Peekable, vec::IntoIter};
 
 use super::{
     drop_keyspace_parser::DropKeySpaceQueryParser, drop_table_parser::DropTableQueryParser,
+    drop_type_parser::DropTypeQueryParser,
 };
-use crate::utils::parser_constants::{KEYSPACE, TABLE};
+use crate::utils::parser_constants::{KEYSPACE, TABLE, TYPE};
 use Token::*;
 
 pub struct DropQueryParser;
@@ -30,6 +34,10 @@ fn reserv(tokens: &mut Peekable<IntoIter<Token>>) -> Result<Box<dyn Query>, Erro
             let query = DropTableQueryParser::parse(tokens)?;
             Ok(Box::new(query))
         }
+        token if is_keyword(&token, TYPE) => {
+            let query = DropTypeQueryParser::parse(tokens)?;
+            Ok(Box::new(query))
+        }
         _ => Err(Errors::SyntaxError(
             "Invalid Syntaxis in DROP, missing title".to_string(),
         )),
@@ -0,0 +1,93 @@
+use crate::utils::parser_constants::{EXISTS, IF};
+use crate::{
+    parsers::tokens::token::Token,
+    queries::drop_type_query::DropTypeQuery,
+    utils::{errors::Errors, types::token_conversor::get_next_value},
+};
+use std::{iter::Peekable, vec::IntoIter};
+
+const UNEXPECTED_TYPE_ERR: &str = "Unexpected token in type_name";
+
+pub struct DropTypeQueryParser;
+
+impl DropTypeQueryParser {
+    pub fn parse(tokens: &mut Peekable<IntoIter<Token>>) -> Result<DropTypeQuery, Errors> {
+        let mut drop_query = DropTypeQuery::new();
+        if_exists(tokens, &mut drop_query)?;
+        type_name(tokens, &mut drop_query)
+            .and_then(|_| finish(tokens))
+            .map(|_| drop_query)
+    }
+}
+
+fn if_exists(tokens: &mut Peekable<IntoIter<Token>>, query: &mut DropTypeQuery) -> Result<(), Errors> {
+    let Some(Token::Reserved(res)) = tokens.peek() else {
+        return Ok(());
+    };
+    if res != IF {
+        return Err(Errors::SyntaxError(String::from(UNEXPECTED_TYPE_ERR)));
+    }
+    tokens.next();
+    match get_next_value(tokens)? {
+        Token::Reserved(res) if res == *EXISTS => {
+            query.if_exist = Some(true);
+            Ok(())
+        }
+        _ => Err(Errors::SyntaxError(String::from(
+            "Unexpected token after IF",
+        ))),
+    }
+}
+
+fn type_name(tokens: &mut Peekable<IntoIter<Token>>, query: &mut DropTypeQuery) -> Result<(), Errors> {
+    match get_next_value(tokens)? {
+        Token::Identifier(name) => {
+            query.type_name = name;
+            Ok(())
+        }
+        _ => Err(Errors::SyntaxError(String::from(UNEXPECTED_TYPE_ERR))),
+    }
+}
+
+fn finish(tokens: &mut Peekable<IntoIter<Token>>) -> Result<(), Errors> {
+    if tokens.next().is_none() {
+        return Ok(());
+    }
+    Err(Errors::SyntaxError(String::from(
+        "DROP with left over paramameters",
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_type() {
+        let tokens = vec![Token::Identifier(String::from("kp.coordinates"))];
+        let query = DropTypeQueryParser::parse(&mut tokens.into_iter().peekable()).unwrap();
+        assert_eq!(query.type_name, "kp.coordinates");
+        assert_eq!(query.if_exist, None);
+    }
+
+    #[test]
+    fn test_drop_type_if_exists() {
+        let tokens = vec![
+            Token::Reserved(String::from(IF)),
+            Token::Reserved(String::from(EXISTS)),
+            Token::Identifier(String::from("coordinates")),
+        ];
+        let query = DropTypeQueryParser::parse(&mut tokens.into_iter().peekable()).unwrap();
+        assert_eq!(query.type_name, "coordinates");
+        assert_eq!(query.if_exist, Some(true));
+    }
+
+    #[test]
+    fn test_drop_type_left_over_tokens() {
+        let tokens = vec![
+            Token::Identifier(String::from("coordinates")),
+            Token::Identifier(String::from("extra")),
+        ];
+        assert!(DropTypeQueryParser::parse(&mut tokens.into_iter().peekable()).is_err());
+    }
+}
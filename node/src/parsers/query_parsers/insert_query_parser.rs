@@ -1,3 +1,4 @@
+use crate::parsers::tokens::data_type::DataType;
 use crate::parsers::tokens::literal::Literal;
 use crate::parsers::tokens::terms::BooleanOperations::Logical;
 use crate::parsers::tokens::terms::LogicalOperators::*;
@@ -5,8 +6,8 @@ use crate::parsers::tokens::terms::Term;
 use crate::parsers::tokens::token::Token;
 use crate::queries::insert_query::InsertQuery;
 use crate::utils::errors::Errors;
-use crate::utils::parser_constants::{COMMA, EXISTS, IF, INTO, VALUES};
-use crate::utils::types::token_conversor::get_next_value;
+use crate::utils::parser_constants::{COMMA, EXISTS, IF, INTO, JSON, VALUES};
+use crate::utils::types::token_conversor::{get_next_value, is_keyword};
 use std::iter::Peekable;
 use std::vec::IntoIter;
 
@@ -33,13 +34,29 @@ fn table(tokens: &mut Peekable<IntoIter<Token>>, query: &mut InsertQuery) -> Res
     match get_next_value(tokens)? {
         Token::Identifier(identifier) => {
             query.table_name = identifier;
-            headers(tokens, query)
+            match tokens.peek() {
+                Some(token) if is_keyword(token, JSON) => json(tokens, query),
+                _ => headers(tokens, query),
+            }
         }
         _ => Err(Errors::SyntaxError(String::from(
             "Unexpected token in table_name",
         ))),
     }
 }
+fn json(tokens: &mut Peekable<IntoIter<Token>>, query: &mut InsertQuery) -> Result<(), Errors> {
+    tokens.next();
+    match get_next_value(tokens)? {
+        Token::Term(Term::Literal(literal)) if literal.data_type == DataType::Text => {
+            query.json = Some(literal.value);
+            if_clause(tokens, query)
+        }
+        _ => Err(Errors::SyntaxError(String::from(
+            "JSON not followed by a string",
+        ))),
+    }
+}
+
 fn headers(tokens: &mut Peekable<IntoIter<Token>>, query: &mut InsertQuery) -> Result<(), Errors> {
     match get_next_value(tokens)? {
         Token::ParenList(list) => {
@@ -262,6 +279,9 @@ mod tests {
                 Literal::new(col2.to_string(), DataType::Text),
             ],
             if_exists,
+            json: None,
+            timestamp: None,
+            null_columns: Vec::new(),
         }
     }
 
@@ -410,4 +430,30 @@ mod tests {
         let result = InsertQueryParser::parse(tokens);
         assert_error(result, "Unexpected token in if-clause");
     }
+
+    #[test]
+    fn test_insert_query_parser_json() {
+        let json = r#"{"id": 3, "name": "Thiago"}"#;
+        let tokens = vec![
+            Token::Reserved(String::from(INTO)),
+            Token::Identifier(String::from("kp.table_name")),
+            Token::Reserved(String::from(JSON)),
+            Token::Term(Term::Literal(Literal::new(json.to_string(), DataType::Text))),
+        ];
+        let result = InsertQueryParser::parse(tokens).unwrap();
+        assert_eq!(result.json, Some(json.to_string()));
+        assert!(result.headers.is_empty());
+    }
+
+    #[test]
+    fn test_insert_query_parser_json_without_string() {
+        let tokens = vec![
+            Token::Reserved(String::from(INTO)),
+            Token::Identifier(String::from("kp.table_name")),
+            Token::Reserved(String::from(JSON)),
+            Token::Term(Term::Literal(Literal::new("3".to_string(), DataType::Int))),
+        ];
+        let result = InsertQueryParser::parse(tokens);
+        assert_error(result, "JSON not followed by a string");
+    }
 }
@@ -1,16 +1,23 @@
+pub mod alter_query_parser;
 pub mod alter_table_parser;
+pub mod alter_type_parser;
+pub mod batch_query_parser;
 pub mod create_keyspace_parser;
 pub mod create_query_parser;
 pub mod create_table_query_parser;
+pub mod create_type_parser;
 pub mod delete_query_parser;
+pub mod describe_query_parser;
 pub mod drop_keyspace_parser;
 pub mod drop_query_parser;
 pub mod drop_table_parser;
+pub mod drop_type_parser;
 pub mod if_clause_parser;
 pub mod insert_query_parser;
 pub mod order_by_clause_parser;
 pub mod select_query_parser;
 pub mod set_clause_parser;
+pub mod truncate_query_parser;
 pub mod update_query_parser;
 pub mod use_query_parser;
 pub mod where_clause_parser;
@@ -4,7 +4,8 @@ use crate::parsers::tokens::terms::{ArithMath, Term};
 use crate::parsers::tokens::token::Token;
 use crate::queries::select_query::SelectQuery;
 use crate::utils::errors::Errors;
-use crate::utils::parser_constants::{BY, COMMA, FROM, ORDER, WHERE};
+use crate::utils::parser_constants::{BY, COMMA, FROM, JSON, ORDER, WHERE};
+use crate::utils::types::token_conversor::is_keyword;
 use std::vec::IntoIter;
 
 pub struct SelectQueryParser;
@@ -19,7 +20,11 @@ impl SelectQueryParser {
 
 fn columns(tokens: &mut IntoIter<Token>, query: &mut SelectQuery) -> Result<(), Errors> {
     match get_next_value(tokens)? {
-        Token::IterateToken(list) => {
+        Token::IterateToken(mut list) => {
+            if is_json_keyword(&list) {
+                query.json = true;
+                list.remove(0);
+            }
             query.columns = get_columns(list)?;
             from(tokens, query)
         }
@@ -29,6 +34,17 @@ fn columns(tokens: &mut IntoIter<Token>, query: &mut SelectQuery) -> Result<(),
     }
 }
 
+/// `JSON` is not reserved, it is only the keyword when more selectors follow it, so
+/// `SELECT json FROM ...` still selects a column named json
+fn is_json_keyword(list: &[Token]) -> bool {
+    match list {
+        [first, second, ..] => {
+            is_keyword(first, JSON) && !matches!(second, Token::Symbol(symbol) if symbol == COMMA)
+        }
+        _ => false,
+    }
+}
+
 fn from(tokens: &mut IntoIter<Token>, query: &mut SelectQuery) -> Result<(), Errors> {
     match get_next_value(tokens)? {
         Token::Reserved(res) if res == *FROM => table(tokens, query),
@@ -149,6 +165,7 @@ fn get_next_value(tokens: &mut IntoIter<Token>) -> Result<Token, Errors> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parsers::query_parser::query_lexer;
     use crate::parsers::tokens::token::Token;
 
     fn assert_error(result: Result<SelectQuery, Errors>, expected: &str) {
@@ -169,10 +186,44 @@ mod tests {
             table_name: "kp.table_name".to_string(),
             where_clause: None,
             order_clauses: None,
+            json: false,
         };
         assert_eq!(expected, SelectQueryParser::parse(tokens).unwrap());
     }
 
+    #[test]
+    fn test_select_query_parser_json() {
+        let tokens = vec![
+            Token::IterateToken(vec![
+                Token::Reserved(String::from(JSON)),
+                Token::Identifier(String::from("id")),
+            ]),
+            Token::Reserved(String::from(FROM)),
+            Token::Identifier(String::from("kp.table_name")),
+        ];
+        let query = SelectQueryParser::parse(tokens).unwrap();
+        assert!(query.json);
+        assert_eq!(query.columns, vec![String::from("id")]);
+    }
+
+    #[test]
+    fn test_select_query_parser_non_reserved_keywords_as_columns() {
+        let mut tokens = query_lexer(String::from(
+            "SELECT json, type FROM kp.table_name WHERE counter = 1",
+        ))
+        .unwrap();
+        tokens.remove(0);
+        let query = SelectQueryParser::parse(tokens).unwrap();
+        assert!(!query.json);
+        assert_eq!(query.columns, vec![String::from("json"), String::from("type")]);
+
+        let mut tokens = query_lexer(String::from("SELECT JSON json FROM kp.table_name")).unwrap();
+        tokens.remove(0);
+        let query = SelectQueryParser::parse(tokens).unwrap();
+        assert!(query.json);
+        assert_eq!(query.columns, vec![String::from("json")]);
+    }
+
     #[test]
     fn test_select_query_parser_unexpected_columns() {
         let tokens = vec![Token::Reserved(String::from(FROM))];
@@ -0,0 +1,83 @@
+use crate::parsers::tokens::token::Token;
+use crate::queries::truncate_query::TruncateQuery;
+use crate::utils::errors::Errors;
+use crate::utils::parser_constants::TABLE;
+use crate::utils::types::token_conversor::get_next_value;
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+pub struct TruncateQueryParser;
+
+impl TruncateQueryParser {
+    pub fn parse(tokens_list: Vec<Token>) -> Result<TruncateQuery, Errors> {
+        let mut truncate_query = TruncateQuery::new();
+        let tokens = &mut tokens_list.into_iter().peekable();
+        if let Some(Token::Reserved(res)) = tokens.peek() {
+            if res == TABLE {
+                tokens.next();
+            }
+        }
+        table(tokens, &mut truncate_query)?;
+        finish(tokens)?;
+        Ok(truncate_query)
+    }
+}
+
+fn table(tokens: &mut Peekable<IntoIter<Token>>, query: &mut TruncateQuery) -> Result<(), Errors> {
+    match get_next_value(tokens)? {
+        Token::Identifier(identifier) => {
+            query.table_name = identifier;
+            Ok(())
+        }
+        _ => Err(Errors::SyntaxError(String::from(
+            "Unexpected token in table_name",
+        ))),
+    }
+}
+
+fn finish(tokens: &mut Peekable<IntoIter<Token>>) -> Result<(), Errors> {
+    if tokens.next().is_none() {
+        return Ok(());
+    }
+    Err(Errors::SyntaxError(String::from(
+        "TRUNCATE with left over paramameters",
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(tokens: Vec<Token>) -> Result<TruncateQuery, Errors> {
+        TruncateQueryParser::parse(tokens)
+    }
+
+    #[test]
+    fn test_truncate_table() {
+        let tokens = vec![Token::Identifier(String::from("kp.table_name"))];
+        assert_eq!(parse(tokens).unwrap().table_name, "kp.table_name");
+    }
+
+    #[test]
+    fn test_truncate_with_table_keyword() {
+        let tokens = vec![
+            Token::Reserved(String::from(TABLE)),
+            Token::Identifier(String::from("kp.table_name")),
+        ];
+        assert_eq!(parse(tokens).unwrap().table_name, "kp.table_name");
+    }
+
+    #[test]
+    fn test_truncate_left_over_tokens() {
+        let tokens = vec![
+            Token::Identifier(String::from("kp.table_name")),
+            Token::Identifier(String::from("other")),
+        ];
+        assert!(parse(tokens).is_err());
+    }
+
+    #[test]
+    fn test_truncate_without_table() {
+        assert!(parse(vec![]).is_err());
+    }
+}
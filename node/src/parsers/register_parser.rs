@@ -1,12 +1,50 @@
+use crate::event_bus::EventType;
 use crate::executables::executable::Executable;
 use crate::executables::register_executable::RegisterExecutable;
 use crate::parsers::parser::Parser;
 use crate::utils::errors::Errors;
+use crate::utils::types::bytes_cursor::BytesCursor;
 
 pub struct RegisterParser;
 
 impl Parser for RegisterParser {
-    fn parse(&self, _body: &[u8]) -> Result<Box<dyn Executable>, Errors> {
-        Ok(Box::new(RegisterExecutable))
+    /// body: `[string list]` with the event types the connection registers to
+    fn parse(&self, body: &[u8]) -> Result<Box<dyn Executable>, Errors> {
+        let mut cursor = BytesCursor::new(body);
+        let names = cursor.read_string_list()?;
+        if names.is_empty() {
+            return Err(Errors::ProtocolError(String::from(
+                "REGISTER must include at least one event type",
+            )));
+        }
+        let event_types = names
+            .iter()
+            .map(|name| EventType::from_name(name))
+            .collect::<Result<Vec<EventType>, Errors>>()?;
+        Ok(Box::new(RegisterExecutable::new(event_types)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_parser() {
+        let body = [
+            0x00, 0x01, 0x00, 0x0D, b'S', b'T', b'A', b'T', b'U', b'S', b'_', b'C', b'H', b'A',
+            b'N', b'G', b'E',
+        ];
+        assert!(RegisterParser.parse(&body).is_ok());
+    }
+
+    #[test]
+    fn test_register_parser_rejects_unknown_events() {
+        let body = [0x00, 0x01, 0x00, 0x04, b'P', b'I', b'N', b'G'];
+        assert!(matches!(
+            RegisterParser.parse(&body),
+            Err(Errors::ProtocolError(_))
+        ));
+        assert!(RegisterParser.parse(&[0x00, 0x00]).is_err());
     }
 }
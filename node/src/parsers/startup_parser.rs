@@ -1,6 +1,7 @@
 use crate::executables::executable::Executable;
 use crate::executables::startup_executable::StartupExecutable;
 use crate::parsers::parser::Parser;
+use crate::utils::compression::Compression;
 use crate::utils::errors::Errors;
 use crate::utils::types::bytes_cursor::BytesCursor;
 use std::collections::HashMap;
@@ -8,7 +9,6 @@ use std::collections::HashMap;
 const CQL_VERSION: &str = "CQL_VERSION";
 const COMPRESSION: &str = "COMPRESSION";
 const VERSION_VALUE: &str = "3.0.0";
-const COMPRESSION_VALUE: &str = "iz4";
 
 pub struct StartupParser;
 
@@ -32,12 +32,7 @@ fn validate_config(config: &HashMap<String, String>) -> Result<(), Errors> {
         None => return Err(Errors::ConfigError("Missing CQL_VERSION".to_string())),
     }
     if let Some(compression_value) = config.get(COMPRESSION) {
-        if compression_value != COMPRESSION_VALUE {
-            return Err(Errors::ConfigError(format!(
-                "COMPRESSION must be {}, the value provided (COMPRESSION {}) is unsupported",
-                COMPRESSION_VALUE, config[COMPRESSION]
-            )));
-        }
+        Compression::from_name(compression_value)?;
     }
     Ok(())
 }
@@ -47,7 +42,11 @@ impl Parser for StartupParser {
         let mut cursor = BytesCursor::new(body);
         let config = cursor.read_string_map()?;
         validate_config(&config)?;
-        let executable = StartupExecutable::new(config);
+        let compression = match config.get(COMPRESSION) {
+            Some(name) => Some(Compression::from_name(name)?),
+            None => None,
+        };
+        let executable = StartupExecutable::new(config, compression);
         Ok(Box::new(executable))
     }
 }
@@ -80,7 +79,7 @@ mod tests {
             0x00, 0x05, b'3', b'.', b'0', b'.', b'0', // "3.0.0"
             0x00, 0x0B, b'C', b'O', b'M', b'P', b'R', b'E', b'S', b'S', b'I', b'O',
             b'N', // "COMPRESSION"
-            0x00, 0x03, b'i', b'z', b'4', // "iz4"
+            0x00, 0x03, b'l', b'z', b'4', // "lz4"
         ];
 
         let mut cursor = BytesCursor::new(&bytes);
@@ -89,6 +88,21 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_snappy_compression() {
+        let bytes = vec![
+            0x00, 0x02, // n = 2
+            0x00, 0x0B, b'C', b'Q', b'L', b'_', b'V', b'E', b'R', b'S', b'I', b'O',
+            b'N', // "CQL_VERSION"
+            0x00, 0x05, b'3', b'.', b'0', b'.', b'0', // "3.0.0"
+            0x00, 0x0B, b'C', b'O', b'M', b'P', b'R', b'E', b'S', b'S', b'I', b'O',
+            b'N', // "COMPRESSION"
+            0x00, 0x06, b's', b'n', b'a', b'p', b'p', b'y', // "snappy"
+        ];
+
+        assert!(StartupParser.parse(&bytes).is_ok());
+    }
+
     #[test]
     fn test_validate_config_invalid_key() {
         let bytes = vec![
@@ -118,7 +132,7 @@ mod tests {
             0x00, 0x01, // n = 1
             0x00, 0x0B, b'C', b'O', b'M', b'P', b'R', b'E', b'S', b'S', b'I', b'O',
             b'N', // "COMPRESSION"
-            0x00, 0x03, b'i', b'z', b'4', // "iz4"
+            0x00, 0x03, b'l', b'z', b'4', // "lz4"
         ];
 
         let mut cursor = BytesCursor::new(&bytes);
@@ -145,7 +159,7 @@ mod tests {
             0x00, 0x05, b'3', b'.', b'0', b'.', b'0', // "3.0.0"
             0x00, 0x0A, b'C', b'O', b'M', b'P', b'R', b'E', b'S', b'S', b'I',
             b'O', // "COMPRESSION"
-            0x00, 0x03, b'i', b'z', b'4', // "iz4"
+            0x00, 0x03, b'l', b'z', b'4', // "lz4"
             0x00, 0x03, b'k', b'e', b'y', // extra key
             0x00, 0x05, b'v', b'a', b'l', b'u', b'e', // extra value
         ];
@@ -210,7 +224,10 @@ mod tests {
 
         assert!(result.is_err());
         if let Err(Errors::ConfigError(msg)) = result {
-            assert_eq!(msg,format!("COMPRESSION must be {}, the value provided (COMPRESSION INVALID) is unsupported", COMPRESSION_VALUE));
+            assert_eq!(
+                msg,
+                "COMPRESSION must be lz4 or snappy, the value provided (COMPRESSION INVALID) is unsupported"
+            );
         } else {
             panic!("Expected ConfigError due to invalid COMPRESSION");
         }
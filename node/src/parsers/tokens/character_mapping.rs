@@ -16,6 +16,8 @@ const CHARACTER_MAPPINGS: &[(&str, &str)] = &[
     ("{", " { "),
     (";", ""),
     (",", " , "),
+    (":", " : "),
+    ("?", " ? "),
 ];
 
 pub struct CharacterMappings {
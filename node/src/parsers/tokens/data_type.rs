@@ -10,6 +10,7 @@ pub enum DataType {
     Text,
     Duration,
     Time,
+    Udt(String),
 }
 
 pub fn string_to_data_type(word: &str) -> Option<Token> {
@@ -34,5 +35,22 @@ pub fn data_type_to_string(data_type: &DataType) -> String {
         DataType::Text => "text".to_string(),
         DataType::Duration => "duration".to_string(),
         DataType::Time => "time".to_string(),
+        DataType::Udt(name) => format!("frozen<{}>", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_udt_to_string() {
+        let data_type = DataType::Udt("coordinates".to_string());
+        assert_eq!(data_type_to_string(&data_type), "frozen<coordinates>");
+    }
+
+    #[test]
+    fn test_udt_name_is_not_a_data_type() {
+        assert_eq!(string_to_data_type("coordinates"), None);
     }
 }
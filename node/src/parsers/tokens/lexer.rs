@@ -197,6 +197,14 @@ fn replace_simple_chars(query: &str) -> String {
                 result.push(current);
                 continue;
             }
+            // `:name` is a bind marker, unless it follows an identifier as in `{field:value}`
+            if current == ':'
+                && (next.is_alphabetic() || next == '_')
+                && !result.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+            {
+                result.push(current);
+                continue;
+            }
         }
         if let Some(replace) = characters.get_mapping(&current.to_string()) {
             result.push_str(replace);
@@ -283,6 +291,16 @@ mod tests {
         assert_eq!(resultado, expected);
     }
 
+    #[test]
+    fn test_bind_markers() {
+        let input = "WHERE id = :id AND name = ?;";
+        let resultado = standardize(input);
+        let expected = vec!["WHERE", "id", "=", ":id", "AND", "name", "=", "?"];
+        assert_eq!(resultado, expected);
+        let resultado = standardize("{lat:1.5, city: 'x'}");
+        assert_eq!(resultado, vec!["{", "lat", ":", "1.5", ",", "city", ":", "'x'", "}"]);
+    }
+
     #[test]
     fn test_standardize_with_comments() {
         let input = r#"hola // comentario
@@ -3,6 +3,7 @@
 //! Este módulo define la estructura `Literal` y varias funciones para manejar valores literales con tipos de datos específicos, como `Int`, `Decimal`, `Boolean`, `Text`, `Date`, y `Time`.
 
 use super::{data_type::DataType, terms::Term, token::Token};
+use crate::utils::errors::Errors;
 use serde::{Deserialize, Serialize};
 
 /// Representa un valor literal con un tipo de dato asociado.
@@ -181,6 +182,102 @@ pub fn create_literal(value: &str, data_type: DataType) -> Literal {
     }
 }
 
+/// Creates a user defined type `Literal` from its fields.
+///
+/// The value is stored in its canonical form `{field: value, ...}`, with text, date and time
+/// values between single quotes and their quotes doubled. The type name stays empty until the
+/// literal is bound to a column.
+///
+/// # Example
+/// ```ignore
+/// let lat = Literal::new("1.0".to_string(), Decimal);
+/// let literal = create_udt_literal(vec![("lat".to_string(), lat)]);
+/// assert_eq!(literal.value, "{lat: 1.0}");
+/// ```
+pub fn create_udt_literal(fields: Vec<(String, Literal)>) -> Literal {
+    let values: Vec<String> = fields
+        .into_iter()
+        .map(|(name, literal)| match literal.data_type {
+            Text | Date | Time => format!("{}: '{}'", name, literal.value.replace('\'', "''")),
+            _ => format!("{}: {}", name, literal.value),
+        })
+        .collect();
+    Literal {
+        value: format!("{{{}}}", values.join(", ")),
+        data_type: Udt(String::new()),
+    }
+}
+
+/// Splits the canonical value of a UDT literal into `(field, value)` pairs.
+///
+/// Values are returned as they were written (quoted and escaped if they were), keeping commas
+/// inside quotes or nested UDTs.
+pub fn split_udt_value(value: &str) -> Result<Vec<(String, String)>, Errors> {
+    let trimmed = value.trim();
+    if !(trimmed.starts_with('{') && trimmed.ends_with('}')) {
+        return Err(Errors::Invalid(format!("Invalid user type value: {}", value)));
+    }
+    let inner = &trimmed[1..trimmed.len() - 1];
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if quoted && chars.peek() == Some(&'\'') => {
+                current.push(c);
+                current.push(c);
+                chars.next();
+                continue;
+            }
+            '\'' => quoted = !quoted,
+            '{' if !quoted => depth += 1,
+            '}' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    let mut fields = Vec::new();
+    for part in parts {
+        let Some((name, field_value)) = part.split_once(':') else {
+            return Err(Errors::Invalid(format!("Invalid user type field: {}", part)));
+        };
+        fields.push((name.trim().to_string(), field_value.trim().to_string()));
+    }
+    Ok(fields)
+}
+
+/// Returns the value of a field of a UDT literal, unquoted, if it exists.
+pub fn get_udt_field(value: &str, field: &str) -> Result<Option<String>, Errors> {
+    for (name, field_value) in split_udt_value(value)? {
+        if name == field {
+            return Ok(Some(unquote_udt_field(&field_value)));
+        }
+    }
+    Ok(None)
+}
+
+/// Removes the quotes of a field value returned by `split_udt_value` and undoes the doubled
+/// quotes inside it.
+pub fn unquote_udt_field(field_value: &str) -> String {
+    match field_value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        Some(inner) => inner.replace("''", "'"),
+        None => field_value.to_string(),
+    }
+}
+
 use DataType::*;
 
 impl PartialOrd for Literal {
@@ -227,6 +324,7 @@ impl PartialOrd for Literal {
             Date => todo!(),
             Duration => todo!(),
             Time => todo!(),
+            Udt(_) => Some(self.value.cmp(&other.value)),
         }
     }
 }
@@ -379,4 +477,63 @@ mod tests {
         let token = Token::Term(Term::Literal(literal));
         assert_eq!(result, token);
     }
+
+    #[test]
+    fn test_create_udt_literal() {
+        let literal = create_udt_literal(vec![
+            ("lat".to_string(), Literal::new("1.0".to_string(), Decimal)),
+            ("name".to_string(), Literal::new("home".to_string(), Text)),
+        ]);
+        assert_eq!(literal.value, "{lat: 1.0, name: 'home'}");
+        assert_eq!(literal.data_type, Udt(String::new()));
+    }
+
+    #[test]
+    fn test_split_udt_value_with_quoted_comma() {
+        let fields = split_udt_value("{lat: 1.0, name: 'a, b'}").unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                ("lat".to_string(), "1.0".to_string()),
+                ("name".to_string(), "'a, b'".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_udt_field() {
+        let value = "{lat: 1.0, name: 'home'}";
+        assert_eq!(get_udt_field(value, "lat").unwrap(), Some("1.0".to_string()));
+        assert_eq!(get_udt_field(value, "name").unwrap(), Some("home".to_string()));
+        assert_eq!(get_udt_field(value, "lon").unwrap(), None);
+    }
+
+    #[test]
+    fn test_udt_text_with_quotes() {
+        let literal = create_udt_literal(vec![
+            ("name".to_string(), Literal::new("O'Hare".to_string(), Text)),
+            ("code".to_string(), Literal::new("ORD".to_string(), Text)),
+        ]);
+        assert_eq!(literal.value, "{name: 'O''Hare', code: 'ORD'}");
+        assert_eq!(
+            split_udt_value(&literal.value).unwrap(),
+            vec![
+                ("name".to_string(), "'O''Hare'".to_string()),
+                ("code".to_string(), "'ORD'".to_string())
+            ]
+        );
+        assert_eq!(
+            get_udt_field(&literal.value, "name").unwrap(),
+            Some("O'Hare".to_string())
+        );
+        assert_eq!(
+            split_udt_value("{name: 'it''s, fine'}").unwrap(),
+            vec![("name".to_string(), "'it''s, fine'".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_split_udt_value_invalid() {
+        assert!(split_udt_value("lat: 1.0").is_err());
+    }
 }
@@ -18,6 +18,8 @@ pub enum Term {
     ArithMath(ArithMath),
     /// Una operación booleana.
     BooleanOperations(BooleanOperations),
+    /// A bind marker of a prepared statement, `?` or `:name`.
+    BindMarker(Option<String>),
 }
 
 /// Representa una operación booleana: lógica o de comparación.
@@ -99,6 +101,20 @@ fn to_boolean(word: &str) -> Option<Token> {
     }
 }
 
+fn to_bind_marker(word: &str) -> Option<Token> {
+    if word == "?" {
+        return Some(Token::Term(Term::BindMarker(None)));
+    }
+    let name = word.strip_prefix(':')?;
+    let mut chars = name.chars();
+    if !chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        || !chars.all(|c| c.is_alphanumeric() || c == '_')
+    {
+        return None;
+    }
+    Some(Token::Term(Term::BindMarker(Some(name.to_ascii_lowercase()))))
+}
+
 /// Convierte una cadena en un término (token aritmético, booleano o literal).
 ///
 /// # Parámetros
@@ -114,6 +130,9 @@ fn to_boolean(word: &str) -> Option<Token> {
 /// let token = string_to_term("+").unwrap();
 /// assert_eq!(format!("{:?}", token), "Term::ArithMath(ArithMath::Suma)");
 pub fn string_to_term(word: &str) -> Option<Token> {
+    if let Some(token) = to_bind_marker(word) {
+        return Some(token);
+    }
     if let Some(token) = to_math(word) {
         return Some(token);
     }
@@ -130,6 +149,20 @@ pub fn string_to_term(word: &str) -> Option<Token> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_to_bind_marker() {
+        assert_eq!(
+            string_to_term("?"),
+            Some(Token::Term(Term::BindMarker(None)))
+        );
+        assert_eq!(
+            string_to_term(":User_Id"),
+            Some(Token::Term(Term::BindMarker(Some("user_id".to_string()))))
+        );
+        assert_eq!(to_bind_marker(":"), None);
+        assert_eq!(to_bind_marker(":1a"), None);
+    }
+
     #[test]
     fn test_to_math() {
         assert_eq!(
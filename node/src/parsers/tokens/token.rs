@@ -1,11 +1,12 @@
 use super::data_type::{string_to_data_type, DataType};
+use super::literal::create_udt_literal;
 use super::reserved_words::WordsReserved;
 use super::symbols::Symbols;
 use super::terms::{string_to_term, Term};
 use crate::utils::errors::Errors;
 use crate::utils::parser_constants::{
-    AND, ASC, BY, CLOSE_BRACE, CLOSE_PAREN, DESC, EXISTS, IF, NOT, OPEN_BRACE, OPEN_PAREN, OR,
-    SELECT, SET, WHERE,
+    AND, APPLY, ASC, BATCH_KEYWORD, BY, CLOSE_BRACE, CLOSE_PAREN, DESC, EXISTS, IF, NOT, OPEN_BRACE,
+    OPEN_PAREN, OR, SELECT, SET, WHERE,
 };
 
 /// Enum que representa los distintos tipos de tokens que se pueden generar durante
@@ -88,20 +89,25 @@ fn sub_list_token(
     res: &mut Vec<Token>,
     reserved: String,
 ) -> Result<bool, Errors> {
-    let temp;
+    let close: fn(&str) -> bool;
     if reserved == WHERE {
-        temp = tokenize_recursive(words, close_sub_list_where, i)?;
+        close = close_sub_list_where;
     } else if reserved == SELECT {
-        temp = tokenize_recursive(words, close_sub_list_select, i)?;
+        close = close_sub_list_select;
     } else if reserved == BY {
-        temp = tokenize_recursive(words, close_sub_list_order_by, i)?;
+        close = close_sub_list_order_by;
     } else if reserved == SET {
-        temp = tokenize_recursive(words, close_sub_list_select, i)?;
+        close = close_sub_list_select;
     } else if reserved == IF {
-        temp = tokenize_recursive(words, close_sub_list_if, i)?;
+        close = close_sub_list_if;
     } else {
         return Ok(false);
     }
+    let temp = tokenize_recursive(
+        words,
+        |words, i| close(&words[i]) || is_apply_batch(words, i),
+        i,
+    )?;
     res.push(Token::IterateToken(temp));
     Ok(true)
 }
@@ -119,20 +125,45 @@ fn init_sub_list_token(
     } //Nos fijamos la word ACTUAL
     if words[*i] == OPEN_PAREN {
         *i += 1;
-        let temp = tokenize_recursive(words, close_sub_list_parentheses, i)?;
+        let temp = tokenize_recursive(words, |words, i| close_sub_list_parentheses(&words[i]), i)?;
         *i += 1;
         res.push(Token::ParenList(temp));
         return Ok(true);
     } else if words[*i] == OPEN_BRACE {
         *i += 1;
-        let temp = tokenize_recursive(words, close_sub_list_key_icon, i)?;
+        let temp = tokenize_recursive(words, |words, i| close_sub_list_key_icon(&words[i]), i)?;
         *i += 1;
-        res.push(Token::BraceList(temp));
+        res.push(brace_list_or_udt_literal(temp));
         return Ok(true);
     }
     Ok(false)
 }
 
+/// If the brace list has the form `{field: literal, ...}` it becomes a user defined type
+/// literal, otherwise it stays a `BraceList`.
+fn brace_list_or_udt_literal(list: Vec<Token>) -> Token {
+    if list.len() % 4 != 3 {
+        return Token::BraceList(list);
+    }
+    let mut fields = Vec::new();
+    for chunk in list.chunks(4) {
+        match chunk {
+            [Token::Identifier(name), Token::Symbol(colon), Token::Term(Term::Literal(literal)), separator @ ..]
+                if colon == ":" && matches!(separator, [] | [Token::Symbol(_)]) =>
+            {
+                if let [Token::Symbol(comma)] = separator {
+                    if comma != "," {
+                        return Token::BraceList(list);
+                    }
+                }
+                fields.push((name.to_string(), literal.clone()));
+            }
+            _ => return Token::BraceList(list),
+        }
+    }
+    Token::Term(Term::Literal(create_udt_literal(fields)))
+}
+
 fn close_sub_list_order_by(word: &str) -> bool {
     let reserved = WordsReserved::new();
     let word_upper = word.to_ascii_uppercase();
@@ -145,8 +176,15 @@ fn close_sub_list_key_icon(word: &str) -> bool {
 
 fn close_sub_list_select(word: &str) -> bool {
     let reserved = WordsReserved::new();
-    let word_upper = word.to_ascii_uppercase();
-    reserved.is_reserved(&word_upper)
+    reserved.is_reserved(&word.to_ascii_uppercase())
+}
+
+/// `APPLY` is not reserved, but `APPLY BATCH` ends the last statement of a batch
+fn is_apply_batch(words: &[String], i: usize) -> bool {
+    words[i].eq_ignore_ascii_case(APPLY)
+        && words
+            .get(i + 1)
+            .is_some_and(|next| next.eq_ignore_ascii_case(BATCH_KEYWORD))
 }
 
 fn close_sub_list_parentheses(word: &str) -> bool {
@@ -181,7 +219,7 @@ fn close_sub_list_if(word: &str) -> bool {
 /// Un `Result` con un vector de tokens o un error de tipo `Errors`.
 fn tokenize_recursive<F>(words: &[String], closure: F, i: &mut usize) -> Result<Vec<Token>, Errors>
 where
-    F: Fn(&str) -> bool,
+    F: Fn(&[String], usize) -> bool,
 {
     let mut res = Vec::new();
     while *i < words.len() {
@@ -189,7 +227,7 @@ where
         if init_sub_list_token(words, i, &mut res)? {
             continue;
         }
-        if closure(word) {
+        if closure(words, *i) {
             return Ok(res);
         }
         if let Some(token) = match_tokenize(word.to_string()) {
@@ -243,7 +281,7 @@ where
 /// ```
 pub fn tokenize(words: Vec<String>) -> Result<Vec<Token>, Errors> {
     // Definimos una closure que siempre devuelve false
-    let fn_false = |_: &str| false;
+    let fn_false = |_: &[String], _: usize| false;
     tokenize_recursive(&words, fn_false, &mut 0)
 }
 
@@ -835,4 +873,17 @@ mod tests {
             Token::Term(Term::Literal(Literal::new("1".to_string(), DataType::Int))),
         ])
     }
+
+    #[test]
+    fn test_tokenize_udt_literal() {
+        let query = crate::parsers::tokens::lexer::standardize(
+            "INSERT INTO flights (id, position) VALUES (1, {lat: 1.0, lon: -2.5})",
+        );
+        let result = tokenize(query).unwrap();
+        let udt = Literal::new("{lat: 1.0, lon: -2.5}".to_string(), DataType::Udt(String::new()));
+        let Token::ParenList(values) = &result[result.len() - 1] else {
+            panic!("Expected values list");
+        };
+        assert_eq!(values[2], Token::Term(Term::Literal(udt)));
+    }
 }
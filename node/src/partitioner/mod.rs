@@ -0,0 +1,38 @@
+pub mod murmur3_partitioner;
+pub mod partition_key;
+
+use murmur3_partitioner::Murmur3Partitioner;
+
+/// Position of a partition in the ring.
+pub type Token = i64;
+
+/// Range of the ring `(start, end]`, the token that owns it is the one that closes it.
+pub type TokenRange = (Token, Token);
+
+/// whether the token falls in the range `(start, end]`, a range with `start == end` is the whole ring
+pub fn contains(range: &TokenRange, token: Token) -> bool {
+    let (start, end) = *range;
+    if start < end {
+        start < token && token <= end
+    } else {
+        start < token || token <= end
+    }
+}
+
+/// Turns the serialized partition key into the token that decides its replicas.
+///
+/// Every node must use the same partitioner, otherwise the same key falls in different ranges
+pub trait Partitioner: Sync {
+    fn name(&self) -> &'static str;
+
+    /// token of the serialized partition key
+    fn token(&self, key: &[u8]) -> Token;
+
+    /// smallest token of the ring, no partition key gets it
+    fn min_token(&self) -> Token;
+}
+
+/// partitioner of the cluster
+pub fn partitioner() -> &'static dyn Partitioner {
+    &Murmur3Partitioner
+}
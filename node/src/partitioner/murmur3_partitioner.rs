@@ -0,0 +1,104 @@
+use super::{Partitioner, Token};
+
+const C1: u64 = 0x87c3_7b91_1142_53d5;
+const C2: u64 = 0x4cf5_ad43_2745_937f;
+
+/// Same partitioner as the `Murmur3Partitioner` of Cassandra: the token is the first half
+/// of the 128 bit murmur3 x64, with seed 0.
+pub struct Murmur3Partitioner;
+
+impl Partitioner for Murmur3Partitioner {
+    fn name(&self) -> &'static str {
+        "org.apache.cassandra.dht.Murmur3Partitioner"
+    }
+
+    /// `i64::MIN` is left out of the ring, as Cassandra does
+    fn token(&self, key: &[u8]) -> Token {
+        match murmur3_x64_128(key).0 as i64 {
+            i64::MIN => i64::MAX,
+            token => token,
+        }
+    }
+
+    fn min_token(&self) -> Token {
+        i64::MIN
+    }
+}
+
+/// 128 bit murmur3 x64. The bytes of the tail are taken as signed, like the
+/// Cassandra implementation, so that the tokens match.
+fn murmur3_x64_128(data: &[u8]) -> (u64, u64) {
+    let (mut h1, mut h2) = (0u64, 0u64);
+    let blocks = data.chunks_exact(16);
+    let tail = blocks.remainder();
+    for block in blocks {
+        let k1 = u64::from_le_bytes(block[..8].try_into().unwrap_or_default());
+        let k2 = u64::from_le_bytes(block[8..].try_into().unwrap_or_default());
+        h1 ^= mix_k1(k1);
+        h1 = h1
+            .rotate_left(27)
+            .wrapping_add(h2)
+            .wrapping_mul(5)
+            .wrapping_add(0x52dc_e729);
+        h2 ^= mix_k2(k2);
+        h2 = h2
+            .rotate_left(31)
+            .wrapping_add(h1)
+            .wrapping_mul(5)
+            .wrapping_add(0x3849_5ab5);
+    }
+    let (mut k1, mut k2) = (0u64, 0u64);
+    for (i, byte) in tail.iter().enumerate() {
+        let byte = *byte as i8 as i64 as u64;
+        if i < 8 {
+            k1 ^= byte << (i * 8);
+        } else {
+            k2 ^= byte << ((i - 8) * 8);
+        }
+    }
+    if tail.len() > 8 {
+        h2 ^= mix_k2(k2);
+    }
+    if !tail.is_empty() {
+        h1 ^= mix_k1(k1);
+    }
+    h1 ^= data.len() as u64;
+    h2 ^= data.len() as u64;
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    h1 = fmix(h1);
+    h2 = fmix(h2);
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    (h1, h2)
+}
+
+fn mix_k1(k1: u64) -> u64 {
+    k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2)
+}
+
+fn mix_k2(k2: u64) -> u64 {
+    k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1)
+}
+
+fn fmix(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    k ^ (k >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_match_cassandra() {
+        let partitioner = Murmur3Partitioner;
+        // SELECT token(id) of a table with an int partition key in Cassandra
+        assert_eq!(partitioner.token(&0i32.to_be_bytes()), -3485513579396041028);
+        assert_eq!(partitioner.token(&1i32.to_be_bytes()), -4069959284402364209);
+        assert_eq!(partitioner.token(&2i32.to_be_bytes()), -3248873570005575792);
+    }
+}
@@ -0,0 +1,194 @@
+use super::{partitioner, Token};
+use crate::data_access::row::Row;
+use crate::parsers::tokens::data_type::DataType;
+use crate::parsers::tokens::literal::Literal;
+use crate::utils::errors::Errors;
+use crate::utils::functions::{get_columns_from_table, get_table_primary_key};
+use chrono::{NaiveDate, NaiveTime, Timelike};
+
+/// The days of a `date` are counted from 2^31, which is the epoch.
+const DATE_EPOCH_OFFSET: i64 = 1 << 31;
+
+/// Values of the partition key of a row, in the order the table defined them and with the
+/// type of each column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionKey {
+    components: Vec<Literal>,
+}
+
+impl PartitionKey {
+    pub fn new(components: Vec<Literal>) -> Self {
+        Self { components }
+    }
+
+    /// builds the partition key of the table with the value of each of its partition columns
+    pub fn from_columns<F>(table_name: &str, value_of: F) -> Result<Self, Errors>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let columns = get_columns_from_table(table_name)?;
+        let mut components = Vec::new();
+        for column in get_table_primary_key(table_name)?.partition_keys {
+            let Some(value) = value_of(&column) else {
+                return Err(Errors::SyntaxError(format!(
+                    "Missing value for partition key column {}",
+                    column
+                )));
+            };
+            let data_type = columns.get(&column).cloned().unwrap_or(DataType::Text);
+            components.push(Literal::new(value, data_type));
+        }
+        Ok(Self::new(components))
+    }
+
+    /// partition key of a stored row of the table
+    pub fn from_row(table_name: &str, row: &Row) -> Result<Self, Errors> {
+        Self::from_columns(table_name, |column| {
+            row.columns
+                .iter()
+                .find(|row_column| row_column.column_name == column)
+                .map(|row_column| row_column.value.value.to_string())
+        })
+    }
+
+    pub fn values(&self) -> Vec<String> {
+        self.components
+            .iter()
+            .map(|literal| literal.value.to_string())
+            .collect()
+    }
+
+    /// A simple partition key is serialized as is. In a composite one each component carries
+    /// its length in front and a 0 byte at the end, so ('ab', 'c') and ('a', 'bc') do not match
+    pub fn serialize(&self) -> Result<Vec<u8>, Errors> {
+        if let [component] = self.components.as_slice() {
+            return encode(component);
+        }
+        let mut bytes = Vec::new();
+        for component in &self.components {
+            let encoded = encode(component)?;
+            let length = u16::try_from(encoded.len()).map_err(|_| {
+                Errors::Invalid(String::from("Partition key component is too long"))
+            })?;
+            bytes.extend_from_slice(&length.to_be_bytes());
+            bytes.extend(encoded);
+            bytes.push(0);
+        }
+        Ok(bytes)
+    }
+
+    pub fn token(&self) -> Result<Token, Errors> {
+        Ok(partitioner().token(&self.serialize()?))
+    }
+}
+
+/// Value in the binary format of the protocol, the same Cassandra uses to compute the token.
+///
+/// `duration` cannot be part of a partition key and user types are taken as text
+fn encode(literal: &Literal) -> Result<Vec<u8>, Errors> {
+    let value = literal.value.as_str();
+    match &literal.data_type {
+        DataType::Int => match value.parse::<i32>() {
+            Ok(int) => Ok(int.to_be_bytes().to_vec()),
+            Err(_) => Err(invalid(literal)),
+        },
+        DataType::Boolean => match value.to_ascii_lowercase().as_str() {
+            "true" => Ok(vec![1]),
+            "false" => Ok(vec![0]),
+            _ => Err(invalid(literal)),
+        },
+        DataType::Decimal => encode_decimal(value).ok_or_else(|| invalid(literal)),
+        DataType::Date => {
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| invalid(literal))?;
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)
+                .ok_or_else(|| Errors::ServerError(String::from("Invalid epoch")))?;
+            let days = (date - epoch).num_days() + DATE_EPOCH_OFFSET;
+            Ok((days as u32).to_be_bytes().to_vec())
+        }
+        DataType::Time => {
+            let time = NaiveTime::parse_from_str(value, "%H:%M:%S%.f")
+                .map_err(|_| invalid(literal))?;
+            let nanos = time.num_seconds_from_midnight() as i64 * 1_000_000_000
+                + time.nanosecond() as i64;
+            Ok(nanos.to_be_bytes().to_vec())
+        }
+        DataType::Text | DataType::Duration | DataType::Udt(_) => Ok(value.as_bytes().to_vec()),
+    }
+}
+
+/// `<scale: int><unscaled: varint>`
+fn encode_decimal(value: &str) -> Option<Vec<u8>> {
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let unscaled = format!("{}{}", integer, fraction).parse::<i128>().ok()?;
+    let mut bytes = (fraction.len() as i32).to_be_bytes().to_vec();
+    let digits = unscaled.to_be_bytes();
+    // varint: drop the extra bytes that only repeat the sign
+    let mut start = 0;
+    while start < digits.len() - 1
+        && ((digits[start] == 0x00 && digits[start + 1] & 0x80 == 0)
+            || (digits[start] == 0xFF && digits[start + 1] & 0x80 != 0))
+    {
+        start += 1;
+    }
+    bytes.extend_from_slice(&digits[start..]);
+    Some(bytes)
+}
+
+fn invalid(literal: &Literal) -> Errors {
+    Errors::Invalid(format!(
+        "Invalid value {} for a partition key of type {:?}",
+        literal.value, literal.data_type
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(components: &[(&str, DataType)]) -> PartitionKey {
+        PartitionKey::new(
+            components
+                .iter()
+                .map(|(value, data_type)| Literal::new(value.to_string(), data_type.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_composite_keys_do_not_collide() {
+        let first = key(&[("ab", DataType::Text), ("c", DataType::Text)]);
+        let second = key(&[("a", DataType::Text), ("bc", DataType::Text)]);
+        assert_eq!(
+            first.serialize().unwrap(),
+            vec![0, 2, b'a', b'b', 0, 0, 1, b'c', 0]
+        );
+        assert_ne!(first.serialize().unwrap(), second.serialize().unwrap());
+        assert_ne!(first.token().unwrap(), second.token().unwrap());
+    }
+
+    #[test]
+    fn test_values_are_encoded_by_type() {
+        assert_eq!(
+            key(&[("1", DataType::Int)]).token().unwrap(),
+            -4069959284402364209
+        );
+        assert_eq!(
+            key(&[("1", DataType::Int)]).serialize().unwrap(),
+            vec![0, 0, 0, 1]
+        );
+        assert_eq!(
+            key(&[("1970-01-02", DataType::Date)]).serialize().unwrap(),
+            vec![0x80, 0, 0, 1]
+        );
+        assert_eq!(
+            key(&[("-1.5", DataType::Decimal)]).serialize().unwrap(),
+            vec![0, 0, 0, 1, 0xF1]
+        );
+        assert_eq!(
+            key(&[("true", DataType::Boolean)]).serialize().unwrap(),
+            vec![1]
+        );
+        assert!(key(&[("one", DataType::Int)]).serialize().is_err());
+        assert!(key(&[("2147483648", DataType::Int)]).serialize().is_err());
+    }
+}
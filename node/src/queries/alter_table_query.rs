@@ -1,6 +1,7 @@
 use super::query::Query;
 use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
 use crate::parsers::tokens::data_type::data_type_to_string;
+use crate::partitioner::partition_key::PartitionKey;
 use crate::utils::constants::KEYSPACE_METADATA_PATH;
 use crate::utils::functions::{check_table_name, split_keyspace_table};
 use crate::utils::response::Response;
@@ -127,7 +128,7 @@ impl Query for AlterTableQuery {
         }
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
         Ok(None)
     }
 
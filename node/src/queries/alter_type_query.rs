@@ -0,0 +1,111 @@
+use super::alter_table_query::Operations;
+use super::query::Query;
+use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
+use crate::parsers::tokens::data_type::data_type_to_string;
+use crate::partitioner::partition_key::PartitionKey;
+use crate::utils::constants::KEYSPACE_METADATA_PATH;
+use crate::utils::functions::{check_table_name, split_keyspace_table};
+use crate::utils::response::Response;
+use crate::{parsers::tokens::data_type::DataType, utils::errors::Errors};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct AlterTypeQuery {
+    pub type_name: String,
+    pub operation: Option<Operations>,
+    pub first_field: String,
+    pub second_field: String,
+    pub data: DataType,
+}
+
+impl Default for AlterTypeQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlterTypeQuery {
+    pub fn new() -> AlterTypeQuery {
+        AlterTypeQuery {
+            type_name: String::new(),
+            operation: None,
+            first_field: String::new(),
+            second_field: String::new(),
+            data: DataType::Int,
+        }
+    }
+
+    fn add(&self) -> Result<(), Errors> {
+        let (keyspace_name, type_name) = split_keyspace_table(&self.type_name)?;
+        use_keyspace_meta_data(|handler| {
+            handler.add_type_field(
+                KEYSPACE_METADATA_PATH.to_owned(),
+                keyspace_name,
+                type_name,
+                &self.first_field,
+                self.data.to_owned(),
+            )
+        })
+    }
+
+    fn rename(&self) -> Result<(), Errors> {
+        let (keyspace_name, type_name) = split_keyspace_table(&self.type_name)?;
+        use_keyspace_meta_data(|handler| {
+            handler.rename_type_field(
+                KEYSPACE_METADATA_PATH.to_owned(),
+                keyspace_name,
+                type_name,
+                &self.first_field,
+                &self.second_field,
+            )
+        })
+    }
+}
+
+impl Query for AlterTypeQuery {
+    fn run(&self) -> Result<Vec<u8>, Errors> {
+        let options = match &self.operation {
+            Some(Operations::ADD) => {
+                self.add()?;
+                format!(
+                    "{} ADD {} {}",
+                    self.type_name,
+                    self.first_field,
+                    data_type_to_string(&self.data)
+                )
+            }
+            Some(Operations::RENAME) => {
+                self.rename()?;
+                format!(
+                    "{} RENAME {} TO {}",
+                    self.type_name, self.first_field, self.second_field
+                )
+            }
+            _ => {
+                return Err(Errors::SyntaxError(
+                    "Invalid Operation to Alter Type".to_string(),
+                ))
+            }
+        };
+        Response::schema_change("ALTERED", "TYPE", &options)
+    }
+
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
+        Ok(None)
+    }
+
+    fn get_keyspace(&self) -> Result<String, Errors> {
+        let (kp, _) = split_keyspace_table(&self.type_name)?;
+        Ok(kp.to_string())
+    }
+
+    fn set_table(&mut self) -> Result<(), Errors> {
+        self.type_name = check_table_name(&self.type_name)?;
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -1 +1,202 @@
+use crate::partitioner::partition_key::PartitionKey;
+use crate::queries::delete_query::DeleteQuery;
+use crate::queries::insert_query::InsertQuery;
+use crate::queries::query::Query;
+use crate::queries::update_query::UpdateQuery;
+use crate::utils::errors::Errors;
+use crate::utils::response::Response;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
 
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub enum BatchType {
+    #[default]
+    Logged,
+    Unlogged,
+    Counter,
+}
+
+impl BatchType {
+    /// given the byte used by the BATCH opcode, returns the matching batch type
+    pub fn from_u8(value: u8) -> Result<Self, Errors> {
+        match value {
+            0 => Ok(BatchType::Logged),
+            1 => Ok(BatchType::Unlogged),
+            2 => Ok(BatchType::Counter),
+            _ => Err(Errors::ProtocolError(format!(
+                "Invalid batch type {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Statements that can be part of a batch, only modifications.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub enum BatchStatement {
+    Insert(InsertQuery),
+    Update(UpdateQuery),
+    Delete(DeleteQuery),
+}
+
+impl BatchStatement {
+    #[allow(clippy::borrowed_box)]
+    pub fn from_query(query: &Box<dyn Query>) -> Result<Self, Errors> {
+        if let Some(insert) = query.as_any().downcast_ref::<InsertQuery>() {
+            return Ok(BatchStatement::Insert(insert.to_owned()));
+        } else if let Some(update) = query.as_any().downcast_ref::<UpdateQuery>() {
+            return Ok(BatchStatement::Update(update.to_owned()));
+        } else if let Some(delete) = query.as_any().downcast_ref::<DeleteQuery>() {
+            return Ok(BatchStatement::Delete(delete.to_owned()));
+        }
+        Err(Errors::Invalid(String::from(
+            "Only INSERT, UPDATE and DELETE statements are allowed in a batch",
+        )))
+    }
+
+    pub fn into_query(self) -> Box<dyn Query> {
+        match self {
+            BatchStatement::Insert(query) => Box::new(query),
+            BatchStatement::Update(query) => Box::new(query),
+            BatchStatement::Delete(query) => Box::new(query),
+        }
+    }
+
+    /// timestamp of the values written by the statement, if it was set
+    pub fn timestamp(&self) -> Option<i64> {
+        match self {
+            BatchStatement::Insert(query) => query.timestamp,
+            BatchStatement::Update(query) => query.timestamp,
+            BatchStatement::Delete(query) => query.timestamp,
+        }
+    }
+
+    fn as_query_mut(&mut self) -> &mut dyn Query {
+        match self {
+            BatchStatement::Insert(query) => query,
+            BatchStatement::Update(query) => query,
+            BatchStatement::Delete(query) => query,
+        }
+    }
+
+    fn as_query(&self) -> &dyn Query {
+        match self {
+            BatchStatement::Insert(query) => query,
+            BatchStatement::Update(query) => query,
+            BatchStatement::Delete(query) => query,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BatchQuery {
+    pub batch_type: BatchType,
+    pub statements: Vec<BatchStatement>,
+}
+
+impl BatchQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_logged(&self) -> bool {
+        self.batch_type == BatchType::Logged
+    }
+
+    /// timestamp set on the statements of the batch, they all share the same one
+    pub fn timestamp(&self) -> Option<i64> {
+        self.statements.iter().find_map(BatchStatement::timestamp)
+    }
+
+    /// writes every statement with the timestamp already set on the batch, the one sent by the
+    /// client, or with `now` if there is none
+    pub fn share_timestamp(&mut self, now: i64) {
+        let timestamp = self.timestamp().unwrap_or(now);
+        self.set_timestamp(timestamp);
+    }
+
+    fn check_statements(&self) -> Result<(), Errors> {
+        if self.statements.is_empty() {
+            return Err(Errors::Invalid(String::from(
+                "A batch must contain at least one statement",
+            )));
+        }
+        if self.batch_type == BatchType::Counter {
+            return Err(Errors::Invalid(String::from(
+                "COUNTER batches are not supported, the node has no counter columns",
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Query for BatchQuery {
+    /// applies the statements of the batch locally, in order
+    fn run(&self) -> Result<Vec<u8>, Errors> {
+        for statement in &self.statements {
+            statement.as_query().run()?;
+        }
+        Response::void()
+    }
+
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
+        Ok(None)
+    }
+
+    fn get_keyspace(&self) -> Result<String, Errors> {
+        match self.statements.first() {
+            Some(statement) => statement.as_query().get_keyspace(),
+            None => Err(Errors::Invalid(String::from("Empty batch"))),
+        }
+    }
+
+    fn set_table(&mut self) -> Result<(), Errors> {
+        self.check_statements()?;
+        for statement in self.statements.iter_mut() {
+            statement.as_query_mut().set_table()?;
+        }
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn set_timestamp(&mut self, timestamp: i64) {
+        for statement in self.statements.iter_mut() {
+            statement.as_query_mut().set_timestamp(timestamp);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_batches_are_not_supported() {
+        let mut batch = BatchQuery {
+            batch_type: BatchType::Counter,
+            statements: vec![BatchStatement::Insert(InsertQuery::default())],
+        };
+        assert!(batch.set_table().is_err());
+        let mut batch = BatchQuery {
+            batch_type: BatchType::Counter,
+            statements: vec![BatchStatement::Update(UpdateQuery::default())],
+        };
+        assert!(matches!(batch.set_table(), Err(Errors::Invalid(_))));
+    }
+
+    #[test]
+    fn test_empty_batch_is_invalid() {
+        assert!(BatchQuery::new().set_table().is_err());
+    }
+
+    #[test]
+    fn test_batch_type_from_u8() {
+        assert_eq!(BatchType::from_u8(0).unwrap(), BatchType::Logged);
+        assert_eq!(BatchType::from_u8(1).unwrap(), BatchType::Unlogged);
+        assert_eq!(BatchType::from_u8(2).unwrap(), BatchType::Counter);
+        assert!(BatchType::from_u8(3).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use crate::batchlog::batchlog_entry::BatchlogEntry;
+use crate::batchlog::handler::BatchlogHandler;
+use crate::partitioner::partition_key::PartitionKey;
+use crate::utils::response::Response;
+use crate::{queries::query::Query, utils::errors::Errors};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub enum BatchlogOperation {
+    Store(BatchlogEntry),
+    Remove(String),
+}
+
+/// Internal query between nodes that stores or removes a batchlog entry.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct BatchlogQuery {
+    pub operation: BatchlogOperation,
+}
+
+impl BatchlogQuery {
+    pub fn store(entry: BatchlogEntry) -> Self {
+        Self {
+            operation: BatchlogOperation::Store(entry),
+        }
+    }
+
+    pub fn remove(id: &str) -> Self {
+        Self {
+            operation: BatchlogOperation::Remove(id.to_string()),
+        }
+    }
+}
+
+impl Query for BatchlogQuery {
+    fn run(&self) -> Result<Vec<u8>, Errors> {
+        match &self.operation {
+            BatchlogOperation::Store(entry) => BatchlogHandler::store(entry)?,
+            BatchlogOperation::Remove(id) => BatchlogHandler::remove(id)?,
+        }
+        Response::void()
+    }
+
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
+        Ok(None)
+    }
+
+    fn get_keyspace(&self) -> Result<String, Errors> {
+        match &self.operation {
+            BatchlogOperation::Store(entry) => entry.batch.get_keyspace(),
+            BatchlogOperation::Remove(_) => Ok(String::new()),
+        }
+    }
+
+    fn set_table(&mut self) -> Result<(), Errors> {
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -1,11 +1,13 @@
+use crate::meta_data::keyspaces::keyspace::{NETWORK_TOPOLOGY_STRATEGY, SIMPLE_STRATEGY};
 use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
+use crate::partitioner::partition_key::PartitionKey;
 use crate::queries::query::Query;
 use crate::utils::constants::{KEYSPACE_METADATA_PATH, REPLICATION, STRATEGY};
 use crate::utils::errors::Errors;
 use crate::utils::response::Response;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct CreateKeyspaceQuery {
@@ -36,28 +38,92 @@ impl CreateKeyspaceQuery {
         None
     }
 
+    /// accepts both the short name of the class and the full one (`org.apache.cassandra.locator.*`)
     fn get_strategy(&self) -> Option<String> {
-        if let Some(strategy) = self.replication.get(STRATEGY) {
-            return Some(strategy.to_string());
+        self.replication
+            .get(STRATEGY)
+            .map(|strategy| strategy.rsplit('.').next().unwrap_or(strategy).to_string())
+    }
+
+    /// replicas per datacenter, every option except the class is a datacenter
+    fn get_datacenters(&self) -> Result<BTreeMap<String, usize>, Errors> {
+        let mut datacenters = BTreeMap::new();
+        for (key, value) in &self.replication {
+            if key == STRATEGY {
+                continue;
+            }
+            if key == REPLICATION {
+                return Err(Errors::ConfigError(format!(
+                    "{} does not accept {}, give the replicas of each datacenter",
+                    NETWORK_TOPOLOGY_STRATEGY, REPLICATION
+                )));
+            }
+            let factor = value.parse::<usize>().map_err(|_| {
+                Errors::ConfigError(format!(
+                    "Replication factor of datacenter {} must be a number, got {}",
+                    key, value
+                ))
+            })?;
+            datacenters.insert(key.to_string(), factor);
+        }
+        if datacenters.is_empty() {
+            return Err(Errors::ConfigError(format!(
+                "{} needs the replicas of at least one datacenter",
+                NETWORK_TOPOLOGY_STRATEGY
+            )));
+        }
+        Ok(datacenters)
+    }
+
+    /// validates the replication options according to the class
+    fn check_replication(&self) -> Result<BTreeMap<String, usize>, Errors> {
+        match self.get_strategy().as_deref() {
+            Some(NETWORK_TOPOLOGY_STRATEGY) => self.get_datacenters(),
+            None | Some(SIMPLE_STRATEGY) => {
+                if let Some(key) = self
+                    .replication
+                    .keys()
+                    .find(|key| *key != STRATEGY && *key != REPLICATION)
+                {
+                    return Err(Errors::ConfigError(format!(
+                        "Unrecognized option {} for {}",
+                        key, SIMPLE_STRATEGY
+                    )));
+                }
+                if let Some(factor) = self.replication.get(REPLICATION) {
+                    if factor.parse::<usize>().is_err() {
+                        return Err(Errors::ConfigError(format!(
+                            "Replication factor must be a number, got {}",
+                            factor
+                        )));
+                    }
+                }
+                Ok(BTreeMap::new())
+            }
+            Some(class) => Err(Errors::ConfigError(format!(
+                "Unable to find replication strategy class {}",
+                class
+            ))),
         }
-        None
     }
 }
 
 impl Query for CreateKeyspaceQuery {
     fn run(&self) -> Result<Vec<u8>, Errors> {
+        let datacenters = self.check_replication()?;
         use_keyspace_meta_data(|handler| {
-            handler.add_keyspace(
+            handler.add_keyspace_with_datacenters(
                 KEYSPACE_METADATA_PATH.to_owned(),
                 &self.keyspace,
                 self.get_strategy(),
                 self.get_replication(),
+                datacenters,
             )
         })?;
         Response::schema_change("CREATED", "KEYSPACE", &self.keyspace)
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
         Ok(None)
     }
 
@@ -73,3 +139,57 @@ impl Query for CreateKeyspaceQuery {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(options: &[(&str, &str)]) -> CreateKeyspaceQuery {
+        let mut query = CreateKeyspaceQuery::new();
+        query.keyspace = "ks".to_string();
+        for (key, value) in options {
+            query.replication.insert(key.to_string(), value.to_string());
+        }
+        query
+    }
+
+    #[test]
+    fn test_network_topology_options() {
+        let datacenters = query(&[
+            (
+                STRATEGY,
+                "org.apache.cassandra.locator.NetworkTopologyStrategy",
+            ),
+            ("dc1", "3"),
+            ("dc2", "2"),
+        ])
+        .check_replication()
+        .unwrap();
+        assert_eq!(
+            datacenters,
+            BTreeMap::from([("dc1".to_string(), 3), ("dc2".to_string(), 2)])
+        );
+        assert!(matches!(
+            query(&[(STRATEGY, NETWORK_TOPOLOGY_STRATEGY)]).check_replication(),
+            Err(Errors::ConfigError(_))
+        ));
+        assert!(matches!(
+            query(&[(STRATEGY, NETWORK_TOPOLOGY_STRATEGY), ("dc1", "three")]).check_replication(),
+            Err(Errors::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_replication_options() {
+        assert!(query(&[(REPLICATION, "1")]).check_replication().is_ok());
+        assert!(matches!(
+            query(&[(STRATEGY, SIMPLE_STRATEGY), ("dc1", "3")]).check_replication(),
+            Err(Errors::ConfigError(_))
+        ));
+        assert!(matches!(
+            query(&[(STRATEGY, "OldNetworkTopologyStrategy"), (REPLICATION, "1")])
+                .check_replication(),
+            Err(Errors::ConfigError(_))
+        ));
+    }
+}
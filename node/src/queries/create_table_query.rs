@@ -1,6 +1,8 @@
 use crate::data_access::data_access_handler::use_data_access;
+use crate::meta_data::keyspaces::table::Table;
 use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
 use crate::parsers::tokens::data_type::DataType;
+use crate::partitioner::partition_key::PartitionKey;
 use crate::queries::query::Query;
 use crate::utils::constants::KEYSPACE_METADATA_PATH;
 use crate::utils::errors::Errors;
@@ -9,13 +11,17 @@ use crate::utils::response::Response;
 use crate::utils::types::primary_key::PrimaryKey;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct CreateTableQuery {
     pub table_name: String,
     pub columns: HashMap<String, DataType>,
     pub primary_key: PrimaryKey,
+    #[serde(default)]
+    pub clustering_order: HashMap<String, String>,
+    #[serde(default)]
+    pub options: BTreeMap<String, String>,
 }
 
 impl CreateTableQuery {
@@ -24,6 +30,8 @@ impl CreateTableQuery {
             table_name: String::new(),
             columns: HashMap::new(),
             primary_key: PrimaryKey::new_empty(),
+            clustering_order: HashMap::new(),
+            options: BTreeMap::new(),
         }
     }
 
@@ -34,12 +42,24 @@ impl CreateTableQuery {
                 KEYSPACE_METADATA_PATH.to_owned(),
                 kesypace_name,
                 table,
-                self.primary_key.to_owned(),
-                self.columns.to_owned(),
+                Table::new(self.primary_key.to_owned(), self.columns.to_owned())
+                    .with_properties(self.clustering_order.to_owned(), self.options.to_owned()),
             )
         })
     }
 
+    fn check_user_types(&self) -> Result<(), Errors> {
+        let (kesypace_name, _) = split_keyspace_table(&self.table_name)?;
+        use_keyspace_meta_data(|handler| {
+            for data_type in self.columns.values() {
+                if let DataType::Udt(type_name) = data_type {
+                    handler.get_type(KEYSPACE_METADATA_PATH.to_owned(), kesypace_name, type_name)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
     fn push_on_data_acces(&self) -> Result<(), Errors> {
         use_data_access(|data_access| data_access.create_table(&self.table_name))
     }
@@ -53,12 +73,13 @@ impl Default for CreateTableQuery {
 
 impl Query for CreateTableQuery {
     fn run(&self) -> Result<Vec<u8>, Errors> {
+        self.check_user_types()?;
         self.push_on_data_acces()?;
         self.push_on_meta_data()?;
         Response::schema_change("CREATED", "TABLE", &self.table_name)
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
         Ok(None)
     }
 
@@ -0,0 +1,89 @@
+use crate::meta_data::keyspaces::user_type::UserType;
+use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
+use crate::parsers::tokens::data_type::DataType;
+use crate::partitioner::partition_key::PartitionKey;
+use crate::queries::query::Query;
+use crate::utils::constants::KEYSPACE_METADATA_PATH;
+use crate::utils::errors::Errors;
+use crate::utils::functions::{check_table_name, split_keyspace_table};
+use crate::utils::response::Response;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::HashSet;
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTypeQuery {
+    pub type_name: String,
+    pub fields: Vec<(String, DataType)>,
+}
+
+impl CreateTypeQuery {
+    pub fn new() -> Self {
+        Self {
+            type_name: String::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    fn check_fields(&self) -> Result<(), Errors> {
+        if self.fields.is_empty() {
+            return Err(Errors::SyntaxError(String::from(
+                "A type must define at least one field",
+            )));
+        }
+        let names: HashSet<&String> = self.fields.iter().map(|(name, _)| name).collect();
+        if names.len() != self.fields.len() {
+            return Err(Errors::Invalid(String::from("There is a repeated field")));
+        }
+        Ok(())
+    }
+
+    fn push_on_meta_data(&self) -> Result<(), Errors> {
+        let (keyspace_name, type_name) = split_keyspace_table(&self.type_name)?;
+        use_keyspace_meta_data(|handler| {
+            for (_, data_type) in &self.fields {
+                if let DataType::Udt(inner) = data_type {
+                    handler.get_type(KEYSPACE_METADATA_PATH.to_owned(), keyspace_name, inner)?;
+                }
+            }
+            handler.add_type(
+                KEYSPACE_METADATA_PATH.to_owned(),
+                keyspace_name,
+                type_name,
+                UserType::new(self.fields.to_owned()),
+            )
+        })
+    }
+}
+
+impl Default for CreateTypeQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Query for CreateTypeQuery {
+    fn run(&self) -> Result<Vec<u8>, Errors> {
+        self.check_fields()?;
+        self.push_on_meta_data()?;
+        Response::schema_change("CREATED", "TYPE", &self.type_name)
+    }
+
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
+        Ok(None)
+    }
+
+    fn get_keyspace(&self) -> Result<String, Errors> {
+        let (kp, _) = split_keyspace_table(&self.type_name)?;
+        Ok(kp.to_string())
+    }
+
+    fn set_table(&mut self) -> Result<(), Errors> {
+        self.type_name = check_table_name(&self.type_name)?;
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
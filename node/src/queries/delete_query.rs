@@ -1,11 +1,13 @@
 use super::if_clause::IfClause;
 use super::{query::Query, where_logic::where_clause::WhereClause};
 use crate::data_access::data_access_handler::use_data_access;
+use crate::partitioner::partition_key::PartitionKey;
 use crate::utils::errors::Errors;
 use crate::utils::functions::{
     check_table_name, get_partition_key_from_where, split_keyspace_table,
 };
 use crate::utils::response::Response;
+use crate::utils::types::timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 
@@ -14,6 +16,8 @@ pub struct DeleteQuery {
     pub table_name: String,
     pub where_clause: Option<WhereClause>,
     pub if_clause: Option<IfClause>,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
 }
 
 impl DeleteQuery {
@@ -26,6 +30,7 @@ impl DeleteQuery {
             table_name,
             where_clause,
             if_clause,
+            timestamp: None,
         }
     }
 }
@@ -39,12 +44,17 @@ impl Query for DeleteQuery {
         };
         self.get_partition()?;
         let _apllied = use_data_access(|data_access| {
-            data_access.set_deleted_rows(&self.table_name, where_clause, &self.if_clause)
+            data_access.set_deleted_rows(
+                &self.table_name,
+                where_clause,
+                &self.if_clause,
+                &self.timestamp.map(Timestamp::new_from_i64).unwrap_or_default(),
+            )
         })?;
         Response::void()
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
         Ok(Some(get_partition_key_from_where(
             &self.table_name,
             &self.where_clause,
@@ -64,4 +74,12 @@ impl Query for DeleteQuery {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn set_timestamp(&mut self, timestamp: i64) {
+        self.timestamp = Some(timestamp);
+    }
+
+    fn is_conditional(&self) -> bool {
+        self.if_clause.is_some()
+    }
 }
@@ -0,0 +1,143 @@
+use super::query::Query;
+use crate::meta_data::meta_data_handler::{use_client_meta_data, use_keyspace_meta_data};
+use crate::partitioner::partition_key::PartitionKey;
+use crate::utils::constants::{CLIENT_METADATA_PATH, KEYSPACE_METADATA_PATH};
+use crate::utils::errors::Errors;
+use crate::utils::functions::{check_table_name, split_keyspace_table};
+use crate::utils::response::Response;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+const DESCRIBE_KEYSPACE: &str = "system_schema";
+const DESCRIBE_TABLE: &str = "describe";
+const KEYSPACE_NAME: &str = "keyspace_name";
+const TYPE: &str = "type";
+const NAME: &str = "name";
+const CREATE_STATEMENT: &str = "create_statement";
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub enum DescribeTarget {
+    Keyspaces,
+    Tables,
+    Keyspace(String),
+    Table(String),
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct DescribeQuery {
+    pub target: DescribeTarget,
+}
+
+impl DescribeQuery {
+    pub fn new(target: DescribeTarget) -> Self {
+        Self { target }
+    }
+
+    fn describe_keyspaces() -> Result<Vec<Vec<String>>, Errors> {
+        let mut names = use_keyspace_meta_data(|handler| {
+            handler.get_keyspaces_names(KEYSPACE_METADATA_PATH.to_string())
+        })?;
+        names.sort();
+        Ok(names
+            .into_iter()
+            .map(|name| vec![name.to_string(), "keyspace".to_string(), name])
+            .collect())
+    }
+
+    fn describe_tables() -> Result<Vec<Vec<String>>, Errors> {
+        let mut rows = Vec::new();
+        for keyspace in Self::describe_keyspaces()? {
+            let keyspace = &keyspace[0];
+            let mut tables = use_keyspace_meta_data(|handler| {
+                handler.get_tables_from_keyspace(KEYSPACE_METADATA_PATH.to_string(), keyspace)
+            })?;
+            tables.sort();
+            for table in tables {
+                rows.push(vec![keyspace.to_string(), "table".to_string(), table]);
+            }
+        }
+        Ok(rows)
+    }
+
+    fn describe_keyspace(keyspace: &str) -> Result<Vec<Vec<String>>, Errors> {
+        let statements = use_keyspace_meta_data(|handler| {
+            handler.describe_keyspace(KEYSPACE_METADATA_PATH.to_string(), keyspace)
+        })?;
+        Ok(statements
+            .into_iter()
+            .map(|(kind, name, statement)| vec![keyspace.to_string(), kind, name, statement])
+            .collect())
+    }
+
+    fn describe_table(table_name: &str) -> Result<Vec<Vec<String>>, Errors> {
+        let (keyspace, table) = split_keyspace_table(table_name)?;
+        let statement = use_keyspace_meta_data(|handler| {
+            handler.describe_table(KEYSPACE_METADATA_PATH.to_string(), keyspace, table)
+        })?;
+        Ok(vec![vec![
+            keyspace.to_string(),
+            "table".to_string(),
+            table.to_string(),
+            statement,
+        ]])
+    }
+}
+
+impl Query for DescribeQuery {
+    fn run(&self) -> Result<Vec<u8>, Errors> {
+        let (headers, rows) = match &self.target {
+            DescribeTarget::Keyspaces => {
+                (vec![KEYSPACE_NAME, TYPE, NAME], Self::describe_keyspaces()?)
+            }
+            DescribeTarget::Tables => (vec![KEYSPACE_NAME, TYPE, NAME], Self::describe_tables()?),
+            DescribeTarget::Keyspace(keyspace) => (
+                vec![KEYSPACE_NAME, TYPE, NAME, CREATE_STATEMENT],
+                Self::describe_keyspace(keyspace)?,
+            ),
+            DescribeTarget::Table(table) => (
+                vec![KEYSPACE_NAME, TYPE, NAME, CREATE_STATEMENT],
+                Self::describe_table(table)?,
+            ),
+        };
+        Response::text_rows(DESCRIBE_KEYSPACE, DESCRIBE_TABLE, &headers, &rows)
+    }
+
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
+        Ok(None)
+    }
+
+    fn get_keyspace(&self) -> Result<String, Errors> {
+        match &self.target {
+            DescribeTarget::Keyspace(keyspace) => Ok(keyspace.to_string()),
+            DescribeTarget::Table(table) => {
+                let (keyspace, _) = split_keyspace_table(table)?;
+                Ok(keyspace.to_string())
+            }
+            _ => Ok(String::new()),
+        }
+    }
+
+    /// resolves the keyspace in use when the statement does not name one
+    fn set_table(&mut self) -> Result<(), Errors> {
+        match &mut self.target {
+            DescribeTarget::Table(table) => *table = check_table_name(table)?,
+            DescribeTarget::Keyspace(keyspace) if keyspace.is_empty() => {
+                let Some(in_use) = use_client_meta_data(|handler| {
+                    handler.get_keyspace(CLIENT_METADATA_PATH.to_string())
+                })?
+                else {
+                    return Err(Errors::SyntaxError(String::from(
+                        "Keyspace not defined and non keyspace in usage",
+                    )));
+                };
+                *keyspace = in_use;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -1,5 +1,6 @@
 use crate::data_access::data_access_handler::use_data_access;
 use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
+use crate::partitioner::partition_key::PartitionKey;
 use crate::utils::response::Response;
 use crate::{
     queries::query::Query,
@@ -45,7 +46,7 @@ impl Query for DropKeySpaceQuery {
         Response::schema_change("DROPPED", "KEYSPACE", &self.keyspace)
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
         Ok(None)
     }
 
@@ -1,5 +1,6 @@
 use crate::data_access::data_access_handler::use_data_access;
 use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
+use crate::partitioner::partition_key::PartitionKey;
 use crate::utils::constants::KEYSPACE_METADATA_PATH;
 use crate::utils::functions::{check_table_name, split_keyspace_table};
 use crate::utils::response::Response;
@@ -40,7 +41,7 @@ impl Query for DropTableQuery {
         Response::schema_change("DROPPED", "TABLE", &self.table_name)
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
         Ok(None)
     }
 
@@ -0,0 +1,73 @@
+use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
+use crate::partitioner::partition_key::PartitionKey;
+use crate::utils::constants::KEYSPACE_METADATA_PATH;
+use crate::utils::functions::{check_table_name, split_keyspace_table};
+use crate::utils::response::Response;
+use crate::{queries::query::Query, utils::errors::Errors};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct DropTypeQuery {
+    pub type_name: String,
+    pub if_exist: Option<bool>,
+}
+
+impl DropTypeQuery {
+    pub fn new() -> Self {
+        Self {
+            type_name: String::new(),
+            if_exist: None,
+        }
+    }
+
+    fn exists(&self) -> Result<bool, Errors> {
+        let (keyspace_name, type_name) = split_keyspace_table(&self.type_name)?;
+        use_keyspace_meta_data(|handler| {
+            Ok(handler
+                .get_type(KEYSPACE_METADATA_PATH.to_owned(), keyspace_name, type_name)
+                .is_ok())
+        })
+    }
+
+    fn push_on_meta_data(&self) -> Result<(), Errors> {
+        let (keyspace_name, type_name) = split_keyspace_table(&self.type_name)?;
+        use_keyspace_meta_data(|handler| {
+            handler.drop_type(KEYSPACE_METADATA_PATH.to_owned(), keyspace_name, type_name)
+        })
+    }
+}
+
+impl Query for DropTypeQuery {
+    fn run(&self) -> Result<Vec<u8>, Errors> {
+        if self.if_exist == Some(true) && !self.exists()? {
+            return Response::void();
+        }
+        self.push_on_meta_data()?;
+        Response::schema_change("DROPPED", "TYPE", &self.type_name)
+    }
+
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
+        Ok(None)
+    }
+
+    fn get_keyspace(&self) -> Result<String, Errors> {
+        let (kp, _) = split_keyspace_table(&self.type_name)?;
+        Ok(kp.to_string())
+    }
+
+    fn set_table(&mut self) -> Result<(), Errors> {
+        self.type_name = check_table_name(&self.type_name)?;
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Default for DropTypeQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,12 +1,17 @@
 use crate::data_access::column::Column;
 use crate::data_access::data_access_handler::use_data_access;
 use crate::data_access::row::Row;
+use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
 use crate::parsers::tokens::data_type::DataType;
+use crate::partitioner::partition_key::PartitionKey;
+use crate::utils::constants::KEYSPACE_METADATA_PATH;
 use crate::utils::functions::{
-    check_table_name, get_columns_from_table, get_table_clustering_columns, get_table_partition,
+    check_literal_data_type, check_table_name, get_columns_from_table, get_table_clustering_columns, get_table_partition,
     split_keyspace_table,
 };
+use crate::utils::json::json_to_literals;
 use crate::utils::response::Response;
+use crate::utils::types::timestamp::Timestamp;
 use crate::{parsers::tokens::literal::Literal, queries::query::Query, utils::errors::Errors};
 use serde::{Deserialize, Serialize};
 use std::any::Any;
@@ -18,6 +23,13 @@ pub struct InsertQuery {
     pub headers: Vec<String>,
     pub values: Vec<Literal>,
     pub if_exists: Option<bool>,
+    #[serde(default)]
+    pub json: Option<String>,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+    /// columns bound to null, their stored values are removed
+    #[serde(default)]
+    pub null_columns: Vec<String>,
 }
 
 impl InsertQuery {
@@ -30,7 +42,16 @@ impl InsertQuery {
             )));
         }
         self.check_data_types_and_existance(&columns)?;
-
+        if let Some(column) = self
+            .null_columns
+            .iter()
+            .find(|column| !columns.contains_key(*column))
+        {
+            return Err(Errors::SyntaxError(format!(
+                "Column {} is not defined",
+                column
+            )));
+        }
         Ok(())
     }
 
@@ -53,9 +74,10 @@ impl InsertQuery {
                 "Values doesnt match given headers",
             )));
         }
+        let (keyspace, _) = split_keyspace_table(&self.table_name)?;
         for (value, header) in self.values.iter().zip(self.headers.iter()) {
             if let Some(column_data_type) = columns.get(header) {
-                if &value.data_type != column_data_type {
+                if !check_literal_data_type(keyspace, column_data_type, value)? {
                     return Err(Errors::SyntaxError(format!(
                         "Value datatype for {} do not match the defined column",
                         header
@@ -86,10 +108,17 @@ impl InsertQuery {
                 "Primary keys not defined",
             )));
         };
-        Ok(Row::new(
+        let mut row = Row::new(
             row_values,
-            [&partition_keys[..], &clustering_columns[..]].concat(),
-        ))
+            [&partition_keys.values()[..], &clustering_columns[..]].concat(),
+        );
+        if let Some(timestamp) = self.timestamp {
+            row.set_timestamp(Timestamp::new_from_i64(timestamp));
+            for column in &mut row.columns {
+                column.timestamp = Timestamp::new_from_i64(timestamp);
+            }
+        }
+        Ok(row)
     }
 
     fn get_keys(&self, set: HashSet<String>) -> Result<Option<Vec<String>>, Errors> {
@@ -111,6 +140,31 @@ impl InsertQuery {
         Ok(Some(partition_keys))
     }
 
+    /// the partition key follows the order of the table definition, not the one of the headers
+    fn get_partition_key(&self, set: HashSet<String>) -> Result<Option<PartitionKey>, Errors> {
+        self.get_keys(set)?;
+        PartitionKey::from_columns(&self.table_name, |column| {
+            self.headers
+                .iter()
+                .position(|header| header == column)
+                .map(|index| self.values[index].value.to_string())
+        })
+        .map(Some)
+    }
+
+    /// replaces headers and values with the ones of an `INSERT ... JSON` document
+    fn set_json_values(&mut self, json: &str) -> Result<(), Errors> {
+        let columns = get_columns_from_table(&self.table_name)?;
+        let (keyspace, _) = split_keyspace_table(&self.table_name)?;
+        let user_types = use_keyspace_meta_data(|handler| {
+            handler.get_types_from_keyspace(KEYSPACE_METADATA_PATH.to_string(), keyspace)
+        })?;
+        let (headers, values) = json_to_literals(json, &columns, &user_types)?;
+        self.headers = headers;
+        self.values = values;
+        Ok(())
+    }
+
     fn get_clustering_columns(&self) -> Result<Option<Vec<String>>, Errors> {
         self.get_keys(get_table_clustering_columns(&self.table_name)?)
     }
@@ -124,20 +178,24 @@ impl Query for InsertQuery {
             let row = self.build_row(&self.values)?;
             if let Some(if_exists) = self.if_exists {
                 if if_exists {
-                    applied = Some(data_access.simple_update_row(&self.table_name, &row)?);
+                    applied = Some(data_access.simple_update_row(
+                        &self.table_name,
+                        &row,
+                        &self.null_columns,
+                    )?);
                 } else {
                     applied = Some(data_access.insert(&self.table_name, &row)?);
                 }
             } else {
-                data_access.insert_or_update(&self.table_name, &row)?;
+                data_access.insert_or_update(&self.table_name, &row, &self.null_columns)?;
             }
             Ok(applied)
         })?;
         Response::void()
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
-        self.get_keys(get_table_partition(&self.table_name)?)
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
+        self.get_partition_key(get_table_partition(&self.table_name)?)
     }
 
     fn get_keyspace(&self) -> Result<String, Errors> {
@@ -147,10 +205,21 @@ impl Query for InsertQuery {
 
     fn set_table(&mut self) -> Result<(), Errors> {
         self.table_name = check_table_name(&self.table_name)?;
+        if let Some(json) = self.json.take() {
+            self.set_json_values(&json)?;
+        }
         Ok(())
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn set_timestamp(&mut self, timestamp: i64) {
+        self.timestamp = Some(timestamp);
+    }
+
+    fn is_conditional(&self) -> bool {
+        self.if_exists.is_some()
+    }
 }
@@ -1,11 +1,16 @@
 pub mod alter_keyspace_query;
 pub mod alter_table_query;
+pub mod alter_type_query;
 pub mod batch_query;
+pub mod batchlog_query;
 pub mod create_keyspace_query;
 pub mod create_table_query;
+pub mod create_type_query;
 pub mod delete_query;
+pub mod describe_query;
 pub mod drop_keyspace_query;
 pub mod drop_table_query;
+pub mod drop_type_query;
 pub mod evaluate;
 pub mod if_clause;
 pub mod insert_query;
@@ -13,6 +18,7 @@ pub mod order_by_clause;
 pub mod query;
 pub mod select_query;
 pub mod set_logic;
+pub mod truncate_query;
 pub mod update_query;
 pub mod use_query;
 pub mod where_logic;
@@ -1,11 +1,19 @@
+use crate::partitioner::partition_key::PartitionKey;
 use crate::queries::alter_table_query::AlterTableQuery;
+use crate::queries::alter_type_query::AlterTypeQuery;
+use crate::queries::batch_query::BatchQuery;
+use crate::queries::batchlog_query::BatchlogQuery;
 use crate::queries::create_keyspace_query::CreateKeyspaceQuery;
 use crate::queries::create_table_query::CreateTableQuery;
+use crate::queries::create_type_query::CreateTypeQuery;
 use crate::queries::delete_query::DeleteQuery;
+use crate::queries::describe_query::DescribeQuery;
 use crate::queries::drop_keyspace_query::DropKeySpaceQuery;
 use crate::queries::drop_table_query::DropTableQuery;
+use crate::queries::drop_type_query::DropTypeQuery;
 use crate::queries::insert_query::InsertQuery;
 use crate::queries::select_query::SelectQuery;
+use crate::queries::truncate_query::TruncateQuery;
 use crate::queries::update_query::UpdateQuery;
 use crate::queries::use_query::UseQuery;
 use crate::utils::errors::Errors;
@@ -14,10 +22,19 @@ use std::any::Any;
 
 pub trait Query: Any {
     fn run(&self) -> Result<Vec<u8>, Errors>;
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors>;
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors>;
     fn get_keyspace(&self) -> Result<String, Errors>;
     fn set_table(&mut self) -> Result<(), Errors>;
     fn as_any(&self) -> &dyn Any;
+
+    /// sets the timestamp, in milliseconds, of the values written by the query.
+    /// Queries that do not write rows ignore it
+    fn set_timestamp(&mut self, _timestamp: i64) {}
+
+    /// returns true for the writes with an IF condition, which need a serial consistency
+    fn is_conditional(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -32,6 +49,13 @@ pub enum QueryEnum {
     DropKeyspace(DropKeySpaceQuery),
     DropTable(DropTableQuery),
     AlterTable(AlterTableQuery),
+    CreateType(CreateTypeQuery),
+    AlterType(AlterTypeQuery),
+    DropType(DropTypeQuery),
+    Truncate(TruncateQuery),
+    Describe(DescribeQuery),
+    Batch(BatchQuery),
+    Batchlog(BatchlogQuery),
 }
 
 impl QueryEnum {
@@ -47,6 +71,13 @@ impl QueryEnum {
             QueryEnum::DropKeyspace(query) => Box::new(query),
             QueryEnum::DropTable(query) => Box::new(query),
             QueryEnum::AlterTable(query) => Box::new(query),
+            QueryEnum::CreateType(query) => Box::new(query),
+            QueryEnum::AlterType(query) => Box::new(query),
+            QueryEnum::DropType(query) => Box::new(query),
+            QueryEnum::Truncate(query) => Box::new(query),
+            QueryEnum::Describe(query) => Box::new(query),
+            QueryEnum::Batch(query) => Box::new(query),
+            QueryEnum::Batchlog(query) => Box::new(query),
         }
     }
 
@@ -72,6 +103,20 @@ impl QueryEnum {
             return Some(QueryEnum::DropTable(drop_table.to_owned()));
         } else if let Some(alter_table) = query.as_any().downcast_ref::<AlterTableQuery>() {
             return Some(QueryEnum::AlterTable(alter_table.to_owned()));
+        } else if let Some(create_type) = query.as_any().downcast_ref::<CreateTypeQuery>() {
+            return Some(QueryEnum::CreateType(create_type.to_owned()));
+        } else if let Some(alter_type) = query.as_any().downcast_ref::<AlterTypeQuery>() {
+            return Some(QueryEnum::AlterType(alter_type.to_owned()));
+        } else if let Some(drop_type) = query.as_any().downcast_ref::<DropTypeQuery>() {
+            return Some(QueryEnum::DropType(drop_type.to_owned()));
+        } else if let Some(truncate) = query.as_any().downcast_ref::<TruncateQuery>() {
+            return Some(QueryEnum::Truncate(truncate.to_owned()));
+        } else if let Some(describe) = query.as_any().downcast_ref::<DescribeQuery>() {
+            return Some(QueryEnum::Describe(describe.to_owned()));
+        } else if let Some(batch) = query.as_any().downcast_ref::<BatchQuery>() {
+            return Some(QueryEnum::Batch(batch.to_owned()));
+        } else if let Some(batchlog) = query.as_any().downcast_ref::<BatchlogQuery>() {
+            return Some(QueryEnum::Batchlog(batchlog.to_owned()));
         }
         None
     }
@@ -92,6 +137,13 @@ impl QueryEnum {
             QueryEnum::DropKeyspace(drop_keyspace) => {
                 QueryEnum::DropKeyspace(drop_keyspace.to_owned())
             }
+            QueryEnum::CreateType(create_type) => QueryEnum::CreateType(create_type.to_owned()),
+            QueryEnum::AlterType(alter_type) => QueryEnum::AlterType(alter_type.to_owned()),
+            QueryEnum::DropType(drop_type) => QueryEnum::DropType(drop_type.to_owned()),
+            QueryEnum::Truncate(truncate) => QueryEnum::Truncate(truncate.to_owned()),
+            QueryEnum::Describe(describe) => QueryEnum::Describe(describe.to_owned()),
+            QueryEnum::Batch(batch) => QueryEnum::Batch(batch.to_owned()),
+            QueryEnum::Batchlog(batchlog) => QueryEnum::Batchlog(batchlog.to_owned()),
         }
     }
 }
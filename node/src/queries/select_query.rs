@@ -2,6 +2,8 @@ use super::query::Query;
 use super::where_logic::where_clause::WhereClause;
 use crate::data_access::data_access_handler::use_data_access;
 use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
+use crate::parsers::tokens::data_type::DataType;
+use crate::partitioner::partition_key::PartitionKey;
 use crate::queries::order_by_clause::OrderByClause;
 use crate::utils::constants::{ASTERIK, KEYSPACE_METADATA_PATH};
 use crate::utils::errors::Errors;
@@ -18,6 +20,8 @@ pub struct SelectQuery {
     pub columns: Vec<String>,
     pub where_clause: Option<WhereClause>,
     pub order_clauses: Option<Vec<OrderByClause>>,
+    #[serde(default)]
+    pub json: bool,
 }
 
 impl SelectQuery {
@@ -27,6 +31,7 @@ impl SelectQuery {
             columns: Vec::new(),
             where_clause: None,
             order_clauses: None,
+            json: false,
         }
     }
 
@@ -41,12 +46,33 @@ impl SelectQuery {
             return Ok(());
         }
         for column in &self.columns {
-            if !table_columns.contains_key(column.to_string().as_str()) {
-                return Err(Errors::SyntaxError(format!(
-                    "Column {} not found in table columns",
-                    column
-                )));
+            if table_columns.contains_key(column.to_string().as_str()) {
+                continue;
+            }
+            if let Some((udt_column, field)) = column.split_once('.') {
+                if let Some(DataType::Udt(type_name)) = table_columns.get(udt_column) {
+                    self.check_udt_field(type_name, field)?;
+                    continue;
+                }
             }
+            return Err(Errors::SyntaxError(format!(
+                "Column {} not found in table columns",
+                column
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_udt_field(&self, type_name: &str, field: &str) -> Result<(), Errors> {
+        let (keyspace, _) = split_keyspace_table(&self.table_name)?;
+        let user_type = use_keyspace_meta_data(|handler| {
+            handler.get_type(KEYSPACE_METADATA_PATH.to_string(), keyspace, type_name)
+        })?;
+        if user_type.get_field_type(field).is_none() {
+            return Err(Errors::SyntaxError(format!(
+                "Field {} not found in type {}",
+                field, type_name
+            )));
         }
         Ok(())
     }
@@ -66,6 +92,20 @@ impl SelectQuery {
         }
         Ok(())
     }
+
+    /// without ORDER BY the rows follow the clustering order declared by the table
+    fn order_clauses(
+        &self,
+        keyspace: &str,
+        table: &str,
+    ) -> Result<Option<Vec<OrderByClause>>, Errors> {
+        if self.order_clauses.is_some() {
+            return Ok(self.order_clauses.clone());
+        }
+        use_keyspace_meta_data(|handler| {
+            handler.get_clustering_order(KEYSPACE_METADATA_PATH.to_string(), keyspace, table)
+        })
+    }
 }
 
 impl Default for SelectQuery {
@@ -84,10 +124,11 @@ impl Query for SelectQuery {
             )));
         };
         self.get_partition()?;
+        let (kesypace_name, table) = split_keyspace_table(&self.table_name)?;
+        let order_clauses = self.order_clauses(kesypace_name, table)?;
         let rows = use_data_access(|data_access| {
-            data_access.select_rows(&self.table_name, where_clause, &self.order_clauses)
+            data_access.select_rows(&self.table_name, where_clause, &order_clauses)
         })?;
-        let (kesypace_name, table) = split_keyspace_table(&self.table_name)?;
         if self.columns.first() == Some(&ASTERIK.to_string()) {
             let aux = use_keyspace_meta_data(|handler| {
                 handler.get_columns_type(KEYSPACE_METADATA_PATH.to_string(), kesypace_name, table)
@@ -98,7 +139,7 @@ impl Query for SelectQuery {
         Response::rows(rows, kesypace_name, table, &self.columns)
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
         Ok(Some(get_partition_key_from_where(
             &self.table_name,
             &self.where_clause,
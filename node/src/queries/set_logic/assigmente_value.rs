@@ -6,4 +6,6 @@ pub enum AssignmentValue {
     Simple(Literal),
     Column(String),
     Arithmetic(String, ArithMath, Literal),
+    /// the column is set to null, its value is removed from the row
+    Null,
 }
@@ -0,0 +1,54 @@
+use crate::data_access::data_access_handler::use_data_access;
+use crate::hinted_handoff::handler::Handler;
+use crate::partitioner::partition_key::PartitionKey;
+use crate::utils::functions::{check_table_name, get_columns_from_table, split_keyspace_table};
+use crate::utils::response::Response;
+use crate::{queries::query::Query, utils::errors::Errors};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct TruncateQuery {
+    pub table_name: String,
+}
+
+impl TruncateQuery {
+    pub fn new() -> Self {
+        Self {
+            table_name: String::new(),
+        }
+    }
+}
+
+impl Query for TruncateQuery {
+    fn run(&self) -> Result<Vec<u8>, Errors> {
+        get_columns_from_table(&self.table_name)?;
+        use_data_access(|data_access| data_access.truncate_table(&self.table_name))?;
+        Handler::remove_table_hints(&self.table_name)?;
+        Response::void()
+    }
+
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
+        Ok(None)
+    }
+
+    fn get_keyspace(&self) -> Result<String, Errors> {
+        let (kp, _) = split_keyspace_table(&self.table_name)?;
+        Ok(kp.to_string())
+    }
+
+    fn set_table(&mut self) -> Result<(), Errors> {
+        self.table_name = check_table_name(&self.table_name)?;
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Default for TruncateQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -5,12 +5,14 @@ use super::{
 use crate::data_access::data_access_handler::use_data_access;
 use crate::parsers::tokens::data_type::DataType;
 use crate::parsers::tokens::literal::Literal;
+use crate::partitioner::partition_key::PartitionKey;
 use crate::utils::errors::Errors;
 use crate::utils::functions::{
-    check_table_name, get_columns_from_table, get_partition_key_from_where, get_table_pk,
+    check_literal_data_type, check_table_name, get_columns_from_table, get_partition_key_from_where, get_table_pk,
     split_keyspace_table,
 };
 use crate::utils::response::Response;
+use crate::utils::types::timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::cmp::PartialEq;
@@ -22,6 +24,8 @@ pub struct UpdateQuery {
     pub changes: HashMap<String, AssignmentValue>,
     pub where_clause: Option<WhereClause>,
     pub if_clause: Option<IfClause>,
+    #[serde(default)]
+    pub timestamp: Option<i64>,
 }
 
 impl UpdateQuery {
@@ -31,6 +35,7 @@ impl UpdateQuery {
             changes: HashMap::new(),
             where_clause: None,
             if_clause: None,
+            timestamp: None,
         }
     }
 
@@ -73,6 +78,7 @@ impl UpdateQuery {
                     self.check_data_type_matching(set_col, &columns, literal)?;
                     self.check_data_type_matching(column, &columns, literal)?;
                 }
+                AssignmentValue::Null => {}
             }
         }
         Ok(())
@@ -97,8 +103,9 @@ impl UpdateQuery {
         columns: &HashMap<String, DataType>,
         literal: &Literal,
     ) -> Result<(), Errors> {
+        let (keyspace, _) = split_keyspace_table(&self.table_name)?;
         if let Some(data_type) = columns.get(column) {
-            if data_type != &literal.data_type {
+            if !check_literal_data_type(keyspace, data_type, literal)? {
                 return Err(Errors::SyntaxError(format!(
                     "Value to set ({}) does not match the column defined type",
                     literal.value
@@ -130,12 +137,13 @@ impl Query for UpdateQuery {
                 &self.changes,
                 where_clause,
                 &self.if_clause,
+                &self.timestamp.map(Timestamp::new_from_i64).unwrap_or_default(),
             )
         })?;
         Response::void()
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
         Ok(Some(get_partition_key_from_where(
             &self.table_name,
             &self.where_clause,
@@ -155,4 +163,12 @@ impl Query for UpdateQuery {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn set_timestamp(&mut self, timestamp: i64) {
+        self.timestamp = Some(timestamp);
+    }
+
+    fn is_conditional(&self) -> bool {
+        self.if_clause.is_some()
+    }
 }
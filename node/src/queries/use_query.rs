@@ -1,5 +1,6 @@
 use super::query::Query;
 use crate::meta_data::meta_data_handler::{use_client_meta_data, use_keyspace_meta_data};
+use crate::partitioner::partition_key::PartitionKey;
 use crate::utils::constants::CLIENT_METADATA_PATH;
 use crate::utils::errors::Errors;
 use crate::utils::response::Response;
@@ -36,7 +37,7 @@ impl Query for UseQuery {
         Response::set_keyspace(&self.keyspace_name)
     }
 
-    fn get_partition(&self) -> Result<Option<Vec<String>>, Errors> {
+    fn get_partition(&self) -> Result<Option<PartitionKey>, Errors> {
         Ok(None)
     }
 
@@ -34,7 +34,7 @@ impl ComparisonExpr {
 
     pub fn get_primary_key(
         &self,
-        pk: &mut Vec<String>,
+        pk: &mut HashMap<String, String>,
         table_pk: &HashSet<String>,
     ) -> Result<(), Errors> {
         if !table_pk.contains(&self.column_name) {
@@ -42,7 +42,7 @@ impl ComparisonExpr {
         }
         match self.operator {
             Equal => {
-                pk.push(self.literal.value.to_string());
+                pk.insert(self.column_name.to_string(), self.literal.value.to_string());
                 Ok(())
             }
             _ => Err(Errors::SyntaxError(String::from(
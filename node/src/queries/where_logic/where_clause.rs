@@ -45,9 +45,10 @@ impl Evaluate for WhereClause {
 }
 
 impl WhereClause {
+    /// collects the value of each partition key column, by column name
     pub fn get_primary_key(
         &self,
-        pk: &mut Vec<String>,
+        pk: &mut HashMap<String, String>,
         table_pk: &HashSet<String>,
     ) -> Result<(), Errors> {
         match self {
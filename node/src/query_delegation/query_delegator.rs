@@ -1,14 +1,23 @@
+use crate::client_warnings;
+use crate::gossip::failure_detector;
+use crate::gossip::schema_agreement;
 use crate::hinted_handoff::handler::Handler;
 use crate::hinted_handoff::stored_query::StoredQuery;
 use crate::meta_data::meta_data_handler::{use_keyspace_meta_data, use_node_meta_data};
+use crate::meta_data::keyspaces::keyspace::ReplicaPlacement;
+use crate::meta_data::nodes::node::{Node, State};
+use crate::partitioner::partition_key::PartitionKey;
 use crate::queries::query::{Query, QueryEnum};
+use crate::queries::select_query::SelectQuery;
 use crate::query_delegation::query_serializer::QuerySerializer;
 use crate::read_reparation::read_repair::ReadRepair;
+use crate::tracing::trace_session::{self, TraceSession};
 use crate::utils::config_constants::TIMEOUT_SECS;
-use crate::utils::consistency_level::ConsistencyLevel;
+use crate::utils::consistency_level::{AckCount, ConsistencyLevel};
 use crate::utils::constants::{KEYSPACE_METADATA_PATH, NODES_METADATA_PATH};
 use crate::utils::errors::Errors;
 use crate::utils::functions::{read_from_stream_no_zero, write_to_stream};
+use crate::utils::response::Response;
 use crate::utils::types::node_ip::NodeIp;
 use std::collections::HashMap;
 use std::net::TcpStream;
@@ -17,14 +26,15 @@ use std::thread;
 use std::time::Duration;
 
 pub struct QueryDelegator {
-    primary_key: Option<Vec<String>>,
+    primary_key: Option<PartitionKey>,
     query: Box<dyn Query>,
     consistency: ConsistencyLevel,
+    serial_consistency: ConsistencyLevel,
 }
 
 impl QueryDelegator {
     pub fn new(
-        primary_key: Option<Vec<String>>,
+        primary_key: Option<PartitionKey>,
         query: Box<dyn Query>,
         consistency: ConsistencyLevel,
     ) -> Self {
@@ -32,15 +42,35 @@ impl QueryDelegator {
             primary_key,
             query,
             consistency,
+            serial_consistency: ConsistencyLevel::Serial,
         }
     }
 
+    /// sets the consistency of the condition of a conditional write, SERIAL by default
+    pub fn with_serial_consistency(mut self, serial_consistency: ConsistencyLevel) -> Self {
+        self.serial_consistency = serial_consistency;
+        self
+    }
+
     pub fn send(&self) -> Result<Vec<u8>, Errors> {
+        let replicas = self.get_nodes_ip()?;
+        let own_ip = use_node_meta_data(|handler| handler.get_own_ip(&NODES_METADATA_PATH))?;
+        let nodes = use_node_meta_data(|handler| handler.get_full_nodes_list(&NODES_METADATA_PATH))?;
+        let datacenters: HashMap<NodeIp, String> = nodes
+            .iter()
+            .map(|node| (node.get_ip().clone(), node.get_datacenter().to_string()))
+            .collect();
+        let required = self.required_acks(&replicas, &datacenters, &own_ip)?;
+        let alive = Self::alive_count(&replicas, &own_ip, &nodes, &datacenters);
+        self.consistency.check_available(&required, &alive)?;
         let responses = Arc::new(Mutex::new(HashMap::new()));
         let (tx, rx) = mpsc::channel();
         let error = Arc::new(Mutex::new(None));
+        let hint_is_ack = self.consistency == ConsistencyLevel::Any;
+        let trace = trace_session::current();
+        let warnings = client_warnings::current();
 
-        for ip in self.get_nodes_ip()? {
+        for ip in replicas {
             let Some(query_enum) = QueryEnum::from_query(&self.query) else {
                 return Err(Errors::ServerError(String::from(
                     "QueryEnum does not exist",
@@ -48,9 +78,29 @@ impl QueryDelegator {
             };
             let tx = tx.clone();
             let error = Arc::clone(&error);
+            let trace = trace.clone();
+            let warnings = warnings.clone();
+            // do not wait for the timeout of a replica the failure detector already marked as down
+            let down = Self::is_down(&ip, &own_ip);
+            if !down {
+                trace_session::trace(&format!("Sending query to replica {}", ip.get_string_ip()));
+            }
             let _ = thread::spawn(move || {
-                match QueryDelegator::send_to_node(ip, query_enum.into_query()) {
-                    Ok((ip, response)) => if tx.send((ip, response)).is_ok() {},
+                if let Some(warnings) = warnings {
+                    client_warnings::attach(warnings);
+                }
+                let result = if down {
+                    let reason = format!("Node {} is down", ip.get_string_ip());
+                    QueryDelegator::store_hint(ip, query_enum.into_query(), trace, reason)
+                } else {
+                    QueryDelegator::send_to_replica(ip, query_enum.into_query(), trace)
+                };
+                match result {
+                    Ok((ip, response)) => if tx.send(Some((ip, response))).is_ok() {},
+                    // the replica is unavailable and a hint was stored, with ANY it counts as an ack
+                    Err(Errors::UnavailableException(_)) if hint_is_ack => {
+                        if tx.send(None).is_ok() {}
+                    }
                     Err(e) => {
                         let mut error_lock = error.lock().unwrap();
                         *error_lock = Some(e);
@@ -58,16 +108,27 @@ impl QueryDelegator {
                 }
             });
         }
-        // get responses until n = consistency
+        if !self.is_read() && !self.query.is_conditional() {
+            self.send_to_pending()?;
+        }
+        drop(tx);
+        // get responses until the acks cover the consistency, the ones of the datacenters
+        // that do not count are kept for the read repair
         let timeout = Duration::from_secs(TIMEOUT_SECS);
-        for _ in 0..self.consistency.get_consistency(self.get_replication()?) {
+        let mut acks = AckCount::default();
+        while !acks.covers(&required) {
             match rx.recv_timeout(timeout) {
-                Ok((ip, response)) => {
+                Ok(Some((ip, response))) => {
+                    if let Some(datacenter) = datacenters.get(&ip) {
+                        acks.add(datacenter);
+                    }
                     let mut res = responses.lock().unwrap();
                     res.insert(ip, response);
                 }
+                Ok(None) => acks.add_hint(),
                 _ => {
                     return match error.lock().unwrap().take() {
+                        Some(Errors::ServerError(msg)) => Err(self.replica_failure(msg)),
                         Some(e) => Err(e),
                         None => Err(Errors::ReadTimeout(String::from("Timeout"))),
                     };
@@ -75,25 +136,220 @@ impl QueryDelegator {
             }
         }
         let final_responses = responses.lock().unwrap();
-        self.get_response(final_responses.to_owned())
+        if final_responses.is_empty() {
+            return Response::void();
+        }
+        let response = self.get_response(final_responses.to_owned())?;
+        if Response::schema_change_body(&response).is_some() {
+            Self::wait_for_schema_agreement()?;
+        }
+        Ok(response)
     }
 
-    fn get_replication(&self) -> Result<usize, Errors> {
-        if self.primary_key.is_none() {
-            return use_node_meta_data(|handler| handler.get_nodes_quantity(NODES_METADATA_PATH));
+    /// A schema change is answered once every live node has the new schema, or with a warning
+    /// if they do not agree in time. Every request that changes the schema goes through here,
+    /// whether it was sent in a QUERY, an EXECUTE or a BATCH
+    fn wait_for_schema_agreement() -> Result<(), Errors> {
+        if !schema_agreement::wait_for_agreement()? {
+            client_warnings::warn(
+                "Schema agreement was not reached, not every live node has the same schema version",
+            );
         }
-        use_keyspace_meta_data(|handler| {
-            handler.get_replication(
-                KEYSPACE_METADATA_PATH.to_string(),
-                &self.query.get_keyspace()?,
+        Ok(())
+    }
+
+    /// Writes also go to the joining nodes that will be replicas of the partition, so they do
+    /// not miss what is written while they fetch their ranges. They do not count towards the
+    /// consistency: if they are not reached a hint is stored
+    fn send_to_pending(&self) -> Result<(), Errors> {
+        let pending = use_node_meta_data(|handler| {
+            handler.get_pending_ips(
+                &NODES_METADATA_PATH,
+                &self.primary_key,
+                self.query.get_keyspace()?,
             )
-        })
+        })?;
+        for ip in pending {
+            let Some(query_enum) = QueryEnum::from_query(&self.query) else {
+                return Err(Errors::ServerError(String::from(
+                    "QueryEnum does not exist",
+                )));
+            };
+            trace_session::trace(&format!(
+                "Sending query to pending replica {}",
+                ip.get_string_ip()
+            ));
+            let _ = thread::spawn(move || {
+                let _ = QueryDelegator::send_to_replica(ip, query_enum.into_query(), None);
+            });
+        }
+        Ok(())
+    }
+
+    /// acks needed for the consistency level, validating that it can be used for the query.
+    ///
+    /// The condition of a conditional write must be agreed by the serial consistency, so it
+    /// needs at least a quorum even if the consistency of the write is lower
+    fn required_acks(
+        &self,
+        replicas: &[NodeIp],
+        datacenters: &HashMap<NodeIp, String>,
+        own_ip: &NodeIp,
+    ) -> Result<AckCount, Errors> {
+        let replication = self.get_replication(replicas, datacenters)?;
+        let local = datacenters.get(own_ip).map(String::as_str).unwrap_or_default();
+        if self.is_read() {
+            self.consistency.validate_for_read()?;
+            return Ok(self.consistency.required_acks(&replication, local));
+        }
+        self.consistency.validate_for_write()?;
+        let required = self.consistency.required_acks(&replication, local);
+        if !self.query.is_conditional() {
+            return Ok(required);
+        }
+        if !self.serial_consistency.is_serial() {
+            return Err(Errors::Invalid(format!(
+                "Invalid serial consistency level {}",
+                self.serial_consistency.name()
+            )));
+        }
+        Ok(required.max(self.serial_consistency.required_acks(&replication, local)))
+    }
+
+    /// replicas that are not known to be down, the rest would only get a hint
+    fn alive_count(
+        replicas: &[NodeIp],
+        own_ip: &NodeIp,
+        nodes: &[Node],
+        datacenters: &HashMap<NodeIp, String>,
+    ) -> AckCount {
+        let mut alive = AckCount::default();
+        for ip in replicas
+            .iter()
+            .filter(|ip| !Self::is_down(ip, own_ip))
+            .filter(|ip| {
+                nodes.iter().any(|node| {
+                    node.get_ip() == *ip
+                        && node.state != State::Inactive
+                        && node.state != State::ShuttingDown
+                })
+            })
+        {
+            if let Some(datacenter) = datacenters.get(ip) {
+                alive.add(datacenter);
+            }
+        }
+        alive
+    }
+
+    fn is_read(&self) -> bool {
+        self.query.as_any().downcast_ref::<SelectQuery>().is_some()
+    }
+
+    /// a replica that fails while executing the query is reported as a read or write failure
+    fn replica_failure(&self, msg: String) -> Errors {
+        if self.is_read() {
+            Errors::ReadFailure(msg)
+        } else {
+            Errors::WriteFailure(msg)
+        }
+    }
+
+    /// sends the query to every node of the cluster, all of them must acknowledge it.
+    ///
+    /// No hints are stored: if a node is inactive or the failure detector convicted it the query
+    /// fails with `UnavailableException` before being sent, and if a node fails or does not
+    /// answer in time it fails with `TruncateError`
+    pub fn send_to_all_nodes(&self) -> Result<Vec<u8>, Errors> {
+        let nodes = use_node_meta_data(|handler| handler.get_full_nodes_list(&NODES_METADATA_PATH))?;
+        let own_ip = use_node_meta_data(|handler| handler.get_own_ip(&NODES_METADATA_PATH))?;
+        if let Some(node) = nodes.iter().find(|node| {
+            node.state == State::Inactive || Self::is_down(node.get_ip(), &own_ip)
+        }) {
+            return Err(Errors::UnavailableException(format!(
+                "Node {} is not available",
+                node.get_ip().get_string_ip()
+            )));
+        }
+        let (tx, rx) = mpsc::channel();
+        for node in &nodes {
+            let Some(query_enum) = QueryEnum::from_query(&self.query) else {
+                return Err(Errors::ServerError(String::from(
+                    "QueryEnum does not exist",
+                )));
+            };
+            let tx = tx.clone();
+            let ip = NodeIp::new_from_ip(node.get_ip());
+            let _ = thread::spawn(move || {
+                let result = QueryDelegator::send_without_hint(&ip, query_enum.into_query());
+                if tx.send(result).is_ok() {}
+            });
+        }
+        let timeout = Duration::from_secs(TIMEOUT_SECS);
+        let mut response = Vec::new();
+        for _ in 0..nodes.len() {
+            match rx.recv_timeout(timeout) {
+                Ok(Ok(node_response)) => response = node_response,
+                Ok(Err(Errors::ServerError(msg))) => return Err(Errors::TruncateError(msg)),
+                Ok(Err(e)) => return Err(e),
+                Err(_) => {
+                    return Err(Errors::TruncateError(String::from(
+                        "Not every node acknowledged the query",
+                    )))
+                }
+            }
+        }
+        Ok(response)
+    }
+
+    /// sends the query to the node without storing a hint if it is not reachable
+    pub fn send_without_hint(ip: &NodeIp, query: Box<dyn Query>) -> Result<Vec<u8>, Errors> {
+        let mut stream = TcpStream::connect(ip.get_query_delegation_socket())
+            .map_err(|e| Errors::UnavailableException(e.to_string()))?;
+        write_to_stream(&mut stream, QuerySerializer::serialize(&query)?.as_slice())?;
+        let response = read_from_stream_no_zero(&mut stream)?;
+        if let Some(e) = Errors::deserialize(response.as_slice()) {
+            return Err(e);
+        }
+        Ok(response)
+    }
+
+    /// Replication factor of the query in total and in each datacenter. With
+    /// `NetworkTopologyStrategy` every datacenter has its own factor, otherwise it is the amount
+    /// of replicas placed in it
+    fn get_replication(
+        &self,
+        replicas: &[NodeIp],
+        datacenters: &HashMap<NodeIp, String>,
+    ) -> Result<AckCount, Errors> {
+        let mut replication = AckCount::default();
+        for ip in replicas {
+            if let Some(datacenter) = datacenters.get(ip) {
+                replication.add(datacenter);
+            }
+        }
+        if self.primary_key.is_none() {
+            replication.total =
+                use_node_meta_data(|handler| handler.get_nodes_quantity(&NODES_METADATA_PATH))?;
+            return Ok(replication);
+        }
+        let keyspace = self.query.get_keyspace()?;
+        replication.total = use_keyspace_meta_data(|handler| {
+            handler.get_replication(KEYSPACE_METADATA_PATH.to_string(), &keyspace)
+        })?;
+        let placement = use_keyspace_meta_data(|handler| {
+            handler.get_placement(KEYSPACE_METADATA_PATH.to_string(), &keyspace)
+        })?;
+        if let ReplicaPlacement::NetworkTopology(factors) = placement {
+            replication.datacenters = factors;
+        }
+        Ok(replication)
     }
 
     fn get_nodes_ip(&self) -> Result<Vec<NodeIp>, Errors> {
         use_node_meta_data(|handler| {
             handler.get_partition_full_ips(
-                NODES_METADATA_PATH,
+                &NODES_METADATA_PATH,
                 &self.primary_key,
                 self.query.get_keyspace()?,
             )
@@ -101,21 +357,65 @@ impl QueryDelegator {
     }
 
     pub fn send_to_node(ip: NodeIp, query: Box<dyn Query>) -> Result<(NodeIp, Vec<u8>), Errors> {
+        Self::send_to_replica(ip, query, None)
+    }
+
+    /// sends the query to a replica, storing a hint if it is not reachable.
+    ///
+    /// If the request is being traced the replica records its own events, which are added
+    /// to the session of the coordinator
+    fn send_to_replica(
+        ip: NodeIp,
+        query: Box<dyn Query>,
+        trace: Option<TraceSession>,
+    ) -> Result<(NodeIp, Vec<u8>), Errors> {
+        let context = trace.as_ref().map(|session| session.context());
         match TcpStream::connect(ip.get_query_delegation_socket()) {
             Ok(mut stream) => {
-                write_to_stream(&mut stream, QuerySerializer::serialize(&query)?.as_slice())?;
+                let serialized = QuerySerializer::serialize_traced(&query, context)?;
+                write_to_stream(&mut stream, serialized.as_slice())?;
                 let response = read_from_stream_no_zero(&mut stream)?;
                 if let Some(e) = Errors::deserialize(response.as_slice()) {
+                    if let Some(session) = &trace {
+                        session.record(&format!("Replica {} failed: {}", ip.get_string_ip(), e));
+                    }
                     return Err(e);
                 }
-                Ok((ip, response))
-            }
-            Err(e) => {
-                use_node_meta_data(|handler| handler.set_inactive(NODES_METADATA_PATH, &ip))?;
-                Handler::store_query(StoredQuery::new(&query)?, ip)?;
-                Err(Errors::UnavailableException(e.to_string()))
+                let Some(session) = trace else {
+                    return Ok((ip, response));
+                };
+                let traced = QuerySerializer::deserialize_traced_response(&response)?;
+                session.extend(traced.events);
+                session.record(&format!("Received response from {}", ip.get_string_ip()));
+                Ok((ip, traced.response))
             }
+            Err(e) => Self::store_hint(ip, query, trace, e.to_string()),
+        }
+    }
+
+    /// the replica is down: the query is kept as a hint and it is reported as unavailable.
+    /// If the hint could not be kept the error of the store is returned instead, which ANY does
+    /// not count as an ack
+    fn store_hint(
+        ip: NodeIp,
+        query: Box<dyn Query>,
+        trace: Option<TraceSession>,
+        reason: String,
+    ) -> Result<(NodeIp, Vec<u8>), Errors> {
+        if let Some(session) = &trace {
+            session.record(&format!(
+                "Replica {} is down, storing a hint",
+                ip.get_string_ip()
+            ));
         }
+        Handler::store_query(StoredQuery::new(&query)?, ip.clone())?;
+        client_warnings::warn(&format!("Hint stored for {}", ip.get_string_ip()));
+        Err(Errors::UnavailableException(reason))
+    }
+
+    /// the failure detector convicted the replica, the own node is always up
+    fn is_down(ip: &NodeIp, own_ip: &NodeIp) -> bool {
+        ip != own_ip && !failure_detector::is_alive(ip)
     }
 
     fn get_response(&self, responses: HashMap<NodeIp, Vec<u8>>) -> Result<Vec<u8>, Errors> {
@@ -128,6 +428,10 @@ impl QueryDelegator {
             .collect();
         // Si hay respuestas tipo row, delega a read repair
         if !responses_to_repair.is_empty() {
+            trace_session::trace(&format!(
+                "Reconciling {} responses",
+                responses_to_repair.len()
+            ));
             let mut read_repair = ReadRepair::new(&responses_to_repair)?;
             return read_repair.get_response();
         }
@@ -1,4 +1,7 @@
+use crate::event_bus;
+use crate::queries::query::Query;
 use crate::query_delegation::query_serializer::QuerySerializer;
+use crate::tracing::trace_session::TraceSession;
 use crate::utils::errors::Errors;
 use crate::utils::functions::{bind_listener, read_exact_from_stream, write_to_stream};
 use crate::utils::types::node_ip::NodeIp;
@@ -27,8 +30,26 @@ impl QueryReceiver {
         Ok(())
     }
 }
+/// If the query comes with a tracing session, the response carries the events of the replica.
 fn handle_query(stream: &mut TcpStream) -> Result<Vec<u8>, Errors> {
     let res = read_exact_from_stream(stream)?;
-    let query = QuerySerializer::deserialize(res.as_slice())?;
-    query.run()
+    let (query, trace) = QuerySerializer::deserialize_traced(res.as_slice())?;
+    let Some(context) = trace else {
+        return run(query);
+    };
+    let session = TraceSession::resume(context);
+    session.record("Executing query on replica");
+    let response = run(query);
+    match &response {
+        Ok(_) => session.record("Query executed on replica"),
+        Err(e) => session.record(&format!("Query failed on replica: {}", e)),
+    }
+    QuerySerializer::serialize_traced_response(response?, session.events())
+}
+
+/// the schema changes applied by the replica are published to the clients registered on it
+fn run(query: Box<dyn Query>) -> Result<Vec<u8>, Errors> {
+    let response = query.run()?;
+    event_bus::publish_if_schema_change(&response);
+    Ok(response)
 }
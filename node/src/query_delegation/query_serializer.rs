@@ -1,16 +1,33 @@
 use crate::queries::query::{Query, QueryEnum};
+use crate::tracing::trace_session::{TraceContext, TraceEvent, TracedResponse};
 use crate::utils::errors::Errors;
 use rmp_serde::{from_slice, to_vec};
+use serde::{Deserialize, Serialize};
+
+/// Query sent to a replica, along with the tracing session if the request is being traced.
+#[derive(Serialize, Deserialize)]
+struct DelegatedQuery {
+    query: QueryEnum,
+    trace: Option<TraceContext>,
+}
 
 pub struct QuerySerializer;
 
 impl QuerySerializer {
     #[allow(clippy::borrowed_box)]
     pub fn serialize(query: &Box<dyn Query>) -> Result<Vec<u8>, Errors> {
-        let Some(query_enum) = QueryEnum::from_query(query) else {
+        Self::serialize_traced(query, None)
+    }
+
+    #[allow(clippy::borrowed_box)]
+    pub fn serialize_traced(
+        query: &Box<dyn Query>,
+        trace: Option<TraceContext>,
+    ) -> Result<Vec<u8>, Errors> {
+        let Some(query) = QueryEnum::from_query(query) else {
             return Err(Errors::ServerError(String::from("")));
         };
-        let Ok(serialized) = to_vec(&query_enum) else {
+        let Ok(serialized) = to_vec(&DelegatedQuery { query, trace }) else {
             return Err(Errors::ServerError(String::from(
                 "Failed to serialize query",
             )));
@@ -19,11 +36,68 @@ impl QuerySerializer {
     }
 
     pub fn deserialize(serialized: &[u8]) -> Result<Box<dyn Query>, Errors> {
-        let Ok(query_enum) = from_slice(serialized) else {
+        Ok(Self::deserialize_traced(serialized)?.0)
+    }
+
+    pub fn deserialize_traced(
+        serialized: &[u8],
+    ) -> Result<(Box<dyn Query>, Option<TraceContext>), Errors> {
+        let Ok(delegated) = from_slice::<DelegatedQuery>(serialized) else {
             return Err(Errors::ServerError(String::from(
                 "Failed to deserialize query",
             )));
         };
-        Ok(QueryEnum::into_query(query_enum))
+        Ok((QueryEnum::into_query(delegated.query), delegated.trace))
+    }
+
+    /// the reply of a replica to a traced query carries the events it recorded
+    pub fn serialize_traced_response(
+        response: Vec<u8>,
+        events: Vec<TraceEvent>,
+    ) -> Result<Vec<u8>, Errors> {
+        to_vec(&TracedResponse { response, events })
+            .map_err(|_| Errors::ServerError(String::from("Failed to serialize response")))
+    }
+
+    pub fn deserialize_traced_response(serialized: &[u8]) -> Result<TracedResponse, Errors> {
+        from_slice(serialized)
+            .map_err(|_| Errors::ServerError(String::from("Failed to deserialize response")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::query_parser::{query_lexer, query_parser};
+
+    #[test]
+    fn test_trace_context_travels_with_the_query() {
+        let query = query_parser(query_lexer("SELECT * FROM ks.t".to_string()).unwrap()).unwrap();
+        let context = TraceContext {
+            session_id: [7; 16],
+            coordinator: String::from("127.0.0.1"),
+        };
+        let serialized = QuerySerializer::serialize_traced(&query, Some(context.clone())).unwrap();
+        let (_, trace) = QuerySerializer::deserialize_traced(&serialized).unwrap();
+        assert_eq!(trace, Some(context));
+        let serialized = QuerySerializer::serialize(&query).unwrap();
+        assert!(QuerySerializer::deserialize_traced(&serialized).unwrap().1.is_none());
+    }
+
+    #[test]
+    fn test_traced_response_round_trip() {
+        let event = TraceEvent {
+            activity: String::from("Query executed"),
+            source: String::from("127.0.0.2"),
+            source_elapsed: 42,
+            thread: String::from("ThreadId(3)"),
+            timestamp: 1,
+        };
+        let serialized =
+            QuerySerializer::serialize_traced_response(vec![0, 0, 0, 1], vec![event.clone()])
+                .unwrap();
+        let traced = QuerySerializer::deserialize_traced_response(&serialized).unwrap();
+        assert_eq!(traced.response, vec![0, 0, 0, 1]);
+        assert_eq!(traced.events, vec![event]);
     }
 }
@@ -1,6 +1,7 @@
 use super::repair_row::RepairRow;
 use super::response_manager::ResponseManager;
 use super::utils::to_hash_rows;
+use crate::client_warnings;
 use crate::utils::types::node_ip::NodeIp;
 use crate::{
     data_access::row::Row,
@@ -43,14 +44,23 @@ impl ReadRepair {
         Ok(())
     }
 
+    /// repairs the replicas whose response differs from the best one, the client is warned
     fn repair(&self) -> Result<(), Errors> {
         let better_response = self.response_manager.cast_to_protocol_row(BEST)?;
+        let mut repaired = 0;
         for ip in self.response_manager.get_ips() {
             let response = self.response_manager.cast_to_protocol_row(&ip)?;
             if response != better_response {
                 self.repair_node(&ip)?;
+                repaired += 1;
             }
         }
+        if repaired > 0 {
+            client_warnings::warn(&format!(
+                "Read repair performed on {} replicas",
+                repaired
+            ));
+        }
         Ok(())
     }
 
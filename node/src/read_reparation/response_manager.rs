@@ -298,6 +298,7 @@ mod tests {
             DataType::Int => 0x0009,      // Código de tipo para `INT`
             DataType::Text => 0x000A,     // Código de tipo para `TEXT`
             DataType::Time => 0x000C,     // Código de tipo para `TIME`
+            DataType::Udt(_) => 0x0030,   // Type code for `UDT`
         }
     }
 
@@ -55,7 +55,7 @@ impl RowResponse {
         for _ in 0..count_columns {
             let name = cursor.read_string()?;
             let value = cursor.read_string()?;
-            let data_type = byte_to_data_type(cursor.read_i16()?)?;
+            let data_type = read_data_type(cursor)?;
             let literal = create_literal(&value, data_type);
             let timestamp = cursor.read_i64()?;
             let column = Column {
@@ -76,8 +76,7 @@ impl RowResponse {
         let count_primary_keys = cursor.read_short()?;
         for _ in 0..count_primary_keys {
             let title = cursor.read_string()?;
-            let data_type_bytes = cursor.read_i16()?;
-            let data_type = byte_to_data_type(data_type_bytes)?;
+            let data_type = read_data_type(&mut cursor)?;
             headers_pks.insert(title, data_type);
         }
         let mut columns: Vec<String> = Vec::new();
@@ -90,6 +89,15 @@ impl RowResponse {
     }
 }
 
+/// reads a data type written with `Response::write_data_type`
+fn read_data_type(cursor: &mut BytesCursor) -> Result<DataType, Errors> {
+    let byte = cursor.read_i16()?;
+    if byte == 0x0030 {
+        return Ok(DataType::Udt(cursor.read_string()?));
+    }
+    byte_to_data_type(byte)
+}
+
 fn byte_to_data_type(byte: i16) -> Result<DataType, Errors> {
     match byte {
         0x0004 => Ok(DataType::Boolean),
@@ -4,6 +4,7 @@ use crate::{utils::{errors::Errors, types::token_conversor::{create_reserved_tok
 use crate::parsers::tokens::terms::ComparisonOperators::Equal;
 use crate::parsers::tokens::terms::LogicalOperators::And;
 use crate::parsers::tokens::data_type::DataType;
+use crate::meta_data::keyspaces::keyspace::{ReplicaPlacement, NETWORK_TOPOLOGY_STRATEGY, SIMPLE_STRATEGY};
 const COLON: &str = ":";
 pub struct BuilderMessage;
 
@@ -123,34 +124,33 @@ impl BuilderMessage {
 
 
 fn brace_list(keyspace: &str) -> Result<Token,Errors> {
-    let (replication, strategy) = get_meta_data_keyspace(keyspace)?;
-    let list: Vec<Token> = vec![
+    let placement = use_keyspace_meta_data(|handler| {
+        handler.get_placement(KEYSPACE_METADATA_PATH.to_owned(), keyspace)
+    })?;
+    let (strategy, factors) = match placement {
+        ReplicaPlacement::Simple(replication) => (
+            SIMPLE_STRATEGY,
+            vec![("replication_factor".to_string(), replication)],
+        ),
+        ReplicaPlacement::NetworkTopology(datacenters) => {
+            (NETWORK_TOPOLOGY_STRATEGY, datacenters.into_iter().collect())
+        }
+    };
+    let mut list: Vec<Token> = vec![
         create_token_literal("class", DataType::Text),
         create_symbol_token(COLON),
-        create_token_literal(&strategy, DataType::Text),
-
-        create_symbol_token(COMMA),
-
-        create_token_literal("replication_factor", DataType::Text),
-        create_symbol_token(COLON),
-        create_token_literal(&replication, DataType::Int)
+        create_token_literal(strategy, DataType::Text),
     ];
-    
+    for (key, factor) in factors {
+        list.push(create_symbol_token(COMMA));
+        list.push(create_token_literal(&key, DataType::Text));
+        list.push(create_symbol_token(COLON));
+        list.push(create_token_literal(&factor.to_string(), DataType::Int));
+    }
 
     Ok(create_brace_list_token(list))
 }
 
-fn get_meta_data_keyspace(keyspace: &str) -> Result<(String, String), Errors> {
-    let replication = use_keyspace_meta_data(|handler| {
-        handler.get_replication( KEYSPACE_METADATA_PATH.to_owned(), keyspace)
-    })?;
-    let strategy = use_keyspace_meta_data(|handler| {
-        handler.get_strategy(KEYSPACE_METADATA_PATH.to_owned(), keyspace)
-    })?;
-    Ok((replication.to_string(), strategy))
-}
-
-
 fn paren_list(path: &str) -> Result<Token, Errors> {
     let (keyspace, table) = path.split_once('.').ok_or_else(|| Errors::ServerError("Failed to read keyspace.table".to_string()))?;
     let (pks, columns) = get_meta_data_table(keyspace, table)?;
@@ -1,20 +1,34 @@
 use crate::{data_access::{data_access_handler::use_data_access, row::Row}, utils::{errors::Errors, constants::{DATA_ACCESS_PATH, NODES_METADATA_PATH, KEYSPACE_METADATA_PATH}, types::node_ip::NodeIp}, query_delegation::query_delegator::QueryDelegator, meta_data::meta_data_handler::{use_keyspace_meta_data, use_node_meta_data}};
 use std::fs;
+use crate::logger::Logger;
+use crate::meta_data::nodes::node::State;
+use crate::partitioner::partition_key::PartitionKey;
 use crate::queries::query::Query;
+use crate::utils::constants::LOGGER_PATH;
 use super::builder_message::BuilderMessage;
 
 pub struct MessageSender;
 
 impl MessageSender {
+    /// Deletes the rows this node is no longer a replica of.
+    ///
+    /// Data is never sent from here: the ranges a node gains when it starts are fetched by it
+    /// and the ones left by a node that leaves are sent by itself, or replicated again by `removenode`
+    /// if it was down, before the ring changes
     pub fn redistribute() -> Result<(), Errors> {
-        if std::path::Path::new(DATA_ACCESS_PATH).exists() {
-            for entry in fs::read_dir(DATA_ACCESS_PATH)
+        let cluster = use_node_meta_data(|handler| handler.get_cluster(&NODES_METADATA_PATH))?;
+        // while joining this node owns no range, what it already fetched is kept
+        if cluster.get_own_node().state == State::Booting {
+            return Ok(());
+        }
+        if std::path::Path::new(DATA_ACCESS_PATH.as_str()).exists() {
+            for entry in fs::read_dir(DATA_ACCESS_PATH.as_str())
                 .map_err(|_| Errors::ServerError("Failed to read directory".to_string()))?
             {
                 let entry = entry.map_err(|_| Errors::ServerError("Failed to open table file".to_string()))?;
                 let file_name = entry.file_name();
                 let file_name_str = file_name.to_string_lossy();
-                let path = format!("{}{}", DATA_ACCESS_PATH, file_name_str);
+                let path = format!("{}{}", DATA_ACCESS_PATH.as_str(), file_name_str);
                 
                 if let Some(keyspace_table) = file_name_str.strip_suffix(".json") {
                     let rows = use_data_access(|data_access| {
@@ -64,14 +78,11 @@ impl MessageSender {
         I: Iterator<Item = Row>,
     {
         let keyspace = get_keyspace(table);
-        let own_node = use_node_meta_data(|handler| handler.get_own_ip(NODES_METADATA_PATH))?;
+        let own_node = use_node_meta_data(|handler| handler.get_own_ip(&NODES_METADATA_PATH))?;
         for row in rows {
-            let nodes_list = use_node_meta_data(|handler| handler.get_partition_full_ips(NODES_METADATA_PATH, &Some(row.primary_key.to_vec()), keyspace.to_owned()))?;
+            let partition_key = PartitionKey::from_row(table, &row)?;
+            let nodes_list = use_node_meta_data(|handler| handler.get_partition_full_ips(&NODES_METADATA_PATH, &Some(partition_key), keyspace.to_owned()))?;
             if !nodes_list.contains(&own_node) {
-                for node_ip in nodes_list {
-                    let insert_query = BuilderMessage::build_insert(row.clone(), table.to_string())?;
-                    Self::send_to_node(node_ip, insert_query);
-                }
                 let delete_query = BuilderMessage::build_delete(row, table.to_owned())?;
                 let _ = delete_query.run();
 
@@ -80,9 +91,14 @@ impl MessageSender {
         Ok(())
     }
 
+    /// the redistribution goes on with the rest of the rows, the error is logged
     fn send_to_node(node_ip: NodeIp, query: Box<dyn Query>){
-        if QueryDelegator::send_to_node(node_ip, query).is_err() {
-            //ingonor el error
+        let ip = node_ip.get_string_ip();
+        if let Err(e) = QueryDelegator::send_to_node(node_ip, query) {
+            Logger::new(&LOGGER_PATH).log_error(&format!(
+                "Could not send the query to {} while redistributing: {}",
+                ip, e
+            ));
         }
     }
 }
@@ -0,0 +1,188 @@
+use crate::data_access::row::Row;
+use crate::partitioner::{contains, Token, TokenRange};
+use crate::utils::errors::Errors;
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+
+pub type Hash = [u8; 16];
+
+/// amount of distinct tokens of the ring
+const RING_SIZE: i128 = 1 << 64;
+
+/// Tree of hashes of the rows of a range of the ring.
+///
+/// The range is split in `2^depth` sub-ranges of the same width, each leaf is the XOR of the hashes
+/// of the rows whose token falls in its sub-range and each inner node the md5 of its two children.
+/// Two replicas with the same data build the same tree regardless of the order of the rows
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MerkleTree {
+    range: TokenRange,
+    depth: u32,
+    /// `levels[0]` is the root and the last level holds the leaves
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    pub fn new<I>(range: TokenRange, depth: u32, rows: I) -> Self
+    where
+        I: IntoIterator<Item = (Token, Hash)>,
+    {
+        let mut leaves = vec![[0; 16]; 1 << depth];
+        for (token, hash) in rows {
+            if !contains(&range, token) {
+                continue;
+            }
+            let leaf = &mut leaves[leaf_of(&range, depth, token)];
+            for (byte, other) in leaf.iter_mut().zip(hash.iter()) {
+                *byte ^= other;
+            }
+        }
+        let mut levels = vec![leaves];
+        while levels[0].len() > 1 {
+            let parents = levels[0]
+                .chunks(2)
+                .map(|children| {
+                    let mut hasher = Md5::new();
+                    hasher.update(children[0]);
+                    hasher.update(children[1]);
+                    hasher.finalize().into()
+                })
+                .collect();
+            levels.insert(0, parents);
+        }
+        Self {
+            range,
+            depth,
+            levels,
+        }
+    }
+
+    pub fn get_range(&self) -> &TokenRange {
+        &self.range
+    }
+
+    /// Sub-ranges whose rows differ between both trees. It only goes down from the root through the
+    /// children with a different hash, so two equal trees are compared looking at a single hash
+    pub fn difference(&self, other: &MerkleTree) -> Vec<TokenRange> {
+        if self.range != other.range || self.depth != other.depth {
+            return vec![self.range];
+        }
+        let mut differing = vec![0];
+        for level in 0..self.levels.len() - 1 {
+            differing = differing
+                .into_iter()
+                .filter(|index| self.levels[level][*index] != other.levels[level][*index])
+                .flat_map(|index| [2 * index, 2 * index + 1])
+                .collect();
+        }
+        let leaves = self.levels.len() - 1;
+        differing
+            .into_iter()
+            .filter(|index| self.levels[leaves][*index] != other.levels[leaves][*index])
+            .map(|index| leaf_range(&self.range, self.depth, index))
+            .collect()
+    }
+}
+
+/// md5 of the row with its columns sorted by name, the order in which they were stored does
+/// not change the hash
+pub fn row_hash(row: &Row) -> Result<Hash, Errors> {
+    let mut columns: Vec<(&str, &str, i64)> = row
+        .columns
+        .iter()
+        .map(|column| {
+            (
+                column.column_name.as_str(),
+                column.value.value.as_str(),
+                column.timestamp.timestamp,
+            )
+        })
+        .collect();
+    columns.sort();
+    let canonical = serde_json::to_vec(&(
+        &row.primary_key,
+        row.deleted,
+        row.timestamp.timestamp,
+        columns,
+    ))
+    .map_err(|_| Errors::ServerError(String::from("Failed to serialize row")))?;
+    Ok(Md5::digest(canonical).into())
+}
+
+/// amount of tokens of the range
+fn width(range: &TokenRange) -> i128 {
+    let width = (range.1 as i128 - range.0 as i128).rem_euclid(RING_SIZE);
+    if width == 0 {
+        RING_SIZE
+    } else {
+        width
+    }
+}
+
+/// Leaf `i` covers the offsets `(i * width / leaves, (i + 1) * width / leaves]` from the
+/// start of the range
+fn leaf_of(range: &TokenRange, depth: u32, token: Token) -> usize {
+    let mut offset = (token as i128 - range.0 as i128).rem_euclid(RING_SIZE);
+    if offset == 0 {
+        // only happens with the whole ring, the start token is the last one
+        offset = RING_SIZE;
+    }
+    (((offset << depth) - 1) / width(range)) as usize
+}
+
+fn leaf_range(range: &TokenRange, depth: u32, index: usize) -> TokenRange {
+    let width = width(range);
+    let bound = |index: usize| wrap(range.0 as i128 + ((index as i128 * width) >> depth));
+    (bound(index), bound(index + 1))
+}
+
+fn wrap(value: i128) -> Token {
+    ((value - i64::MIN as i128).rem_euclid(RING_SIZE) + i64::MIN as i128) as Token
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(value: u8) -> Hash {
+        Md5::digest([value]).into()
+    }
+
+    #[test]
+    fn test_every_token_falls_in_the_leaf_that_contains_it() {
+        let ranges = [(-1000, 1000), (i64::MAX - 10, i64::MIN + 20), (5, 5)];
+        for range in ranges {
+            for token in [-999, 0, 1000, i64::MAX, i64::MIN, i64::MIN + 20, 5, 6] {
+                if !contains(&range, token) {
+                    continue;
+                }
+                let leaf = leaf_range(&range, 3, leaf_of(&range, 3, token));
+                assert!(contains(&leaf, token), "{:?} {} {:?}", range, token, leaf);
+            }
+            assert_eq!(leaf_range(&range, 3, 0).0, range.0);
+            assert_eq!(leaf_range(&range, 3, 7).1, range.1);
+        }
+        assert!(!contains(&(-1000, 1000), -1000));
+        assert!(contains(&(i64::MAX - 10, i64::MIN + 20), i64::MIN));
+    }
+
+    #[test]
+    fn test_only_the_leaves_with_other_rows_differ() {
+        let range = (0, 800);
+        let rows = vec![(10, hash(1)), (150, hash(2)), (790, hash(3))];
+        let mut reordered = rows.clone();
+        reordered.reverse();
+        let local = MerkleTree::new(range, 3, rows.clone());
+        assert!(local
+            .difference(&MerkleTree::new(range, 3, reordered))
+            .is_empty());
+
+        let mut other = rows;
+        other[1] = (150, hash(4));
+        other.push((420, hash(5)));
+        // out of the range, it does not count
+        other.push((900, hash(6)));
+        let remote = MerkleTree::new(range, 3, other);
+        assert_eq!(local.difference(&remote), vec![(100, 200), (400, 500)]);
+    }
+}
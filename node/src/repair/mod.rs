@@ -0,0 +1,4 @@
+pub mod merkle_tree;
+pub mod repair_listener;
+pub mod repair_message;
+pub mod repair_session;
@@ -0,0 +1,55 @@
+use crate::data_access::data_access_handler::use_data_access;
+use crate::repair::merkle_tree::{row_hash, MerkleTree};
+use crate::repair::repair_message::RepairMessage;
+use crate::utils::errors::Errors;
+use crate::utils::functions::{
+    deserialize_from_slice, get_rows_in_ranges, get_rows_with_tokens, read_exact_from_stream,
+    serialize_to_string, start_listener, write_to_stream,
+};
+use crate::utils::types::node_ip::NodeIp;
+use std::net::TcpStream;
+
+/// Serves the requests of the node that coordinates a repair
+pub struct RepairListener;
+
+impl RepairListener {
+    pub fn start_listening(ip: NodeIp) -> Result<(), Errors> {
+        start_listener(ip.get_repair_socket(), Self::handle_connection)
+    }
+
+    /// a request that fails is answered with `Failed`, so the listener keeps serving
+    fn handle_connection(stream: &mut TcpStream) -> Result<(), Errors> {
+        let buf = read_exact_from_stream(stream)?;
+        let response = match Self::handle_message(deserialize_from_slice(buf.as_slice())?) {
+            Ok(response) => response,
+            Err(e) => RepairMessage::Failed(e.to_string()),
+        };
+        write_to_stream(stream, serialize_to_string(&response)?.as_bytes())
+    }
+
+    fn handle_message(message: RepairMessage) -> Result<RepairMessage, Errors> {
+        match message {
+            RepairMessage::TreeRequest {
+                table,
+                range,
+                depth,
+            } => {
+                let mut hashes = Vec::new();
+                for (token, row) in get_rows_with_tokens(&table)? {
+                    hashes.push((token, row_hash(&row)?));
+                }
+                Ok(RepairMessage::Tree(MerkleTree::new(range, depth, hashes)))
+            }
+            RepairMessage::RowsRequest { table, ranges } => {
+                Ok(RepairMessage::Rows(get_rows_in_ranges(&table, &ranges)?))
+            }
+            RepairMessage::Stream { table, rows } => {
+                use_data_access(|data_access| data_access.merge_rows(&table, rows))?;
+                Ok(RepairMessage::Done)
+            }
+            _ => Err(Errors::ServerError(String::from(
+                "Expected a repair request",
+            ))),
+        }
+    }
+}
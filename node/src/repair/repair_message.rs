@@ -0,0 +1,30 @@
+use crate::data_access::row::Row;
+use crate::partitioner::TokenRange;
+use crate::repair::merkle_tree::MerkleTree;
+use serde::{Deserialize, Serialize};
+
+/// Messages between the node that coordinates a repair and the replicas.
+///
+/// A `TreeRequest` is answered with `Tree` holding the tree of the rows of the range, a `RowsRequest`
+/// with `Rows` holding the rows of the requested sub-ranges and a `Stream` with `Done` once the
+/// received rows were merged. If something fails the replica answers `Failed`
+#[derive(Serialize, Deserialize, Debug)]
+pub enum RepairMessage {
+    TreeRequest {
+        table: String,
+        range: TokenRange,
+        depth: u32,
+    },
+    Tree(MerkleTree),
+    RowsRequest {
+        table: String,
+        ranges: Vec<TokenRange>,
+    },
+    Rows(Vec<Row>),
+    Stream {
+        table: String,
+        rows: Vec<Row>,
+    },
+    Done,
+    Failed(String),
+}
@@ -0,0 +1,272 @@
+use crate::data_access::row::Row;
+use crate::meta_data::meta_data_handler::{use_keyspace_meta_data, use_node_meta_data};
+use crate::partitioner::TokenRange;
+use crate::read_reparation::row_comparer::RowComparer;
+use crate::repair::merkle_tree::MerkleTree;
+use crate::repair::repair_message::RepairMessage;
+use crate::utils::config_constants::MERKLE_TREE_DEPTH;
+use crate::utils::constants::{KEYSPACE_METADATA_PATH, NODES_METADATA_PATH};
+use crate::utils::errors::Errors;
+use crate::utils::functions::{
+    connect_to_socket, deserialize_from_slice, read_exact_from_stream, serialize_to_string,
+    write_to_stream,
+};
+use crate::utils::types::node_ip::NodeIp;
+use std::fmt;
+
+/// Repair of the tables of a keyspace coordinated by this node.
+///
+/// For each table and each range of the ring this node is a replica of, the Merkle trees are
+/// requested from every replica, the rows of the sub-ranges where they differ are fetched,
+/// merged by timestamp and each replica is sent what it is missing
+pub struct RepairSession {
+    keyspace: String,
+    tables: Vec<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct RepairSummary {
+    pub ranges: usize,
+    pub repaired_ranges: usize,
+    pub failed_ranges: usize,
+    pub streamed_rows: usize,
+}
+
+impl fmt::Display for RepairSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ranges compared, {} repaired, {} failed, {} rows streamed",
+            self.ranges, self.repaired_ranges, self.failed_ranges, self.streamed_rows
+        )
+    }
+}
+
+impl RepairSession {
+    /// `target` is a keyspace, to repair all its tables, or a single `keyspace.table`
+    pub fn new(target: &str) -> Result<Self, Errors> {
+        let (keyspace, table) = match target.split_once('.') {
+            Some((keyspace, table)) => (keyspace, Some(table)),
+            None => (target, None),
+        };
+        let tables = use_keyspace_meta_data(|handler| {
+            handler.get_tables_from_keyspace(KEYSPACE_METADATA_PATH.to_string(), keyspace)
+        })?;
+        let tables = match table {
+            Some(table) if tables.iter().any(|name| name == table) => vec![table.to_string()],
+            Some(table) => {
+                return Err(Errors::Invalid(format!(
+                    "Table {} does not exist in keyspace {}",
+                    table, keyspace
+                )))
+            }
+            None => tables,
+        };
+        Ok(Self {
+            keyspace: keyspace.to_string(),
+            tables,
+        })
+    }
+
+    /// Repairs range by range reporting the progress to `progress`. A range with a replica that does
+    /// not answer is counted as failed and the rest go on
+    pub fn run<F>(&self, mut progress: F) -> Result<RepairSummary, Errors>
+    where
+        F: FnMut(&str),
+    {
+        let ranges = use_node_meta_data(|handler| {
+            handler.get_own_ranges(&NODES_METADATA_PATH, &self.keyspace)
+        })?;
+        let mut summary = RepairSummary::default();
+        for table in &self.tables {
+            let table = format!("{}.{}", self.keyspace, table);
+            for (i, (range, replicas)) in ranges.iter().enumerate() {
+                summary.ranges += 1;
+                let status = match Self::repair_range(&table, range, replicas) {
+                    Ok((0, _)) => String::from("in sync"),
+                    Ok((sub_ranges, rows)) => {
+                        summary.repaired_ranges += 1;
+                        summary.streamed_rows += rows;
+                        format!("{} sub-ranges differed, {} rows streamed", sub_ranges, rows)
+                    }
+                    Err(e) => {
+                        summary.failed_ranges += 1;
+                        format!("failed: {}", e)
+                    }
+                };
+                progress(&format!(
+                    "[{}] range {}/{} ({}, {}]: {}",
+                    table,
+                    i + 1,
+                    ranges.len(),
+                    range.0,
+                    range.1,
+                    status
+                ));
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Returns how many sub-ranges differed and how many rows were sent
+    fn repair_range(
+        table: &str,
+        range: &TokenRange,
+        replicas: &[NodeIp],
+    ) -> Result<(usize, usize), Errors> {
+        if replicas.len() < 2 {
+            return Ok((0, 0));
+        }
+        let mut trees = Vec::new();
+        for replica in replicas {
+            trees.push(Self::request_tree(replica, table, range)?);
+        }
+        let sub_ranges = differing_ranges(&trees);
+        if sub_ranges.is_empty() {
+            return Ok((0, 0));
+        }
+        let mut replica_rows = Vec::new();
+        for replica in replicas {
+            let message = RepairMessage::RowsRequest {
+                table: table.to_string(),
+                ranges: sub_ranges.clone(),
+            };
+            let RepairMessage::Rows(rows) = Self::send(replica, &message)? else {
+                return Err(Errors::ServerError(String::from("Expected the rows")));
+            };
+            replica_rows.push(rows);
+        }
+        let merged = replica_rows
+            .iter()
+            .cloned()
+            .fold(Vec::new(), RowComparer::compare_response);
+        let mut streamed = 0;
+        for (replica, rows) in replicas.iter().zip(replica_rows.iter()) {
+            let missing = missing_rows(&merged, rows);
+            if missing.is_empty() {
+                continue;
+            }
+            streamed += missing.len();
+            let message = RepairMessage::Stream {
+                table: table.to_string(),
+                rows: missing,
+            };
+            if !matches!(Self::send(replica, &message)?, RepairMessage::Done) {
+                return Err(Errors::ServerError(format!(
+                    "Could not stream the rows to {}",
+                    replica.get_string_ip()
+                )));
+            }
+        }
+        Ok((sub_ranges.len(), streamed))
+    }
+
+    fn request_tree(
+        replica: &NodeIp,
+        table: &str,
+        range: &TokenRange,
+    ) -> Result<MerkleTree, Errors> {
+        let message = RepairMessage::TreeRequest {
+            table: table.to_string(),
+            range: *range,
+            depth: MERKLE_TREE_DEPTH,
+        };
+        match Self::send(replica, &message)? {
+            RepairMessage::Tree(tree) => Ok(tree),
+            _ => Err(Errors::ServerError(String::from("Expected a merkle tree"))),
+        }
+    }
+
+    fn send(replica: &NodeIp, message: &RepairMessage) -> Result<RepairMessage, Errors> {
+        let mut stream = connect_to_socket(replica.get_repair_socket()).map_err(|_| {
+            Errors::ServerError(format!("{} is unreachable", replica.get_string_ip()))
+        })?;
+        write_to_stream(&mut stream, serialize_to_string(message)?.as_bytes())?;
+        let buf = read_exact_from_stream(&mut stream)?;
+        match deserialize_from_slice(buf.as_slice())? {
+            RepairMessage::Failed(e) => Err(Errors::ServerError(format!(
+                "{} failed: {}",
+                replica.get_string_ip(),
+                e
+            ))),
+            response => Ok(response),
+        }
+    }
+}
+
+/// sub-ranges where some replica differs from the first one, sorted and without repetitions
+fn differing_ranges(trees: &[MerkleTree]) -> Vec<TokenRange> {
+    let mut ranges: Vec<TokenRange> = trees
+        .iter()
+        .skip(1)
+        .flat_map(|tree| trees[0].difference(tree))
+        .collect();
+    ranges.sort();
+    ranges.dedup();
+    ranges
+}
+
+/// the merged rows the replica does not have or has with other values
+fn missing_rows(merged: &[Row], replica_rows: &[Row]) -> Vec<Row> {
+    merged
+        .iter()
+        .filter(|row| !replica_rows.contains(row))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_access::column::Column;
+    use crate::parsers::tokens::data_type::DataType;
+    use crate::parsers::tokens::literal::Literal;
+    use crate::repair::merkle_tree::Hash;
+    use crate::utils::types::timestamp::Timestamp;
+
+    fn row(id: &str, name: &str, timestamp: i64) -> Row {
+        let mut row = Row::new(
+            vec![Column {
+                column_name: "name".to_string(),
+                value: Literal::new(name.to_string(), DataType::Text),
+                timestamp: Timestamp::new_from_i64(timestamp),
+            }],
+            vec![id.to_string()],
+        );
+        row.set_timestamp(Timestamp::new_from_i64(timestamp));
+        row
+    }
+
+    fn tree(hashes: &[(i64, u8)]) -> MerkleTree {
+        let hashes: Vec<(i64, Hash)> = hashes
+            .iter()
+            .map(|(token, value)| (*token, [*value; 16]))
+            .collect();
+        MerkleTree::new((0, 400), 2, hashes)
+    }
+
+    #[test]
+    fn test_sub_ranges_where_any_replica_differs() {
+        let trees = [
+            tree(&[(50, 1), (150, 2)]),
+            tree(&[(50, 1), (150, 3)]),
+            tree(&[(50, 1), (150, 3), (350, 4)]),
+        ];
+        assert_eq!(differing_ranges(&trees), vec![(100, 200), (300, 400)]);
+        assert!(differing_ranges(&trees[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_replicas_only_get_the_rows_they_miss() {
+        let first = vec![row("1", "John", 10), row("2", "Jane", 10)];
+        let second = vec![row("1", "Johnny", 20)];
+        let merged = [second.clone(), first.clone()]
+            .into_iter()
+            .fold(Vec::new(), RowComparer::compare_response);
+        let missing = missing_rows(&merged, &first);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].columns[0].value.value, "Johnny");
+        let missing = missing_rows(&merged, &second);
+        assert_eq!(missing, vec![row("2", "Jane", 10)]);
+    }
+}
@@ -1,13 +1,90 @@
 use crate::response_builders::frame_builder::FrameBuilder;
 use crate::utils::errors::Errors;
 use crate::utils::parser_constants::ERROR;
-use crate::utils::types::frame::Frame;
+use crate::utils::types::frame::{Frame, MAX_VERSION, RESPONSE_DIRECTION};
 
 pub struct ErrorBuilder;
 
 impl ErrorBuilder {
     pub fn build_error_frame(request_frame: Frame, error: Errors) -> Result<Frame, Errors> {
-        let error_msg = error.get_bytes_body();
+        let error_msg = Self::for_version(error, request_frame.version).get_bytes_body();
         FrameBuilder::build_response_frame(request_frame, ERROR, error_msg)
     }
+
+    /// Builds the ERROR frame from the header of the request alone, so it also answers
+    /// requests that failed before their frame could be read
+    pub fn build_stream_error_frame(version: u8, stream: i16, error: Errors) -> Frame {
+        let request_version = version & !RESPONSE_DIRECTION;
+        let version = if Frame::is_supported_version(request_version) {
+            request_version
+        } else {
+            MAX_VERSION
+        };
+        let body = Self::for_version(error, version).get_bytes_body();
+        Frame {
+            version: version | RESPONSE_DIRECTION,
+            flags: 0x00,
+            stream,
+            opcode: ERROR,
+            length: body.len() as u32,
+            body,
+        }
+    }
+
+    /// The ReadFailure, WriteFailure and FunctionFailure errors were added in v4,
+    /// a v3 client is sent the equivalent error it knows.
+    fn for_version(error: Errors, version: u8) -> Errors {
+        if version >= 0x04 {
+            return error;
+        }
+        match error {
+            Errors::ReadFailure(msg) => Errors::ReadTimeout(msg),
+            Errors::WriteFailure(msg) => Errors::WriteTimeout(msg),
+            Errors::FunctionFailure(msg) => Errors::Invalid(msg),
+            error => error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parser_constants::QUERY;
+
+    fn request(version: u8) -> Frame {
+        Frame::parse_frame(&[version, 0x00, 0x00, 0x01, QUERY, 0x00, 0x00, 0x00, 0x00]).unwrap()
+    }
+
+    #[test]
+    fn test_failures_by_version() {
+        let error = Errors::WriteFailure(String::from("replica failed"));
+        let frame = ErrorBuilder::build_error_frame(request(0x04), error).unwrap();
+        assert_eq!(frame.body[..2], [0x15, 0x00]);
+        let error = Errors::WriteFailure(String::from("replica failed"));
+        let frame = ErrorBuilder::build_error_frame(request(0x03), error).unwrap();
+        assert_eq!(frame.body[..2], [0x11, 0x00]);
+        let error = Errors::ReadFailure(String::from("replica failed"));
+        let frame = ErrorBuilder::build_error_frame(request(0x03), error).unwrap();
+        assert_eq!(frame.body[..2], [0x12, 0x00]);
+    }
+
+    #[test]
+    fn test_stream_error_frame() {
+        let error = Errors::ServerError(String::from("worker failed"));
+        let frame = ErrorBuilder::build_stream_error_frame(0x04, 7, error);
+        assert_eq!(frame.version, 0x84);
+        assert_eq!(frame.stream, 7);
+        assert_eq!(frame.opcode, ERROR);
+        assert_eq!(frame.body[..2], [0x00, 0x00]);
+        assert_eq!(Frame::parse_frame(&frame.to_bytes()).unwrap(), frame);
+    }
+
+    #[test]
+    fn test_unsupported_version_error() {
+        let frame = request(0x02);
+        let error = frame.validate_request_frame().unwrap_err();
+        let response = ErrorBuilder::build_error_frame(frame, error).unwrap();
+        assert_eq!(response.version, 0x85);
+        assert_eq!(response.body[..2], [0x00, 0x0A]);
+    }
 }
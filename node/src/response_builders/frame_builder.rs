@@ -1,5 +1,10 @@
 use crate::utils::errors::Errors;
-use crate::utils::types::frame::Frame;
+use crate::utils::parser_constants::{EVENT, REGISTER};
+use crate::utils::types::frame::{
+    Frame, CUSTOM_PAYLOAD_FLAG, EVENT_STREAM, MAX_VERSION, RESPONSE_DIRECTION, WARNING_FLAG,
+};
+use crate::utils::types_to_bytes::TypesToBytes;
+use std::collections::HashMap;
 
 pub struct FrameBuilder;
 impl FrameBuilder {
@@ -8,15 +13,78 @@ impl FrameBuilder {
         opcode: u8,
         new_body: Vec<u8>,
     ) -> Result<Frame, Errors> {
-        let response_version = request_frame.version | 0x80;
-        let length = new_body.len() as i32;
+        Self::build_response_frame_with(request_frame, opcode, new_body, &[], &HashMap::new())
+    }
+
+    /// builds an EVENT frame for a connection that uses the given protocol version
+    pub fn build_event_frame(version: u8, body: Vec<u8>) -> Result<Frame, Errors> {
+        let register = Frame {
+            version,
+            flags: 0x00,
+            stream: EVENT_STREAM,
+            opcode: REGISTER,
+            length: 0,
+            body: Vec::new(),
+        };
+        Self::build_response_frame(register, EVENT, body)
+    }
+
+    /// adds the warnings of the request and the custom payload to an already built response
+    pub fn add_warnings_and_payload(
+        response: Frame,
+        warnings: &[String],
+        custom_payload: &HashMap<String, Vec<u8>>,
+    ) -> Result<Frame, Errors> {
+        if warnings.is_empty() && custom_payload.is_empty() {
+            return Ok(response);
+        }
+        let opcode = response.opcode;
+        let body = response.body.clone();
+        Self::build_response_frame_with(response, opcode, body, warnings, custom_payload)
+    }
+
+    /// Builds the response adding the warnings and the custom payload before the body.
+    /// They are only sent from v4 on, in v3 they are dropped.
+    pub fn build_response_frame_with(
+        request_frame: Frame,
+        opcode: u8,
+        new_body: Vec<u8>,
+        warnings: &[String],
+        custom_payload: &HashMap<String, Vec<u8>>,
+    ) -> Result<Frame, Errors> {
+        let request_version = request_frame.version & !RESPONSE_DIRECTION;
+        let version = if Frame::is_supported_version(request_version) {
+            request_version
+        } else {
+            MAX_VERSION
+        };
+        let mut flags = 0x00;
+        let mut body = TypesToBytes::default();
+        if version >= 0x04 && !warnings.is_empty() {
+            flags |= WARNING_FLAG;
+            body.write_short(warnings.len() as u16)?;
+            for warning in warnings {
+                body.write_string(warning)?;
+            }
+        }
+        if version >= 0x04 && !custom_payload.is_empty() {
+            flags |= CUSTOM_PAYLOAD_FLAG;
+            body.write_short(custom_payload.len() as u16)?;
+            for (key, value) in custom_payload {
+                body.write_string(key)?;
+                body.write_int(value.len() as i32)?;
+                body.write_bytes(value);
+            }
+        }
+        body.write_bytes(&new_body);
+        let body = body.into_bytes();
         let mut new_bytes: Vec<u8> = Vec::new();
-        new_bytes.push(response_version);
-        new_bytes.push(request_frame.flags);
+        new_bytes.push(version | RESPONSE_DIRECTION);
+        new_bytes.push(flags);
         new_bytes.extend_from_slice(&request_frame.stream.to_be_bytes());
         new_bytes.push(opcode);
-        new_bytes.extend_from_slice(&length.to_be_bytes());
-        new_bytes.extend(new_body);
+        new_bytes.extend_from_slice(&(body.len() as i32).to_be_bytes());
+        new_bytes.extend(body);
         Frame::parse_frame(&new_bytes)
     }
 }
@@ -25,6 +93,16 @@ impl FrameBuilder {
 mod tests {
     use super::*;
     use crate::utils::parser_constants::{QUERY, RESULT};
+
+    #[test]
+    fn test_build_event_frame() {
+        let frame = FrameBuilder::build_event_frame(0x04, vec![0x00, 0x00]).unwrap();
+        assert_eq!(frame.version, 0x84);
+        assert_eq!(frame.stream, -1);
+        assert_eq!(frame.opcode, EVENT);
+        assert_eq!(frame.length, 2);
+    }
+
     #[test]
     fn test_build_response_frame() {
         let request_bytes = vec![
@@ -45,4 +123,67 @@ mod tests {
             FrameBuilder::build_response_frame(request_frame, RESULT, new_body).unwrap()
         )
     }
+
+    #[test]
+    fn test_response_version() {
+        let request = |version: u8| {
+            Frame::parse_frame(&[version, 0x02, 0x00, 0x01, QUERY, 0x00, 0x00, 0x00, 0x00]).unwrap()
+        };
+        let response = FrameBuilder::build_response_frame(request(0x04), RESULT, vec![]).unwrap();
+        assert_eq!(response.version, 0x84);
+        assert_eq!(response.flags, 0x00);
+        let response = FrameBuilder::build_response_frame(request(0x01), RESULT, vec![]).unwrap();
+        assert_eq!(response.version, 0x85);
+    }
+
+    #[test]
+    fn test_warnings_and_custom_payload() {
+        let request = |version: u8| {
+            Frame::parse_frame(&[version, 0x00, 0x00, 0x01, QUERY, 0x00, 0x00, 0x00, 0x00]).unwrap()
+        };
+        let warnings = vec!["slow".to_string()];
+        let payload = HashMap::from([("k".to_string(), vec![0x01])]);
+        let response = FrameBuilder::build_response_frame_with(
+            request(0x04),
+            RESULT,
+            vec![0xFF],
+            &warnings,
+            &payload,
+        )
+        .unwrap();
+        assert_eq!(response.flags, WARNING_FLAG | CUSTOM_PAYLOAD_FLAG);
+        assert_eq!(
+            response.body,
+            vec![
+                0x00, 0x01, 0x00, 0x04, b's', b'l', b'o', b'w', 0x00, 0x01, 0x00, 0x01, b'k', 0x00,
+                0x00, 0x00, 0x01, 0x01, 0xFF
+            ]
+        );
+        let response = FrameBuilder::build_response_frame_with(
+            request(0x03),
+            RESULT,
+            vec![0xFF],
+            &warnings,
+            &payload,
+        )
+        .unwrap();
+        assert_eq!(response.flags, 0x00);
+        assert_eq!(response.body, vec![0xFF]);
+    }
+
+    #[test]
+    fn test_warnings_added_to_a_built_response() {
+        let request =
+            Frame::parse_frame(&[0x04, 0x00, 0x00, 0x07, QUERY, 0x00, 0x00, 0x00, 0x00]).unwrap();
+        let response = FrameBuilder::build_response_frame(request, RESULT, vec![0xFF]).unwrap();
+        let same = FrameBuilder::add_warnings_and_payload(response.clone(), &[], &HashMap::new());
+        assert_eq!(same.unwrap(), response);
+        let warnings = vec!["Hint stored for 10.0.0.3".to_string()];
+        let response =
+            FrameBuilder::add_warnings_and_payload(response, &warnings, &HashMap::new()).unwrap();
+        assert_eq!(response.version, 0x84);
+        assert_eq!(response.stream, 0x07);
+        assert_eq!(response.flags, WARNING_FLAG);
+        assert_eq!(response.body.last(), Some(&0xFF));
+    }
 }
@@ -0,0 +1,183 @@
+use crate::meta_data::clients::meta_data_client::ClientMetaDataAcces;
+use crate::response_builders::error_builder::ErrorBuilder;
+use crate::utils::errors::Errors;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// Runs the requests of a connection in parallel.
+///
+/// Each request runs in its own worker and its response comes back through a channel as soon
+/// as it finishes, so responses go out in the order they complete and the client matches them
+/// by the stream id of the frame. The number of requests in flight per connection is bounded.
+///
+/// A request whose worker fails or panics is answered with a ServerError for its stream, the
+/// client is never left waiting for it.
+pub struct StreamDispatcher {
+    connection_id: String,
+    in_flight: usize,
+    max_in_flight: usize,
+    sender: Sender<Vec<u8>>,
+    receiver: Receiver<Vec<u8>>,
+}
+
+impl StreamDispatcher {
+    pub fn new(max_in_flight: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            connection_id: ClientMetaDataAcces::connection_id(),
+            in_flight: 0,
+            max_in_flight,
+            sender,
+            receiver,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.in_flight >= self.max_in_flight
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+    }
+
+    /// runs the request of the given version and stream in a worker that shares the client
+    /// state of the connection
+    pub fn dispatch<F>(&mut self, version: u8, stream: i16, request: F)
+    where
+        F: FnOnce() -> Result<Vec<u8>, Errors> + Send + 'static,
+    {
+        self.in_flight += 1;
+        let connection_id = self.connection_id.clone();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            ClientMetaDataAcces::set_connection_id(&connection_id);
+            let response = match panic::catch_unwind(AssertUnwindSafe(request)) {
+                Ok(Ok(response)) => response,
+                Ok(Err(error)) => error_response(version, stream, error),
+                Err(panic) => error_response(version, stream, panic_error(panic)),
+            };
+            let _ = sender.send(response);
+        });
+    }
+
+    /// waits up to `timeout` for a request to finish and returns every response already available
+    pub fn responses(&mut self, timeout: Duration) -> Vec<Vec<u8>> {
+        let mut responses = Vec::new();
+        if self.in_flight == 0 {
+            return responses;
+        }
+        match self.receiver.recv_timeout(timeout) {
+            Ok(response) => responses.push(response),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => {
+                return responses
+            }
+        }
+        while let Ok(response) = self.receiver.try_recv() {
+            responses.push(response);
+        }
+        self.in_flight -= responses.len();
+        responses
+    }
+}
+
+fn error_response(version: u8, stream: i16, error: Errors) -> Vec<u8> {
+    ErrorBuilder::build_stream_error_frame(version, stream, error).to_bytes()
+}
+
+fn panic_error(panic: Box<dyn Any + Send>) -> Errors {
+    let message = if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown cause")
+    };
+    Errors::ServerError(format!("Request failed: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response_builders::frame_builder::FrameBuilder;
+    use crate::utils::parser_constants::{ERROR, QUERY, RESULT};
+    use crate::utils::types::frame::Frame;
+    use std::time::Instant;
+
+    fn response(stream: i16, delay: Duration) -> impl FnOnce() -> Result<Vec<u8>, Errors> {
+        move || {
+            thread::sleep(delay);
+            let mut request = vec![0x04, 0x00];
+            request.extend_from_slice(&stream.to_be_bytes());
+            request.extend_from_slice(&[QUERY, 0x00, 0x00, 0x00, 0x00]);
+            let request = Frame::parse_frame(&request).unwrap();
+            Ok(FrameBuilder::build_response_frame(request, RESULT, vec![])?.to_bytes())
+        }
+    }
+
+    fn wait_responses(dispatcher: &mut StreamDispatcher, quantity: usize) -> Vec<i16> {
+        let start = Instant::now();
+        let mut streams = Vec::new();
+        while streams.len() < quantity && start.elapsed() < Duration::from_secs(5) {
+            for bytes in dispatcher.responses(Duration::from_millis(10)) {
+                streams.push(Frame::parse_frame(&bytes).unwrap().stream);
+            }
+        }
+        streams
+    }
+
+    #[test]
+    fn test_later_request_completes_first() {
+        let mut dispatcher = StreamDispatcher::new(8);
+        dispatcher.dispatch(0x04, 1, response(1, Duration::from_millis(300)));
+        dispatcher.dispatch(0x04, 2, response(2, Duration::ZERO));
+        assert_eq!(dispatcher.in_flight(), 2);
+        assert_eq!(wait_responses(&mut dispatcher, 2), vec![2, 1]);
+        assert_eq!(dispatcher.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_in_flight_requests_are_bounded() {
+        let mut dispatcher = StreamDispatcher::new(1);
+        assert!(!dispatcher.is_full());
+        dispatcher.dispatch(0x04, 1, response(1, Duration::from_millis(50)));
+        assert!(dispatcher.is_full());
+        assert_eq!(wait_responses(&mut dispatcher, 1), vec![1]);
+        assert!(!dispatcher.is_full());
+    }
+
+    fn wait_error(dispatcher: &mut StreamDispatcher) -> Frame {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            if let Some(bytes) = dispatcher.responses(Duration::from_millis(10)).pop() {
+                return Frame::parse_frame(&bytes).unwrap();
+            }
+        }
+        panic!("the failed request was not answered")
+    }
+
+    #[test]
+    fn test_panicking_request_gets_an_error_frame() {
+        let mut dispatcher = StreamDispatcher::new(1);
+        dispatcher.dispatch(0x04, 9, || panic!("worker failed"));
+        let response = wait_error(&mut dispatcher);
+        assert_eq!(response.version, 0x84);
+        assert_eq!(response.stream, 9);
+        assert_eq!(response.opcode, ERROR);
+        assert!(!dispatcher.is_full());
+    }
+
+    #[test]
+    fn test_failed_request_gets_an_error_frame() {
+        let mut dispatcher = StreamDispatcher::new(1);
+        dispatcher.dispatch(0x03, 5, || {
+            Err(Errors::ServerError(String::from("worker failed")))
+        });
+        let response = wait_error(&mut dispatcher);
+        assert_eq!(response.version, 0x83);
+        assert_eq!(response.stream, 5);
+        assert_eq!(response.opcode, ERROR);
+    }
+}
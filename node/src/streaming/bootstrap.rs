@@ -0,0 +1,136 @@
+use crate::data_access::data_access_handler::use_data_access;
+use crate::gossip::schema_agreement;
+use crate::logger::Logger;
+use crate::meta_data::meta_data_handler::{use_keyspace_meta_data, use_node_meta_data};
+use crate::partitioner::TokenRange;
+use crate::streaming::node_removal::NodeRemoval;
+use crate::streaming::streamer::Streamer;
+use crate::utils::config_constants::{BOOTING_TIMEOUT_SECS, BOOTSTRAP_MAX_ATTEMPTS};
+use crate::utils::constants::{KEYSPACE_METADATA_PATH, LOGGER_PATH, NODES_METADATA_PATH};
+use crate::utils::errors::Errors;
+use crate::utils::types::node_ip::NodeIp;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// Entry of a new node into the ring.
+///
+/// While it is `Booting` it owns no range: it fetches from the current replicas the rows
+/// of the ranges it is going to gain and only then becomes `Active`. What is written in the
+/// meantime reaches it anyway, because the writes also go to the nodes that are starting.
+///
+/// A node that replaces a down one starts with its tokens and fetches its ranges from the other
+/// replicas; when it finishes it announces that the down one left the cluster
+pub struct Bootstrap;
+
+/// a range to fetch and the replicas that have it, in the order they are asked
+type PendingRange = (TokenRange, Vec<NodeIp>);
+
+impl Bootstrap {
+    /// If some range could not be fetched it is tried again every `BOOTING_TIMEOUT_SECS`, up to
+    /// `BOOTSTRAP_MAX_ATTEMPTS` times. After that the error is returned and the node does not start
+    pub fn join(seed: &NodeIp, replacing: Option<&NodeIp>) -> Result<(), Errors> {
+        let logger = Logger::new(&LOGGER_PATH);
+        let mut attempt = 1;
+        let streamed = loop {
+            match Self::stream_ranges(seed) {
+                Ok(streamed) => break streamed,
+                Err(e) if attempt < BOOTSTRAP_MAX_ATTEMPTS => {
+                    logger.log_error(&format!(
+                        "Bootstrap attempt {} failed, retrying: {}",
+                        attempt, e
+                    ));
+                    attempt += 1;
+                    sleep(Duration::from_secs(BOOTING_TIMEOUT_SECS));
+                }
+                Err(e) => {
+                    logger.log_error(&format!(
+                        "Bootstrap failed after {} attempts: {}",
+                        attempt, e
+                    ));
+                    return Err(e);
+                }
+            }
+        };
+        use_node_meta_data(|handler| handler.set_own_node_active(&NODES_METADATA_PATH))?;
+        // already active, the tokens belong to this node even if the replaced one is still in the ring
+        if let Some(replaced) = replacing {
+            NodeRemoval::announce(replaced)?;
+        }
+        logger.log_message(&format!("Booting Finished, {} rows streamed", streamed));
+        Ok(())
+    }
+
+    fn stream_ranges(seed: &NodeIp) -> Result<usize, Errors> {
+        // the tables have to exist before storing what arrives
+        schema_agreement::pull_schema(seed)?;
+        let keyspaces = use_keyspace_meta_data(|handler| {
+            handler.get_keyspaces_names(KEYSPACE_METADATA_PATH.to_string())
+        })?;
+        let mut streamed = 0;
+        for keyspace in keyspaces {
+            let ranges = use_node_meta_data(|handler| {
+                handler.get_bootstrap_ranges(&NODES_METADATA_PATH, &keyspace)
+            })?;
+            let tables = use_keyspace_meta_data(|handler| {
+                handler.get_tables_from_keyspace(KEYSPACE_METADATA_PATH.to_string(), &keyspace)
+            })?;
+            for table in tables {
+                let table = format!("{}.{}", keyspace, table);
+                streamed += Self::stream_table(&table, sources_by_liveness(&ranges))?;
+            }
+        }
+        Ok(streamed)
+    }
+
+    /// Each replica is asked at once for every range it is the first option for. The
+    /// ranges of a replica that fails are asked to the next one
+    fn stream_table(table: &str, mut pending: Vec<PendingRange>) -> Result<usize, Errors> {
+        let mut streamed = 0;
+        while let Some((range, sources)) = pending.first() {
+            let Some(source) = sources.first().cloned() else {
+                return Err(Errors::UnavailableException(format!(
+                    "No replica could stream the range ({}, {}] of {}",
+                    range.0, range.1, table
+                )));
+            };
+            let (batch, rest): (Vec<PendingRange>, Vec<PendingRange>) = pending
+                .into_iter()
+                .partition(|(_, sources)| sources.first() == Some(&source));
+            pending = rest;
+            let ranges = batch.iter().map(|(range, _)| *range).collect();
+            match Self::stream_from(&source, table, ranges) {
+                Ok(rows) => streamed += rows,
+                Err(e) => {
+                    Logger::new(&LOGGER_PATH).log_error(&format!(
+                        "Could not stream {} from {}: {}",
+                        table,
+                        source.get_string_ip(),
+                        e
+                    ));
+                    pending.extend(
+                        batch
+                            .into_iter()
+                            .map(|(range, sources)| (range, sources[1..].to_vec())),
+                    );
+                }
+            }
+        }
+        Ok(streamed)
+    }
+
+    /// stores the rows merging them with what was already written here
+    fn stream_from(source: &NodeIp, table: &str, ranges: Vec<TokenRange>) -> Result<usize, Errors> {
+        let rows = Streamer::fetch(source, table, ranges)?;
+        let streamed = rows.len();
+        use_data_access(|data_access| data_access.merge_rows(&table.to_string(), rows))?;
+        Ok(streamed)
+    }
+}
+
+/// the replicas of each range with the ones that are up first
+fn sources_by_liveness(ranges: &[PendingRange]) -> Vec<PendingRange> {
+    ranges
+        .iter()
+        .map(|(range, sources)| (*range, Streamer::by_liveness(sources)))
+        .collect()
+}
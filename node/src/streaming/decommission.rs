@@ -0,0 +1,95 @@
+use crate::gossip::gossip_emitter::GossipEmitter;
+use crate::meta_data::meta_data_handler::{use_keyspace_meta_data, use_node_meta_data};
+use crate::meta_data::nodes::node::State;
+use crate::redistribution::message_sender::MessageSender;
+use crate::streaming::streamer::{ranges_by_target, Streamer};
+use crate::utils::config_constants::{RING_DELAY_MILLIS, SHUTTING_DOWN_TIMEOUT_SECS};
+use crate::utils::constants::{KEYSPACE_METADATA_PATH, NODES_METADATA_PATH};
+use crate::utils::errors::Errors;
+use crate::utils::functions::get_rows_in_ranges;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// Orderly exit of this node from the ring.
+///
+/// It becomes `Leaving`, so the writes also reach the nodes that take its ranges,
+/// sends them all its rows and only once they acknowledged them becomes
+/// `ShuttingDown`. If something fails it goes back to `Active` and stays a replica
+pub struct Decommission;
+
+impl Decommission {
+    pub fn run<F>(mut progress: F) -> Result<(), Errors>
+    where
+        F: FnMut(&str),
+    {
+        let cluster = use_node_meta_data(|handler| handler.get_cluster(&NODES_METADATA_PATH))?;
+        if cluster.get_own_node().state != State::Active {
+            return Err(Errors::Invalid(String::from(
+                "Only an active node can be decommissioned",
+            )));
+        }
+        Self::set_own_state(State::Leaving)?;
+        // the rest have to see it leaving before the rows are sent, otherwise some write might
+        // not reach the node that takes over the range
+        sleep(Duration::from_millis(RING_DELAY_MILLIS));
+        match Self::stream_ranges(&mut progress) {
+            Ok(streamed) => progress(&format!("{} rows streamed", streamed)),
+            Err(e) => {
+                Self::set_own_state(State::Active)?;
+                return Err(e);
+            }
+        }
+        use_node_meta_data(|handler| {
+            handler.set_own_node_to_shutting_down(&NODES_METADATA_PATH)?;
+            handler.update_ranges(&NODES_METADATA_PATH)
+        })?;
+        GossipEmitter::publish_own_state()?;
+        // gossip keeps running until everyone sees it left
+        sleep(Duration::from_secs(SHUTTING_DOWN_TIMEOUT_SECS as u64));
+        MessageSender::send_drop_keyspace()
+    }
+
+    fn set_own_state(state: State) -> Result<(), Errors> {
+        use_node_meta_data(|handler| handler.set_own_state(&NODES_METADATA_PATH, state))?;
+        GossipEmitter::publish_own_state()
+    }
+
+    /// each node that gains ranges of this one is sent the rows of all of them at once
+    fn stream_ranges<F>(progress: &mut F) -> Result<usize, Errors>
+    where
+        F: FnMut(&str),
+    {
+        let own_ip = use_node_meta_data(|handler| handler.get_own_ip(&NODES_METADATA_PATH))?;
+        let keyspaces = use_keyspace_meta_data(|handler| {
+            handler.get_keyspaces_names(KEYSPACE_METADATA_PATH.to_string())
+        })?;
+        let mut streamed = 0;
+        for keyspace in keyspaces {
+            let movements: Vec<_> = use_node_meta_data(|handler| {
+                handler.get_range_movements(&NODES_METADATA_PATH, &keyspace, None)
+            })?
+            .into_iter()
+            .filter(|movement| movement.from.contains(&own_ip))
+            .collect();
+            let targets = ranges_by_target(&movements);
+            let tables = use_keyspace_meta_data(|handler| {
+                handler.get_tables_from_keyspace(KEYSPACE_METADATA_PATH.to_string(), &keyspace)
+            })?;
+            for table in tables {
+                let table = format!("{}.{}", keyspace, table);
+                for (target, ranges) in &targets {
+                    let rows = get_rows_in_ranges(&table, ranges)?;
+                    Streamer::push(target, &table, &rows)?;
+                    streamed += rows.len();
+                    progress(&format!(
+                        "[{}] {} rows streamed to {}",
+                        table,
+                        rows.len(),
+                        target.get_string_ip()
+                    ));
+                }
+            }
+        }
+        Ok(streamed)
+    }
+}
@@ -0,0 +1,6 @@
+pub mod bootstrap;
+pub mod decommission;
+pub mod node_removal;
+pub mod stream_listener;
+pub mod stream_message;
+pub mod streamer;
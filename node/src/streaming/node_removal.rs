@@ -0,0 +1,117 @@
+use crate::gossip::failure_detector;
+use crate::gossip::gossiper::use_gossiper;
+use crate::meta_data::meta_data_handler::{use_keyspace_meta_data, use_node_meta_data};
+use crate::meta_data::nodes::node::State;
+use crate::streaming::streamer::Streamer;
+use crate::utils::constants::{KEYSPACE_METADATA_PATH, NODES_METADATA_PATH};
+use crate::utils::errors::Errors;
+use crate::utils::types::node_ip::NodeIp;
+
+/// Removes from the cluster a down node that is not coming back.
+///
+/// Its ranges are fetched from the replicas that are still up and sent to the nodes that
+/// take them; only then is it announced through gossip that the node left
+pub struct NodeRemoval;
+
+impl NodeRemoval {
+    pub fn run<F>(removed: &NodeIp, mut progress: F) -> Result<(), Errors>
+    where
+        F: FnMut(&str),
+    {
+        Self::check_removable(removed)?;
+        let streamed = Self::stream_ranges(removed, &mut progress)?;
+        progress(&format!("{} rows streamed", streamed));
+        Self::announce(removed)
+    }
+
+    /// publishes that the node left the cluster and removes it from the local ring
+    pub fn announce(removed: &NodeIp) -> Result<(), Errors> {
+        use_gossiper(|gossiper| gossiper.announce_removed(removed))??;
+        use_node_meta_data(|handler| {
+            handler.set_shutting_down(&NODES_METADATA_PATH, removed)?;
+            handler.update_ranges(&NODES_METADATA_PATH)
+        })
+    }
+
+    /// a node is only removed by another one while it is down, one that is up leaves with `decommission`
+    fn check_removable(removed: &NodeIp) -> Result<(), Errors> {
+        let cluster = use_node_meta_data(|handler| handler.get_cluster(&NODES_METADATA_PATH))?;
+        if cluster.get_own_ip() == removed {
+            return Err(Errors::Invalid(String::from(
+                "A node cannot remove itself, use decommission instead",
+            )));
+        }
+        let Some(node) = cluster
+            .get_other_nodes()
+            .iter()
+            .find(|node| node.get_ip() == removed)
+        else {
+            return Err(Errors::Invalid(format!(
+                "{} is not part of the cluster",
+                removed.get_string_ip()
+            )));
+        };
+        if node.state != State::Inactive && failure_detector::is_alive(removed) {
+            return Err(Errors::Invalid(format!(
+                "{} is up, use decommission on it instead",
+                removed.get_string_ip()
+            )));
+        }
+        Ok(())
+    }
+
+    /// each range that moves is fetched from one of the remaining replicas, the ones that are up first
+    fn stream_ranges<F>(removed: &NodeIp, progress: &mut F) -> Result<usize, Errors>
+    where
+        F: FnMut(&str),
+    {
+        let keyspaces = use_keyspace_meta_data(|handler| {
+            handler.get_keyspaces_names(KEYSPACE_METADATA_PATH.to_string())
+        })?;
+        let mut streamed = 0;
+        for keyspace in keyspaces {
+            let movements = use_node_meta_data(|handler| {
+                handler.get_range_movements(&NODES_METADATA_PATH, &keyspace, Some(removed))
+            })?;
+            let tables = use_keyspace_meta_data(|handler| {
+                handler.get_tables_from_keyspace(KEYSPACE_METADATA_PATH.to_string(), &keyspace)
+            })?;
+            for table in tables {
+                let table = format!("{}.{}", keyspace, table);
+                for movement in &movements {
+                    let survivors: Vec<NodeIp> = movement
+                        .from
+                        .iter()
+                        .filter(|ip| *ip != removed)
+                        .cloned()
+                        .collect();
+                    if survivors.is_empty() {
+                        // the only one that had it is the node being removed, there is nowhere to fetch it from
+                        progress(&format!(
+                            "[{}] range ({}, {}]: no replica left, its rows are lost",
+                            table, movement.range.0, movement.range.1
+                        ));
+                        continue;
+                    }
+                    let rows = Streamer::fetch_from_any(
+                        &Streamer::by_liveness(&survivors),
+                        &table,
+                        vec![movement.range],
+                    )?;
+                    for target in &movement.gaining {
+                        Streamer::push(target, &table, &rows)?;
+                    }
+                    streamed += rows.len();
+                    progress(&format!(
+                        "[{}] range ({}, {}]: {} rows re-replicated",
+                        table,
+                        movement.range.0,
+                        movement.range.1,
+                        rows.len()
+                    ));
+                }
+            }
+        }
+        Ok(streamed)
+    }
+}
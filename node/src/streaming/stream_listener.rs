@@ -0,0 +1,48 @@
+use crate::data_access::data_access_handler::use_data_access;
+use crate::streaming::stream_message::StreamMessage;
+use crate::utils::config_constants::STREAMING_CHUNK_ROWS;
+use crate::utils::errors::Errors;
+use crate::utils::functions::{
+    deserialize_from_slice, get_rows_in_ranges, read_exact_from_stream, serialize_to_string,
+    start_listener, write_to_stream,
+};
+use crate::utils::types::node_ip::NodeIp;
+use std::net::TcpStream;
+
+/// Sends the rows requested by the nodes that gain ranges and stores the ones sent to it
+pub struct StreamListener;
+
+impl StreamListener {
+    pub fn start_listening(ip: NodeIp) -> Result<(), Errors> {
+        start_listener(ip.get_streaming_socket(), Self::handle_connection)
+    }
+
+    fn handle_connection(stream: &mut TcpStream) -> Result<(), Errors> {
+        let buf = read_exact_from_stream(stream)?;
+        match deserialize_from_slice(buf.as_slice())? {
+            StreamMessage::Request { table, ranges } => match get_rows_in_ranges(&table, &ranges) {
+                Ok(rows) => {
+                    for chunk in rows.chunks(STREAMING_CHUNK_ROWS) {
+                        Self::reply(stream, &StreamMessage::Rows(chunk.to_vec()))?;
+                    }
+                    Self::reply(stream, &StreamMessage::Complete)
+                }
+                Err(e) => Self::reply(stream, &StreamMessage::Failed(e.to_string())),
+            },
+            StreamMessage::Push { table, rows } => {
+                match use_data_access(|data_access| data_access.merge_rows(&table, rows)) {
+                    Ok(()) => Self::reply(stream, &StreamMessage::Complete),
+                    Err(e) => Self::reply(stream, &StreamMessage::Failed(e.to_string())),
+                }
+            }
+            _ => Self::reply(
+                stream,
+                &StreamMessage::Failed(String::from("Expected a stream request")),
+            ),
+        }
+    }
+
+    fn reply(stream: &mut TcpStream, message: &StreamMessage) -> Result<(), Errors> {
+        write_to_stream(stream, serialize_to_string(message)?.as_bytes())
+    }
+}
@@ -0,0 +1,24 @@
+use crate::data_access::row::Row;
+use crate::partitioner::TokenRange;
+use serde::{Deserialize, Serialize};
+
+/// Messages of a streaming session.
+///
+/// To fetch data a `Request` is sent per table with the missing ranges and the replica
+/// answers with the rows in batches of `Rows` of at most `STREAMING_CHUNK_ROWS`, closing with
+/// `Complete`. To send them, each batch goes in a `Push` that is acknowledged with `Complete` once
+/// stored. If something fails the answer is `Failed`
+#[derive(Serialize, Deserialize, Debug)]
+pub enum StreamMessage {
+    Request {
+        table: String,
+        ranges: Vec<TokenRange>,
+    },
+    Rows(Vec<Row>),
+    Push {
+        table: String,
+        rows: Vec<Row>,
+    },
+    Complete,
+    Failed(String),
+}
@@ -0,0 +1,137 @@
+use crate::data_access::row::Row;
+use crate::gossip::failure_detector;
+use crate::meta_data::nodes::node_meta_data_acces::RangeMovement;
+use crate::partitioner::TokenRange;
+use crate::streaming::stream_message::StreamMessage;
+use crate::utils::config_constants::STREAMING_CHUNK_ROWS;
+use crate::utils::errors::Errors;
+use crate::utils::functions::{
+    connect_to_socket, deserialize_from_slice, read_exact_from_stream, serialize_to_string,
+    write_to_stream,
+};
+use crate::utils::types::node_ip::NodeIp;
+use std::collections::BTreeMap;
+use std::net::TcpStream;
+
+/// Side that starts the streaming sessions against the `StreamListener` of another node
+pub struct Streamer;
+
+impl Streamer {
+    /// fetches the rows of the table the node has in the ranges
+    pub fn fetch(
+        source: &NodeIp,
+        table: &str,
+        ranges: Vec<TokenRange>,
+    ) -> Result<Vec<Row>, Errors> {
+        let mut stream = connect_to_socket(source.get_streaming_socket())?;
+        let request = StreamMessage::Request {
+            table: table.to_string(),
+            ranges,
+        };
+        write_to_stream(&mut stream, serialize_to_string(&request)?.as_bytes())?;
+        let mut rows = Vec::new();
+        loop {
+            match Self::read_message(&mut stream)? {
+                StreamMessage::Rows(chunk) => rows.extend(chunk),
+                StreamMessage::Complete => return Ok(rows),
+                StreamMessage::Failed(e) => return Err(Errors::ServerError(e)),
+                _ => return Err(Errors::ServerError(String::from("Expected streamed rows"))),
+            }
+        }
+    }
+
+    /// each replica is asked in order until one of them sends them
+    pub fn fetch_from_any(
+        sources: &[NodeIp],
+        table: &str,
+        ranges: Vec<TokenRange>,
+    ) -> Result<Vec<Row>, Errors> {
+        let mut errors = Vec::new();
+        for source in sources {
+            match Self::fetch(source, table, ranges.clone()) {
+                Ok(rows) => return Ok(rows),
+                Err(e) => errors.push(format!("{}: {}", source.get_string_ip(), e)),
+            }
+        }
+        Err(Errors::UnavailableException(format!(
+            "No replica could stream {} ({})",
+            table,
+            errors.join(", ")
+        )))
+    }
+
+    /// Sends the rows in batches, each one in its own connection. It only succeeds if the node
+    /// acknowledged that it stored all of them
+    pub fn push(target: &NodeIp, table: &str, rows: &[Row]) -> Result<(), Errors> {
+        for chunk in rows.chunks(STREAMING_CHUNK_ROWS) {
+            let mut stream = connect_to_socket(target.get_streaming_socket())?;
+            let push = StreamMessage::Push {
+                table: table.to_string(),
+                rows: chunk.to_vec(),
+            };
+            write_to_stream(&mut stream, serialize_to_string(&push)?.as_bytes())?;
+            match Self::read_message(&mut stream)? {
+                StreamMessage::Complete => {}
+                StreamMessage::Failed(e) => return Err(Errors::ServerError(e)),
+                _ => {
+                    return Err(Errors::ServerError(String::from(
+                        "Expected the streamed rows to be acknowledged",
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// the nodes ordered with the ones that are up first
+    pub fn by_liveness(nodes: &[NodeIp]) -> Vec<NodeIp> {
+        let mut nodes = nodes.to_vec();
+        nodes.sort_by_key(|ip| !failure_detector::is_alive(ip));
+        nodes
+    }
+
+    fn read_message(stream: &mut TcpStream) -> Result<StreamMessage, Errors> {
+        let buf = read_exact_from_stream(stream)?;
+        deserialize_from_slice(buf.as_slice())
+    }
+}
+
+/// the ranges that each node gains, so every node gets all of them in one go
+pub fn ranges_by_target(movements: &[RangeMovement]) -> BTreeMap<NodeIp, Vec<TokenRange>> {
+    let mut targets: BTreeMap<NodeIp, Vec<TokenRange>> = BTreeMap::new();
+    for movement in movements {
+        for ip in &movement.gaining {
+            targets.entry(ip.clone()).or_default().push(movement.range);
+        }
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(last_byte: u8) -> NodeIp {
+        NodeIp::new_from_single_string(&format!("127.0.0.{}:9042", last_byte)).unwrap()
+    }
+
+    #[test]
+    fn test_each_target_gets_all_the_ranges_it_gains() {
+        let movements = vec![
+            RangeMovement {
+                range: (0, 100),
+                from: vec![ip(1)],
+                gaining: vec![ip(2), ip(3)],
+            },
+            RangeMovement {
+                range: (100, 200),
+                from: vec![ip(1)],
+                gaining: vec![ip(3)],
+            },
+        ];
+        let targets = ranges_by_target(&movements);
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[&ip(2)], vec![(0, 100)]);
+        assert_eq!(targets[&ip(3)], vec![(0, 100), (100, 200)]);
+    }
+}
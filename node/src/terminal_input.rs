@@ -1,14 +1,16 @@
+use crate::hinted_handoff::hint_metrics;
 use crate::meta_data::meta_data_handler::use_node_meta_data;
 use crate::meta_data::nodes::node::State;
-use crate::utils::config_constants::SHUTTING_DOWN_TIMEOUT_SECS;
 use crate::utils::constants::NODES_METADATA_PATH;
 use crate::utils::errors::Errors;
+use crate::utils::types::node_ip::NodeIp;
 use rustls::lock::Mutex;
 use std::io::Write;
 use std::sync::Arc;
-use std::thread::sleep;
 use std::{io, thread};
-use crate::redistribution::message_sender::MessageSender;
+use crate::repair::repair_session::RepairSession;
+use crate::streaming::decommission::Decommission;
+use crate::streaming::node_removal::NodeRemoval;
 
 pub struct TerminalInput {
     file: Option<String>,
@@ -53,11 +55,14 @@ impl TerminalInput {
 
         match command {
             "set_file" => self.set_file(argument),
-            "exit" => Self::exit(),
+            "decommission" | "exit" => Self::decommission(),
+            "removenode" => self.remove_node(argument),
             "pause" => Self::pause(),
             "resume" => Self::resume(),
             "state" => self.state(),
             "states" => Self::states(),
+            "repair" => self.repair(argument),
+            "hints" => self.hints(),
             _ => Err(Errors::Invalid(String::from("Invalid input. Try again."))),
         }
     }
@@ -84,29 +89,23 @@ impl TerminalInput {
         }
     }
 
-    fn exit() -> Result<(), Errors> {
-        println!("Shutting down...");
-        use_node_meta_data(|handler| {
-            handler.set_own_node_to_shutting_down(NODES_METADATA_PATH)?;
-            handler.update_ranges(NODES_METADATA_PATH)
-        })?;
-        MessageSender::redistribute()?;
-        MessageSender::send_drop_keyspace()?;
-        sleep(std::time::Duration::from_secs(
-            SHUTTING_DOWN_TIMEOUT_SECS as u64,
-        ));
+    /// Sends its data to the nodes that take its ranges and only then leaves. If it could not
+    /// be sent the node stays active
+    fn decommission() -> Result<(), Errors> {
+        println!("Decommissioning...");
+        Decommission::run(|progress| println!("{}", progress))?;
         std::process::exit(0);
     }
 
     fn pause() -> Result<(), Errors> {
         use_node_meta_data(|handler| {
             if handler
-                .get_cluster(NODES_METADATA_PATH)?
+                .get_cluster(&NODES_METADATA_PATH)?
                 .get_own_node()
                 .state
                 == State::Active
             {
-                handler.set_own_state(NODES_METADATA_PATH, State::StandBy)?
+                handler.set_own_state(&NODES_METADATA_PATH, State::StandBy)?
             }
             Ok(())
         })
@@ -115,12 +114,12 @@ impl TerminalInput {
     fn resume() -> Result<(), Errors> {
         use_node_meta_data(|handler| {
             if handler
-                .get_cluster(NODES_METADATA_PATH)?
+                .get_cluster(&NODES_METADATA_PATH)?
                 .get_own_node()
                 .state
                 == State::StandBy
             {
-                handler.set_own_state(NODES_METADATA_PATH, State::Active)?
+                handler.set_own_state(&NODES_METADATA_PATH, State::Active)?
             }
             Ok(())
         })
@@ -129,7 +128,7 @@ impl TerminalInput {
     fn state(&self) -> Result<(), Errors> {
         use_node_meta_data(|handler| {
             let state = handler
-                .get_cluster(NODES_METADATA_PATH)?
+                .get_cluster(&NODES_METADATA_PATH)?
                 .get_own_node()
                 .state
                 .to_string();
@@ -141,4 +140,38 @@ impl TerminalInput {
     fn states() -> Result<(), Errors> {
         Ok(())
     }
+
+    /// Repairs a keyspace or a single table with the rest of its replicas. The progress of each range
+    /// is shown in the console and the summary goes where the rest of the commands print
+    fn repair(&self, argument: Option<&str>) -> Result<(), Errors> {
+        let Some(target) = argument else {
+            return Err(Errors::Invalid(String::from(
+                "No keyspace provided. Usage: repair <keyspace>[.<table>]",
+            )));
+        };
+        let session = RepairSession::new(target.trim())?;
+        let summary = session.run(|progress| println!("{}", progress))?;
+        self.print(&format!("Repair of {} finished: {}", target.trim(), summary));
+        Ok(())
+    }
+
+    /// Removes a down node from the cluster, replicating its ranges again from the remaining
+    /// replicas
+    fn remove_node(&self, argument: Option<&str>) -> Result<(), Errors> {
+        let Some(ip) = argument else {
+            return Err(Errors::Invalid(String::from(
+                "No node provided. Usage: removenode <ip>:<port>",
+            )));
+        };
+        let removed = NodeIp::new_from_single_string(ip.trim())?;
+        NodeRemoval::run(&removed, |progress| println!("{}", progress))?;
+        self.print(&format!("{} removed from the cluster", removed.get_string_ip()));
+        Ok(())
+    }
+
+    /// counters of the hints since the node started
+    fn hints(&self) -> Result<(), Errors> {
+        self.print(&format!("Hints: {}", hint_metrics::snapshot()));
+        Ok(())
+    }
 }
@@ -1,12 +1,16 @@
 #[cfg(test)]
 mod tests {
+    use crate::executables::prepared_statement::PreparedStatement;
     use crate::meta_data::meta_data_handler::{use_client_meta_data, use_keyspace_meta_data};
     use crate::parsers::query_parser::{query_lexer, query_parser};
     use crate::utils::constants::CLIENT_METADATA_PATH;
+    use crate::utils::errors::Errors;
+    use crate::utils::response::Response;
     use crate::utils::test_functions::{
-        add_one_finished, check_and_run_teardown, get_query_result, setup,
+        add_one_finished, check_and_run_teardown, get_query_result, get_rows_select, setup,
     };
     use crate::utils::types::bytes_cursor::BytesCursor;
+    use crate::utils::types::query_parameters::{BoundValue, BoundValues};
 
     #[test]
     fn use_valid_keyspace() {
@@ -241,4 +245,295 @@ mod tests {
         add_one_finished();
         check_and_run_teardown();
     }
+
+    #[test]
+    fn user_defined_type_column() {
+        setup();
+
+        let result = get_query_result("CREATE TYPE test.coordinates (lat decimal, lon decimal)");
+        assert!(result.is_ok());
+        let result = get_query_result(
+            "CREATE TABLE test.positions (id int, position frozen<coordinates>, PRIMARY KEY (id))",
+        );
+        assert!(result.is_ok());
+
+        let result = get_query_result(
+            "INSERT INTO test.positions (id, position) VALUES (1, {lat: 1.5, lon: -2.5})",
+        );
+        assert!(result.is_ok());
+        let result = get_query_result(
+            "INSERT INTO test.positions (id, position) VALUES (2, {lat: 1.5, alt: 2.5})",
+        );
+        assert!(result.is_err());
+
+        let result =
+            get_query_result("SELECT position.lat, position FROM test.positions WHERE id = 1");
+        let rows = get_rows_select(result.unwrap());
+        let position = rows[0].get_some_column(&"position".to_string()).unwrap();
+        assert_eq!(position.value.value, "{lat: 1.5, lon: -2.5}");
+        let protocol = Response::protocol_row(
+            rows,
+            "test",
+            "positions",
+            vec!["position.lat".to_string()],
+        )
+        .unwrap();
+        let mut cursor = BytesCursor::new(protocol.as_slice());
+        assert_eq!(cursor.read_int().unwrap(), 2);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_string().unwrap(), "test");
+        assert_eq!(cursor.read_string().unwrap(), "positions");
+        assert_eq!(cursor.read_string().unwrap(), "position.lat");
+        assert_eq!(cursor.read_i16().unwrap(), 0x0006);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_string().unwrap(), "1.5");
+
+        assert!(get_query_result("DROP TYPE test.coordinates").is_err());
+        assert!(get_query_result("DROP TABLE test.positions").is_ok());
+        assert!(get_query_result("DROP TYPE test.coordinates").is_ok());
+
+        add_one_finished();
+        check_and_run_teardown();
+    }
+
+    #[test]
+    fn insert_and_select_json() {
+        setup();
+
+        let result = get_query_result(
+            "CREATE TABLE test.json_users (id int, name text, score decimal, PRIMARY KEY (id))",
+        );
+        assert!(result.is_ok());
+
+        let tokens = query_lexer(
+            r#"INSERT INTO test.json_users JSON '{"id": "1", "name": "Ana", "score": 9.5}'"#
+                .to_string(),
+        )
+        .unwrap();
+        let mut query = query_parser(tokens).unwrap();
+        assert!(query.set_table().is_ok());
+        assert!(query.run().is_ok());
+
+        let tokens = query_lexer(
+            r#"INSERT INTO test.json_users JSON '{"id": 2, "unknown": 1}'"#.to_string(),
+        )
+        .unwrap();
+        let mut query = query_parser(tokens).unwrap();
+        assert!(query.set_table().is_err());
+
+        let result = get_query_result("SELECT JSON id, name, score FROM test.json_users WHERE id = 1");
+        let rows = get_rows_select(result.unwrap());
+        let protocol = Response::protocol_row(
+            rows,
+            "test",
+            "json_users",
+            vec!["id".to_string(), "name".to_string(), "score".to_string()],
+        )
+        .unwrap();
+        let json = Response::json_rows(&protocol).unwrap();
+        let mut cursor = BytesCursor::new(json.as_slice());
+        assert_eq!(cursor.read_int().unwrap(), 2);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_string().unwrap(), "test");
+        assert_eq!(cursor.read_string().unwrap(), "json_users");
+        assert_eq!(cursor.read_string().unwrap(), "[json]");
+        assert_eq!(cursor.read_i16().unwrap(), 0x000A);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(
+            cursor.read_string().unwrap(),
+            r#"{"id":1,"name":"Ana","score":9.5}"#
+        );
+
+        assert!(get_query_result("DROP TABLE test.json_users").is_ok());
+
+        add_one_finished();
+        check_and_run_teardown();
+    }
+
+    #[test]
+    fn truncate_table() {
+        setup();
+
+        let result = get_query_result(
+            "CREATE TABLE test.truncated (id int, name text, PRIMARY KEY (id))",
+        );
+        assert!(result.is_ok());
+        let result = get_query_result("INSERT INTO test.truncated (id, name) VALUES (1, 'Ana')");
+        assert!(result.is_ok());
+
+        let result = get_query_result("TRUNCATE TABLE test.truncated");
+        assert_eq!(result.unwrap(), vec![0, 0, 0, 1]);
+        let result = get_query_result("SELECT id FROM test.truncated WHERE id = 1");
+        assert!(get_rows_select(result.unwrap()).is_empty());
+
+        assert!(get_query_result("TRUNCATE test.not_a_table").is_err());
+        assert!(get_query_result("DROP TABLE test.truncated").is_ok());
+
+        add_one_finished();
+        check_and_run_teardown();
+    }
+
+    #[test]
+    fn describe_keyspace_and_table() {
+        setup();
+
+        let result = get_query_result("DESCRIBE TABLE test.que").unwrap();
+        let mut cursor = BytesCursor::new(result.as_slice());
+        assert_eq!(cursor.read_int().unwrap(), 2);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_int().unwrap(), 4);
+        assert_eq!(cursor.read_string().unwrap(), "system_schema");
+        assert_eq!(cursor.read_string().unwrap(), "describe");
+        for header in ["keyspace_name", "type", "name", "create_statement"] {
+            assert_eq!(cursor.read_string().unwrap(), header);
+            assert_eq!(cursor.read_i16().unwrap(), 0x000A);
+        }
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_string().unwrap(), "test");
+        assert_eq!(cursor.read_string().unwrap(), "table");
+        assert_eq!(cursor.read_string().unwrap(), "que");
+        assert_eq!(
+            cursor.read_string().unwrap(),
+            "CREATE TABLE test.que (\n    id int,\n    age int,\n    height int,\n    name text,\n    PRIMARY KEY (id)\n);"
+        );
+
+        let result = get_query_result("DESCRIBE KEYSPACE test").unwrap();
+        let body = String::from_utf8_lossy(&result).to_string();
+        assert!(body.contains(
+            "CREATE KEYSPACE test WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 1};"
+        ));
+        assert!(body.contains("CREATE TABLE test.tb1"));
+
+        let result = get_query_result("DESCRIBE KEYSPACES").unwrap();
+        assert!(String::from_utf8_lossy(&result).contains("test"));
+
+        assert!(get_query_result("DESCRIBE TABLE test.not_a_table").is_err());
+
+        add_one_finished();
+        check_and_run_teardown();
+    }
+
+    #[test]
+    fn batch_applies_every_statement() {
+        setup();
+
+        let result = get_query_result(
+            "CREATE TABLE test.batched (id int, name text, PRIMARY KEY (id))",
+        );
+        assert!(result.is_ok());
+        let result = get_query_result(
+            "BEGIN BATCH INSERT INTO test.batched (id, name) VALUES (1, 'Ana'); INSERT INTO test.batched (id, name) VALUES (2, 'Eva'); UPDATE test.batched SET name = 'Luz' WHERE id = 1; DELETE FROM test.batched WHERE id = 2; APPLY BATCH;",
+        );
+        assert_eq!(result.unwrap(), vec![0, 0, 0, 1]);
+
+        let result = get_query_result("SELECT name FROM test.batched WHERE id = 1").unwrap();
+        let rows = get_rows_select(result);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get_row_hash().get("name").unwrap().value, "Luz");
+        let result = get_query_result("SELECT name FROM test.batched WHERE id = 2").unwrap();
+        assert!(get_rows_select(result).iter().all(|row| row.is_deleted()));
+
+        assert!(get_query_result("BEGIN BATCH SELECT id FROM test.batched APPLY BATCH").is_err());
+        assert!(get_query_result("DROP TABLE test.batched").is_ok());
+
+        add_one_finished();
+        check_and_run_teardown();
+    }
+
+    #[test]
+    fn prepared_statement_binds_values() {
+        setup();
+        use_client_meta_data(|handler| handler.add_new_client(CLIENT_METADATA_PATH.to_string()))
+            .unwrap();
+
+        let result = get_query_result(
+            "CREATE TABLE test.prepared (id int, name text, PRIMARY KEY (id))",
+        );
+        assert!(result.is_ok());
+        let statement = PreparedStatement::prepare(String::from(
+            "INSERT INTO test.prepared (id, name) VALUES (?, :name)",
+        ))
+        .unwrap();
+        let metadata = statement.metadata().unwrap();
+        assert_eq!(&metadata[..6], &[0, 0, 0, 4, 0, 16]);
+        let again = PreparedStatement::prepare(String::from(
+            "INSERT INTO test.prepared (id, name) VALUES (?, :name)",
+        ))
+        .unwrap();
+        assert_eq!(statement.id, again.id);
+
+        let query = statement
+            .bind(&[
+                BoundValue::Value(7i32.to_be_bytes().to_vec()),
+                BoundValue::Value(b"Ana".to_vec()),
+            ])
+            .unwrap();
+        assert!(query.run().is_ok());
+        let result = get_query_result("SELECT name FROM test.prepared WHERE id = 7").unwrap();
+        let rows = get_rows_select(result);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get_row_hash().get("name").unwrap().value, "Ana");
+
+        assert!(statement
+            .bind(&[BoundValue::Value(7i32.to_be_bytes().to_vec())]).is_err());
+        let named = BoundValues::Named(vec![
+            ("name".to_string(), BoundValue::Value(b"Eva".to_vec())),
+            ("id".to_string(), BoundValue::Value(8i32.to_be_bytes().to_vec())),
+        ]);
+        assert!(statement.bind_values(&named).unwrap().run().is_ok());
+        let result = get_query_result("SELECT name FROM test.prepared WHERE id = 8").unwrap();
+        let rows = get_rows_select(result);
+        assert_eq!(rows[0].get_row_hash().get("name").unwrap().value, "Eva");
+        let missing = BoundValues::Named(vec![(
+            "name".to_string(),
+            BoundValue::Value(b"Eva".to_vec()),
+        )]);
+        assert!(statement.bind_values(&missing).is_err());
+
+        let unset = statement
+            .bind(&[
+                BoundValue::Value(9i32.to_be_bytes().to_vec()),
+                BoundValue::Unset,
+            ])
+            .unwrap();
+        assert!(unset.run().is_ok());
+        let result = get_query_result("SELECT id, name FROM test.prepared WHERE id = 9").unwrap();
+        let rows = get_rows_select(result);
+        assert_eq!(rows.len(), 1);
+        assert!(!rows[0].get_row_hash().contains_key("name"));
+
+        let null = statement
+            .bind(&[
+                BoundValue::Value(7i32.to_be_bytes().to_vec()),
+                BoundValue::Null,
+            ])
+            .unwrap();
+        assert!(null.run().is_ok());
+        let result = get_query_result("SELECT id, name FROM test.prepared WHERE id = 7").unwrap();
+        let rows = get_rows_select(result);
+        assert_eq!(rows.len(), 1);
+        assert!(!rows[0].get_row_hash().contains_key("name"));
+        assert!(statement
+            .bind(&[BoundValue::Null, BoundValue::Value(b"Ana".to_vec())])
+            .is_err());
+
+        let ad_hoc = PreparedStatement::parse(String::from(
+            "SELECT name FROM test.prepared WHERE id = ?",
+        ))
+        .unwrap();
+        assert!(matches!(
+            PreparedStatement::get(&ad_hoc.id),
+            Err(Errors::Unprepared(_, _))
+        ));
+        assert!(matches!(
+            PreparedStatement::get(&[0xAB; 16]),
+            Err(Errors::Unprepared(_, _))
+        ));
+        assert!(get_query_result("DROP TABLE test.prepared").is_ok());
+
+        add_one_finished();
+        check_and_run_teardown();
+    }
 }
@@ -177,6 +177,29 @@ mod tests {
         check_and_run_teardown();
     }
 
+    #[test]
+    fn select_test_clustering_order_desc() {
+        setup();
+        get_query_result(
+            "CREATE TABLE test.seldesc (id int, name text, PRIMARY KEY (id, name)) WITH CLUSTERING ORDER BY (name DESC)",
+        )
+        .unwrap();
+        get_query_result("INSERT INTO test.seldesc (id, name) VALUES (1, 'Ivan')").unwrap();
+        get_query_result("INSERT INTO test.seldesc (id, name) VALUES (1, 'Thiago')").unwrap();
+        get_query_result("INSERT INTO test.seldesc (id, name) VALUES (1, 'Mati')").unwrap();
+
+        let result = get_query_result("select * from test.seldesc WHERE id = 1");
+        let rows = get_rows_select(result.unwrap());
+        let names: Vec<String> = rows
+            .iter()
+            .map(|row| row.get_row_hash().get("name").unwrap().value.clone())
+            .collect();
+        assert_eq!(names, vec!["Thiago", "Mati", "Ivan"]);
+
+        add_one_finished();
+        check_and_run_teardown();
+    }
+
     #[test]
     fn select_test_where_invalid_type_comparison() {
         setup();
@@ -0,0 +1,2 @@
+pub mod trace_session;
+pub mod trace_store;
@@ -0,0 +1,241 @@
+use crate::utils::functions::get_own_ip;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Session data sent with the delegated queries so the replicas record their events in the
+/// same session.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct TraceContext {
+    pub session_id: [u8; 16],
+    pub coordinator: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct TraceEvent {
+    pub activity: String,
+    pub source: String,
+    /// microseconds since the session started in the node that recorded the event
+    pub source_elapsed: i32,
+    pub thread: String,
+    /// microseconds since the epoch, used to order the events of every node
+    pub timestamp: i64,
+}
+
+/// Response of a replica to a traced query: the usual response and the events the replica
+/// recorded while running it.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TracedResponse {
+    pub response: Vec<u8>,
+    pub events: Vec<TraceEvent>,
+}
+
+/// Tracing session of a request. Clones share the events, so the threads that send the query
+/// to the replicas can add the ones they return.
+#[derive(Debug, Clone)]
+pub struct TraceSession {
+    context: TraceContext,
+    source: String,
+    pub request: String,
+    pub started_at: i64,
+    events: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+static LAST_MICROS: AtomicI64 = AtomicI64::new(0);
+
+thread_local! {
+    static CURRENT: RefCell<Option<TraceSession>> = const { RefCell::new(None) };
+}
+
+impl TraceSession {
+    /// starts a new session coordinated by this node
+    pub fn new(request: &str) -> TraceSession {
+        let source = own_source();
+        let context = TraceContext {
+            session_id: new_session_id(),
+            coordinator: source.clone(),
+        };
+        Self::with_context(context, source, request)
+    }
+
+    /// continues, in a replica, the session started by the coordinator
+    pub fn resume(context: TraceContext) -> TraceSession {
+        Self::with_context(context, own_source(), "")
+    }
+
+    fn with_context(context: TraceContext, source: String, request: &str) -> TraceSession {
+        TraceSession {
+            context,
+            source,
+            request: request.to_string(),
+            started_at: now_micros(),
+            events: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn context(&self) -> TraceContext {
+        self.context.clone()
+    }
+
+    pub fn session_id(&self) -> [u8; 16] {
+        self.context.session_id
+    }
+
+    pub fn record(&self, activity: &str) {
+        let timestamp = now_micros();
+        let event = TraceEvent {
+            activity: activity.to_string(),
+            source: self.source.clone(),
+            source_elapsed: (timestamp - self.started_at).clamp(0, i32::MAX as i64) as i32,
+            thread: format!("{:?}", thread::current().id()),
+            timestamp,
+        };
+        self.lock_events().push(event);
+    }
+
+    /// adds the events recorded by a replica
+    pub fn extend(&self, events: Vec<TraceEvent>) {
+        self.lock_events().extend(events);
+    }
+
+    /// returns the events ordered by the moment they were recorded
+    pub fn events(&self) -> Vec<TraceEvent> {
+        let mut events = self.lock_events().clone();
+        events.sort_by_key(|event| event.timestamp);
+        events
+    }
+
+    pub fn elapsed(&self) -> i32 {
+        (now_micros() - self.started_at).clamp(0, i32::MAX as i64) as i32
+    }
+
+    fn lock_events(&self) -> std::sync::MutexGuard<'_, Vec<TraceEvent>> {
+        self.events
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// starts tracing the request handled by the current thread
+pub fn begin(session: TraceSession) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(session));
+}
+
+/// records an event in the session of the current thread, if it is being traced
+pub fn trace(activity: &str) {
+    CURRENT.with(|current| {
+        if let Some(session) = current.borrow().as_ref() {
+            session.record(activity)
+        }
+    });
+}
+
+pub fn current() -> Option<TraceSession> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// stops tracing the current thread and returns its session
+pub fn end() -> Option<TraceSession> {
+    CURRENT.with(|current| current.borrow_mut().take())
+}
+
+/// formats the session id as an uuid
+pub fn uuid_string(id: &[u8; 16]) -> String {
+    let hex: String = id.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// uuid v4
+fn new_session_id() -> [u8; 16] {
+    let mut id = rand::random::<[u8; 16]>();
+    id[6] = (id[6] & 0x0F) | 0x40;
+    id[8] = (id[8] & 0x3F) | 0x80;
+    id
+}
+
+fn own_source() -> String {
+    get_own_ip()
+        .map(|ip| ip.get_string_ip())
+        .unwrap_or_default()
+}
+
+/// Microseconds since the epoch, always greater than those of the previous call: two events
+/// of the same node never tie and keep the order in which they were recorded
+fn now_micros() -> i64 {
+    let now = Utc::now().timestamp_micros();
+    let previous = LAST_MICROS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or(now);
+    now.max(previous + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_traced_threads_record_events() {
+        trace("not traced");
+        assert!(current().is_none());
+        begin(TraceSession::new("QUERY"));
+        trace("Parsing request");
+        let session = end().unwrap();
+        trace("after the end");
+        let events = session.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].activity, "Parsing request");
+    }
+
+    #[test]
+    fn test_replica_events_are_merged_in_order() {
+        let session = TraceSession::new("QUERY");
+        session.record("Sending query to replica");
+        let replica = TraceSession::resume(session.context());
+        replica.record("Executing query on replica");
+        session.record("Received response from replica");
+        session.extend(replica.events());
+        let activities: Vec<String> = session
+            .events()
+            .into_iter()
+            .map(|event| event.activity)
+            .collect();
+        assert_eq!(
+            activities,
+            vec![
+                "Sending query to replica",
+                "Executing query on replica",
+                "Received response from replica"
+            ]
+        );
+        assert_eq!(replica.session_id(), session.session_id());
+    }
+
+    #[test]
+    fn test_timestamps_never_repeat() {
+        let timestamps: Vec<i64> = (0..1000).map(|_| now_micros()).collect();
+        assert!(timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_uuid_string() {
+        let id = [
+            0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0x4d, 0xef, 0x80, 0x11, 0x22, 0x33, 0x44, 0x55,
+            0x66, 0x77,
+        ];
+        assert_eq!(uuid_string(&id), "12345678-9abc-4def-8011-223344556677");
+        let random = new_session_id();
+        assert_eq!(random[6] >> 4, 4);
+    }
+}
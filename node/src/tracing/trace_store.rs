@@ -0,0 +1,144 @@
+use crate::executables::prepared_statement::PreparedStatement;
+use crate::logger::Logger;
+use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
+use crate::parsers::query_parser::{query_lexer, query_parser};
+use crate::queries::batch_query::{BatchQuery, BatchStatement, BatchType};
+use crate::queries::query::Query;
+use crate::query_delegation::query_delegator::QueryDelegator;
+use crate::tracing::trace_session::{uuid_string, TraceSession};
+use crate::utils::consistency_level::ConsistencyLevel;
+use crate::utils::constants::LOGGER_PATH;
+use crate::utils::errors::Errors;
+use crate::utils::types::query_parameters::{BoundValue, BoundValues};
+use std::thread;
+
+pub const TRACES_KEYSPACE: &str = "system_traces";
+
+/// `duration` is a data type for the lexer, so the session duration is stored in
+/// `duration_micros`.
+const SCHEMA: [&str; 3] = [
+    "CREATE KEYSPACE system_traces WITH replication = {'class': 'SimpleStrategy', 'replication_factor' : 2}",
+    "CREATE TABLE system_traces.sessions (session_id text, coordinator text, request text, started_at text, duration_micros int, PRIMARY KEY (session_id))",
+    "CREATE TABLE system_traces.events (session_id text, event_id text, activity text, source text, source_elapsed int, thread text, PRIMARY KEY (session_id, event_id))",
+];
+
+const INSERT_SESSION: &str = "INSERT INTO system_traces.sessions (session_id, coordinator, request, started_at, duration_micros) VALUES (?, ?, ?, ?, ?)";
+const INSERT_EVENT: &str = "INSERT INTO system_traces.events (session_id, event_id, activity, source, source_elapsed, thread) VALUES (?, ?, ?, ?, ?, ?)";
+
+/// Stores the session and its events in `system_traces` from another thread, so the traced
+/// request does not wait for it. The trace is best effort: a failure is logged and never fails
+/// the traced request
+pub fn persist(session: TraceSession) {
+    thread::spawn(move || {
+        if let Err(e) = store(&session) {
+            Logger::new(&LOGGER_PATH).log_error(&format!("Could not store the trace: {}", e));
+        }
+    });
+}
+
+fn store(session: &TraceSession) -> Result<(), Errors> {
+    ensure_schema()?;
+    let session_id = uuid_string(&session.session_id());
+    let context = session.context();
+    insert(
+        INSERT_SESSION,
+        vec![
+            text(&session_id),
+            text(&context.coordinator),
+            text(&session.request),
+            text(&session.started_at.to_string()),
+            int(session.elapsed()),
+        ],
+    )?;
+    // all the events belong to the same partition, they go together in a single batch
+    let mut batch = BatchQuery {
+        batch_type: BatchType::Unlogged,
+        statements: Vec::new(),
+    };
+    for (sequence, event) in session.events().into_iter().enumerate() {
+        let query = bind(
+            INSERT_EVENT,
+            vec![
+                text(&session_id),
+                text(&event_id(event.timestamp, sequence)),
+                text(&event.activity),
+                text(&event.source),
+                int(event.source_elapsed),
+                text(&event.thread),
+            ],
+        )?;
+        batch.statements.push(BatchStatement::from_query(&query)?);
+    }
+    let Some(first) = batch.statements.first() else {
+        return Ok(());
+    };
+    let mut first = first.clone().into_query();
+    first.set_table()?;
+    let pk = first.get_partition()?;
+    let mut batch: Box<dyn Query> = Box::new(batch);
+    batch.set_table()?;
+    QueryDelegator::new(pk, batch, ConsistencyLevel::Any).send()?;
+    Ok(())
+}
+
+/// creates the keyspace and its tables the first time a request is traced
+fn ensure_schema() -> Result<(), Errors> {
+    if use_keyspace_meta_data(|handler| handler.exists_keyspace(TRACES_KEYSPACE))? {
+        return Ok(());
+    }
+    for cql in SCHEMA {
+        send(
+            query_parser(query_lexer(cql.to_string())?)?,
+            ConsistencyLevel::One,
+        )?;
+    }
+    Ok(())
+}
+
+fn insert(cql: &str, values: Vec<BoundValue>) -> Result<(), Errors> {
+    send(bind(cql, values)?, ConsistencyLevel::Any)
+}
+
+fn bind(cql: &str, values: Vec<BoundValue>) -> Result<Box<dyn Query>, Errors> {
+    PreparedStatement::prepare(cql.to_string())?.bind_values(&BoundValues::Positional(values))
+}
+
+fn send(mut query: Box<dyn Query>, consistency: ConsistencyLevel) -> Result<(), Errors> {
+    query.set_table()?;
+    let pk = query.get_partition()?;
+    QueryDelegator::new(pk, query, consistency).send()?;
+    Ok(())
+}
+
+/// the events of a session are sorted by their clustering key
+fn event_id(timestamp: i64, sequence: usize) -> String {
+    format!("{:020}-{:04}", timestamp, sequence)
+}
+
+fn text(value: &str) -> BoundValue {
+    BoundValue::Value(value.as_bytes().to_vec())
+}
+
+fn int(value: i32) -> BoundValue {
+    BoundValue::Value(value.to_be_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_ids_sort_like_the_events() {
+        let ids = [event_id(999, 3), event_id(1000, 1), event_id(1000, 2)];
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(sorted, ids);
+    }
+
+    #[test]
+    fn test_schema_parses() {
+        for cql in SCHEMA {
+            assert!(query_parser(query_lexer(cql.to_string()).unwrap()).is_ok());
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::meta_data::keyspaces::user_type::UserType;
+use crate::parsers::tokens::data_type::DataType;
+use crate::parsers::tokens::literal::{create_udt_literal, Literal};
+use crate::utils::errors::Errors;
+use crate::utils::types::bytes_cursor::BytesCursor;
+use chrono::{Days, NaiveDate};
+use std::collections::HashMap;
+
+/// The days of a `date` are counted from 2^31, which is the epoch.
+const DATE_EPOCH_OFFSET: i64 = 1 << 31;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+/// Decodes a value sent in the body of an EXECUTE according to the type of its bind marker.
+///
+/// The values arrive in the binary format of the protocol and are returned as the literal
+/// that would have been written in the query.
+pub fn decode_value(
+    bytes: &[u8],
+    data_type: &DataType,
+    user_types: &HashMap<String, UserType>,
+) -> Result<Literal, Errors> {
+    let value = match data_type {
+        DataType::Int => decode_int(bytes)?,
+        DataType::Boolean => match bytes {
+            [byte] => (*byte != 0).to_string(),
+            _ => return Err(invalid_length(data_type, bytes)),
+        },
+        DataType::Text => decode_text(bytes)?,
+        DataType::Decimal => decode_decimal(bytes)?,
+        DataType::Date => decode_date(bytes)?,
+        DataType::Time => decode_time(bytes)?,
+        DataType::Duration => decode_duration(bytes)?,
+        DataType::Udt(type_name) => return decode_udt(bytes, type_name, user_types),
+    };
+    Ok(Literal::new(value, data_type.clone()))
+}
+
+fn invalid_length(data_type: &DataType, bytes: &[u8]) -> Errors {
+    Errors::Invalid(format!(
+        "Invalid {} bytes for a value of type {:?}",
+        bytes.len(),
+        data_type
+    ))
+}
+
+/// acepta tanto `int` (4 bytes) como `bigint` (8 bytes)
+fn decode_int(bytes: &[u8]) -> Result<String, Errors> {
+    if let Ok(int) = <[u8; 4]>::try_from(bytes) {
+        return Ok(i32::from_be_bytes(int).to_string());
+    }
+    match <[u8; 8]>::try_from(bytes) {
+        Ok(bigint) => Ok(i64::from_be_bytes(bigint).to_string()),
+        Err(_) => Err(invalid_length(&DataType::Int, bytes)),
+    }
+}
+
+fn decode_text(bytes: &[u8]) -> Result<String, Errors> {
+    String::from_utf8(bytes.to_vec())
+        .map_err(|_| Errors::Invalid(String::from("Text values must be valid UTF-8")))
+}
+
+/// `<scale: int><unscaled: varint>`
+fn decode_decimal(bytes: &[u8]) -> Result<String, Errors> {
+    if bytes.len() < 5 || bytes.len() > 20 {
+        return Err(invalid_length(&DataType::Decimal, bytes));
+    }
+    let scale = i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let unscaled = bytes[4..]
+        .iter()
+        .fold(if bytes[4] & 0x80 != 0 { -1i128 } else { 0 }, |acc, byte| {
+            (acc << 8) | *byte as i128
+        });
+    if scale <= 0 {
+        let zeros = "0".repeat(scale.unsigned_abs() as usize);
+        return Ok(format!("{}{}", unscaled, if unscaled == 0 { "" } else { &zeros }));
+    }
+    let digits = unscaled.unsigned_abs().to_string();
+    let scale = scale as usize;
+    let digits = format!("{:0>width$}", digits, width = scale + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - scale);
+    let sign = if unscaled < 0 { "-" } else { "" };
+    Ok(format!("{}{}.{}", sign, integer, fraction))
+}
+
+fn decode_date(bytes: &[u8]) -> Result<String, Errors> {
+    let Ok(days) = <[u8; 4]>::try_from(bytes) else {
+        return Err(invalid_length(&DataType::Date, bytes));
+    };
+    let days = u32::from_be_bytes(days) as i64 - DATE_EPOCH_OFFSET;
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)
+        .ok_or_else(|| Errors::ServerError(String::from("Invalid epoch")))?;
+    let date = if days >= 0 {
+        epoch.checked_add_days(Days::new(days as u64))
+    } else {
+        epoch.checked_sub_days(Days::new(days.unsigned_abs()))
+    };
+    match date {
+        Some(date) => Ok(date.format("%Y-%m-%d").to_string()),
+        None => Err(Errors::Invalid(String::from("Date out of range"))),
+    }
+}
+
+/// nanoseconds since midnight, the literal only keeps up to the seconds
+fn decode_time(bytes: &[u8]) -> Result<String, Errors> {
+    let Ok(nanos) = <[u8; 8]>::try_from(bytes) else {
+        return Err(invalid_length(&DataType::Time, bytes));
+    };
+    let seconds = i64::from_be_bytes(nanos) / NANOS_PER_SECOND;
+    if !(0..24 * 60 * 60).contains(&seconds) {
+        return Err(Errors::Invalid(String::from("Time out of range")));
+    }
+    Ok(format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    ))
+}
+
+/// `<months: vint><days: vint><nanoseconds: vint>`
+fn decode_duration(bytes: &[u8]) -> Result<String, Errors> {
+    let mut cursor = BytesCursor::new(bytes);
+    let months = read_vint(&mut cursor)?;
+    let days = read_vint(&mut cursor)?;
+    let nanos = read_vint(&mut cursor)?;
+    let mut duration = String::new();
+    if months < 0 || days < 0 || nanos < 0 {
+        duration.push('-');
+    }
+    for (amount, unit) in [(months, "mo"), (days, "d"), (nanos, "ns")] {
+        if amount != 0 {
+            duration += &format!("{}{}", amount.unsigned_abs(), unit);
+        }
+    }
+    if duration.is_empty() || duration == "-" {
+        return Ok(String::from("0s"));
+    }
+    Ok(duration)
+}
+
+/// reads a variable length integer encoded in zig-zag
+fn read_vint(cursor: &mut BytesCursor) -> Result<i64, Errors> {
+    let first = cursor.read_u8()?;
+    let extra_bytes = first.leading_ones() as usize;
+    let mut value = if extra_bytes == 8 {
+        0
+    } else {
+        (first & (0xFF >> extra_bytes)) as u64
+    };
+    for _ in 0..extra_bytes {
+        value = (value << 8) | cursor.read_u8()? as u64;
+    }
+    Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+}
+
+/// the fields arrive in the order they were defined, each one as `[bytes]`
+fn decode_udt(
+    bytes: &[u8],
+    type_name: &str,
+    user_types: &HashMap<String, UserType>,
+) -> Result<Literal, Errors> {
+    let Some(user_type) = user_types.get(type_name) else {
+        return Err(Errors::Invalid(format!("Type {} not found", type_name)));
+    };
+    let mut cursor = BytesCursor::new(bytes);
+    let mut fields = Vec::new();
+    for (field, field_type) in &user_type.fields {
+        if cursor.is_empty() {
+            break;
+        }
+        if let Some(value) = cursor.read_bytes()? {
+            fields.push((field.clone(), decode_value(&value, field_type, user_types)?));
+        }
+    }
+    Ok(create_udt_literal(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(bytes: &[u8], data_type: DataType) -> Result<String, Errors> {
+        decode_value(bytes, &data_type, &HashMap::new()).map(|literal| literal.value)
+    }
+
+    #[test]
+    fn test_decode_int() {
+        assert_eq!(decode(&(-7i32).to_be_bytes(), DataType::Int).unwrap(), "-7");
+        assert_eq!(decode(&(1i64 << 40).to_be_bytes(), DataType::Int).unwrap(), "1099511627776");
+        assert!(decode(&[0, 1], DataType::Int).is_err());
+    }
+
+    #[test]
+    fn test_decode_text_and_boolean() {
+        assert_eq!(decode(b"Ana", DataType::Text).unwrap(), "Ana");
+        assert_eq!(decode(&[1], DataType::Boolean).unwrap(), "true");
+        assert_eq!(decode(&[0], DataType::Boolean).unwrap(), "false");
+    }
+
+    #[test]
+    fn test_decode_decimal() {
+        let mut bytes = 2i32.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&[0x04, 0xD2]);
+        assert_eq!(decode(&bytes, DataType::Decimal).unwrap(), "12.34");
+        let mut bytes = 3i32.to_be_bytes().to_vec();
+        bytes.push(0xFB);
+        assert_eq!(decode(&bytes, DataType::Decimal).unwrap(), "-0.005");
+        let mut bytes = 0i32.to_be_bytes().to_vec();
+        bytes.push(0x05);
+        assert_eq!(decode(&bytes, DataType::Decimal).unwrap(), "5");
+    }
+
+    #[test]
+    fn test_decode_date_and_time() {
+        let days = (DATE_EPOCH_OFFSET + 19997) as u32;
+        assert_eq!(decode(&days.to_be_bytes(), DataType::Date).unwrap(), "2024-10-01");
+        let nanos = (13 * 3600 + 5 * 60 + 9) * NANOS_PER_SECOND;
+        assert_eq!(decode(&nanos.to_be_bytes(), DataType::Time).unwrap(), "13:05:09");
+    }
+
+    #[test]
+    fn test_decode_duration() {
+        // 1 month, 2 days and 3 nanoseconds in zig-zag
+        assert_eq!(decode(&[2, 4, 6], DataType::Duration).unwrap(), "1mo2d3ns");
+    }
+
+    #[test]
+    fn test_decode_udt() {
+        let user_types = HashMap::from([(
+            "coordinates".to_string(),
+            UserType::new(vec![
+                ("lat".to_string(), DataType::Int),
+                ("city".to_string(), DataType::Text),
+            ]),
+        )]);
+        let mut bytes = 4i32.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&3i32.to_be_bytes());
+        bytes.extend_from_slice(&7i32.to_be_bytes());
+        bytes.extend_from_slice(b"Rosario");
+        let literal = decode_value(
+            &bytes,
+            &DataType::Udt("coordinates".to_string()),
+            &user_types,
+        )
+        .unwrap();
+        assert_eq!(literal.value, "{lat: 3, city: 'Rosario'}");
+    }
+}
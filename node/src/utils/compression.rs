@@ -0,0 +1,115 @@
+use crate::utils::errors::Errors;
+use serde::{Deserialize, Serialize};
+
+/// Maximum size accepted for a decompressed body.
+const MAX_UNCOMPRESSED_LENGTH: usize = 256 * 1024 * 1024;
+
+/// Compression algorithm of the bodies negotiated in the STARTUP with the `COMPRESSION` option.
+///
+/// With LZ4 the compressed body carries the original length first as an `[int]`, with Snappy
+/// the raw format is used, which already includes the length.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Compression {
+    Lz4,
+    Snappy,
+}
+
+impl Compression {
+    pub fn from_name(name: &str) -> Result<Self, Errors> {
+        match name {
+            "lz4" => Ok(Compression::Lz4),
+            "snappy" => Ok(Compression::Snappy),
+            _ => Err(Errors::ConfigError(format!(
+                "COMPRESSION must be lz4 or snappy, the value provided (COMPRESSION {}) is unsupported",
+                name
+            ))),
+        }
+    }
+
+    pub fn compress(&self, bytes: &[u8]) -> Result<Vec<u8>, Errors> {
+        match self {
+            Compression::Lz4 => {
+                let mut compressed = (bytes.len() as i32).to_be_bytes().to_vec();
+                compressed.extend(lz4_flex::block::compress(bytes));
+                Ok(compressed)
+            }
+            Compression::Snappy => snap::raw::Encoder::new()
+                .compress_vec(bytes)
+                .map_err(|e| Errors::ServerError(e.to_string())),
+        }
+    }
+
+    pub fn decompress(&self, bytes: &[u8]) -> Result<Vec<u8>, Errors> {
+        match self {
+            Compression::Lz4 => {
+                let Some((length, block)) = bytes.split_first_chunk::<4>() else {
+                    return Err(Errors::ProtocolError(String::from(
+                        "Missing LZ4 uncompressed length",
+                    )));
+                };
+                let length = i32::from_be_bytes(*length);
+                if length < 0 || length as usize > MAX_UNCOMPRESSED_LENGTH {
+                    return Err(Errors::ProtocolError(format!(
+                        "Invalid LZ4 uncompressed length {}",
+                        length
+                    )));
+                }
+                lz4_decompress(block, length as usize)
+            }
+            Compression::Snappy => {
+                let length = snap::raw::decompress_len(bytes)
+                    .map_err(|e| Errors::ProtocolError(e.to_string()))?;
+                if length > MAX_UNCOMPRESSED_LENGTH {
+                    return Err(Errors::ProtocolError(format!(
+                        "Invalid Snappy uncompressed length {}",
+                        length
+                    )));
+                }
+                snap::raw::Decoder::new()
+                    .decompress_vec(bytes)
+                    .map_err(|e| Errors::ProtocolError(e.to_string()))
+            }
+        }
+    }
+}
+
+/// decompresses a raw LZ4 block whose uncompressed length is already known
+pub fn lz4_decompress(block: &[u8], length: usize) -> Result<Vec<u8>, Errors> {
+    lz4_flex::block::decompress(block, length).map_err(|e| Errors::ProtocolError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let body = b"SELECT * FROM flights WHERE airport = 'EZE' ".repeat(20);
+        for compression in [Compression::Lz4, Compression::Snappy] {
+            let compressed = compression.compress(&body).unwrap();
+            assert!(compressed.len() < body.len());
+            assert_eq!(compression.decompress(&compressed).unwrap(), body);
+        }
+    }
+
+    #[test]
+    fn test_lz4_length_prefix() {
+        let compressed = Compression::Lz4.compress(b"abc").unwrap();
+        assert_eq!(compressed[..4], 3i32.to_be_bytes());
+        assert!(Compression::Lz4.decompress(&compressed[..2]).is_err());
+        assert!(Compression::Lz4.decompress(&(-1i32).to_be_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Compression::from_name("lz4").unwrap(), Compression::Lz4);
+        assert_eq!(
+            Compression::from_name("snappy").unwrap(),
+            Compression::Snappy
+        );
+        assert!(matches!(
+            Compression::from_name("gzip"),
+            Err(Errors::ConfigError(_))
+        ));
+    }
+}
@@ -1,8 +1,27 @@
 pub const CQL_VERSION: &str = "3.0.0";
-pub const COMPRESSION: &str = "lz4";
+pub const COMPRESSION: [&str; 2] = ["lz4", "snappy"];
 pub const MAX_CLIENTS: usize = 5;
-pub const HINTED_HANDOFF_HOURS: i64 = 3;
+pub const MAX_IN_FLIGHT_REQUESTS: usize = 128;
+pub const CLIENT_POLL_MILLIS: u64 = 10;
+pub const MAX_HINT_WINDOW_SECS: i64 = 3 * 60 * 60;
+pub const MAX_HINTS_SIZE_PER_NODE_KB: u64 = 10 * 1024;
+pub const HINTED_HANDOFF_THROTTLE_KB: u64 = 1024;
+pub const HINTS_SEGMENT_KB: u64 = 256;
 pub const TIMEOUT_SECS: u64 = 5;
 pub const HINTED_HANDOFF_TIMEOUT_SECS: u64 = 10;
 pub const SHUTTING_DOWN_TIMEOUT_SECS: i64 = 10;
 pub const BOOTING_TIMEOUT_SECS: u64 = 5;
+pub const BOOTSTRAP_MAX_ATTEMPTS: u32 = 5;
+pub const BATCHLOG_REPLAY_SECS: i64 = 30;
+pub const BATCHLOG_REPLAY_INTERVAL_MILLIS: u64 = 10_000;
+pub const BATCH_SIZE_WARN_THRESHOLD: usize = 50;
+pub const NUM_TOKENS: usize = 16;
+pub const DEFAULT_DATACENTER: &str = "datacenter1";
+pub const DEFAULT_RACK: &str = "rack1";
+pub const GOSSIP_INTERVAL_MILLIS: u64 = 1000;
+pub const PHI_CONVICT_THRESHOLD: f64 = 8.0;
+pub const SCHEMA_AGREEMENT_WAIT_MILLIS: u64 = 10_000;
+pub const SCHEMA_AGREEMENT_POLL_MILLIS: u64 = 200;
+pub const MERKLE_TREE_DEPTH: u32 = 8;
+pub const STREAMING_CHUNK_ROWS: usize = 500;
+pub const RING_DELAY_MILLIS: u64 = 3000;
@@ -1,24 +1,53 @@
 use super::errors::Errors;
+use std::collections::BTreeMap;
 
+const ANY: i16 = 0x0000;
 const ONE: i16 = 0x0001;
+const TWO: i16 = 0x0002;
+const THREE: i16 = 0x0003;
 const QUORUM: i16 = 0x0004;
 const ALL: i16 = 0x0005;
+const LOCAL_QUORUM: i16 = 0x0006;
+const EACH_QUORUM: i16 = 0x0007;
+const SERIAL: i16 = 0x0008;
+const LOCAL_SERIAL: i16 = 0x0009;
+const LOCAL_ONE: i16 = 0x000A;
 
 use ConsistencyLevel::*;
 
-#[derive(Debug, PartialEq)]
+/// Consistency levels of the protocol.
+///
+/// LOCAL_ONE, LOCAL_QUORUM and LOCAL_SERIAL only count the replicas of the datacenter of the
+/// coordinator, EACH_QUORUM needs a quorum in every datacenter that has replicas.
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ConsistencyLevel {
+    Any,
     One,
+    Two,
+    Three,
     Quorum,
     All,
+    LocalQuorum,
+    EachQuorum,
+    Serial,
+    LocalSerial,
+    LocalOne,
 }
 
 impl ConsistencyLevel {
     pub fn from_i16(value: i16) -> Result<ConsistencyLevel, Errors> {
         let consistency = match value {
+            ANY => Any,
             ONE => One,
+            TWO => Two,
+            THREE => Three,
             QUORUM => Quorum,
             ALL => All,
+            LOCAL_QUORUM => LocalQuorum,
+            EACH_QUORUM => EachQuorum,
+            SERIAL => Serial,
+            LOCAL_SERIAL => LocalSerial,
+            LOCAL_ONE => LocalOne,
             _ => {
                 return Err(Errors::ProtocolError(format!(
                     "Unknown consistency level: {}",
@@ -29,19 +58,265 @@ impl ConsistencyLevel {
         Ok(consistency)
     }
 
-    pub fn get_consistency(&self, replication_factor: usize) -> usize {
+    /// acks the query needs, given its replication factor in total and in each datacenter
+    pub fn required_acks(&self, replication: &AckCount, local_datacenter: &str) -> AckCount {
+        let mut required = AckCount::default();
         match self {
-            One => 1,
-            Quorum => (replication_factor / 2) + 1,
-            All => replication_factor,
+            Any | One => required.total = 1,
+            Two => required.total = 2,
+            Three => required.total = 3,
+            Quorum | Serial => required.total = quorum(replication.total),
+            All => required.total = replication.total,
+            LocalOne => required.set(local_datacenter, 1),
+            LocalQuorum | LocalSerial => {
+                let factor = replication.in_datacenter(local_datacenter);
+                required.set(local_datacenter, quorum(factor));
+            }
+            EachQuorum => {
+                for (datacenter, factor) in &replication.datacenters {
+                    if *factor > 0 {
+                        required.set(datacenter, quorum(*factor));
+                    }
+                }
+            }
         }
+        required
     }
 
     pub fn to_i16(&self) -> i16 {
         match self {
-            ConsistencyLevel::One => 0x0001,
-            ConsistencyLevel::Quorum => 0x0004,
-            ConsistencyLevel::All => 0x0005,
+            Any => ANY,
+            One => ONE,
+            Two => TWO,
+            Three => THREE,
+            Quorum => QUORUM,
+            All => ALL,
+            LocalQuorum => LOCAL_QUORUM,
+            EachQuorum => EACH_QUORUM,
+            Serial => SERIAL,
+            LocalSerial => LOCAL_SERIAL,
+            LocalOne => LOCAL_ONE,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Any => "ANY",
+            One => "ONE",
+            Two => "TWO",
+            Three => "THREE",
+            Quorum => "QUORUM",
+            All => "ALL",
+            LocalQuorum => "LOCAL_QUORUM",
+            EachQuorum => "EACH_QUORUM",
+            Serial => "SERIAL",
+            LocalSerial => "LOCAL_SERIAL",
+            LocalOne => "LOCAL_ONE",
+        }
+    }
+
+    pub fn is_serial(&self) -> bool {
+        matches!(self, Serial | LocalSerial)
+    }
+
+    /// ANY only makes sense for writes, a hint can not answer a read. SERIAL and LOCAL_SERIAL
+    /// are rejected too, reads never run the condition round of the conditional updates
+    pub fn validate_for_read(&self) -> Result<(), Errors> {
+        if *self == Any {
+            return Err(Errors::Invalid(String::from(
+                "ANY ConsistencyLevel is only supported for writes",
+            )));
+        }
+        if self.is_serial() {
+            return Err(Errors::Invalid(format!(
+                "{} ConsistencyLevel is not supported for reads",
+                self.name()
+            )));
+        }
+        Ok(())
+    }
+
+    /// SERIAL and LOCAL_SERIAL are only used as the serial consistency of conditional updates
+    pub fn validate_for_write(&self) -> Result<(), Errors> {
+        if self.is_serial() {
+            return Err(Errors::Invalid(String::from(
+                "You must use conditional updates for serializable writes",
+            )));
         }
+        Ok(())
+    }
+
+    /// fails before sending the query if there are not enough live replicas to achieve the level.
+    ///
+    /// With ANY the coordinator stores a hint for every replica that is down, so it never fails
+    pub fn check_available(&self, required: &AckCount, alive: &AckCount) -> Result<(), Errors> {
+        if *self == Any {
+            return Ok(());
+        }
+        if alive.total < required.total {
+            return Err(Errors::UnavailableException(format!(
+                "Cannot achieve consistency level {}: {} replicas required but only {} alive",
+                self.name(),
+                required.total,
+                alive.total
+            )));
+        }
+        for (datacenter, needed) in &required.datacenters {
+            let available = alive.in_datacenter(datacenter);
+            if available < *needed {
+                return Err(Errors::UnavailableException(format!(
+                    "Cannot achieve consistency level {}: {} replicas required in {} but only {} alive",
+                    self.name(),
+                    needed,
+                    datacenter,
+                    available
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn quorum(replication_factor: usize) -> usize {
+    (replication_factor / 2) + 1
+}
+
+/// An amount of replicas, in total and in each datacenter: the replication factor of a query,
+/// its live replicas, or the acks it needs or already received.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AckCount {
+    pub total: usize,
+    pub datacenters: BTreeMap<String, usize>,
+}
+
+impl AckCount {
+    /// counts a replica of the datacenter
+    pub fn add(&mut self, datacenter: &str) {
+        self.total += 1;
+        *self.datacenters.entry(datacenter.to_string()).or_insert(0) += 1;
+    }
+
+    /// counts a hint stored for a replica that is down, it belongs to no datacenter
+    pub fn add_hint(&mut self) {
+        self.total += 1;
+    }
+
+    pub fn in_datacenter(&self, datacenter: &str) -> usize {
+        self.datacenters.get(datacenter).copied().unwrap_or(0)
+    }
+
+    /// true if there is at least the required amount in total and in every datacenter
+    pub fn covers(&self, required: &AckCount) -> bool {
+        self.total >= required.total
+            && required
+                .datacenters
+                .iter()
+                .all(|(datacenter, needed)| self.in_datacenter(datacenter) >= *needed)
+    }
+
+    /// what both requirements need together
+    pub fn max(mut self, other: AckCount) -> AckCount {
+        self.total = self.total.max(other.total);
+        for (datacenter, needed) in other.datacenters {
+            let current = self.datacenters.entry(datacenter).or_insert(0);
+            *current = (*current).max(needed);
+        }
+        self
+    }
+
+    fn set(&mut self, datacenter: &str, amount: usize) {
+        self.datacenters.insert(datacenter.to_string(), amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_protocol_code_round_trips() {
+        for code in 0x0000..=0x000A {
+            assert_eq!(ConsistencyLevel::from_i16(code).unwrap().to_i16(), code);
+        }
+        assert!(ConsistencyLevel::from_i16(0x000B).is_err());
+    }
+
+    fn count(total: usize, datacenters: &[(&str, usize)]) -> AckCount {
+        AckCount {
+            total,
+            datacenters: datacenters
+                .iter()
+                .map(|(datacenter, amount)| (datacenter.to_string(), *amount))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_required_replicas() {
+        let replication = |total| count(total, &[]);
+        assert_eq!(Any.required_acks(&replication(3), "dc1"), count(1, &[]));
+        assert_eq!(Two.required_acks(&replication(3), "dc1"), count(2, &[]));
+        assert_eq!(Three.required_acks(&replication(5), "dc1"), count(3, &[]));
+        assert_eq!(Serial.required_acks(&replication(3), "dc1"), count(2, &[]));
+        assert_eq!(All.required_acks(&replication(4), "dc1"), count(4, &[]));
+    }
+
+    #[test]
+    fn test_datacenter_levels_only_count_their_replicas() {
+        let replication = count(8, &[("dc1", 3), ("dc2", 5)]);
+        assert_eq!(Quorum.required_acks(&replication, "dc1"), count(5, &[]));
+        assert_eq!(
+            LocalOne.required_acks(&replication, "dc1"),
+            count(0, &[("dc1", 1)])
+        );
+        assert_eq!(
+            LocalQuorum.required_acks(&replication, "dc2"),
+            count(0, &[("dc2", 3)])
+        );
+        assert_eq!(
+            EachQuorum.required_acks(&replication, "dc1"),
+            count(0, &[("dc1", 2), ("dc2", 3)])
+        );
+        // the acks of another datacenter do not make a local quorum
+        let required = LocalQuorum.required_acks(&replication, "dc1");
+        assert!(!count(5, &[("dc1", 1), ("dc2", 4)]).covers(&required));
+        assert!(count(2, &[("dc1", 2)]).covers(&required));
+    }
+
+    #[test]
+    fn test_counting_acks() {
+        let mut acks = AckCount::default();
+        acks.add("dc1");
+        acks.add_hint();
+        assert_eq!(acks, count(2, &[("dc1", 1)]));
+        let required = count(2, &[]).max(count(0, &[("dc1", 1)]));
+        assert!(acks.covers(&required));
+        assert!(!acks.covers(&count(0, &[("dc2", 1)])));
+    }
+
+    #[test]
+    fn test_unavailable_before_sending() {
+        assert!(matches!(
+            Quorum.check_available(&count(2, &[]), &count(1, &[])),
+            Err(Errors::UnavailableException(_))
+        ));
+        assert!(Three
+            .check_available(&count(3, &[]), &count(3, &[]))
+            .is_ok());
+        assert!(Any.check_available(&count(1, &[]), &count(0, &[])).is_ok());
+        assert!(matches!(
+            LocalQuorum.check_available(&count(0, &[("dc1", 2)]), &count(3, &[("dc1", 1)])),
+            Err(Errors::UnavailableException(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_levels_for_the_operation() {
+        assert!(Any.validate_for_read().is_err());
+        assert!(Any.validate_for_write().is_ok());
+        assert!(Serial.validate_for_read().is_err());
+        assert!(LocalSerial.validate_for_read().is_err());
+        assert!(Quorum.validate_for_read().is_ok());
+        assert!(LocalSerial.validate_for_write().is_err());
     }
 }
@@ -1,20 +1,50 @@
+use std::fs;
+use std::sync::LazyLock;
+
 pub const ERROR_WRITE: &str = "Could not write byte";
 pub const BEST: &str = "BETTER_RESPONSE";
-pub const NODES_METADATA_PATH: &str = "src/meta_data/nodes/metadata.json";
-pub const CLIENT_METADATA_PATH: &str = "src/meta_data/clients/data/";
-pub const DATA_ACCESS_PATH: &str = "src/data_access/data/";
-pub const KEYSPACE_METADATA_PATH: &str = "src/meta_data/keyspaces/metadata.json";
-pub const HINTED_HANDOFF_DATA: &str = "src/hinted_handoff/data";
-pub const IP_FILE: &str = "src/utils/ip.txt";
+pub static NODES_METADATA_PATH: LazyLock<String> =
+    LazyLock::new(|| node_file("src/meta_data/nodes/metadata.json"));
+pub static CLIENT_METADATA_PATH: LazyLock<String> =
+    LazyLock::new(|| node_file("src/meta_data/clients/data/"));
+pub static DATA_ACCESS_PATH: LazyLock<String> =
+    LazyLock::new(|| node_file("src/data_access/data/"));
+pub static KEYSPACE_METADATA_PATH: LazyLock<String> =
+    LazyLock::new(|| node_file("src/meta_data/keyspaces/metadata.json"));
+pub static HINTED_HANDOFF_DATA: LazyLock<String> =
+    LazyLock::new(|| node_file("src/hinted_handoff/data"));
+pub static BATCHLOG_DATA: LazyLock<String> = LazyLock::new(|| node_file("src/batchlog/data"));
+pub static IP_FILE: LazyLock<String> = LazyLock::new(|| node_file("src/utils/ip.txt"));
+pub static CREDENTIALS_FILE: LazyLock<String> =
+    LazyLock::new(|| node_file("src/auth/credentials.json"));
 pub const CONFIG_FILE: &str = "src/config.yaml";
-pub const PREPARE_FILE: &str = "src/executables/queries.txt";
 pub const QUERY_DELEGATION_PORT_MOD: i32 = 1;
 pub const DATA_ACCESS_PORT_MOD: i32 = 2;
 pub const META_DATA_ACCESS_MOD: i32 = 3;
 pub const GOSSIP_MOD: i32 = 4;
 pub const SEED_LISTENER_MOD: i32 = 5;
 pub const HINTS_RECEIVER_MOD: i32 = 6;
+pub const REPAIR_MOD: i32 = 7;
+pub const STREAMING_MOD: i32 = 8;
 pub const REPLICATION: &str = "replication_factor";
 pub const STRATEGY: &str = "class";
 pub const ASTERIK: &str = "*";
-pub const LOGGER_PATH: &str = "server.log";
\ No newline at end of file
+pub static LOGGER_PATH: LazyLock<String> = LazyLock::new(|| node_file("server.log"));
+/// Directory the node writes its files under. Tests use a temporary one, so a test run leaves
+/// the source tree untouched
+static NODE_ROOT: LazyLock<String> = LazyLock::new(|| {
+    if !cfg!(test) {
+        return String::new();
+    }
+    let root = std::env::temp_dir().join("node_tests");
+    let _ = fs::remove_dir_all(&root);
+    format!("{}/", root.display())
+});
+
+fn node_file(relative: &str) -> String {
+    let path = format!("{}{}", *NODE_ROOT, relative);
+    if let Some(parent) = std::path::Path::new(&path).parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    path
+}
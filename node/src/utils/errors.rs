@@ -10,6 +10,9 @@ const IS_BOOTSTRAPPING: &[u8] = &[0x10, 0x02];
 const TRUNCATE_ERROR: &[u8] = &[0x10, 0x03];
 const WRITE_TIMEOUT: &[u8] = &[0x11, 0x00];
 const READ_TIMEOUT: &[u8] = &[0x12, 0x00];
+const READ_FAILURE: &[u8] = &[0x13, 0x00];
+const FUNCTION_FAILURE: &[u8] = &[0x14, 0x00];
+const WRITE_FAILURE: &[u8] = &[0x15, 0x00];
 const SYNTAX_ERROR: &[u8] = &[0x20, 0x00];
 const UNAUTHORIZED: &[u8] = &[0x21, 0x00];
 const INVALID: &[u8] = &[0x22, 0x00];
@@ -33,7 +36,11 @@ pub enum Errors {
     Invalid(String),
     ConfigError(String),
     AlreadyExists(String),
-    Unprepared(String),
+    /// the message and the id of the statement that is not prepared
+    Unprepared(String, Vec<u8>),
+    ReadFailure(String),
+    FunctionFailure(String),
+    WriteFailure(String),
 }
 
 impl Errors {
@@ -53,7 +60,15 @@ impl Errors {
             Errors::Invalid(msg) => join_bytes(INVALID, msg),
             Errors::ConfigError(msg) => join_bytes(CONFIG_ERROR, msg),
             Errors::AlreadyExists(msg) => join_bytes(ALREADY_EXISTS, msg),
-            Errors::Unprepared(msg) => join_bytes(UNPREPARED, msg),
+            Errors::Unprepared(msg, id) => {
+                let mut bytes = join_bytes(UNPREPARED, msg);
+                bytes.extend((id.len() as i16).to_be_bytes());
+                bytes.extend(id);
+                bytes
+            }
+            Errors::ReadFailure(msg) => join_bytes(READ_FAILURE, msg),
+            Errors::FunctionFailure(msg) => join_bytes(FUNCTION_FAILURE, msg),
+            Errors::WriteFailure(msg) => join_bytes(WRITE_FAILURE, msg),
         }
     }
 
@@ -73,7 +88,10 @@ impl Errors {
             INVALID => Errors::Invalid(msg),
             CONFIG_ERROR => Errors::ConfigError(msg),
             ALREADY_EXISTS => Errors::AlreadyExists(msg),
-            UNPREPARED => Errors::Unprepared(msg),
+            UNPREPARED => Errors::Unprepared(msg, Vec::new()),
+            READ_FAILURE => Errors::ReadFailure(msg),
+            FUNCTION_FAILURE => Errors::FunctionFailure(msg),
+            WRITE_FAILURE => Errors::WriteFailure(msg),
             _ => Errors::ServerError(msg),
         }
     }
@@ -137,8 +155,22 @@ impl Errors {
                 data.push(19);
                 data.extend(msg.as_bytes());
             }
-            Errors::Unprepared(msg) => {
+            Errors::Unprepared(msg, id) => {
                 data.push(20);
+                data.extend((id.len() as i16).to_be_bytes());
+                data.extend(id);
+                data.extend(msg.as_bytes());
+            }
+            Errors::ReadFailure(msg) => {
+                data.push(21);
+                data.extend(msg.as_bytes());
+            }
+            Errors::FunctionFailure(msg) => {
+                data.push(22);
+                data.extend(msg.as_bytes());
+            }
+            Errors::WriteFailure(msg) => {
+                data.push(23);
                 data.extend(msg.as_bytes());
             }
         }
@@ -168,7 +200,10 @@ impl Errors {
             17 => Some(Errors::Invalid(msg)),
             18 => Some(Errors::ConfigError(msg)),
             19 => Some(Errors::AlreadyExists(msg)),
-            20 => Some(Errors::Unprepared(msg)),
+            20 => deserialize_unprepared(mensaje),
+            21 => Some(Errors::ReadFailure(msg)),
+            22 => Some(Errors::FunctionFailure(msg)),
+            23 => Some(Errors::WriteFailure(msg)),
             _ => None,
         }
     }
@@ -191,14 +226,39 @@ impl Display for Errors {
             Errors::Invalid(msg) => write!(f, "Invalid: {}", msg),
             Errors::ConfigError(msg) => write!(f, "ConfigError: {}", msg),
             Errors::AlreadyExists(msg) => write!(f, "AlreadyExists: {}", msg),
-            Errors::Unprepared(msg) => write!(f, "Unprepared: {}", msg),
+            Errors::Unprepared(msg, _) => write!(f, "Unprepared: {}", msg),
+            Errors::ReadFailure(msg) => write!(f, "ReadFailure: {}", msg),
+            Errors::FunctionFailure(msg) => write!(f, "FunctionFailure: {}", msg),
+            Errors::WriteFailure(msg) => write!(f, "WriteFailure: {}", msg),
         }
     }
 }
 
+/// `<id length: short><id><message>`, the id goes before the message so it is read back as is
+fn deserialize_unprepared(data: &[u8]) -> Option<Errors> {
+    let length = i16::from_be_bytes(data.get(..2)?.try_into().ok()?).max(0) as usize;
+    let id = data.get(2..2 + length)?.to_vec();
+    let msg = String::from_utf8_lossy(&data[2 + length..]).into_owned();
+    Some(Errors::Unprepared(msg, id))
+}
+
 fn join_bytes(bytes: &[u8], msg: &str) -> Vec<u8> {
     let mut new_bytes = bytes.to_vec();
     new_bytes.extend((msg.len() as i16).to_be_bytes().to_vec());
     new_bytes.extend(msg.as_bytes());
     new_bytes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unprepared_carries_the_statement_id() {
+        let error = Errors::Unprepared(String::from("not prepared"), vec![0xAB, 0xCD]);
+        let body = error.get_bytes_body();
+        assert_eq!(body[..2], *UNPREPARED);
+        assert_eq!(body[body.len() - 4..], [0x00, 0x02, 0xAB, 0xCD]);
+        assert_eq!(Errors::deserialize(&error.serialize()), Some(error));
+    }
+}
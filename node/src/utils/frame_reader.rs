@@ -2,11 +2,14 @@ use crate::utils::types::frame::Frame;
 use crate::utils::errors::Errors;
 use crate::utils::types::bytes_cursor::BytesCursor;
 use crate::utils::parser_constants::{ERROR, AUTHENTICATE, AUTH_SUCCESS, AUTH_CHALLENGE, SUPPORTED, RESULT};
+use cql_types::skip_type_option;
+use crate::utils::types::frame::{CUSTOM_PAYLOAD_FLAG, TRACING_FLAG, WARNING_FLAG};
 
 pub struct FrameReader;
 
 impl FrameReader {
-    pub fn read_frame(frame: Frame) -> Result<String, Errors> {
+    pub fn read_frame(mut frame: Frame) -> Result<String, Errors> {
+        frame.body = Self::skip_prefixes(&frame)?;
         let mut response = String::new();
         match frame.opcode {
             ERROR => {
@@ -29,8 +32,8 @@ impl FrameReader {
             SUPPORTED => {
                 let mut cursor = BytesCursor::new(frame.body.as_slice());
                 response += "SUPPORTED";
-                for (key, value) in cursor.read_string_map().unwrap() {
-                    response += format!("\n{}: {}", key, value).as_str();
+                for (key, values) in cursor.read_string_multimap()? {
+                    response += format!("\n{}: {}", key, values.join(", ")).as_str();
                 }
             }
             RESULT => {
@@ -41,6 +44,23 @@ impl FrameReader {
         Ok(response)
     }
 
+    /// skips the tracing id, the warnings and the custom payload that responses may carry before the body
+    fn skip_prefixes(frame: &Frame) -> Result<Vec<u8>, Errors> {
+        let mut cursor = BytesCursor::new(frame.body.as_slice());
+        if frame.flags & TRACING_FLAG != 0 {
+            let _tracing_id = cursor.read_exact(16)?;
+        }
+        if frame.flags & WARNING_FLAG != 0 {
+            for _ in 0..cursor.read_short()? {
+                let _warning = cursor.read_string()?;
+            }
+        }
+        if frame.flags & CUSTOM_PAYLOAD_FLAG != 0 {
+            cursor.read_bytes_map()?;
+        }
+        cursor.read_remaining_bytes()
+    }
+
     fn show_response(body: Vec<u8>) -> Result<String, Errors> {
         let mut cursor = BytesCursor::new(body.as_slice());
         let mut response = String::new();
@@ -69,7 +89,7 @@ impl FrameReader {
                         header += ", ";
                     }
                     let col_name = cursor.read_string()?;
-                    let _ = cursor.read_i16()?;
+                    skip_type_option(&mut cursor)?;
                     header += &col_name;
                 }
                 response += format!("\n{}", header).as_str();
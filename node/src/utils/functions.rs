@@ -1,6 +1,11 @@
 use crate::meta_data::meta_data_handler::{use_client_meta_data, use_node_meta_data};
 use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
 use crate::parsers::tokens::data_type::DataType;
+use crate::parsers::tokens::literal::Literal;
+use crate::data_access::data_access_handler::use_data_access;
+use crate::data_access::row::Row;
+use crate::partitioner::partition_key::PartitionKey;
+use crate::partitioner::{contains, Token, TokenRange};
 use crate::queries::where_logic::where_clause::WhereClause;
 use crate::utils::constants::{CLIENT_METADATA_PATH, IP_FILE, KEYSPACE_METADATA_PATH, NODES_METADATA_PATH};
 use crate::utils::errors::Errors;
@@ -58,6 +63,29 @@ pub fn get_columns_from_table(table_name: &str) -> Result<HashMap<String, DataTy
     })
 }
 
+/// checks that a literal matches the column data type.
+///
+/// User type values are validated against the type definition of the keyspace
+pub fn check_literal_data_type(
+    keyspace: &str,
+    column_type: &DataType,
+    literal: &Literal,
+) -> Result<bool, Errors> {
+    match (column_type, &literal.data_type) {
+        (DataType::Udt(type_name), DataType::Udt(_)) => {
+            let user_types = use_keyspace_meta_data(|handler| {
+                handler.get_types_from_keyspace(KEYSPACE_METADATA_PATH.to_string(), keyspace)
+            })?;
+            let Some(user_type) = user_types.get(type_name) else {
+                return Err(Errors::Invalid(format!("Type {} does not exist", type_name)));
+            };
+            user_type.check_value(&literal.value, &user_types)?;
+            Ok(true)
+        }
+        _ => Ok(column_type == &literal.data_type),
+    }
+}
+
 /// returns the primary key defined in metadata for table_name
 pub fn get_table_primary_key(table_name: &str) -> Result<PrimaryKey, Errors> {
     let (keyspace, table) = split_keyspace_table(table_name)?;
@@ -116,26 +144,45 @@ pub fn get_int_from_string(string: &String) -> Result<i32, Errors> {
 pub fn get_partition_key_from_where(
     table_name: &str,
     where_clause: &Option<WhereClause>,
-) -> Result<Vec<String>, Errors> {
+) -> Result<PartitionKey, Errors> {
     let Some(where_clause) = where_clause else {
         return Err(Errors::SyntaxError(String::from(
             "Where clause must be defined",
         )));
     };
-    let mut partition_key = Vec::new();
+    let mut partition_values = HashMap::new();
     let table_partition = get_table_partition(table_name)?;
-    where_clause.get_primary_key(&mut partition_key, &table_partition)?;
-    if partition_key.len() != table_partition.len() {
+    where_clause.get_primary_key(&mut partition_values, &table_partition)?;
+    if partition_values.len() != table_partition.len() {
         return Err(Errors::SyntaxError(String::from(
             "Full partition key must be defined in where clause",
         )));
     }
-    Ok(partition_key)
+    PartitionKey::from_columns(table_name, |column| partition_values.get(column).cloned())
+}
+
+/// the rows stored in the table with the token of their partition key
+pub fn get_rows_with_tokens(table_name: &String) -> Result<Vec<(Token, Row)>, Errors> {
+    let rows = use_data_access(|data_access| data_access.get_table_rows(table_name))?;
+    let mut tokens = Vec::new();
+    for row in rows {
+        tokens.push((PartitionKey::from_row(table_name, &row)?.token()?, row));
+    }
+    Ok(tokens)
+}
+
+/// the rows stored in the table whose token falls in any of the ranges
+pub fn get_rows_in_ranges(table_name: &String, ranges: &[TokenRange]) -> Result<Vec<Row>, Errors> {
+    Ok(get_rows_with_tokens(table_name)?
+        .into_iter()
+        .filter(|(token, _)| ranges.iter().any(|range| contains(range, *token)))
+        .map(|(_, row)| row)
+        .collect())
 }
 
 /// this is easy access to the own node ip, using ip.txt defined at the start of the node
 pub fn get_own_ip() -> Result<NodeIp, Errors> {
-    let content = fs::read_to_string(IP_FILE).map_err(|e| ServerError(e.to_string()))?;
+    let content = fs::read_to_string(IP_FILE.as_str()).map_err(|e| ServerError(e.to_string()))?;
     let split = content.split(":").collect::<Vec<&str>>();
     let port = split[1].parse::<u16>().unwrap();
     NodeIp::new_from_string(split[0], port)
@@ -253,12 +300,17 @@ pub fn write_all_to_file(file: &mut File, content: &[u8]) -> Result<(), Errors>
         .map_err(|_| ServerError(String::from("Failed to write to file")))
 }
 
+/// returns the lowercase hexadecimal representation of the bytes
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
 pub fn redistribute_data() -> Result<(), Errors> {
     use_node_meta_data(|handler| {
-        handler.update_ranges(NODES_METADATA_PATH)
+        handler.update_ranges(&NODES_METADATA_PATH)
     })?;
     let booting_ips = use_node_meta_data(|handler| {
-        handler.get_booting_ips(NODES_METADATA_PATH)
+        handler.get_booting_ips(&NODES_METADATA_PATH)
     })?;
     for ip in booting_ips {
          MessageSender::send_meta_data(ip)?
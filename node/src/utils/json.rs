@@ -0,0 +1,270 @@
+use crate::meta_data::keyspaces::user_type::UserType;
+use crate::parsers::tokens::data_type::DataType;
+use crate::parsers::tokens::literal::{
+    create_udt_literal, split_udt_value, to_literal, unquote_udt_field, Literal,
+};
+use crate::parsers::tokens::terms::Term;
+use crate::parsers::tokens::token::Token;
+use crate::utils::errors::Errors;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// Turns the document of an `INSERT ... JSON` into columns and literals.
+///
+/// Each key of the object has to be a column of the table and its value is converted
+/// to the type defined for that column. Keys with a `null` value are skipped.
+pub fn json_to_literals(
+    json: &str,
+    columns: &HashMap<String, DataType>,
+    user_types: &HashMap<String, UserType>,
+) -> Result<(Vec<String>, Vec<Literal>), Errors> {
+    let document: Value = serde_json::from_str(json)
+        .map_err(|e| Errors::Invalid(format!("Could not decode JSON string: {}", e)))?;
+    let Value::Object(object) = document else {
+        return Err(Errors::Invalid(String::from(
+            "JSON values must be an object",
+        )));
+    };
+    let mut headers = Vec::new();
+    let mut values = Vec::new();
+    for (key, value) in object {
+        let Some(data_type) = columns.get(&key) else {
+            return Err(Errors::Invalid(format!(
+                "JSON values map contains unrecognized column: {}",
+                key
+            )));
+        };
+        if value.is_null() {
+            continue;
+        }
+        values.push(json_to_literal(&key, &value, data_type, user_types)?);
+        headers.push(key);
+    }
+    Ok((headers, values))
+}
+
+fn json_to_literal(
+    name: &str,
+    value: &Value,
+    data_type: &DataType,
+    user_types: &HashMap<String, UserType>,
+) -> Result<Literal, Errors> {
+    let converted = match (data_type, value) {
+        (DataType::Int, Value::Number(number))
+            if number.as_i64().is_some_and(|int| i32::try_from(int).is_ok()) =>
+        {
+            Some(number.to_string())
+        }
+        (DataType::Int, Value::String(text)) if text.parse::<i32>().is_ok() => Some(text.clone()),
+        (DataType::Decimal, Value::Number(number)) => Some(number.to_string()),
+        (DataType::Decimal, Value::String(text)) if text.parse::<f64>().is_ok() => {
+            Some(text.clone())
+        }
+        (DataType::Boolean, Value::Bool(boolean)) => Some(boolean.to_string()),
+        (DataType::Boolean, Value::String(text))
+            if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("false") =>
+        {
+            Some(text.to_ascii_lowercase())
+        }
+        (DataType::Text | DataType::Duration, Value::String(text)) => Some(text.clone()),
+        (DataType::Date | DataType::Time, Value::String(text)) => {
+            match to_literal(&format!("'{}'", text)) {
+                Some(Token::Term(Term::Literal(literal))) if &literal.data_type == data_type => {
+                    Some(literal.value)
+                }
+                _ => None,
+            }
+        }
+        (DataType::Udt(type_name), Value::Object(object)) => {
+            return json_to_udt_literal(name, type_name, object, user_types)
+        }
+        _ => None,
+    };
+    match converted {
+        Some(converted) => Ok(Literal::new(converted, data_type.clone())),
+        None => Err(Errors::Invalid(format!(
+            "Invalid JSON value for {}: {}",
+            name, value
+        ))),
+    }
+}
+
+fn json_to_udt_literal(
+    name: &str,
+    type_name: &str,
+    object: &Map<String, Value>,
+    user_types: &HashMap<String, UserType>,
+) -> Result<Literal, Errors> {
+    let Some(user_type) = user_types.get(type_name) else {
+        return Err(Errors::Invalid(format!("Type {} not found", type_name)));
+    };
+    let mut fields = Vec::new();
+    for (field, value) in object {
+        let Some(field_type) = user_type.get_field_type(field) else {
+            return Err(Errors::Invalid(format!(
+                "Field {} is not defined in type {}",
+                field, type_name
+            )));
+        };
+        if value.is_null() {
+            continue;
+        }
+        let field_name = format!("{}.{}", name, field);
+        fields.push((
+            field.to_string(),
+            json_to_literal(&field_name, value, field_type, user_types)?,
+        ));
+    }
+    Ok(create_udt_literal(fields))
+}
+
+/// Turns a stored value into its JSON representation according to the type of the column.
+///
+/// Missing values (`None`) are represented as `null`.
+pub fn literal_to_json(value: &str, data_type: &DataType) -> Value {
+    if value == "None" {
+        return Value::Null;
+    }
+    match data_type {
+        DataType::Int | DataType::Decimal => number_or_string(value),
+        DataType::Boolean => match value.parse::<bool>() {
+            Ok(boolean) => Value::Bool(boolean),
+            Err(_) => Value::String(value.to_string()),
+        },
+        DataType::Udt(_) => udt_to_json(value),
+        _ => Value::String(value.to_string()),
+    }
+}
+
+fn udt_to_json(value: &str) -> Value {
+    let Ok(fields) = split_udt_value(value) else {
+        return Value::String(value.to_string());
+    };
+    let mut object = Map::new();
+    for (field, raw_value) in fields {
+        object.insert(field, raw_udt_field_to_json(&raw_value));
+    }
+    Value::Object(object)
+}
+
+/// the fields of a UDT do not keep their type, it is deduced from the shape of the canonical value
+fn raw_udt_field_to_json(raw_value: &str) -> Value {
+    if raw_value.starts_with('{') {
+        return udt_to_json(raw_value);
+    }
+    if raw_value.len() >= 2 && raw_value.starts_with('\'') && raw_value.ends_with('\'') {
+        return Value::String(unquote_udt_field(raw_value));
+    }
+    if let Ok(boolean) = raw_value.parse::<bool>() {
+        return Value::Bool(boolean);
+    }
+    number_or_string(raw_value)
+}
+
+fn number_or_string(value: &str) -> Value {
+    if let Ok(int) = value.parse::<i64>() {
+        return Value::Number(int.into());
+    }
+    match value.parse::<f64>().ok().and_then(Number::from_f64) {
+        Some(number) => Value::Number(number),
+        None => Value::String(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> HashMap<String, DataType> {
+        HashMap::from([
+            ("id".to_string(), DataType::Int),
+            ("name".to_string(), DataType::Text),
+            ("price".to_string(), DataType::Decimal),
+            ("active".to_string(), DataType::Boolean),
+            ("day".to_string(), DataType::Date),
+            ("position".to_string(), DataType::Udt("coordinates".to_string())),
+        ])
+    }
+
+    fn user_types() -> HashMap<String, UserType> {
+        HashMap::from([(
+            "coordinates".to_string(),
+            UserType::new(vec![
+                ("lat".to_string(), DataType::Decimal),
+                ("city".to_string(), DataType::Text),
+            ]),
+        )])
+    }
+
+    fn literal_of(
+        headers: &[String],
+        values: &[Literal],
+        header: &str,
+    ) -> Option<Literal> {
+        headers
+            .iter()
+            .position(|h| h == header)
+            .map(|index| values[index].clone())
+    }
+
+    #[test]
+    fn test_json_to_literals_coerces_types() {
+        let json = r#"{"id": "3", "name": "Ana", "price": 2.5, "active": true, "day": "2024-10-01", "position": {"lat": 1.5, "city": "Rosario"}}"#;
+        let (headers, values) = json_to_literals(json, &columns(), &user_types()).unwrap();
+        assert_eq!(
+            literal_of(&headers, &values, "id"),
+            Some(Literal::new("3".to_string(), DataType::Int))
+        );
+        assert_eq!(
+            literal_of(&headers, &values, "price"),
+            Some(Literal::new("2.5".to_string(), DataType::Decimal))
+        );
+        assert_eq!(
+            literal_of(&headers, &values, "active"),
+            Some(Literal::new("true".to_string(), DataType::Boolean))
+        );
+        assert_eq!(
+            literal_of(&headers, &values, "day"),
+            Some(Literal::new("2024-10-01".to_string(), DataType::Date))
+        );
+        let position = literal_of(&headers, &values, "position").unwrap();
+        assert_eq!(position.value, "{city: 'Rosario', lat: 1.5}");
+    }
+
+    #[test]
+    fn test_json_to_literals_skips_null() {
+        let (headers, _) =
+            json_to_literals(r#"{"id": 1, "name": null}"#, &columns(), &user_types()).unwrap();
+        assert_eq!(headers, vec!["id".to_string()]);
+    }
+
+    #[test]
+    fn test_json_to_literals_unknown_column() {
+        assert!(json_to_literals(r#"{"id": 1, "other": 2}"#, &columns(), &user_types()).is_err());
+    }
+
+    #[test]
+    fn test_json_to_literals_invalid_value() {
+        assert!(json_to_literals(r#"{"id": "one"}"#, &columns(), &user_types()).is_err());
+        assert!(json_to_literals(r#"{"id": 2147483648}"#, &columns(), &user_types()).is_err());
+        assert!(json_to_literals(r#"{"id": "-2147483649"}"#, &columns(), &user_types()).is_err());
+        assert!(json_to_literals(r#"{"name": 4}"#, &columns(), &user_types()).is_err());
+        assert!(json_to_literals("[1, 2]", &columns(), &user_types()).is_err());
+    }
+
+    #[test]
+    fn test_literal_to_json() {
+        assert_eq!(literal_to_json("3", &DataType::Int), Value::from(3));
+        assert_eq!(literal_to_json("2.5", &DataType::Decimal), Value::from(2.5));
+        assert_eq!(literal_to_json("false", &DataType::Boolean), Value::Bool(false));
+        assert_eq!(literal_to_json("Ana", &DataType::Text), Value::from("Ana"));
+        assert_eq!(literal_to_json("None", &DataType::Text), Value::Null);
+        assert_eq!(
+            literal_to_json(
+                "{lat: 1.5, city: 'Rosario'}",
+                &DataType::Udt("coordinates".to_string())
+            ),
+            serde_json::json!({"lat": 1.5, "city": "Rosario"})
+        );
+    }
+}
@@ -1,8 +1,11 @@
+pub mod bound_values;
+pub mod compression;
 pub mod config_constants;
 pub mod consistency_level;
 pub mod constants;
 pub mod errors;
 pub mod functions;
+pub mod json;
 pub mod parser_constants;
 pub mod response;
 pub mod test_functions;
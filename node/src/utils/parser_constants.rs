@@ -42,11 +42,25 @@ pub const SPACE: &str = " ";
 pub const EXISTS: &str = "EXISTS";
 pub const IF: &str = "IF";
 pub const INTO: &str = "INTO";
+pub const JSON: &str = "JSON";
 pub const VALUES: &str = "VALUES";
 pub const ORDER: &str = "ORDER";
 pub const COMMA: &str = ",";
 pub const KEYSPACE: &str = "KEYSPACE";
 pub const TABLE: &str = "TABLE";
+pub const TABLES: &str = "TABLES";
+pub const DESCRIBE: &str = "DESCRIBE";
+pub const KEYSPACES: &str = "KEYSPACES";
+pub const TYPE: &str = "TYPE";
+pub const BEGIN: &str = "BEGIN";
+pub const APPLY: &str = "APPLY";
+pub const BATCH_KEYWORD: &str = "BATCH";
+pub const LOGGED: &str = "LOGGED";
+pub const UNLOGGED: &str = "UNLOGGED";
+pub const COUNTER: &str = "COUNTER";
+pub const INSERT: &str = "INSERT";
+pub const UPDATE: &str = "UPDATE";
+pub const DELETE: &str = "DELETE";
 pub const DOLLAR: char = '$';
 pub const DOUBLE_QUOTE: char = '"';
 pub const SINGLE_QUOTE: char = '\'';
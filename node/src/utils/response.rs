@@ -1,5 +1,9 @@
+use super::json::literal_to_json;
+use super::types::bytes_cursor::BytesCursor;
 use super::{constants::KEYSPACE_METADATA_PATH, errors::Errors};
 use crate::data_access::column::Column;
+use crate::meta_data::keyspaces::user_type::UserType;
+use crate::parsers::tokens::literal::get_udt_field;
 use crate::meta_data::meta_data_handler::use_keyspace_meta_data;
 use crate::{
     data_access::row::Row, parsers::tokens::data_type::DataType,
@@ -7,6 +11,11 @@ use crate::{
 };
 use std::collections::HashMap;
 
+/// Flags of the metadata of a ROWS result.
+const HAS_MORE_PAGES: i32 = 0x0002;
+const NO_METADATA: i32 = 0x0004;
+const SCHEMA_CHANGE: i32 = 0x0005;
+
 pub struct Response;
 
 impl Response {
@@ -29,13 +38,21 @@ impl Response {
         options: &str,
     ) -> Result<Vec<u8>, Errors> {
         let mut encoder = TypesToBytes::default();
-        encoder.write_int(0x0005)?;
+        encoder.write_int(SCHEMA_CHANGE)?;
         encoder.write_string(change_type)?;
         encoder.write_string(target)?;
         encoder.write_string(options)?;
         Ok(encoder.into_bytes())
     }
 
+    /// returns the `<change_type><target><options>` of a SCHEMA_CHANGE result, if it is one
+    pub fn schema_change_body(result: &[u8]) -> Option<&[u8]> {
+        match result.split_first_chunk::<4>() {
+            Some((kind, body)) if i32::from_be_bytes(*kind) == SCHEMA_CHANGE => Some(body),
+            _ => None,
+        }
+    }
+
     pub fn protocol_row(
         rows: Vec<Row>,
         keyspace: &str,
@@ -63,9 +80,8 @@ impl Response {
         let map_type = get_column(keyspace, table)?;
         for header in &headers {
             encoder.write_string(header)?;
-            if let Some(data_type) = map_type.get(header) {
-                let data_type_id = Response::data_type_to_byte(data_type.clone());
-                encoder.write_i16(data_type_id)?;
+            if let Some(data_type) = get_header_data_type(keyspace, header, &map_type)? {
+                Response::write_type_option(encoder, keyspace, &data_type)?;
             }
         }
         let mut row_count: i32 = 0;
@@ -80,15 +96,196 @@ impl Response {
                 continue;
             }
             for header in &headers {
-                match row.get_some_column(header) {
-                    Ok(column) => encoder.write_string(&column.value.value)?,
-                    _ => encoder.write_string("None")?,
+                match get_header_value(row, header)? {
+                    Some(value) => encoder.write_string(&value)?,
+                    None => encoder.write_string("None")?,
                 }
             }
         }
         Ok(())
     }
 
+    /// writes a protocol rows response where every column is text
+    pub fn text_rows(
+        keyspace: &str,
+        table: &str,
+        headers: &[&str],
+        rows: &[Vec<String>],
+    ) -> Result<Vec<u8>, Errors> {
+        let mut encoder = TypesToBytes::default();
+        encoder.write_int(0x0002)?;
+        encoder.write_int(0x0001)?;
+        encoder.write_int(headers.len() as i32)?;
+        encoder.write_string(keyspace)?;
+        encoder.write_string(table)?;
+        for header in headers {
+            encoder.write_string(header)?;
+            encoder.write_i16(Response::data_type_to_byte(DataType::Text))?;
+        }
+        encoder.write_int(rows.len() as i32)?;
+        for row in rows {
+            for value in row {
+                encoder.write_string(value)?;
+            }
+        }
+        Ok(encoder.into_bytes())
+    }
+
+    /// writes a PREPARED result: the statement id, the metadata of its bind variables
+    /// (with the indexes of the ones that form the partition key) and the metadata of the
+    /// rows it returns, if it returns any
+    pub fn prepared(
+        id: &[u8],
+        keyspace: &str,
+        table: &str,
+        bind_variables: &[(String, DataType)],
+        pk_indexes: &[u16],
+        result_columns: Option<&[(String, DataType)]>,
+    ) -> Result<Vec<u8>, Errors> {
+        let mut encoder = TypesToBytes::default();
+        encoder.write_int(0x0004)?;
+        encoder.write_short(id.len() as u16)?;
+        encoder.write_bytes(id);
+        let has_table = !table.is_empty();
+        encoder.write_int(if has_table { 0x0001 } else { 0x0000 })?;
+        encoder.write_int(bind_variables.len() as i32)?;
+        encoder.write_int(pk_indexes.len() as i32)?;
+        for index in pk_indexes {
+            encoder.write_short(*index)?;
+        }
+        if has_table {
+            encoder.write_string(keyspace)?;
+            encoder.write_string(table)?;
+        }
+        for (name, data_type) in bind_variables {
+            encoder.write_string(name)?;
+            Response::write_type_option(&mut encoder, keyspace, data_type)?;
+        }
+        let Some(columns) = result_columns else {
+            encoder.write_int(0x0004)?;
+            encoder.write_int(0)?;
+            return Ok(encoder.into_bytes());
+        };
+        encoder.write_int(0x0001)?;
+        encoder.write_int(columns.len() as i32)?;
+        encoder.write_string(keyspace)?;
+        encoder.write_string(table)?;
+        for (name, data_type) in columns {
+            encoder.write_string(name)?;
+            Response::write_type_option(&mut encoder, keyspace, data_type)?;
+        }
+        Ok(encoder.into_bytes())
+    }
+
+    /// converts a protocol rows response into the `SELECT JSON` format: a single `[json]`
+    /// text column with one JSON object per row, its keys in the order the columns were selected
+    pub fn json_rows(protocol_rows: &[u8]) -> Result<Vec<u8>, Errors> {
+        let mut cursor = BytesCursor::new(protocol_rows);
+        let kind = cursor.read_int()?;
+        if kind != 0x0002 {
+            return Ok(protocol_rows.to_vec());
+        }
+        let flags = cursor.read_int()?;
+        let columns_count = cursor.read_int()?;
+        let keyspace = cursor.read_string()?;
+        let table = cursor.read_string()?;
+        let mut columns = Vec::new();
+        for _ in 0..columns_count {
+            let name = cursor.read_string()?;
+            columns.push((name, read_type_option(&mut cursor)?));
+        }
+        let rows_count = cursor.read_int()?;
+        let mut encoder = TypesToBytes::default();
+        encoder.write_int(0x0002)?;
+        encoder.write_int(flags)?;
+        encoder.write_int(1)?;
+        encoder.write_string(&keyspace)?;
+        encoder.write_string(&table)?;
+        encoder.write_string("[json]")?;
+        encoder.write_i16(Response::data_type_to_byte(DataType::Text))?;
+        encoder.write_int(rows_count)?;
+        for _ in 0..rows_count {
+            // serde_json::Map sorts its keys, the object is written by hand to keep the order
+            let mut fields = Vec::new();
+            for (name, data_type) in &columns {
+                let value = cursor.read_string()?;
+                let key = serde_json::Value::from(name.as_str());
+                fields.push(format!("{}:{}", key, literal_to_json(&value, data_type)));
+            }
+            encoder.write_string(&format!("{{{}}}", fields.join(",")))?;
+        }
+        Ok(encoder.into_bytes())
+    }
+
+    /// applies the query parameters to a protocol rows response: returns only the page that
+    /// starts at the paging state, with the paging state of the next one if there are rows left,
+    /// and leaves out the columns metadata if the client asked to skip it
+    pub fn page_rows(
+        protocol_rows: &[u8],
+        skip_metadata: bool,
+        page_size: Option<i32>,
+        paging_state: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Errors> {
+        let mut cursor = BytesCursor::new(protocol_rows);
+        if cursor.read_int()? != 0x0002
+            || (!skip_metadata && page_size.is_none() && paging_state.is_none())
+        {
+            return Ok(protocol_rows.to_vec());
+        }
+        let flags = cursor.read_int()?;
+        let columns_count = cursor.read_int()?;
+        let mut metadata = TypesToBytes::default();
+        metadata.write_string(&cursor.read_string()?)?;
+        metadata.write_string(&cursor.read_string()?)?;
+        for _ in 0..columns_count {
+            metadata.write_string(&cursor.read_string()?)?;
+            copy_type_option(&mut cursor, &mut metadata)?;
+        }
+        let rows_count = cursor.read_int()?.max(0) as usize;
+        let start = match paging_state {
+            Some(state) => match <[u8; 4]>::try_from(state) {
+                Ok(offset) => i32::from_be_bytes(offset).max(0) as usize,
+                Err(_) => return Err(Errors::ProtocolError(String::from("Invalid paging state"))),
+            },
+            None => 0,
+        };
+        let end = match page_size {
+            Some(size) => rows_count.min(start.saturating_add(size as usize)),
+            None => rows_count,
+        };
+        let mut rows = TypesToBytes::default();
+        for row in 0..rows_count {
+            for _ in 0..columns_count {
+                let value = cursor.read_string()?;
+                if (start..end).contains(&row) {
+                    rows.write_string(&value)?;
+                }
+            }
+        }
+
+        let mut encoder = TypesToBytes::default();
+        encoder.write_int(0x0002)?;
+        let mut new_flags = flags & !(HAS_MORE_PAGES | NO_METADATA);
+        if end < rows_count {
+            new_flags |= HAS_MORE_PAGES;
+        }
+        if skip_metadata {
+            new_flags |= NO_METADATA;
+        }
+        encoder.write_int(new_flags)?;
+        encoder.write_int(columns_count)?;
+        if end < rows_count {
+            encoder.write_int(4)?;
+            encoder.write_int(end as i32)?;
+        }
+        if !skip_metadata {
+            encoder.write_bytes(&metadata.into_bytes());
+        }
+        encoder.write_int(end.saturating_sub(start) as i32)?;
+        encoder.write_bytes(&rows.into_bytes());
+        Ok(encoder.into_bytes())
+    }
+
     pub fn rows(
         rows: Vec<Row>,
         keyspace: &str,
@@ -124,8 +321,7 @@ impl Response {
         for column in columns {
             encoder.write_string(&column.column_name)?;
             encoder.write_string(&column.value.value)?;
-            let data_type_id = Response::data_type_to_byte(column.value.data_type.clone());
-            encoder.write_i16(data_type_id)?;
+            Response::write_data_type(encoder, &column.value.data_type)?;
             encoder.write_i64(column.timestamp.timestamp)?;
         }
         Ok(())
@@ -143,8 +339,7 @@ impl Response {
         encoder.write_short(pks.len() as u16)?;
         for (pk, type_) in pks {
             encoder.write_string(&pk)?;
-            let data_type_id = Response::data_type_to_byte(type_);
-            encoder.write_i16(data_type_id)?;
+            Response::write_data_type(encoder, &type_)?;
         }
         encoder.write_short(columns.len() as u16)?;
         for name in columns {
@@ -153,6 +348,35 @@ impl Response {
         Ok(())
     }
 
+    /// writes the data type id used between nodes, followed by the type name for user types
+    pub fn write_data_type(encoder: &mut TypesToBytes, data_type: &DataType) -> Result<(), Errors> {
+        encoder.write_i16(Response::data_type_to_byte(data_type.clone()))?;
+        if let DataType::Udt(name) = data_type {
+            encoder.write_string(name)?;
+        }
+        Ok(())
+    }
+
+    /// writes the protocol type option. User types carry keyspace, name and their fields
+    fn write_type_option(
+        encoder: &mut TypesToBytes,
+        keyspace: &str,
+        data_type: &DataType,
+    ) -> Result<(), Errors> {
+        encoder.write_i16(Response::data_type_to_byte(data_type.clone()))?;
+        if let DataType::Udt(name) = data_type {
+            let user_type = get_user_type(keyspace, name)?;
+            encoder.write_string(keyspace)?;
+            encoder.write_string(name)?;
+            encoder.write_short(user_type.fields.len() as u16)?;
+            for (field, field_type) in &user_type.fields {
+                encoder.write_string(field)?;
+                Response::write_type_option(encoder, keyspace, field_type)?;
+            }
+        }
+        Ok(())
+    }
+
     fn data_type_to_byte(data: DataType) -> i16 {
         match data {
             DataType::Boolean => 0x0004,  // Código de tipo para `BOOLEAN`
@@ -162,6 +386,7 @@ impl Response {
             DataType::Int => 0x0009,      // Código de tipo para `INT`
             DataType::Text => 0x000A,     // Código de tipo para `TEXT`
             DataType::Time => 0x000C,     // Código de tipo para `TIME`
+            DataType::Udt(_) => 0x0030,   // Type code for `UDT`
         }
     }
 }
@@ -181,6 +406,86 @@ fn get_column(keyspace: &str, table: &str) -> Result<HashMap<String, DataType>,
     })
 }
 
+fn get_user_type(keyspace: &str, type_name: &str) -> Result<UserType, Errors> {
+    use_keyspace_meta_data(|handler| {
+        handler.get_type(KEYSPACE_METADATA_PATH.to_string(), keyspace, type_name)
+    })
+}
+
+/// returns the data type of a header, resolving `column.field` accesses on user types
+pub fn get_header_data_type(
+    keyspace: &str,
+    header: &str,
+    map_type: &HashMap<String, DataType>,
+) -> Result<Option<DataType>, Errors> {
+    if let Some(data_type) = map_type.get(header) {
+        return Ok(Some(data_type.clone()));
+    }
+    let Some((column, field)) = header.split_once('.') else {
+        return Ok(None);
+    };
+    let Some(DataType::Udt(type_name)) = map_type.get(column) else {
+        return Ok(None);
+    };
+    Ok(get_user_type(keyspace, type_name)?
+        .get_field_type(field)
+        .cloned())
+}
+
+/// returns the value of a header in the row, resolving `column.field` accesses on user types
+fn get_header_value(row: &Row, header: &String) -> Result<Option<String>, Errors> {
+    if let Ok(column) = row.get_some_column(header) {
+        return Ok(Some(column.value.value));
+    }
+    let Some((column, field)) = header.split_once('.') else {
+        return Ok(None);
+    };
+    match row.get_some_column(&column.to_string()) {
+        Ok(column) => get_udt_field(&column.value.value, field),
+        _ => Ok(None),
+    }
+}
+
+/// copies a protocol type option, including the fields of user types
+fn copy_type_option(cursor: &mut BytesCursor, encoder: &mut TypesToBytes) -> Result<(), Errors> {
+    let type_id = cursor.read_i16()?;
+    encoder.write_i16(type_id)?;
+    if type_id == 0x0030 {
+        encoder.write_string(&cursor.read_string()?)?;
+        encoder.write_string(&cursor.read_string()?)?;
+        let fields = cursor.read_short()?;
+        encoder.write_i16(fields)?;
+        for _ in 0..fields {
+            encoder.write_string(&cursor.read_string()?)?;
+            copy_type_option(cursor, encoder)?;
+        }
+    }
+    Ok(())
+}
+
+/// reads a protocol type option, skipping the metadata of user types
+fn read_type_option(cursor: &mut BytesCursor) -> Result<DataType, Errors> {
+    let data_type = match cursor.read_i16()? {
+        0x0004 => DataType::Boolean,
+        0x000B => DataType::Date,
+        0x0006 => DataType::Decimal,
+        0x000F => DataType::Duration,
+        0x0009 => DataType::Int,
+        0x000C => DataType::Time,
+        0x0030 => {
+            cursor.read_string()?;
+            let name = cursor.read_string()?;
+            for _ in 0..cursor.read_short()? {
+                cursor.read_string()?;
+                read_type_option(cursor)?;
+            }
+            DataType::Udt(name)
+        }
+        _ => DataType::Text,
+    };
+    Ok(data_type)
+}
+
 fn filter_keys(vec: Vec<String>, map: HashMap<String, DataType>) -> HashMap<String, DataType> {
     let mut result: HashMap<String, DataType> = HashMap::new();
     for elem in vec {
@@ -193,6 +498,7 @@ fn filter_keys(vec: Vec<String>, map: HashMap<String, DataType>) -> HashMap<Stri
 
 #[cfg(test)]
 mod tests {
+    use crate::utils::types::bytes_cursor::BytesCursor;
     use crate::{parsers::tokens::data_type::DataType, utils::response::Response};
 
     #[test]
@@ -224,10 +530,158 @@ mod tests {
         assert!(body.contains("options"));
     }
 
+    #[test]
+    fn test_schema_change_body() {
+        let result = Response::schema_change("DROPPED", "KEYSPACE", "ks").unwrap();
+        assert_eq!(Response::schema_change_body(&result), Some(&result[4..]));
+        assert!(Response::schema_change_body(&Response::void().unwrap()).is_none());
+    }
+
     #[test]
     fn test_data_type_to_byte() {
         assert_eq!(Response::data_type_to_byte(DataType::Boolean), 0x0004);
         assert_eq!(Response::data_type_to_byte(DataType::Int), 0x0009);
         assert_eq!(Response::data_type_to_byte(DataType::Text), 0x000A);
     }
+
+    #[test]
+    fn test_write_udt_data_type() {
+        let mut encoder = crate::utils::types_to_bytes::TypesToBytes::default();
+        Response::write_data_type(&mut encoder, &DataType::Udt("point".to_string())).unwrap();
+        assert_eq!(
+            encoder.into_bytes(),
+            vec![0x00, 0x30, 0x00, 0x05, b'p', b'o', b'i', b'n', b't']
+        );
+    }
+
+    #[test]
+    fn test_prepared_without_result_metadata() {
+        let bind_variables = vec![("id".to_string(), DataType::Int)];
+        let bytes =
+            Response::prepared(&[0xAB, 0xCD], "kp", "users", &bind_variables, &[0], None).unwrap();
+        let mut cursor = BytesCursor::new(bytes.as_slice());
+        assert_eq!(cursor.read_int().unwrap(), 0x0004);
+        assert_eq!(cursor.read_short_bytes().unwrap(), Some(vec![0xAB, 0xCD]));
+        assert_eq!(cursor.read_int().unwrap(), 0x0001);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_short().unwrap(), 0);
+        assert_eq!(cursor.read_string().unwrap(), "kp");
+        assert_eq!(cursor.read_string().unwrap(), "users");
+        assert_eq!(cursor.read_string().unwrap(), "id");
+        assert_eq!(cursor.read_i16().unwrap(), 0x0009);
+        assert_eq!(cursor.read_int().unwrap(), 0x0004);
+        assert_eq!(cursor.read_int().unwrap(), 0);
+        assert!(cursor.is_empty());
+    }
+
+    #[test]
+    fn test_json_rows() {
+        let mut encoder = crate::utils::types_to_bytes::TypesToBytes::default();
+        encoder.write_int(0x0002).unwrap();
+        encoder.write_int(0x0001).unwrap();
+        encoder.write_int(2).unwrap();
+        encoder.write_string("kp").unwrap();
+        encoder.write_string("users").unwrap();
+        encoder.write_string("id").unwrap();
+        encoder.write_i16(0x0009).unwrap();
+        encoder.write_string("name").unwrap();
+        encoder.write_i16(0x000A).unwrap();
+        encoder.write_int(1).unwrap();
+        encoder.write_string("1").unwrap();
+        encoder.write_string("None").unwrap();
+
+        let json = Response::json_rows(&encoder.into_bytes()).unwrap();
+        let mut cursor = crate::utils::types::bytes_cursor::BytesCursor::new(&json);
+        assert_eq!(cursor.read_int().unwrap(), 0x0002);
+        assert_eq!(cursor.read_int().unwrap(), 0x0001);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_string().unwrap(), "kp");
+        assert_eq!(cursor.read_string().unwrap(), "users");
+        assert_eq!(cursor.read_string().unwrap(), "[json]");
+        assert_eq!(cursor.read_i16().unwrap(), 0x000A);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_string().unwrap(), r#"{"id":1,"name":null}"#);
+    }
+
+    #[test]
+    fn test_json_rows_keep_column_order() {
+        let mut encoder = crate::utils::types_to_bytes::TypesToBytes::default();
+        encoder.write_int(0x0002).unwrap();
+        encoder.write_int(0x0001).unwrap();
+        encoder.write_int(2).unwrap();
+        encoder.write_string("kp").unwrap();
+        encoder.write_string("users").unwrap();
+        encoder.write_string("name").unwrap();
+        encoder.write_i16(0x000A).unwrap();
+        encoder.write_string("id").unwrap();
+        encoder.write_i16(0x0009).unwrap();
+        encoder.write_int(1).unwrap();
+        encoder.write_string("Ana").unwrap();
+        encoder.write_string("1").unwrap();
+
+        let json = Response::json_rows(&encoder.into_bytes()).unwrap();
+        let mut cursor = crate::utils::types::bytes_cursor::BytesCursor::new(&json);
+        cursor.read_int().unwrap();
+        cursor.read_int().unwrap();
+        cursor.read_int().unwrap();
+        cursor.read_string().unwrap();
+        cursor.read_string().unwrap();
+        cursor.read_string().unwrap();
+        cursor.read_i16().unwrap();
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_string().unwrap(), r#"{"name":"Ana","id":1}"#);
+    }
+
+    fn id_rows(count: i32) -> Vec<u8> {
+        let mut encoder = crate::utils::types_to_bytes::TypesToBytes::default();
+        encoder.write_int(0x0002).unwrap();
+        encoder.write_int(0x0001).unwrap();
+        encoder.write_int(1).unwrap();
+        encoder.write_string("kp").unwrap();
+        encoder.write_string("users").unwrap();
+        encoder.write_string("id").unwrap();
+        encoder.write_i16(0x0009).unwrap();
+        encoder.write_int(count).unwrap();
+        for id in 0..count {
+            encoder.write_string(&id.to_string()).unwrap();
+        }
+        encoder.into_bytes()
+    }
+
+    #[test]
+    fn test_page_rows() {
+        let page = Response::page_rows(&id_rows(5), false, Some(2), None).unwrap();
+        let mut cursor = BytesCursor::new(&page);
+        assert_eq!(cursor.read_int().unwrap(), 0x0002);
+        assert_eq!(cursor.read_int().unwrap(), 0x0003);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        let paging_state = cursor.read_bytes().unwrap().unwrap();
+        assert_eq!(paging_state, 2i32.to_be_bytes().to_vec());
+        assert_eq!(cursor.read_string().unwrap(), "kp");
+        assert_eq!(cursor.read_string().unwrap(), "users");
+        assert_eq!(cursor.read_string().unwrap(), "id");
+        assert_eq!(cursor.read_i16().unwrap(), 0x0009);
+        assert_eq!(cursor.read_int().unwrap(), 2);
+        assert_eq!(cursor.read_string().unwrap(), "0");
+        assert_eq!(cursor.read_string().unwrap(), "1");
+
+        let last = Response::page_rows(&id_rows(5), true, Some(4), Some(&paging_state)).unwrap();
+        let mut cursor = BytesCursor::new(&last);
+        assert_eq!(cursor.read_int().unwrap(), 0x0002);
+        assert_eq!(cursor.read_int().unwrap(), 0x0005);
+        assert_eq!(cursor.read_int().unwrap(), 1);
+        assert_eq!(cursor.read_int().unwrap(), 3);
+        assert_eq!(cursor.read_string().unwrap(), "2");
+        assert_eq!(cursor.read_string().unwrap(), "3");
+        assert_eq!(cursor.read_string().unwrap(), "4");
+        assert!(cursor.is_empty());
+    }
+
+    #[test]
+    fn test_page_rows_without_parameters() {
+        let rows = id_rows(3);
+        assert_eq!(Response::page_rows(&rows, false, None, None).unwrap(), rows);
+        assert!(Response::page_rows(&rows, false, Some(1), Some(&[1, 2])).is_err());
+    }
 }
@@ -3,6 +3,7 @@ use crate::data_access::row::Row;
 use crate::meta_data::meta_data_handler::MetaDataHandler;
 use crate::parsers::query_parser::{query_lexer, query_parser};
 use crate::read_reparation::row_response::RowResponse;
+use crate::utils::constants::IP_FILE;
 use crate::utils::errors::Errors;
 use crate::utils::types::node_ip::NodeIp;
 use std::fs::File;
@@ -10,10 +11,12 @@ use std::io::Write;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Once;
 use std::thread;
+use std::time::Duration;
 
 static INIT: Once = Once::new();
 static FINISHED: AtomicUsize = AtomicUsize::new(0);
-const INTEGRATION_TESTS_QUANTITY: usize = 66;
+const LISTENER_RETRIES: usize = 100;
+const INTEGRATION_TESTS_QUANTITY: usize = 73;
 
 pub fn add_one_finished() {
     FINISHED.fetch_add(1, Ordering::SeqCst);
@@ -41,7 +44,7 @@ pub fn setup() {
             MetaDataHandler::start_listening(metadata_ip).unwrap();
         });
 
-        if get_query_result("CREATE KEYSPACE test WITH replication = {'replication_factor' : 1}").is_ok() {
+        if create_test_keyspace().is_ok() {
             get_query_result("CREATE TABLE test.tb1 (id int, name text, second text, PRIMARY KEY(id, name))").unwrap();
             get_query_result("CREATE TABLE test.del (id int, name text, second text, PRIMARY KEY(id, name))").unwrap();
             get_query_result("CREATE TABLE test.upd (id int, name text, age int, height int, PRIMARY KEY(id, name))").unwrap();
@@ -52,6 +55,20 @@ pub fn setup() {
     });
 }
 
+/// creates the test keyspace, waiting for the handlers to start listening
+fn create_test_keyspace() -> Result<Vec<u8>, Errors> {
+    let query = "CREATE KEYSPACE test WITH replication = {'replication_factor' : 1}";
+    for _ in 0..LISTENER_RETRIES {
+        match get_query_result(query) {
+            Err(Errors::ServerError(msg)) if msg == "Error connecting to socket." => {
+                thread::sleep(Duration::from_millis(10))
+            }
+            result => return result,
+        }
+    }
+    get_query_result(query)
+}
+
 pub fn teardown() {
     let query = "DROP KEYSPACE test".to_string();
     let tokens = query_lexer(query).unwrap();
@@ -60,7 +77,7 @@ pub fn teardown() {
 }
 
 pub fn store_ip(ip: &NodeIp) -> Result<(), Errors> {
-    let mut file = File::create("src/utils/ip.txt").expect("Error creating file");
+    let mut file = File::create(IP_FILE.as_str()).expect("Error creating file");
     file.write_all(ip.get_string_ip().as_bytes())
         .expect("Error writing to file");
     Ok(())
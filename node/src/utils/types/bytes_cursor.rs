@@ -1,5 +1,7 @@
 use crate::utils::consistency_level::ConsistencyLevel;
 use crate::utils::errors::Errors;
+use crate::utils::types::query_parameters::BoundValue;
+use cql_types::TypeOptionReader;
 use std::collections::HashMap;
 use std::io::{Cursor, Read};
 
@@ -19,6 +21,11 @@ impl BytesCursor {
         }
     }
 
+    /// returns true if every byte was already read
+    pub fn is_empty(&self) -> bool {
+        self.cursor.position() as usize >= self.cursor.get_ref().len()
+    }
+
     pub fn read_u8(&mut self) -> Result<u8, Errors> {
         let mut buf = [0u8; 1];
         self.cursor
@@ -124,6 +131,31 @@ impl BytesCursor {
         Ok(map)
     }
 
+    /// reads a `[string list]`: `<n: short>` followed by n `[string]`
+    pub fn read_string_list(&mut self) -> Result<Vec<String>, Errors> {
+        let n = self.read_short()?;
+        let mut list = Vec::new();
+        for _ in 0..n {
+            list.push(self.read_string()?);
+        }
+        Ok(list)
+    }
+
+    /// reads a `[string multimap]`: `<n: short>` followed by n `<string><string list>` pairs
+    pub fn read_string_multimap(&mut self) -> Result<HashMap<String, Vec<String>>, Errors> {
+        let n = self.read_short()?;
+        let mut map = HashMap::new();
+        for _ in 0..n {
+            let key = self.read_string()?;
+            let mut values = Vec::new();
+            for _ in 0..self.read_short()? {
+                values.push(self.read_string()?);
+            }
+            map.insert(key, values);
+        }
+        Ok(map)
+    }
+
     pub fn read_bytes(&mut self) -> Result<Option<Vec<u8>>, Errors> {
         let n = self.read_int()?;
         match n {
@@ -140,6 +172,38 @@ impl BytesCursor {
         }
     }
 
+    /// reads a `[bytes map]`: `<n: short>` followed by n `<string><bytes>` pairs
+    pub fn read_bytes_map(&mut self) -> Result<HashMap<String, Vec<u8>>, Errors> {
+        let n = self.read_short()?;
+        let mut map = HashMap::new();
+        for _ in 0..n {
+            let key = self.read_string()?;
+            map.insert(key, self.read_bytes()?.unwrap_or_default());
+        }
+        Ok(map)
+    }
+
+    /// reads a `[value]`: `[bytes]` where a length of -1 is null and -2 is not set
+    pub fn read_value(&mut self) -> Result<BoundValue, Errors> {
+        let n = self.read_int()?;
+        match n {
+            -1 => Ok(BoundValue::Null),
+            -2 => Ok(BoundValue::Unset),
+            _ if n < 0 => Err(Errors::ProtocolError(format!("Invalid value length {}", n))),
+            _ => Ok(BoundValue::Value(self.read_exact(n as usize)?)),
+        }
+    }
+
+    /// reads `<n: short>` followed by n `[value]`
+    pub fn read_values(&mut self) -> Result<Vec<BoundValue>, Errors> {
+        let n = self.read_short()?;
+        let mut values = Vec::new();
+        for _ in 0..n {
+            values.push(self.read_value()?);
+        }
+        Ok(values)
+    }
+
     pub fn read_bool(&mut self) -> Result<bool, Errors> {
         let byte = self.read_u8()?; // Lee un byte
         match byte {
@@ -150,6 +214,22 @@ impl BytesCursor {
     }
 }
 
+impl TypeOptionReader for BytesCursor {
+    type Error = Errors;
+
+    fn read_i16(&mut self) -> Result<i16, Errors> {
+        self.read_i16()
+    }
+
+    fn read_short(&mut self) -> Result<i16, Errors> {
+        self.read_short()
+    }
+
+    fn read_string(&mut self) -> Result<String, Errors> {
+        self.read_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,6 +330,20 @@ mod tests {
         assert_eq!(cursor.read_string_map().unwrap(), expected);
     }
 
+    #[test]
+    fn test_read_string_list() {
+        let bytes = vec![
+            0x00, 0x02, // n
+            0x00, 0x02, b'u', b'p', // first
+            0x00, 0x04, b'd', b'o', b'w', b'n', // second
+        ];
+        let mut cursor = BytesCursor::new(&bytes);
+        assert_eq!(
+            cursor.read_string_list().unwrap(),
+            vec!["up".to_string(), "down".to_string()]
+        );
+    }
+
     #[test]
     fn test_read_bytes() {
         let data = vec![
@@ -1,5 +1,22 @@
+use crate::utils::compression::Compression;
 use crate::utils::errors::Errors;
 use crate::utils::types::bytes_cursor::BytesCursor;
+use std::collections::HashMap;
+
+/// Protocol versions the node accepts.
+pub const MIN_VERSION: u8 = 0x03;
+pub const MAX_VERSION: u8 = 0x05;
+pub const RESPONSE_DIRECTION: u8 = 0x80;
+/// stream of the EVENT messages, which the server sends without a request
+pub const EVENT_STREAM: i16 = -1;
+
+pub const COMPRESSION_FLAG: u8 = 0x01;
+pub const TRACING_FLAG: u8 = 0x02;
+pub const CUSTOM_PAYLOAD_FLAG: u8 = 0x04;
+pub const WARNING_FLAG: u8 = 0x08;
+pub const USE_BETA_FLAG: u8 = 0x10;
+
+const HEADER_LENGTH: usize = 9;
 
 /// this structure represents a Cassandra protocol frame
 #[derive(Debug, PartialEq, Clone)]
@@ -47,15 +64,20 @@ impl Frame {
         bytes
     }
 
+    /// returns true if the node speaks the given protocol version
+    pub fn is_supported_version(version: u8) -> bool {
+        (MIN_VERSION..=MAX_VERSION).contains(&version)
+    }
+
     /// validates version, flags and stream of the frame
     pub fn validate_request_frame(&self) -> Result<(), Errors> {
-        if self.version != 0x03 {
+        if !Frame::is_supported_version(self.version) {
             return Err(Errors::ProtocolError(format!(
-                "Version {} is incorrect",
+                "Invalid or unsupported protocol version ({}); supported versions are (3/v3, 4/v4, 5/v5)",
                 self.version
             )));
         }
-        if self.flags != 0x00 {
+        if self.flags & !self.allowed_flags() != 0 {
             return Err(Errors::ProtocolError(format!(
                 "Flag {} is incorrect",
                 self.flags
@@ -68,6 +90,92 @@ impl Frame {
         }
         Ok(())
     }
+
+    fn allowed_flags(&self) -> u8 {
+        match self.version {
+            0x03 => COMPRESSION_FLAG | TRACING_FLAG,
+            0x04 => COMPRESSION_FLAG | TRACING_FLAG | CUSTOM_PAYLOAD_FLAG,
+            _ => TRACING_FLAG | CUSTOM_PAYLOAD_FLAG | USE_BETA_FLAG,
+        }
+    }
+
+    /// decompresses the body if the compression flag is set, the compression must have been negotiated
+    pub fn decompress(&mut self, compression: Option<Compression>) -> Result<(), Errors> {
+        if self.flags & COMPRESSION_FLAG == 0 {
+            return Ok(());
+        }
+        let Some(compression) = compression else {
+            return Err(Errors::ProtocolError(String::from(
+                "Compression was not negotiated",
+            )));
+        };
+        self.body = compression.decompress(&self.body)?;
+        self.length = self.body.len() as u32;
+        self.flags &= !COMPRESSION_FLAG;
+        Ok(())
+    }
+
+    /// compresses the body with the negotiated compression, empty bodies are sent as they are
+    pub fn compress(&mut self, compression: Option<Compression>) -> Result<(), Errors> {
+        let Some(compression) = compression else {
+            return Ok(());
+        };
+        if self.body.is_empty() {
+            return Ok(());
+        }
+        self.body = compression.compress(&self.body)?;
+        self.length = self.body.len() as u32;
+        self.flags |= COMPRESSION_FLAG;
+        Ok(())
+    }
+
+    /// marks the response as traced, the session id goes before the rest of the body
+    pub fn set_tracing_id(&mut self, session_id: &[u8; 16]) {
+        let mut body = session_id.to_vec();
+        body.append(&mut self.body);
+        self.body = body;
+        self.length = self.body.len() as u32;
+        self.flags |= TRACING_FLAG;
+    }
+
+    /// returns the custom payload sent before the body of the request, if any
+    pub fn custom_payload(&self) -> Result<HashMap<String, Vec<u8>>, Errors> {
+        if self.flags & CUSTOM_PAYLOAD_FLAG == 0 {
+            return Ok(HashMap::new());
+        }
+        BytesCursor::new(&self.body).read_bytes_map()
+    }
+
+    /// returns the body of the request without the custom payload
+    pub fn request_body(&self) -> Result<Vec<u8>, Errors> {
+        if self.flags & CUSTOM_PAYLOAD_FLAG == 0 {
+            return Ok(self.body.clone());
+        }
+        let mut cursor = BytesCursor::new(&self.body);
+        cursor.read_bytes_map()?;
+        cursor.read_remaining_bytes()
+    }
+
+    /// splits a stream of bytes that carries several whole frames, as the payload of the v5 segments
+    pub fn split_frames(bytes: &[u8]) -> Result<Vec<Vec<u8>>, Errors> {
+        let mut frames = Vec::new();
+        let mut start = 0;
+        while start < bytes.len() {
+            let Some(header) = bytes.get(start..start + HEADER_LENGTH) else {
+                return Err(Errors::ProtocolError(String::from(
+                    "Incomplete frame header",
+                )));
+            };
+            let length = u32::from_be_bytes([header[5], header[6], header[7], header[8]]) as usize;
+            let end = start + HEADER_LENGTH + length;
+            let Some(frame) = bytes.get(start..end) else {
+                return Err(Errors::ProtocolError(String::from("Incomplete frame body")));
+            };
+            frames.push(frame.to_vec());
+            start = end;
+        }
+        Ok(frames)
+    }
 }
 
 #[cfg(test)]
@@ -88,4 +196,113 @@ mod tests {
         assert_eq!(result.body, vec![16, 3, 53, 18, 34]);
         Ok(())
     }
+
+    fn frame(version: u8, flags: u8, body: Vec<u8>) -> Frame {
+        Frame {
+            version,
+            flags,
+            stream: 1,
+            opcode: 7,
+            length: body.len() as u32,
+            body,
+        }
+    }
+
+    #[test]
+    fn test_supported_versions() {
+        for version in [0x03, 0x04, 0x05] {
+            assert!(frame(version, 0, vec![]).validate_request_frame().is_ok());
+        }
+        for version in [0x01, 0x02, 0x06, 0x83] {
+            assert!(matches!(
+                frame(version, 0, vec![]).validate_request_frame(),
+                Err(Errors::ProtocolError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_flags_by_version() {
+        assert!(frame(0x03, TRACING_FLAG, vec![])
+            .validate_request_frame()
+            .is_ok());
+        assert!(frame(0x03, CUSTOM_PAYLOAD_FLAG, vec![])
+            .validate_request_frame()
+            .is_err());
+        assert!(frame(0x04, CUSTOM_PAYLOAD_FLAG, vec![])
+            .validate_request_frame()
+            .is_ok());
+        assert!(frame(0x04, USE_BETA_FLAG, vec![])
+            .validate_request_frame()
+            .is_err());
+        assert!(frame(0x05, USE_BETA_FLAG, vec![])
+            .validate_request_frame()
+            .is_ok());
+        assert!(frame(0x04, COMPRESSION_FLAG, vec![])
+            .validate_request_frame()
+            .is_ok());
+        assert!(frame(0x05, COMPRESSION_FLAG, vec![])
+            .validate_request_frame()
+            .is_err());
+    }
+
+    #[test]
+    fn test_custom_payload() {
+        let mut body = vec![0x00, 0x01, 0x00, 0x03, b'k', b'e', b'y'];
+        body.extend_from_slice(&2i32.to_be_bytes());
+        body.extend_from_slice(&[0xCA, 0xFE, 0x00, 0x07]);
+        let frame = frame(0x04, CUSTOM_PAYLOAD_FLAG, body);
+        let payload = frame.custom_payload().unwrap();
+        assert_eq!(payload.get("key"), Some(&vec![0xCA, 0xFE]));
+        assert_eq!(frame.request_body().unwrap(), vec![0x00, 0x07]);
+    }
+
+    #[test]
+    fn test_tracing_id_goes_first() {
+        let mut response = frame(0x04, WARNING_FLAG, vec![0, 0]);
+        response.set_tracing_id(&[9; 16]);
+        assert_eq!(response.flags, WARNING_FLAG | TRACING_FLAG);
+        assert_eq!(response.length, 18);
+        assert_eq!(&response.body[..16], &[9; 16]);
+        assert_eq!(&response.body[16..], &[0, 0]);
+    }
+
+    #[test]
+    fn test_split_frames() {
+        let first = frame(0x05, 0, vec![1, 2, 3]).to_bytes();
+        let second = frame(0x05, 0, vec![]).to_bytes();
+        let frames = Frame::split_frames(&[first.clone(), second.clone()].concat()).unwrap();
+        assert_eq!(frames, vec![first.clone(), second]);
+        assert!(Frame::split_frames(&first[..first.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_compressed_body() {
+        let body = b"flight AR1234 departs from EZE ".repeat(10);
+        for compression in [Compression::Lz4, Compression::Snappy] {
+            let mut frame = frame(0x04, 0, body.clone());
+            frame.compress(Some(compression)).unwrap();
+            assert_eq!(frame.flags, COMPRESSION_FLAG);
+            assert_eq!(frame.length as usize, frame.body.len());
+            frame.decompress(Some(compression)).unwrap();
+            assert_eq!(frame.flags, 0);
+            assert_eq!(frame.body, body);
+        }
+    }
+
+    #[test]
+    fn test_compression_not_negotiated() {
+        let mut compressed = frame(
+            0x04,
+            COMPRESSION_FLAG,
+            vec![0x00, 0x00, 0x00, 0x01, 0x10, 0x07],
+        );
+        assert!(matches!(
+            compressed.decompress(None),
+            Err(Errors::ProtocolError(_))
+        ));
+        let mut empty = frame(0x04, 0, vec![]);
+        empty.compress(Some(Compression::Lz4)).unwrap();
+        assert_eq!(empty.flags, 0);
+    }
 }
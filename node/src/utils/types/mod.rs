@@ -2,7 +2,8 @@ pub mod bytes_cursor;
 pub mod frame;
 pub mod node_ip;
 pub mod primary_key;
-pub mod range;
+pub mod query_parameters;
+pub mod segment;
 pub mod timestamp;
 pub mod tls_stream;
 pub mod token_conversor;
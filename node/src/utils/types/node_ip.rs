@@ -1,6 +1,6 @@
 use crate::utils::constants::{
     DATA_ACCESS_PORT_MOD, GOSSIP_MOD, HINTS_RECEIVER_MOD, META_DATA_ACCESS_MOD,
-    QUERY_DELEGATION_PORT_MOD, SEED_LISTENER_MOD,
+    QUERY_DELEGATION_PORT_MOD, REPAIR_MOD, SEED_LISTENER_MOD, STREAMING_MOD,
 };
 use crate::utils::errors::Errors;
 use crate::utils::errors::Errors::ServerError;
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Hash, Eq, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Hash, Eq, PartialOrd, Ord, Clone)]
 pub struct NodeIp {
     ip: IpAddr,
     port: u16,
@@ -80,6 +80,14 @@ impl NodeIp {
         SocketAddr::new(self.ip, self.port + HINTS_RECEIVER_MOD as u16)
     }
 
+    pub fn get_repair_socket(&self) -> SocketAddr {
+        SocketAddr::new(self.ip, self.port + REPAIR_MOD as u16)
+    }
+
+    pub fn get_streaming_socket(&self) -> SocketAddr {
+        SocketAddr::new(self.ip, self.port + STREAMING_MOD as u16)
+    }
+
     pub fn get_string_ip(&self) -> String {
         format!("{}:{}", self.ip, self.port)
     }
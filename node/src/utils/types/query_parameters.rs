@@ -0,0 +1,264 @@
+use crate::utils::errors::Errors;
+use crate::utils::types::bytes_cursor::BytesCursor;
+
+const VALUES_FLAG: u32 = 0x01;
+const SKIP_METADATA_FLAG: u32 = 0x02;
+const PAGE_SIZE_FLAG: u32 = 0x04;
+const PAGING_STATE_FLAG: u32 = 0x08;
+const SERIAL_CONSISTENCY_FLAG: u32 = 0x10;
+const DEFAULT_TIMESTAMP_FLAG: u32 = 0x20;
+const NAMES_FLAG: u32 = 0x40;
+const KEYSPACE_FLAG: u32 = 0x80;
+const NOW_IN_SECONDS_FLAG: u32 = 0x100;
+
+const SERIAL: i16 = 0x0008;
+const LOCAL_SERIAL: i16 = 0x0009;
+
+/// Value of a bind marker. Since v4 a value can be unset, in which case the
+/// column is not modified.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BoundValue {
+    Value(Vec<u8>),
+    Null,
+    Unset,
+}
+
+/// Values sent by the client for the bind markers of the query.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BoundValues {
+    Positional(Vec<BoundValue>),
+    Named(Vec<(String, BoundValue)>),
+}
+
+impl BoundValues {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            BoundValues::Positional(values) => values.is_empty(),
+            BoundValues::Named(values) => values.is_empty(),
+        }
+    }
+
+    fn has_unset(&self) -> bool {
+        match self {
+            BoundValues::Positional(values) => values.contains(&BoundValue::Unset),
+            BoundValues::Named(values) => {
+                values.iter().any(|(_, value)| *value == BoundValue::Unset)
+            }
+        }
+    }
+}
+
+/// Parameters of a QUERY or an EXECUTE: `<consistency><flags>` followed by the fields the
+/// flags indicate.
+///
+/// The timestamp arrives in microseconds and is stored in milliseconds, like the rest of the
+/// timestamps of the node. In v5 the flags take an int instead of a byte.
+#[derive(Debug, PartialEq, Clone)]
+pub struct QueryParameters {
+    pub consistency: i16,
+    pub values: BoundValues,
+    pub skip_metadata: bool,
+    pub page_size: Option<i32>,
+    pub paging_state: Option<Vec<u8>>,
+    pub serial_consistency: Option<i16>,
+    pub timestamp: Option<i64>,
+}
+
+impl QueryParameters {
+    pub fn new(consistency: i16) -> Self {
+        Self {
+            consistency,
+            values: BoundValues::Positional(Vec::new()),
+            skip_metadata: false,
+            page_size: None,
+            paging_state: None,
+            serial_consistency: None,
+            timestamp: None,
+        }
+    }
+
+    /// reads the parameters, the flags are optional so clients that only send the consistency keep working
+    pub fn parse(cursor: &mut BytesCursor, version: u8) -> Result<Self, Errors> {
+        let mut parameters = Self::new(cursor.read_short()?);
+        if cursor.is_empty() {
+            return Ok(parameters);
+        }
+        let flags = if version >= 0x05 {
+            cursor.read_u32()?
+        } else {
+            cursor.read_u8()? as u32
+        };
+        if flags & VALUES_FLAG != 0 {
+            parameters.values = if flags & NAMES_FLAG != 0 {
+                read_named_values(cursor)?
+            } else {
+                BoundValues::Positional(cursor.read_values()?)
+            };
+            if version < 0x04 && parameters.values.has_unset() {
+                return Err(Errors::ProtocolError(String::from(
+                    "Unset values are not supported before protocol v4",
+                )));
+            }
+        }
+        parameters.skip_metadata = flags & SKIP_METADATA_FLAG != 0;
+        if flags & PAGE_SIZE_FLAG != 0 {
+            let page_size = cursor.read_int()?;
+            if page_size <= 0 {
+                return Err(Errors::ProtocolError(String::from(
+                    "The page size must be greater than 0",
+                )));
+            }
+            parameters.page_size = Some(page_size);
+        }
+        if flags & PAGING_STATE_FLAG != 0 {
+            parameters.paging_state = cursor.read_bytes()?;
+        }
+        if flags & SERIAL_CONSISTENCY_FLAG != 0 {
+            let serial_consistency = cursor.read_short()?;
+            if serial_consistency != SERIAL && serial_consistency != LOCAL_SERIAL {
+                return Err(Errors::ProtocolError(format!(
+                    "Invalid serial consistency level: {}",
+                    serial_consistency
+                )));
+            }
+            parameters.serial_consistency = Some(serial_consistency);
+        }
+        if flags & DEFAULT_TIMESTAMP_FLAG != 0 {
+            let timestamp = cursor.read_i64()?;
+            if timestamp == i64::MIN {
+                return Err(Errors::ProtocolError(String::from(
+                    "Invalid default timestamp",
+                )));
+            }
+            parameters.timestamp = Some(timestamp / 1000);
+        }
+        if flags & KEYSPACE_FLAG != 0 {
+            return Err(Errors::Invalid(String::from(
+                "Per query keyspaces are not supported",
+            )));
+        }
+        if flags & NOW_IN_SECONDS_FLAG != 0 {
+            let _now_in_seconds = cursor.read_int()?;
+        }
+        Ok(parameters)
+    }
+}
+
+fn read_named_values(cursor: &mut BytesCursor) -> Result<BoundValues, Errors> {
+    let mut values = Vec::new();
+    for _ in 0..cursor.read_short()? {
+        let name = cursor.read_string()?;
+        values.push((name.to_lowercase(), cursor.read_value()?));
+    }
+    Ok(BoundValues::Named(values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> Result<QueryParameters, Errors> {
+        QueryParameters::parse(&mut BytesCursor::new(bytes), 0x04)
+    }
+
+    #[test]
+    fn test_only_consistency() {
+        let parameters = parse(&[0x00, 0x04]).unwrap();
+        assert_eq!(parameters, QueryParameters::new(0x0004));
+    }
+
+    #[test]
+    fn test_positional_values() {
+        let mut bytes = vec![
+            0x00,
+            0x01,
+            (VALUES_FLAG | SKIP_METADATA_FLAG) as u8,
+            0x00,
+            0x02,
+        ];
+        bytes.extend_from_slice(&4i32.to_be_bytes());
+        bytes.extend_from_slice(&7i32.to_be_bytes());
+        bytes.extend_from_slice(&(-1i32).to_be_bytes());
+        let parameters = parse(&bytes).unwrap();
+        assert_eq!(
+            parameters.values,
+            BoundValues::Positional(vec![
+                BoundValue::Value(7i32.to_be_bytes().to_vec()),
+                BoundValue::Null
+            ])
+        );
+        assert!(parameters.skip_metadata);
+    }
+
+    #[test]
+    fn test_named_values() {
+        let mut bytes = vec![0x00, 0x01, (VALUES_FLAG | NAMES_FLAG) as u8, 0x00, 0x01];
+        bytes.extend_from_slice(&[0x00, 0x04]);
+        bytes.extend_from_slice(b"Name");
+        bytes.extend_from_slice(&3i32.to_be_bytes());
+        bytes.extend_from_slice(b"Ana");
+        let parameters = parse(&bytes).unwrap();
+        assert_eq!(
+            parameters.values,
+            BoundValues::Named(vec![(
+                "name".to_string(),
+                BoundValue::Value(b"Ana".to_vec())
+            )])
+        );
+    }
+
+    #[test]
+    fn test_paging_serial_consistency_and_timestamp() {
+        let flags =
+            PAGE_SIZE_FLAG | PAGING_STATE_FLAG | SERIAL_CONSISTENCY_FLAG | DEFAULT_TIMESTAMP_FLAG;
+        let mut bytes = vec![0x00, 0x05, flags as u8];
+        bytes.extend_from_slice(&100i32.to_be_bytes());
+        bytes.extend_from_slice(&2i32.to_be_bytes());
+        bytes.extend_from_slice(&[0xAA, 0xBB]);
+        bytes.extend_from_slice(&SERIAL.to_be_bytes());
+        bytes.extend_from_slice(&1_700_000_000_000_000i64.to_be_bytes());
+        let parameters = parse(&bytes).unwrap();
+        assert_eq!(parameters.consistency, 0x0005);
+        assert_eq!(parameters.page_size, Some(100));
+        assert_eq!(parameters.paging_state, Some(vec![0xAA, 0xBB]));
+        assert_eq!(parameters.serial_consistency, Some(SERIAL));
+        assert_eq!(parameters.timestamp, Some(1_700_000_000_000));
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let mut bytes = vec![0x00, 0x01, PAGE_SIZE_FLAG as u8];
+        bytes.extend_from_slice(&0i32.to_be_bytes());
+        assert!(parse(&bytes).is_err());
+        let mut bytes = vec![0x00, 0x01, SERIAL_CONSISTENCY_FLAG as u8];
+        bytes.extend_from_slice(&0x0001i16.to_be_bytes());
+        assert!(parse(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_unset_values_by_version() {
+        let mut bytes = vec![0x00, 0x01, VALUES_FLAG as u8, 0x00, 0x01];
+        bytes.extend_from_slice(&(-2i32).to_be_bytes());
+        let parameters = parse(&bytes).unwrap();
+        assert_eq!(
+            parameters.values,
+            BoundValues::Positional(vec![BoundValue::Unset])
+        );
+        let result = QueryParameters::parse(&mut BytesCursor::new(&bytes), 0x03);
+        assert!(matches!(result, Err(Errors::ProtocolError(_))));
+    }
+
+    #[test]
+    fn test_v5_flags() {
+        let mut bytes = vec![0x00, 0x01];
+        bytes.extend_from_slice(&(PAGE_SIZE_FLAG | NOW_IN_SECONDS_FLAG).to_be_bytes());
+        bytes.extend_from_slice(&10i32.to_be_bytes());
+        bytes.extend_from_slice(&1_700_000_000i32.to_be_bytes());
+        let parameters = QueryParameters::parse(&mut BytesCursor::new(&bytes), 0x05).unwrap();
+        assert_eq!(parameters.page_size, Some(10));
+        let mut bytes = vec![0x00, 0x01];
+        bytes.extend_from_slice(&KEYSPACE_FLAG.to_be_bytes());
+        let result = QueryParameters::parse(&mut BytesCursor::new(&bytes), 0x05);
+        assert!(matches!(result, Err(Errors::Invalid(_))));
+    }
+}
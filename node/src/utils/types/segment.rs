@@ -0,0 +1,171 @@
+use crate::utils::compression::lz4_decompress;
+use crate::utils::errors::Errors;
+use crate::utils::types::bytes_cursor::BytesCursor;
+
+/// Maximum size of the payload of a segment, the length is encoded in 17 bits.
+pub const MAX_PAYLOAD_LENGTH: usize = (1 << 17) - 1;
+
+const LENGTH_BITS: u32 = 17;
+const UNCOMPRESSED_HEADER_LENGTH: usize = 3;
+const COMPRESSED_HEADER_LENGTH: usize = 5;
+const CRC24_INIT: u32 = 0x875060;
+const CRC24_POLY: u32 = 0x1974F0B;
+const CRC32_INITIAL_BYTES: [u8; 4] = [0xFA, 0x2D, 0x55, 0xCA];
+
+/// Framing layer of v5. Once the STARTUP was negotiated in v5, the frames travel
+/// inside segments:
+///
+/// `<header LE><crc24 of the header: 3 bytes LE><payload><crc32 of the payload: 4 bytes LE>`
+///
+/// Without compression the header takes 3 bytes: the length of the payload in the first 17 bits
+/// and in bit 17 whether the segment holds complete frames. With LZ4 it takes 5 bytes: the
+/// compressed length, the original length (0 if the payload was not compressed) and the
+/// self-contained bit. A frame bigger than a segment is split among several segments
+/// that are not self-contained.
+pub fn encode_segments(payload: &[u8], compressed: bool) -> Vec<u8> {
+    if payload.len() <= MAX_PAYLOAD_LENGTH {
+        return encode_segment(payload, true, compressed);
+    }
+    payload
+        .chunks(MAX_PAYLOAD_LENGTH)
+        .flat_map(|chunk| encode_segment(chunk, false, compressed))
+        .collect()
+}
+
+/// reads every segment of the bytes and returns their payloads joined, checking both CRCs
+pub fn decode_segments(bytes: &[u8], compressed: bool) -> Result<Vec<u8>, Errors> {
+    let header_length = if compressed {
+        COMPRESSED_HEADER_LENGTH
+    } else {
+        UNCOMPRESSED_HEADER_LENGTH
+    };
+    let mut cursor = BytesCursor::new(bytes);
+    let mut payload = Vec::new();
+    while !cursor.is_empty() {
+        let header = read_le(&cursor.read_exact(header_length)?);
+        let header_crc = read_le(&cursor.read_exact(3)?) as u32;
+        if crc24(header, header_length) != header_crc {
+            return Err(Errors::ProtocolError(String::from(
+                "Segment header CRC mismatch",
+            )));
+        }
+        let length = (header & MAX_PAYLOAD_LENGTH as u64) as usize;
+        let segment_payload = cursor.read_exact(length)?;
+        let payload_crc = cursor.read_exact(4)?;
+        if crc32(&segment_payload).to_le_bytes().as_slice() != payload_crc.as_slice() {
+            return Err(Errors::ProtocolError(String::from(
+                "Segment payload CRC mismatch",
+            )));
+        }
+        let uncompressed_length = (header >> LENGTH_BITS) as usize & MAX_PAYLOAD_LENGTH;
+        if compressed && uncompressed_length != 0 {
+            payload.extend(lz4_decompress(&segment_payload, uncompressed_length)?);
+        } else {
+            payload.extend(segment_payload);
+        }
+    }
+    Ok(payload)
+}
+
+fn encode_segment(payload: &[u8], self_contained: bool, compressed: bool) -> Vec<u8> {
+    let (header, header_length, payload) = if compressed {
+        let lz4_payload = lz4_flex::block::compress(payload);
+        // if compressing does not shrink the payload it is sent as is, with original length 0
+        let (payload, uncompressed_length) = if lz4_payload.len() < payload.len() {
+            (lz4_payload, payload.len() as u64)
+        } else {
+            (payload.to_vec(), 0)
+        };
+        let header = payload.len() as u64
+            | uncompressed_length << LENGTH_BITS
+            | (self_contained as u64) << (2 * LENGTH_BITS);
+        (header, COMPRESSED_HEADER_LENGTH, payload)
+    } else {
+        let header = payload.len() as u64 | (self_contained as u64) << LENGTH_BITS;
+        (header, UNCOMPRESSED_HEADER_LENGTH, payload.to_vec())
+    };
+    let mut bytes = header.to_le_bytes()[..header_length].to_vec();
+    bytes.extend_from_slice(&crc24(header, header_length).to_le_bytes()[..3]);
+    bytes.extend_from_slice(&payload);
+    bytes.extend_from_slice(&crc32(&payload).to_le_bytes());
+    bytes
+}
+
+fn read_le(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .rev()
+        .fold(0, |value, byte| (value << 8) | *byte as u64)
+}
+
+/// CRC24 of the bytes of the header, processed from the least significant one
+fn crc24(header: u64, length: usize) -> u32 {
+    let mut crc = CRC24_INIT;
+    let mut bytes = header;
+    for _ in 0..length {
+        crc ^= ((bytes & 0xFF) as u32) << 16;
+        bytes >>= 8;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+        }
+    }
+    crc & 0xFFFFFF
+}
+
+fn crc32(payload: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&CRC32_INITIAL_BYTES);
+    hasher.update(payload);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let payload = vec![0x05, 0x00, 0x00, 0x01, 0x07, 0x00, 0x00, 0x00, 0x00];
+        let segments = encode_segments(&payload, false);
+        assert_eq!(segments.len(), payload.len() + 10);
+        assert_eq!(read_le(&segments[..3]), payload.len() as u64 | 1 << 17);
+        assert_eq!(decode_segments(&segments, false).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_large_payload_is_split() {
+        let payload: Vec<u8> = (0..MAX_PAYLOAD_LENGTH + 10).map(|i| i as u8).collect();
+        let segments = encode_segments(&payload, false);
+        assert_eq!(segments.len(), payload.len() + 20);
+        assert_eq!(read_le(&segments[..3]), MAX_PAYLOAD_LENGTH as u64);
+        assert_eq!(decode_segments(&segments, false).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_corrupted_segment() {
+        let mut segments = encode_segments(&[0x01, 0x02, 0x03], false);
+        segments[7] ^= 0xFF;
+        assert!(matches!(
+            decode_segments(&segments, false),
+            Err(Errors::ProtocolError(_))
+        ));
+        let mut segments = encode_segments(&[0x01, 0x02, 0x03], false);
+        segments[1] ^= 0x01;
+        assert!(decode_segments(&segments, false).is_err());
+    }
+
+    #[test]
+    fn test_compressed_segments() {
+        let payload = b"SELECT * FROM flights ".repeat(100);
+        let segments = encode_segments(&payload, true);
+        assert!(segments.len() < payload.len());
+        assert_eq!(decode_segments(&segments, true).unwrap(), payload);
+        // a payload that cannot be compressed travels uncompressed
+        let segments = encode_segments(&[0x01, 0x02], true);
+        assert_eq!(read_le(&segments[..5]), 2 | 1 << 34);
+        assert_eq!(decode_segments(&segments, true).unwrap(), vec![0x01, 0x02]);
+    }
+}
@@ -3,7 +3,7 @@ use crate::utils::errors::Errors::ServerError;
 use rustls::pki_types::pem::PemObject;
 use rustls::pki_types::{CertificateDer, PrivateKeyDer};
 use rustls::{ServerConfig, ServerConnection, StreamOwned};
-use std::io::{Read, Write};
+use std::io::{ErrorKind, Read, Write};
 use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::path::Path;
 use std::sync::Arc;
@@ -47,6 +47,35 @@ pub fn read_exact_from_tls_stream(
     Ok(buffer[0..].to_vec())
 }
 
+/// reads the bytes that are already available without blocking longer than the socket read
+/// timeout, returns false if the client closed the connection
+pub fn read_available_from_tls_stream(
+    stream: &mut StreamOwned<ServerConnection, TcpStream>,
+    buffer: &mut Vec<u8>,
+) -> Result<bool, Errors> {
+    let mut chunk = [0; 4096];
+    match stream.read(&mut chunk) {
+        Ok(0) => Ok(false),
+        Ok(read) => {
+            buffer.extend_from_slice(&chunk[..read]);
+            Ok(true)
+        }
+        Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(true),
+        Err(_) => Err(ServerError(String::from("Failed to read stream"))),
+    }
+}
+
+/// takes the next length prefixed message out of the buffer, if it was completely read
+pub fn next_message(buffer: &mut Vec<u8>) -> Option<Vec<u8>> {
+    let size = i32::from_be_bytes(buffer.get(..4)?.try_into().ok()?).max(0) as usize;
+    if buffer.len() < 4 + size {
+        return None;
+    }
+    let message = buffer[4..4 + size].to_vec();
+    buffer.drain(..4 + size);
+    Some(message)
+}
+
 pub fn read_from_stream_no_zero(
     stream: &mut StreamOwned<ServerConnection, TcpStream>,
 ) -> Result<Vec<u8>, Errors> {
@@ -119,3 +148,19 @@ pub fn connect_to_socket(
         .map_err(|_| ServerError(String::from("Error connecting to socket.")))?;
     get_stream_owned(stream, Arc::new(config))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_message() {
+        let mut buffer = 3i32.to_be_bytes().to_vec();
+        buffer.extend_from_slice(&[0x01, 0x02]);
+        assert_eq!(next_message(&mut buffer), None);
+        buffer.extend_from_slice(&[0x03, 0x00, 0x00]);
+        assert_eq!(next_message(&mut buffer), Some(vec![0x01, 0x02, 0x03]));
+        assert_eq!(buffer, vec![0x00, 0x00]);
+        assert_eq!(next_message(&mut buffer), None);
+    }
+}
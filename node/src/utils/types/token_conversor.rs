@@ -102,6 +102,16 @@ pub fn get_reserved_string(tokens: &mut Peekable<IntoIter<Token>>) -> Result<Str
     }
 }
 
+/// Whether the token is the given keyword. The keywords that are not reserved in CQL are
+/// tokenized as identifiers, so they are only keywords where the statement expects them
+pub fn is_keyword(token: &Token, keyword: &str) -> bool {
+    match token {
+        Token::Reserved(reserved) => reserved == keyword,
+        Token::Identifier(identifier) => identifier.eq_ignore_ascii_case(keyword),
+        _ => false,
+    }
+}
+
 pub fn get_list(tokens: &mut Peekable<IntoIter<Token>>) -> Result<Vec<Token>, Errors> {
     let token = get_next_value(tokens)?;
     match token {
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use cql_types::skip_type_option;
+
 use crate::utils::{
         bytes_cursor::BytesCursor, consistency_level::ConsistencyLevel, constants::{OP_AUTH_RESPONSE, OP_ERROR, OP_RESULT, ROW_RESPONSE}, frame::Frame, types_to_bytes::TypesToBytes
     };
@@ -36,7 +38,10 @@ impl CassandraClient {
             body.len() as u32,
             body,
         );
-        let _ = self.send_and_receive(&mut frame)?;
+        let response = self.send_and_receive(&mut frame)?;
+        if response.opcode != OP_ERROR {
+            self.connection.enable_compression();
+        }
         Ok(())
     }
 
@@ -44,6 +49,7 @@ impl CassandraClient {
         let mut types_to_bytes = TypesToBytes::default();
         let mut options_map = HashMap::new();
         options_map.insert("CQL_VERSION".to_string(), "3.0.0".to_string());
+        options_map.insert("COMPRESSION".to_string(), "lz4".to_string());
         types_to_bytes.write_string_map(&options_map)?;
         Ok(types_to_bytes.into_bytes())
     }
@@ -64,7 +70,8 @@ impl CassandraClient {
     }
 
     fn get_authenticate_body(&self, user: &str, password: &str) -> Result<Vec<u8>, String> {
-        let credentials = format!("{}:{}", user, password);
+        // SASL PLAIN: [authzid] NUL authcid NUL passwd
+        let credentials = format!("\0{}\0{}", user, password);
         let mut types_to_bytes = TypesToBytes::default();
         types_to_bytes.write_long_string(&credentials)?;
         Ok(types_to_bytes.into_bytes())
@@ -155,7 +162,7 @@ impl CassandraClient {
         let mut column_names = Vec::new();
         for _ in 0..columns_count {
             let column_name = cursor.read_string()?;
-            let _ = cursor.read_i16()?;
+            skip_type_option(&mut cursor)?;
             column_names.push(column_name);
         }
 
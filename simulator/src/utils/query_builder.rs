@@ -7,6 +7,7 @@ pub struct QueryBuilder {
     logical_operators: Vec<String>,
     order_by: Option<(String, String)>,
     if_condition: Option<String>,
+    json: Option<String>,
 }
 
 impl QueryBuilder {
@@ -20,6 +21,7 @@ impl QueryBuilder {
             logical_operators: Vec::new(),
             order_by: None,
             if_condition: None,
+            json: None,
         }
     }
 
@@ -36,6 +38,12 @@ impl QueryBuilder {
         self
     }
 
+    /// Method to insert a whole row as a JSON document
+    pub fn insert_json(mut self, json: &str) -> Self {
+        self.json = Some(json.to_string());
+        self
+    }
+
     /// Method to set the columns and values to be updated    
     pub fn update(mut self, set: Vec<(&str, &str)>) -> Self {
         self.columns.clear();
@@ -75,6 +83,7 @@ impl QueryBuilder {
     pub fn build(self) -> String {
         let mut query = match self.query_type.as_str() {
             "SELECT" => format!("SELECT {} FROM {}", self.columns.join(", "), self.table),
+            "INSERT" if self.json.is_some() => format!("INSERT INTO {} JSON '{}'", self.table, self.json.as_deref().unwrap_or_default()),
             "INSERT" => format!("INSERT INTO {} ({}) VALUES ({})", self.table, self.columns.join(", "), self.values.join(", ")),
             "UPDATE" => format!("UPDATE {} SET {}", self.table, self.columns.join(", ")),
             "DELETE" => format!("DELETE FROM {}", self.table),
@@ -131,6 +140,16 @@ mod tests {
         assert_eq!(query, expected);
     }
 
+    #[test]
+    fn test_insert_json_query() {
+        let query = QueryBuilder::new("INSERT", "flight")
+            .insert_json(r#"{"id": 1, "status": "on time"}"#)
+            .build();
+
+        let expected = r#"INSERT INTO flight JSON '{"id": 1, "status": "on time"}'"#;
+        assert_eq!(query, expected);
+    }
+
     #[test]
     fn test_update_query() {
         let query = QueryBuilder::new("UPDATE", "flight")
@@ -1,4 +1,5 @@
 use crate::errors::Errors;
+use cql_types::TypeOptionReader;
 use std::collections::HashMap;
 use std::io::{Cursor, Read};
 
@@ -97,6 +98,21 @@ impl BytesCursor {
         Ok(map)
     }
 
+    pub fn read_string_multimap(&mut self) -> Result<HashMap<String, Vec<String>>, Errors> {
+        let n = self.read_short()? as usize;
+        let mut map = HashMap::new();
+
+        for _ in 0..n {
+            let key = self.read_string()?;
+            let mut values = Vec::new();
+            for _ in 0..self.read_short()? {
+                values.push(self.read_string()?);
+            }
+            map.insert(key, values);
+        }
+        Ok(map)
+    }
+
     pub fn read_bytes(&mut self) -> Result<Option<Vec<u8>>, Errors> {
         let n = self.read_int()?;
         match n {
@@ -114,6 +130,22 @@ impl BytesCursor {
     }
 }
 
+impl TypeOptionReader for BytesCursor {
+    type Error = Errors;
+
+    fn read_i16(&mut self) -> Result<i16, Errors> {
+        self.read_i16()
+    }
+
+    fn read_short(&mut self) -> Result<i16, Errors> {
+        self.read_short()
+    }
+
+    fn read_string(&mut self) -> Result<String, Errors> {
+        self.read_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
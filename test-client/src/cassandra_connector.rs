@@ -8,6 +8,7 @@ use crate::frame::Frame;
 
 pub struct CassandraConnection {
     stream: StreamOwned<ClientConnection, TcpStream>,
+    compression: bool,
 }
 
 impl CassandraConnection {
@@ -34,11 +35,25 @@ impl CassandraConnection {
         // Create a new Stream with the connection and the socket
         let stream = StreamOwned::new(connector, socket);
 
-        Ok(Self { stream })
+        Ok(Self {
+            stream,
+            compression: false,
+        })
+    }
+
+    /// From now on the frame bodies are compressed with LZ4, as negotiated in the STARTUP
+    pub fn enable_compression(&mut self) {
+        self.compression = true;
     }
 
     pub fn write_stream(&mut self, frame: &Frame) -> Result<(), String> {
-        let frame_bytes = frame.to_bytes();
+        let frame_bytes = if self.compression {
+            let mut compressed = frame.clone();
+            compressed.compress();
+            compressed.to_bytes()
+        } else {
+            frame.to_bytes()
+        };
 
         let mut message = (frame_bytes.len() as i32).to_be_bytes().to_vec();
         message.extend(frame_bytes);
@@ -65,7 +80,10 @@ impl CassandraConnection {
             self.stream
                 .read_exact(&mut buffer)
                 .map_err(|_| String::from("Failed to read full message"))?;
-            Frame::parse_frame(&buffer[0..]).map_err(|_| String::from("Failed to parse frame"))
+            let mut frame = Frame::parse_frame(&buffer[0..])
+                .map_err(|_| String::from("Failed to parse frame"))?;
+            frame.decompress().map_err(|e| e.to_string())?;
+            Ok(frame)
         }
     }
     
@@ -15,6 +15,9 @@ const INVALID: &[u8] = &[0x22, 0x00];
 const CONFIG_ERROR: &[u8] = &[0x23, 0x00];
 const ALREADY_EXISTS: &[u8] = &[0x24, 0x00];
 const UNPREPARED: &[u8] = &[0x25, 0x00];
+const READ_FAILURE: &[u8] = &[0x13, 0x00];
+const FUNCTION_FAILURE: &[u8] = &[0x14, 0x00];
+const WRITE_FAILURE: &[u8] = &[0x15, 0x00];
 
 #[derive(Debug, PartialEq)]
 pub enum Errors {
@@ -33,6 +36,9 @@ pub enum Errors {
     ConfigError(String),
     AlreadyExists(String),
     Unprepared(String),
+    ReadFailure(String),
+    FunctionFailure(String),
+    WriteFailure(String),
 }
 
 impl Errors {
@@ -53,6 +59,9 @@ impl Errors {
             Errors::ConfigError(msg) => join_bytes(CONFIG_ERROR, msg),
             Errors::AlreadyExists(msg) => join_bytes(ALREADY_EXISTS, msg),
             Errors::Unprepared(msg) => join_bytes(UNPREPARED, msg),
+            Errors::ReadFailure(msg) => join_bytes(READ_FAILURE, msg),
+            Errors::FunctionFailure(msg) => join_bytes(FUNCTION_FAILURE, msg),
+            Errors::WriteFailure(msg) => join_bytes(WRITE_FAILURE, msg),
         }
     }
 
@@ -73,6 +82,9 @@ impl Errors {
             CONFIG_ERROR => Errors::ConfigError(msg),
             ALREADY_EXISTS => Errors::AlreadyExists(msg),
             UNPREPARED => Errors::Unprepared(msg),
+            READ_FAILURE => Errors::ReadFailure(msg),
+            FUNCTION_FAILURE => Errors::FunctionFailure(msg),
+            WRITE_FAILURE => Errors::WriteFailure(msg),
             _ => Errors::ServerError(msg),
         }
     }
@@ -96,6 +108,9 @@ impl Display for Errors {
             Errors::ConfigError(msg) => write!(f, "ConfigError: {}", msg),
             Errors::AlreadyExists(msg) => write!(f, "AlreadyExists: {}", msg),
             Errors::Unprepared(msg) => write!(f, "Unprepared: {}", msg),
+            Errors::ReadFailure(msg) => write!(f, "ReadFailure: {}", msg),
+            Errors::FunctionFailure(msg) => write!(f, "FunctionFailure: {}", msg),
+            Errors::WriteFailure(msg) => write!(f, "WriteFailure: {}", msg),
         }
     }
 }
@@ -1,7 +1,13 @@
 use crate::bytes_cursor::BytesCursor;
 use crate::errors::Errors;
+use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
+pub const COMPRESSION_FLAG: u8 = 0x01;
+pub const TRACING_FLAG: u8 = 0x02;
+pub const CUSTOM_PAYLOAD_FLAG: u8 = 0x04;
+pub const WARNING_FLAG: u8 = 0x08;
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Frame {
     pub version: u8,
     pub flags: u8,
@@ -44,6 +50,88 @@ impl Frame {
         bytes
     }
 
+    /// compresses the body with LZ4, preceded by its original length
+    pub fn compress(&mut self) {
+        if self.body.is_empty() {
+            return;
+        }
+        let mut body = (self.body.len() as i32).to_be_bytes().to_vec();
+        body.extend(lz4_flex::block::compress(&self.body));
+        self.body = body;
+        self.length = self.body.len() as u32;
+        self.flags |= COMPRESSION_FLAG;
+    }
+
+    /// decompresses the body if the node sent it compressed
+    pub fn decompress(&mut self) -> Result<(), Errors> {
+        if self.flags & COMPRESSION_FLAG == 0 {
+            return Ok(());
+        }
+        let mut cursor = BytesCursor::new(&self.body);
+        let length = cursor.read_int()?;
+        let block = cursor.read_remaining_bytes()?;
+        self.body = lz4_flex::block::decompress(&block, length.max(0) as usize)
+            .map_err(|e| Errors::ProtocolError(e.to_string()))?;
+        self.length = self.body.len() as u32;
+        self.flags &= !COMPRESSION_FLAG;
+        Ok(())
+    }
+
+    /// removes the tracing session id that goes before the body of a traced response,
+    /// returning it formatted as an uuid
+    pub fn take_tracing_id(&mut self) -> Result<Option<String>, Errors> {
+        if self.flags & TRACING_FLAG == 0 {
+            return Ok(None);
+        }
+        let mut cursor = BytesCursor::new(&self.body);
+        let id = cursor.read_exact(16)?;
+        self.body = cursor.read_remaining_bytes()?;
+        self.length = self.body.len() as u32;
+        self.flags &= !TRACING_FLAG;
+        let hex: String = id.iter().map(|byte| format!("{:02x}", byte)).collect();
+        Ok(Some(format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )))
+    }
+
+    /// removes the warnings that go before the body, after the tracing id
+    pub fn take_warnings(&mut self) -> Result<Vec<String>, Errors> {
+        if self.flags & WARNING_FLAG == 0 {
+            return Ok(Vec::new());
+        }
+        let mut cursor = BytesCursor::new(&self.body);
+        let mut warnings = Vec::new();
+        for _ in 0..cursor.read_short()? {
+            warnings.push(cursor.read_string()?);
+        }
+        self.body = cursor.read_remaining_bytes()?;
+        self.length = self.body.len() as u32;
+        self.flags &= !WARNING_FLAG;
+        Ok(warnings)
+    }
+
+    /// removes the custom payload that goes before the body, after the warnings
+    pub fn take_custom_payload(&mut self) -> Result<HashMap<String, Vec<u8>>, Errors> {
+        if self.flags & CUSTOM_PAYLOAD_FLAG == 0 {
+            return Ok(HashMap::new());
+        }
+        let mut cursor = BytesCursor::new(&self.body);
+        let mut payload = HashMap::new();
+        for _ in 0..cursor.read_short()? {
+            let key = cursor.read_string()?;
+            payload.insert(key, cursor.read_bytes()?.unwrap_or_default());
+        }
+        self.body = cursor.read_remaining_bytes()?;
+        self.length = self.body.len() as u32;
+        self.flags &= !CUSTOM_PAYLOAD_FLAG;
+        Ok(payload)
+    }
+
     pub fn validate_request_frame(&self) -> Result<(), Errors> {
         if self.version != 0x03 {
             return Err(Errors::ProtocolError(format!(
@@ -84,4 +172,49 @@ mod tests {
         assert_eq!(result.body, vec![16, 3, 53, 18, 34]);
         Ok(())
     }
+
+    #[test]
+    fn test_take_tracing_id() -> Result<(), Errors> {
+        let mut body = vec![0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0x4d, 0xef];
+        body.extend([0x80, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77]);
+        body.extend([0x00, 0x00, 0x00, 0x01]);
+        let mut frame = Frame {
+            version: 0x83,
+            flags: TRACING_FLAG,
+            stream: 1,
+            opcode: 8,
+            length: 20,
+            body,
+        };
+        assert_eq!(
+            frame.take_tracing_id()?,
+            Some(String::from("12345678-9abc-4def-8011-223344556677"))
+        );
+        assert_eq!(frame.body, vec![0, 0, 0, 1]);
+        assert_eq!(frame.take_tracing_id()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_take_warnings_and_custom_payload() -> Result<(), Errors> {
+        let mut frame = Frame {
+            version: 0x84,
+            flags: WARNING_FLAG | CUSTOM_PAYLOAD_FLAG,
+            stream: 1,
+            opcode: 8,
+            length: 19,
+            body: vec![
+                0x00, 0x01, 0x00, 0x04, b's', b'l', b'o', b'w', 0x00, 0x01, 0x00, 0x01, b'k', 0x00,
+                0x00, 0x00, 0x01, 0x01, 0xFF,
+            ],
+        };
+        assert_eq!(frame.take_warnings()?, vec!["slow".to_string()]);
+        assert_eq!(
+            frame.take_custom_payload()?,
+            HashMap::from([("k".to_string(), vec![0x01])])
+        );
+        assert_eq!(frame.body, vec![0xFF]);
+        assert_eq!(frame.flags, 0x00);
+        Ok(())
+    }
 }
@@ -1,3 +1,4 @@
+use cql_types::skip_type_option;
 use std::fs::File;
 use std::io;
 use std::io::{BufRead, BufReader, Write};
@@ -15,26 +16,43 @@ const QUERY: u8 = 7;
 const RESULT: u8 = 8;
 const PREPARE: u8 = 9;
 const EXECUTE: u8 = 10;
+const BATCH: u8 = 13;
 const AUTH_CHALLENGE: u8 = 14;
 const AUTH_RESPONSE: u8 = 15;
 const AUTH_SUCCESS: u8 = 16;
-const VERSION: u8 = 3;
+// v4 is the first version whose responses carry warnings and custom payloads
+const VERSION: u8 = 4;
 const FLAG: u8 = 0;
+const TRACING_FLAG: u8 = 0x02;
+const CUSTOM_PAYLOAD_FLAG: u8 = 0x04;
 
 fn main() {
     let node = get_user_data("FULL IP (ip:port): ");
     let mut connector = CassandraConnection::new(&node).expect("Failed to connect to Cassandra");
 
     let mut input = String::new();
+    let mut last_trace: Option<String> = None;
     while io::stdin().read_line(&mut input).is_ok() {
         match input.trim() {
             "startup" => send_startup(&mut connector),
+            "startup_lz4" => send_startup_lz4(&mut connector),
             "admin" => send_auth_admin(&mut connector),
             "auth_response" => send_auth_response(&mut connector),
             "options" => send_options(&mut connector),
             "query" => send_query(&mut connector),
+            "traced_query" => send_traced_query(&mut connector),
+            "payload_query" => send_payload_query(&mut connector),
+            "trace" => {
+                let Some(session_id) = &last_trace else {
+                    println!("No traced query was sent");
+                    input.clear();
+                    continue;
+                };
+                send_trace_query(&mut connector, session_id)
+            }
             "prepare" => send_prepare(&mut connector),
             "execute" => send_execute(&mut connector),
+            "batch" => send_batch(&mut connector),
             "queries" => {
                 send_queries(&mut connector);
                 input.clear();
@@ -47,7 +65,20 @@ fn main() {
         }
         println!();
         match connector.read_stream() {
-            Ok(frame) => {
+            Ok(mut frame) => {
+                if input.trim() == "startup_lz4" && frame.opcode != ERROR {
+                    connector.enable_compression();
+                }
+                if let Some(session_id) = frame.take_tracing_id().unwrap() {
+                    println!("Tracing session: {}", session_id);
+                    last_trace = Some(session_id);
+                }
+                for warning in frame.take_warnings().unwrap() {
+                    println!("Warning: {}", warning);
+                }
+                for (key, value) in frame.take_custom_payload().unwrap() {
+                    println!("Custom payload: {} = {}", key, String::from_utf8_lossy(&value));
+                }
                 match frame.opcode {
                     ERROR => {
                         let mut cursor = BytesCursor::new(frame.body.as_slice());
@@ -70,8 +101,8 @@ fn main() {
                     SUPPORTED => {
                         let mut cursor = BytesCursor::new(frame.body.as_slice());
                         println!("SUPPORTED");
-                        for (key, value) in cursor.read_string_map().unwrap() {
-                            println!("{}: {}", key, value);
+                        for (key, values) in cursor.read_string_multimap().unwrap() {
+                            println!("{}: {}", key, values.join(", "));
                         }
                     }
                     RESULT => {
@@ -114,16 +145,28 @@ fn send_startup(connector: &mut CassandraConnection) {
     connector.write_stream(&Frame::parse_frame(startup_bytes.as_slice()).unwrap()).unwrap()
 }
 
+fn send_startup_lz4(connector: &mut CassandraConnection) {
+    let mut body = vec![0x00, 0x02]; // n = 2
+    for option in ["CQL_VERSION", "3.0.0", "COMPRESSION", "lz4"] {
+        body.extend_from_slice((option.len() as u16).to_be_bytes().as_slice());
+        body.extend_from_slice(option.as_bytes());
+    }
+    let frame_bytes = build_frame(body, STARTUP);
+    connector.write_stream(&Frame::parse_frame(frame_bytes.as_slice()).unwrap()).unwrap()
+}
+
 fn send_auth_admin(connector: &mut CassandraConnection) {
     let auth_response_bytes = vec![
-        VERSION, FLAG, 0x00, 0x01, 0x0F, 0x00, 0x00, 0x00, 0x12, 0x00, 0x00, 0x00, 0x0E, b'a', b'd',
-        b'm', b'i', b'n', b':', b'p', b'a', b's', b's', b'w', b'o', b'r', b'd',
+        VERSION, FLAG, 0x00, 0x01, 0x0F, 0x00, 0x00, 0x00, 0x13, 0x00, 0x00, 0x00, 0x0F, 0x00, b'a',
+        b'd', b'm', b'i', b'n', 0x00, b'p', b'a', b's', b's', b'w', b'o', b'r', b'd',
     ];
     connector.write_stream(&Frame::parse_frame(auth_response_bytes.as_slice()).unwrap()).unwrap()
 }
 
 fn send_auth_response(connector: &mut CassandraConnection) {
     let credentiasl = get_user_data("Enter credentials (user:password) :");
+    // SASL PLAIN: [authzid] NUL authcid NUL passwd
+    let credentiasl = format!("\0{}", credentiasl.replacen(':', "\0", 1));
     let mut body = Vec::new();
     body.extend_from_slice((credentiasl.len() as i32).to_be_bytes().as_slice());
     body.extend_from_slice(credentiasl.as_bytes());
@@ -139,12 +182,52 @@ fn send_options(connector: &mut CassandraConnection) {
 fn send_query(connector: &mut CassandraConnection) {
     let query = get_user_data("Query: ");
     let consistency = get_user_data("Consistency: ");
+    let frame_bytes = build_frame(query_body(&query, &consistency), QUERY);
+    connector.write_stream(&Frame::parse_frame(frame_bytes.as_slice()).unwrap()).unwrap()
+}
+
+/// sends the query asking the node to trace it, the response carries the session id
+fn send_traced_query(connector: &mut CassandraConnection) {
+    let query = get_user_data("Query: ");
+    let consistency = get_user_data("Consistency: ");
+    let mut frame_bytes = build_frame(query_body(&query, &consistency), QUERY);
+    frame_bytes[1] = TRACING_FLAG;
+    connector.write_stream(&Frame::parse_frame(frame_bytes.as_slice()).unwrap()).unwrap()
+}
+
+/// sends the query with a custom payload entry, the node sends it back with the response
+fn send_payload_query(connector: &mut CassandraConnection) {
+    let query = get_user_data("Query: ");
+    let consistency = get_user_data("Consistency: ");
+    let entry = get_user_data("Custom payload (key=value): ");
+    let (key, value) = entry.split_once('=').unwrap_or((entry.as_str(), ""));
+    let mut body = vec![0x00, 0x01]; // n = 1
+    body.extend_from_slice((key.len() as u16).to_be_bytes().as_slice());
+    body.extend_from_slice(key.as_bytes());
+    body.extend_from_slice((value.len() as i32).to_be_bytes().as_slice());
+    body.extend_from_slice(value.as_bytes());
+    body.extend(query_body(&query, &consistency));
+    let mut frame_bytes = build_frame(body, QUERY);
+    frame_bytes[1] = CUSTOM_PAYLOAD_FLAG;
+    connector.write_stream(&Frame::parse_frame(frame_bytes.as_slice()).unwrap()).unwrap()
+}
+
+/// fetches the events recorded for the last traced query
+fn send_trace_query(connector: &mut CassandraConnection, session_id: &str) {
+    let query = format!(
+        "SELECT * FROM system_traces.events WHERE session_id = '{}'",
+        session_id
+    );
+    let frame_bytes = build_frame(query_body(&query, "1"), QUERY);
+    connector.write_stream(&Frame::parse_frame(frame_bytes.as_slice()).unwrap()).unwrap()
+}
+
+fn query_body(query: &str, consistency: &str) -> Vec<u8> {
     let mut body = Vec::new();
     body.extend_from_slice((query.len() as i32).to_be_bytes().as_slice());
     body.extend_from_slice(query.as_bytes());
-    body.extend_from_slice(&parse_string_to_i16_be_bytes(consistency));
-    let frame_bytes = build_frame(body, QUERY);
-    connector.write_stream(&Frame::parse_frame(frame_bytes.as_slice()).unwrap()).unwrap()
+    body.extend_from_slice(&parse_string_to_i16_be_bytes(consistency.to_string()));
+    body
 }
 fn send_queries(connector: &mut CassandraConnection) {
     let path = get_user_data("Queries path : ");
@@ -169,7 +252,10 @@ fn send_queries(connector: &mut CassandraConnection) {
         connector.write_stream(&Frame::parse_frame(frame_bytes.as_slice()).unwrap()).unwrap();
 
         match connector.read_stream() {
-            Ok(frame) => {
+            Ok(mut frame) => {
+                for warning in frame.take_warnings().unwrap() {
+                    println!("Warning: {}", warning);
+                }
                 match frame.opcode {
                     ERROR => {
                         let mut cursor = BytesCursor::new(frame.body.as_slice());
@@ -203,15 +289,45 @@ fn send_prepare(connector: &mut CassandraConnection) {
 }
 
 fn send_execute(connector: &mut CassandraConnection) {
-    let id = get_user_data("Query id: ");
+    let id = get_user_data("Prepared id (hex): ");
     let consistency = get_user_data("Consistency: ");
+    let id: Vec<u8> = (0..id.len() / 2)
+        .map(|i| u8::from_str_radix(&id[2 * i..2 * i + 2], 16).unwrap())
+        .collect();
     let mut body = Vec::new();
-    body.extend_from_slice(&parse_string_to_i16_be_bytes(id));
+    body.extend_from_slice(&(id.len() as i16).to_be_bytes());
+    body.extend_from_slice(&id);
     body.extend_from_slice(&parse_string_to_i16_be_bytes(consistency));
+    body.push(FLAG);
     let frame_bytes = build_frame(body, EXECUTE);
     connector.write_stream(&Frame::parse_frame(frame_bytes.as_slice()).unwrap()).unwrap()
 }
 
+fn send_batch(connector: &mut CassandraConnection) {
+    let batch_type = get_user_data("Batch type (0 logged, 1 unlogged, 2 counter): ");
+    let mut queries = Vec::new();
+    loop {
+        let query = get_user_data("Query (empty to finish): ");
+        if query.is_empty() {
+            break;
+        }
+        queries.push(query);
+    }
+    let consistency = get_user_data("Consistency: ");
+    let mut body = vec![batch_type.parse::<u8>().unwrap()];
+    body.extend_from_slice(&(queries.len() as i16).to_be_bytes());
+    for query in queries {
+        body.push(0);
+        body.extend_from_slice((query.len() as i32).to_be_bytes().as_slice());
+        body.extend_from_slice(query.as_bytes());
+        body.extend_from_slice(&0i16.to_be_bytes());
+    }
+    body.extend_from_slice(&parse_string_to_i16_be_bytes(consistency));
+    body.push(FLAG);
+    let frame_bytes = build_frame(body, BATCH);
+    connector.write_stream(&Frame::parse_frame(frame_bytes.as_slice()).unwrap()).unwrap()
+}
+
 fn build_frame(body: Vec<u8>, opcode: u8) -> Vec<u8> {
     let mut frame = vec![VERSION, FLAG, 0x00, 0x01, opcode];
     frame.extend_from_slice((body.len() as i32).to_be_bytes().as_slice());
@@ -231,6 +347,12 @@ fn show_response(body: Vec<u8>) -> Result<(), Errors> {
             println!("Use keyspace was succesful");
             Ok(())
         },
+        4 => {
+            let id = cursor.read_short_bytes()?.unwrap_or_default();
+            let id: String = id.iter().map(|byte| format!("{:02x}", byte)).collect();
+            println!("Prepared statement id: {}", id);
+            Ok(())
+        },
         5 => {
             let change = cursor.read_string()?;
             let target = cursor.read_string()?;
@@ -250,7 +372,7 @@ fn show_response(body: Vec<u8>) -> Result<(), Errors> {
                     header += ", ";
                 }
                 let col_name = cursor.read_string()?;
-                let _ = cursor.read_i16()?;
+                skip_type_option(&mut cursor)?;
                 header += &col_name;
             }
             println!("{}", header);